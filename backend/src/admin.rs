@@ -0,0 +1,1321 @@
+use axum::{
+    Json,
+    body::{Body, Bytes},
+    extract::{Query, Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    pin::Pin,
+};
+use tokio::{io::AsyncWriteExt, sync::mpsc};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::{
+    Stores,
+    analytics::AnalyticsData,
+    assets,
+    assets::Asset,
+    git::GitCommit,
+    storage::{BlobStore, PersistentStore},
+    templates::Template,
+};
+
+/// Archive layout version written to the manifest
+const FORMAT_VERSION: u32 = 1;
+
+/// Default restore upload limit: 1 GiB
+const DEFAULT_MAX_BACKUP_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Default limit on one decompressed archive file: 256 MiB
+const DEFAULT_MAX_RESTORE_FILE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Default limit on all decompressed archive files together: 2 GiB
+const DEFAULT_MAX_RESTORE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Size of the chunks a backup is streamed in
+const ARCHIVE_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Chunks buffered ahead of a slow client
+const ARCHIVE_CHANNEL_CHUNKS: usize = 4;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const PROJECTS_FILE: &str = "projects.json";
+const TEMPLATES_FILE: &str = "templates.json";
+const COMMITS_FILE: &str = "commits.json";
+const ANALYTICS_FILE: &str = "analytics.json";
+const ASSETS_FILE: &str = "assets.json";
+const ASSET_DIR: &str = "assets/";
+
+fn env_bytes(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Limit on the compressed restore upload, checked while it streams in
+pub(crate) fn max_backup_bytes() -> u64 {
+    env_bytes("MAX_BACKUP_BYTES", DEFAULT_MAX_BACKUP_BYTES)
+}
+
+/// Limits on how far an archive may decompress, so a small upload cannot
+/// expand into more memory than the restore is meant to use
+#[derive(Clone, Copy, Debug)]
+pub struct ArchiveLimits {
+    /// Largest decompressed size of one file
+    pub file_bytes: u64,
+    /// Largest decompressed size of every file read, together
+    pub total_bytes: u64,
+}
+
+impl ArchiveLimits {
+    pub(crate) fn from_env() -> Self {
+        ArchiveLimits {
+            file_bytes: env_bytes("MAX_RESTORE_FILE_BYTES", DEFAULT_MAX_RESTORE_FILE_BYTES),
+            total_bytes: env_bytes("MAX_RESTORE_BYTES", DEFAULT_MAX_RESTORE_BYTES),
+        }
+    }
+}
+
+/// Middleware guarding admin routes with `Authorization: Bearer $ADMIN_TOKEN`.
+///
+/// Without `ADMIN_TOKEN` the routes are disabled: a restore can overwrite
+/// every store, so they never fall back to being open.
+pub async fn require_admin(req: Request, next: Next) -> Result<Response, StatusCode> {
+    let provided = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    check_admin(std::env::var("ADMIN_TOKEN").ok().as_deref(), provided)?;
+    Ok(next.run(req).await)
+}
+
+fn check_admin(configured: Option<&str>, provided: Option<&str>) -> Result<(), StatusCode> {
+    let Some(token) = configured.filter(|t| !t.is_empty()) else {
+        tracing::warn!("Refused admin request: ADMIN_TOKEN is not set");
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    if constant_time_eq(provided.unwrap_or_default().as_bytes(), token.as_bytes()) {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Number of records of each kind in an archive
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct BackupCounts {
+    pub projects: usize,
+    pub templates: usize,
+    pub commits: usize,
+    pub assets: usize,
+    pub analytics_events: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ManifestFile {
+    pub path: String,
+    pub size: usize,
+    /// Hex-encoded SHA-256 of the file
+    pub sha256: String,
+}
+
+/// Describes a backup archive; stored as `manifest.json` at its root
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Manifest {
+    pub format_version: u32,
+    pub created_at: f64, // JS timestamp
+    pub app_version: String,
+    pub counts: BackupCounts,
+    pub files: Vec<ManifestFile>,
+    /// Asset files deleted while the backup was written; their assets, or
+    /// just those variants, are left out of the archive
+    #[serde(default)]
+    pub skipped_files: Vec<String>,
+}
+
+/// Full contents of every store, as held in memory
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    pub projects: HashMap<String, serde_json::Value>,
+    pub templates: HashMap<String, Template>,
+    pub commits: HashMap<String, Vec<GitCommit>>,
+    pub analytics: Vec<AnalyticsData>,
+    pub assets: HashMap<String, Asset>,
+    /// Asset file id -> bytes, for the original and every variant
+    pub blobs: HashMap<String, Vec<u8>>,
+}
+
+impl Snapshot {
+    fn counts(&self) -> BackupCounts {
+        BackupCounts {
+            projects: self.projects.len(),
+            templates: self.templates.len(),
+            commits: self.commits.values().map(Vec::len).sum(),
+            assets: self.assets.len(),
+            analytics_events: self.analytics.len(),
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Serialize a snapshot into a ZIP archive in memory, with the asset
+/// files held in `snapshot.blobs`.
+#[cfg(test)]
+pub fn build_archive(snapshot: &Snapshot, created_at: f64) -> Result<Vec<u8>, String> {
+    write_archive(Vec::new(), snapshot, created_at, |id| {
+        snapshot
+            .blobs
+            .get(id)
+            .cloned()
+            .ok_or_else(|| std::io::ErrorKind::NotFound.into())
+    })
+}
+
+/// Write a snapshot as a ZIP archive to `out`, without seeking.
+///
+/// Asset files are fetched through `read_blob` one at a time, so only one
+/// of them is in memory at once. They are written before `assets.json`:
+/// the snapshot does not hold the files, so one can be deleted before it
+/// is reached, and its asset is then left out and the file recorded in the
+/// manifest. The manifest goes last, once every checksum is known; readers
+/// find entries through the central directory.
+pub fn write_archive<W: Write>(
+    out: W,
+    snapshot: &Snapshot,
+    created_at: f64,
+    mut read_blob: impl FnMut(&str) -> std::io::Result<Vec<u8>>,
+) -> Result<W, String> {
+    let mut zip = ZipWriter::new_stream(out);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut files = Vec::new();
+    let mut add = |zip: &mut ZipWriter<_>, path: String, data: &[u8]| -> Result<(), String> {
+        zip.start_file(path.as_str(), options)
+            .map_err(|e| e.to_string())?;
+        zip.write_all(data).map_err(|e| e.to_string())?;
+        files.push(ManifestFile {
+            path,
+            size: data.len(),
+            sha256: hex(&Sha256::digest(data)),
+        });
+        Ok(())
+    };
+    let mut fetch = |id: &str| match read_blob(id) {
+        Ok(data) => Ok(Some(data)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::warn!("Leaving {} out of backup: file was deleted", id);
+            Ok(None)
+        }
+        Err(e) => Err(format!("Failed to read asset {}: {}", id, e)),
+    };
+
+    add(
+        &mut zip,
+        PROJECTS_FILE.to_string(),
+        &json(&snapshot.projects)?,
+    )?;
+    add(
+        &mut zip,
+        TEMPLATES_FILE.to_string(),
+        &json(&snapshot.templates)?,
+    )?;
+    add(
+        &mut zip,
+        COMMITS_FILE.to_string(),
+        &json(&snapshot.commits)?,
+    )?;
+    add(
+        &mut zip,
+        ANALYTICS_FILE.to_string(),
+        &json(&snapshot.analytics)?,
+    )?;
+
+    let mut ids: Vec<&String> = snapshot.assets.keys().collect();
+    ids.sort();
+    let mut assets = HashMap::new();
+    let mut skipped_files = Vec::new();
+    for id in ids {
+        let asset = &snapshot.assets[id];
+        let Some(data) = fetch(&asset.id)? else {
+            skipped_files.extend(asset.file_ids().map(str::to_string));
+            continue;
+        };
+        add(&mut zip, format!("{}{}", ASSET_DIR, asset.id), &data)?;
+        let mut kept = Asset {
+            variants: Vec::new(),
+            ..asset.clone()
+        };
+        for variant in &asset.variants {
+            match fetch(&variant.id)? {
+                Some(data) => {
+                    add(&mut zip, format!("{}{}", ASSET_DIR, variant.id), &data)?;
+                    kept.variants.push(variant.clone());
+                }
+                None => skipped_files.push(variant.id.clone()),
+            }
+        }
+        assets.insert(id.clone(), kept);
+    }
+    add(&mut zip, ASSETS_FILE.to_string(), &json(&assets)?)?;
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        created_at,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        counts: BackupCounts {
+            assets: assets.len(),
+            ..snapshot.counts()
+        },
+        files,
+        skipped_files,
+    };
+    zip.start_file(MANIFEST_FILE, options)
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&json(&manifest)?)
+        .map_err(|e| e.to_string())?;
+    let mut out = zip.finish().map_err(|e| e.to_string())?.into_inner();
+    out.flush().map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// Blocking `Write` end of a streamed response body
+struct BodyWriter(mpsc::Sender<std::io::Result<Bytes>>);
+
+impl Write for BodyWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .blocking_send(Ok(Bytes::copy_from_slice(buf)))
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| e.to_string())
+}
+
+/// Why an archive file could not be read
+enum EntryError {
+    /// Missing or corrupt; reading the other files still goes on
+    Unreadable(String),
+    /// Over a size limit; the whole archive is rejected at once
+    TooLarge(String),
+}
+
+impl EntryError {
+    fn message(self) -> String {
+        match self {
+            EntryError::Unreadable(e) | EntryError::TooLarge(e) => e,
+        }
+    }
+}
+
+/// Parse and validate an archive: manifest version, checksums, store
+/// contents and asset files. Returns every problem found.
+///
+/// Files are decompressed up to `limits`. The sizes in the ZIP headers are
+/// only used to reject early; the limits hold on the bytes actually read.
+pub fn read_archive<R: Read + Seek>(
+    reader: R,
+    limits: ArchiveLimits,
+) -> Result<(Manifest, Snapshot), Vec<String>> {
+    let mut archive = ZipArchive::new(reader).map_err(|e| vec![e.to_string()])?;
+    let mut remaining = limits.total_bytes;
+    let mut read_file = |path: &str| -> Result<Vec<u8>, EntryError> {
+        let file = archive
+            .by_name(path)
+            .map_err(|_| EntryError::Unreadable(format!("{} is missing", path)))?;
+        let limit = limits.file_bytes.min(remaining);
+        let too_large = || {
+            EntryError::TooLarge(if limit < limits.file_bytes {
+                format!(
+                    "Archive decompresses to more than {} bytes",
+                    limits.total_bytes
+                )
+            } else {
+                format!(
+                    "{} decompresses to more than {} bytes",
+                    path, limits.file_bytes
+                )
+            })
+        };
+        if file.size() > limit {
+            return Err(too_large());
+        }
+        let mut buf = Vec::new();
+        file.take(limit + 1)
+            .read_to_end(&mut buf)
+            .map_err(|e| EntryError::Unreadable(format!("{} could not be read: {}", path, e)))?;
+        if buf.len() as u64 > limit {
+            return Err(too_large());
+        }
+        remaining -= buf.len() as u64;
+        Ok(buf)
+    };
+
+    let manifest: Manifest = read_file(MANIFEST_FILE)
+        .map_err(EntryError::message)
+        .and_then(|raw| {
+            serde_json::from_slice(&raw).map_err(|e| format!("{} is invalid: {}", MANIFEST_FILE, e))
+        })
+        .map_err(|e| vec![e])?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(vec![format!(
+            "Unsupported archive format version {} (this server reads up to {})",
+            manifest.format_version, FORMAT_VERSION
+        )]);
+    }
+
+    let mut errors = Vec::new();
+    let mut contents = HashMap::new();
+    for entry in &manifest.files {
+        match read_file(&entry.path) {
+            Ok(raw) if hex(&Sha256::digest(&raw)) != entry.sha256 => {
+                errors.push(format!("{} does not match its checksum", entry.path));
+            }
+            Ok(raw) => {
+                contents.insert(entry.path.clone(), raw);
+            }
+            Err(EntryError::Unreadable(e)) => errors.push(e),
+            Err(EntryError::TooLarge(e)) => return Err(vec![e]),
+        }
+    }
+
+    fn parse<T: serde::de::DeserializeOwned + Default>(
+        contents: &mut HashMap<String, Vec<u8>>,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) -> T {
+        match contents.remove(path) {
+            Some(raw) => serde_json::from_slice(&raw).unwrap_or_else(|e| {
+                errors.push(format!("{} is invalid: {}", path, e));
+                T::default()
+            }),
+            None => {
+                errors.push(format!("{} is not listed in the manifest", path));
+                T::default()
+            }
+        }
+    }
+
+    let mut snapshot = Snapshot {
+        projects: parse(&mut contents, PROJECTS_FILE, &mut errors),
+        templates: parse(&mut contents, TEMPLATES_FILE, &mut errors),
+        commits: parse(&mut contents, COMMITS_FILE, &mut errors),
+        analytics: parse(&mut contents, ANALYTICS_FILE, &mut errors),
+        assets: parse(&mut contents, ASSETS_FILE, &mut errors),
+        blobs: HashMap::new(),
+    };
+    for (id, project) in &snapshot.projects {
+        if !project.is_object() {
+            errors.push(format!("Project {} is not an object", id));
+        }
+    }
+
+    // Ids become file names, so only files of listed assets whose content
+    // matches are restored
+    let mut listed = HashSet::new();
+    for (key, asset) in &snapshot.assets {
+        if key != &asset.id {
+            errors.push(format!("Asset {} is listed under {}", asset.id, key));
+        }
+        for file_id in asset.file_ids() {
+            if !assets::is_file_id(file_id) {
+                errors.push(format!("Asset file id {:?} is not a content hash", file_id));
+            } else if !contents.contains_key(&format!("{}{}", ASSET_DIR, file_id)) {
+                errors.push(format!("Asset file {}{} is missing", ASSET_DIR, file_id));
+            } else {
+                listed.insert(file_id.to_string());
+            }
+        }
+        errors.extend(check_asset_files(asset, &contents));
+    }
+    snapshot.blobs = contents
+        .into_iter()
+        .filter_map(|(path, raw)| {
+            let id = path.strip_prefix(ASSET_DIR)?;
+            if listed.contains(id) {
+                Some((id.to_string(), raw))
+            } else {
+                errors.push(format!("{} is not listed in {}", path, ASSETS_FILE));
+                None
+            }
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok((manifest, snapshot))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Check the archived files of an asset against its record: the original
+/// must hash to the recorded hash that its id is named after, and each
+/// variant must belong to it and have the recorded size.
+fn check_asset_files(asset: &Asset, contents: &HashMap<String, Vec<u8>>) -> Vec<String> {
+    let mut errors = Vec::new();
+    let file = |id: &str| contents.get(&format!("{}{}", ASSET_DIR, id));
+    let stem = asset.hash.get(..32).unwrap_or_default();
+    if stem.is_empty() || !asset.id.starts_with(stem) {
+        errors.push(format!("Asset {} is not named after its hash", asset.id));
+    }
+    if file(&asset.id)
+        .is_some_and(|data| hex(&Sha256::digest(data)) != asset.hash || data.len() != asset.size)
+    {
+        errors.push(format!(
+            "Asset file {}{} does not match its hash",
+            ASSET_DIR, asset.id
+        ));
+    }
+    for variant in &asset.variants {
+        if !variant.id.starts_with(&format!("{}-", stem)) {
+            errors.push(format!(
+                "Variant {} does not belong to asset {}",
+                variant.id, asset.id
+            ));
+        }
+        if file(&variant.id).is_some_and(|data| data.len() != variant.size) {
+            errors.push(format!(
+                "Asset file {}{} does not match its size",
+                ASSET_DIR, variant.id
+            ));
+        }
+    }
+    errors
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RestoreMode {
+    /// Add archive records and overwrite ones with the same id; keep the rest
+    #[default]
+    Merge,
+    /// Make every store match the archive exactly
+    Replace,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct RestoreParams {
+    #[serde(default)]
+    pub mode: RestoreMode,
+    /// Validate and report without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// What a restore changes in one store
+#[derive(Clone, Debug, Default, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct StoreChanges {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// Per-store changes; commit histories are compared per project
+#[derive(Clone, Debug, Default, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct RestoreChanges {
+    pub projects: StoreChanges,
+    pub templates: StoreChanges,
+    pub commit_histories: StoreChanges,
+    pub assets: StoreChanges,
+    pub analytics_events: StoreChanges,
+}
+
+#[derive(Clone, Debug, Serialize, utoipa::ToSchema)]
+pub struct RestoreReport {
+    pub mode: RestoreMode,
+    pub dry_run: bool,
+    /// Whether the stores were modified
+    pub applied: bool,
+    pub manifest: Option<Manifest>,
+    pub changes: RestoreChanges,
+    pub errors: Vec<String>,
+}
+
+fn diff_map<V: PartialEq>(
+    current: &HashMap<String, V>,
+    incoming: &HashMap<String, V>,
+    mode: RestoreMode,
+) -> StoreChanges {
+    let mut changes = StoreChanges::default();
+    for (key, value) in incoming {
+        match current.get(key) {
+            None => changes.added += 1,
+            Some(existing) if existing == value => changes.unchanged += 1,
+            Some(_) => changes.updated += 1,
+        }
+    }
+    if mode == RestoreMode::Replace {
+        changes.removed = current
+            .keys()
+            .filter(|k| !incoming.contains_key(*k))
+            .count();
+    }
+    changes
+}
+
+/// Analytics events have no id; identical events count as the same record
+fn event_key(event: &AnalyticsData) -> String {
+    serde_json::to_string(event).unwrap_or_default()
+}
+
+fn merge_events(current: &[AnalyticsData], incoming: &[AnalyticsData]) -> Vec<AnalyticsData> {
+    let mut seen: HashSet<String> = current.iter().map(event_key).collect();
+    let mut merged = current.to_vec();
+    for event in incoming {
+        if seen.insert(event_key(event)) {
+            merged.push(event.clone());
+        }
+    }
+    merged
+}
+
+fn diff_events(
+    current: &[AnalyticsData],
+    incoming: &[AnalyticsData],
+    mode: RestoreMode,
+) -> StoreChanges {
+    let current_keys: HashSet<String> = current.iter().map(event_key).collect();
+    let incoming_keys: HashSet<String> = incoming.iter().map(event_key).collect();
+    StoreChanges {
+        added: incoming_keys.difference(&current_keys).count(),
+        updated: 0,
+        removed: match mode {
+            RestoreMode::Merge => 0,
+            RestoreMode::Replace => current_keys.difference(&incoming_keys).count(),
+        },
+        unchanged: incoming_keys.intersection(&current_keys).count(),
+    }
+}
+
+/// Compute what restoring `incoming` over `current` would change.
+pub fn plan_restore(current: &Snapshot, incoming: &Snapshot, mode: RestoreMode) -> RestoreChanges {
+    RestoreChanges {
+        projects: diff_map(&current.projects, &incoming.projects, mode),
+        templates: diff_map(&current.templates, &incoming.templates, mode),
+        commit_histories: diff_map(&current.commits, &incoming.commits, mode),
+        assets: diff_map(&current.assets, &incoming.assets, mode),
+        analytics_events: diff_events(&current.analytics, &incoming.analytics, mode),
+    }
+}
+
+fn merged_map<V: Clone>(
+    current: &HashMap<String, V>,
+    incoming: &HashMap<String, V>,
+    mode: RestoreMode,
+) -> HashMap<String, V> {
+    match mode {
+        RestoreMode::Merge => {
+            let mut merged = current.clone();
+            merged.extend(incoming.iter().map(|(k, v)| (k.clone(), v.clone())));
+            merged
+        }
+        RestoreMode::Replace => incoming.clone(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/backup",
+    tag = "admin",
+    responses(
+        (status = 200, description = "ZIP archive of every store with a manifest", content_type = "application/zip"),
+        (status = 401, description = "Missing or wrong admin token"),
+        (status = 500, description = "Archive could not be built"),
+        (status = 503, description = "Admin routes are disabled because `ADMIN_TOKEN` is not set")
+    )
+)]
+pub async fn get_backup(State(stores): State<Stores>) -> Result<Response, StatusCode> {
+    let snapshot = take_current(&stores).await;
+    let created_at = crate::now_millis();
+
+    // The archive is written on a blocking thread and sent in chunks as it
+    // is produced; a failure part way through aborts the response body
+    let (tx, rx) = mpsc::channel(ARCHIVE_CHANNEL_CHUNKS);
+    let runtime = tokio::runtime::Handle::current();
+    let blobs = stores.assets.clone();
+    tokio::task::spawn_blocking(move || {
+        let out = BufWriter::with_capacity(ARCHIVE_CHUNK_BYTES, BodyWriter(tx.clone()));
+        let written = write_archive(out, &snapshot, created_at, |id| {
+            runtime.block_on(blobs.get_blob(id))
+        });
+        if let Err(e) = written {
+            tracing::error!("Failed to build backup: {}", e);
+            let _ = tx.blocking_send(Err(std::io::Error::other(e)));
+        }
+    });
+    let chunks = futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    });
+
+    let filename = format!("leptos-studio-backup-{}.zip", created_at as u64);
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/zip")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(Body::from_stream(chunks))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/restore",
+    tag = "admin",
+    params(RestoreParams),
+    request_body(content = Vec<u8>, content_type = "application/zip", description = "Archive from `/api/admin/backup`"),
+    responses(
+        (status = 200, description = "Restored, or the dry-run report", body = RestoreReport),
+        (status = 400, description = "Archive failed validation", body = RestoreReport),
+        (status = 401, description = "Missing or wrong admin token"),
+        (status = 413, description = "Upload exceeds `MAX_BACKUP_BYTES`", body = RestoreReport),
+        (status = 500, description = "Restore could not be persisted; stores were rolled back and any rollback that failed is listed", body = RestoreReport),
+        (status = 503, description = "Admin routes are disabled because `ADMIN_TOKEN` is not set")
+    )
+)]
+pub async fn post_restore(
+    State(stores): State<Stores>,
+    Query(params): Query<RestoreParams>,
+    body: Body,
+) -> impl IntoResponse {
+    let mut report = RestoreReport {
+        mode: params.mode,
+        dry_run: params.dry_run,
+        applied: false,
+        manifest: None,
+        changes: RestoreChanges::default(),
+        errors: Vec::new(),
+    };
+
+    let (upload, file) = match spool_upload(body, max_backup_bytes()).await {
+        Ok(spooled) => spooled,
+        Err((status, error)) => {
+            report.errors.push(error);
+            return (status, Json(report));
+        }
+    };
+    let limits = ArchiveLimits::from_env();
+    let parsed = tokio::task::spawn_blocking(move || {
+        let parsed = read_archive(BufReader::new(file), limits);
+        drop(upload);
+        parsed
+    })
+    .await;
+    let (manifest, incoming) = match parsed {
+        Ok(Ok(parsed)) => parsed,
+        Ok(Err(errors)) => {
+            report.errors = errors;
+            return (StatusCode::BAD_REQUEST, Json(report));
+        }
+        Err(_) => {
+            report
+                .errors
+                .push("Archive could not be processed".to_string());
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(report));
+        }
+    };
+    report.manifest = Some(manifest);
+
+    if params.dry_run {
+        let current = take_current(&stores).await;
+        report.changes = plan_restore(&current, &incoming, params.mode);
+        return (StatusCode::OK, Json(report));
+    }
+
+    let mode = params.mode;
+    let mut projects = stores.projects.records_mut().await;
+    let mut templates = stores.templates.records_mut().await;
+    let mut commits = stores.git.records_mut().await;
+    let mut analytics = stores.analytics.records_mut().await;
+    let mut asset_index = stores.assets.records_mut().await;
+
+    // Files no asset refers to yet; they are removed again if the restore fails
+    let new_blobs = unreferenced(incoming.blobs.keys().map(String::as_str), &asset_index);
+
+    // Files are content-addressed, so writing them before the index is harmless
+    for (id, data) in &incoming.blobs {
+        if let Err(e) = stores.assets.put_blob(id, data).await {
+            report
+                .errors
+                .push(format!("Failed to write asset {}: {}", id, e));
+            stores.assets.delete_blobs(new_blobs.into_iter()).await;
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(report));
+        }
+    }
+
+    let previous = Snapshot {
+        projects: projects.clone(),
+        templates: templates.clone(),
+        commits: commits.clone(),
+        analytics: analytics.clone(),
+        assets: asset_index.clone(),
+        blobs: HashMap::new(),
+    };
+    report.changes = plan_restore(&previous, &incoming, mode);
+
+    **projects = merged_map(&previous.projects, &incoming.projects, mode);
+    **templates = merged_map(&previous.templates, &incoming.templates, mode);
+    **commits = merged_map(&previous.commits, &incoming.commits, mode);
+    **analytics = match mode {
+        RestoreMode::Merge => merge_events(&previous.analytics, &incoming.analytics),
+        RestoreMode::Replace => incoming.analytics.clone(),
+    };
+    **asset_index = merged_map(&previous.assets, &incoming.assets, mode);
+
+    let failures = persist_in_turn(vec![
+        persist_step(&stores.projects, &projects, &previous.projects),
+        persist_step(&stores.templates, &templates, &previous.templates),
+        persist_step(&stores.git, &commits, &previous.commits),
+        persist_step(&stores.analytics, &analytics, &previous.analytics),
+        persist_step(&stores.assets, &asset_index, &previous.assets),
+    ])
+    .await;
+
+    if !failures.is_empty() {
+        tracing::error!("Restore failed, rolled back: {:?}", failures);
+        report.errors = failures;
+        **projects = previous.projects;
+        **templates = previous.templates;
+        **commits = previous.commits;
+        **analytics = previous.analytics;
+        **asset_index = previous.assets;
+        stores.assets.delete_blobs(new_blobs.into_iter()).await;
+        return (StatusCode::INTERNAL_SERVER_ERROR, Json(report));
+    }
+
+    // Drop files of assets the restore removed
+    let orphaned = unreferenced(
+        previous.assets.values().flat_map(Asset::file_ids),
+        &asset_index,
+    );
+    stores.assets.delete_blobs(orphaned.into_iter()).await;
+
+    report.applied = true;
+    (StatusCode::OK, Json(report))
+}
+
+/// A store's write during a restore
+type PersistFuture<'a> = Pin<Box<dyn Future<Output = std::io::Result<()>> + Send + 'a>>;
+
+/// Name, restore write and rollback write of one store
+type PersistStep<'a> = (&'static str, PersistFuture<'a>, PersistFuture<'a>);
+
+fn persist_step<'a, S: PersistentStore>(
+    store: &'a S,
+    restored: &'a S::Records,
+    previous: &'a S::Records,
+) -> PersistStep<'a> {
+    (
+        S::NAME,
+        Box::pin(store.persist(restored)),
+        Box::pin(store.persist(previous)),
+    )
+}
+
+/// Persist stores in turn, each given as its name, the write of the
+/// restored records and the write of its previous ones. At the first
+/// failure the stores written so far, and the one that failed, get their
+/// previous records back; later stores are not touched. Returns every
+/// failure, including rollbacks that failed and left a store's file
+/// holding the restored records.
+async fn persist_in_turn(steps: Vec<PersistStep<'_>>) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut written = Vec::new();
+    for (name, write, rollback) in steps {
+        let result = write.await;
+        written.push((name, rollback));
+        if let Err(e) = result {
+            errors.push(format!("Failed to persist {}: {}", name, e));
+            break;
+        }
+    }
+    if errors.is_empty() {
+        return errors;
+    }
+    for (name, rollback) in written.into_iter().rev() {
+        if let Err(e) = rollback.await {
+            errors.push(format!(
+                "Failed to roll back {}, its file keeps the restored data: {}",
+                name, e
+            ));
+        }
+    }
+    errors
+}
+
+/// Those of `ids` that no asset in `index` refers to
+fn unreferenced<'a>(
+    ids: impl Iterator<Item = &'a str>,
+    index: &HashMap<String, Asset>,
+) -> Vec<&'a str> {
+    let referenced: HashSet<&str> = index.values().flat_map(Asset::file_ids).collect();
+    ids.filter(|id| !referenced.contains(id)).collect()
+}
+
+/// Temporary file holding a restore upload; removed when dropped
+struct TempUpload(PathBuf);
+
+impl Drop for TempUpload {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            tracing::warn!("Failed to remove {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+/// Write a restore upload to a temporary file as it arrives, up to `limit`
+/// bytes. ZIP readers need to seek, so the body cannot be read in place.
+async fn spool_upload(
+    body: Body,
+    limit: u64,
+) -> Result<(TempUpload, std::fs::File), (StatusCode, String)> {
+    let internal = |e: std::io::Error| {
+        tracing::error!("Failed to buffer restore upload: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Archive could not be buffered".to_string(),
+        )
+    };
+    let path = std::env::temp_dir().join(format!(
+        "leptos-studio-restore-{}.zip",
+        uuid::Uuid::new_v4()
+    ));
+    let mut file = tokio::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .await
+        .map_err(internal)?;
+    let upload = TempUpload(path);
+
+    let mut received = 0u64;
+    let mut chunks = body.into_data_stream();
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk.map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Upload could not be read: {}", e),
+            )
+        })?;
+        received += chunk.len() as u64;
+        if received > limit {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("Archive is larger than {} bytes", limit),
+            ));
+        }
+        file.write_all(&chunk).await.map_err(internal)?;
+    }
+    file.flush().await.map_err(internal)?;
+
+    let mut file = file.into_std().await;
+    file.seek(SeekFrom::Start(0)).map_err(internal)?;
+    Ok((upload, file))
+}
+
+/// Current store contents without asset files. All read locks are held
+/// together so the snapshot is consistent across stores.
+async fn take_current(stores: &Stores) -> Snapshot {
+    let projects = stores.projects.records().await;
+    let templates = stores.templates.records().await;
+    let commits = stores.git.records().await;
+    let analytics = stores.analytics.records().await;
+    let assets = stores.assets.records().await;
+    Snapshot {
+        projects: projects.clone(),
+        templates: templates.clone(),
+        commits: commits.clone(),
+        analytics: analytics.clone(),
+        assets: assets.clone(),
+        blobs: HashMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const LIMITS: ArchiveLimits = ArchiveLimits {
+        file_bytes: 1024 * 1024,
+        total_bytes: 4 * 1024 * 1024,
+    };
+
+    const LOGO: &[u8] = b"png";
+
+    fn logo_hash() -> String {
+        hex(&Sha256::digest(LOGO))
+    }
+
+    fn logo_id() -> String {
+        format!("{}.png", &logo_hash()[..32])
+    }
+
+    fn sample() -> Snapshot {
+        let mut snapshot = Snapshot::default();
+        snapshot
+            .projects
+            .insert("p1".to_string(), serde_json::json!({ "name": "One" }));
+        snapshot.commits.insert(
+            "p1".to_string(),
+            vec![GitCommit {
+                id: "c1".to_string(),
+                message: "Initial".to_string(),
+                timestamp: 1.0,
+                snapshot: serde_json::json!({}),
+            }],
+        );
+        snapshot.assets.insert(
+            logo_id(),
+            Asset {
+                id: logo_id(),
+                filename: "logo.png".to_string(),
+                mime_type: "image/png".to_string(),
+                size: LOGO.len(),
+                hash: logo_hash(),
+                created_at: 1.0,
+                width: None,
+                height: None,
+                dominant_color: None,
+                variants: Vec::new(),
+            },
+        );
+        snapshot.blobs.insert(logo_id(), LOGO.to_vec());
+        snapshot
+    }
+
+    #[test]
+    fn test_admin_routes_fail_closed() {
+        assert_eq!(
+            check_admin(None, Some("anything")),
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert_eq!(
+            check_admin(Some(""), Some("")),
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert_eq!(
+            check_admin(Some("secret"), None),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            check_admin(Some("secret"), Some("guess")),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(check_admin(Some("secret"), Some("secret")), Ok(()));
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let archive = build_archive(&sample(), 42.0).unwrap();
+        let (manifest, restored) = read_archive(Cursor::new(&archive), LIMITS).unwrap();
+
+        assert_eq!(manifest.format_version, FORMAT_VERSION);
+        assert_eq!(manifest.counts.projects, 1);
+        assert_eq!(manifest.counts.commits, 1);
+        assert_eq!(manifest.counts.assets, 1);
+        assert_eq!(restored.projects["p1"]["name"], "One");
+        assert_eq!(restored.blobs[&logo_id()], LOGO);
+    }
+
+    #[tokio::test]
+    async fn test_backup_is_streamed_as_a_readable_archive() {
+        let stores = Stores {
+            projects: Default::default(),
+            templates: Default::default(),
+            git: Default::default(),
+            analytics: Default::default(),
+            assets: Default::default(),
+        };
+        **stores.projects.records_mut().await = sample().projects;
+        **stores.git.records_mut().await = sample().commits;
+
+        let response = get_backup(State(stores)).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let (manifest, restored) = read_archive(Cursor::new(&body), LIMITS).unwrap();
+        assert_eq!(manifest.counts.projects, 1);
+        assert_eq!(manifest.counts.commits, 1);
+        assert_eq!(restored.projects["p1"]["name"], "One");
+    }
+
+    /// Copy an archive without one of its entries
+    fn without_entry(archive: &[u8], path: &str) -> Vec<u8> {
+        let mut source = ZipArchive::new(Cursor::new(archive)).unwrap();
+        let mut out = ZipWriter::new(Cursor::new(Vec::new()));
+        for i in 0..source.len() {
+            let file = source.by_index_raw(i).unwrap();
+            if file.name().unwrap() != path {
+                out.raw_copy_file(file).unwrap();
+            }
+        }
+        out.finish().unwrap().into_inner()
+    }
+
+    /// Add files to an archive and list them in its manifest
+    fn with_files(archive: &[u8], extra: &[(String, &[u8])]) -> Vec<u8> {
+        let mut source = ZipArchive::new(Cursor::new(archive)).unwrap();
+        let mut manifest: Manifest =
+            serde_json::from_reader(source.by_name(MANIFEST_FILE).unwrap()).unwrap();
+        let mut out = ZipWriter::new(Cursor::new(Vec::new()));
+        for i in 0..source.len() {
+            let file = source.by_index_raw(i).unwrap();
+            if file.name().unwrap() != MANIFEST_FILE {
+                out.raw_copy_file(file).unwrap();
+            }
+        }
+        for (path, data) in extra {
+            out.start_file(path.as_str(), SimpleFileOptions::default())
+                .unwrap();
+            out.write_all(data).unwrap();
+            manifest.files.push(ManifestFile {
+                path: path.clone(),
+                size: data.len(),
+                sha256: hex(&Sha256::digest(data)),
+            });
+        }
+        out.start_file(MANIFEST_FILE, SimpleFileOptions::default())
+            .unwrap();
+        out.write_all(&json(&manifest).unwrap()).unwrap();
+        out.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_backup_leaves_out_assets_whose_files_were_deleted() {
+        let mut snapshot = sample();
+        snapshot.blobs.clear();
+        let archive = build_archive(&snapshot, 0.0).unwrap();
+
+        let (manifest, restored) = read_archive(Cursor::new(&archive), LIMITS).unwrap();
+        assert_eq!(manifest.skipped_files, vec![logo_id()]);
+        assert_eq!(manifest.counts.assets, 0);
+        assert!(restored.assets.is_empty());
+        assert_eq!(restored.projects.len(), 1);
+    }
+
+    #[test]
+    fn test_read_archive_reports_missing_asset_files() {
+        let path = format!("assets/{}", logo_id());
+        let archive = without_entry(&build_archive(&sample(), 0.0).unwrap(), &path);
+
+        let errors = read_archive(Cursor::new(&archive), LIMITS).unwrap_err();
+        assert!(errors.contains(&format!("Asset file {} is missing", path)));
+        assert!(read_archive(Cursor::new(b"not a zip"), LIMITS).is_err());
+    }
+
+    #[test]
+    fn test_read_archive_stops_at_size_limits() {
+        // 8 MiB of one character compresses to a few KiB
+        let mut snapshot = sample();
+        snapshot.projects.insert(
+            "bomb".to_string(),
+            serde_json::json!({ "padding": "a".repeat(8 * 1024 * 1024) }),
+        );
+        let archive = build_archive(&snapshot, 0.0).unwrap();
+        assert!(archive.len() < 64 * 1024);
+
+        let errors = read_archive(Cursor::new(&archive), LIMITS).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![format!(
+                "projects.json decompresses to more than {} bytes",
+                LIMITS.file_bytes
+            )]
+        );
+
+        let limits = ArchiveLimits {
+            file_bytes: 16 * 1024 * 1024,
+            ..LIMITS
+        };
+        let errors = read_archive(Cursor::new(&archive), limits).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![format!(
+                "Archive decompresses to more than {} bytes",
+                limits.total_bytes
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spool_upload_enforces_the_upload_limit() {
+        let (upload, mut file) = spool_upload(Body::from("archive"), 7).await.unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "archive");
+        let path = upload.0.clone();
+        drop(upload);
+        assert!(!path.exists());
+
+        let (status, _) = spool_upload(Body::from("archive"), 6).await.err().unwrap();
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn test_read_archive_rejects_unsafe_asset_files() {
+        // A file outside the asset dir, not listed in assets.json
+        let archive = with_files(
+            &build_archive(&sample(), 0.0).unwrap(),
+            &[
+                (
+                    "assets/../../etc/cron.d/x".to_string(),
+                    b"* * * * * root sh",
+                ),
+                ("assets//tmp/absolute".to_string(), b"x"),
+            ],
+        );
+        let errors = read_archive(Cursor::new(&archive), LIMITS).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(
+            errors
+                .iter()
+                .all(|e| e.contains("is not listed in assets.json"))
+        );
+
+        // A listed asset whose id is a path
+        let mut snapshot = sample();
+        let mut asset = snapshot.assets.remove(&logo_id()).unwrap();
+        asset.id = "../escape.png".to_string();
+        snapshot.assets.insert(asset.id.clone(), asset);
+        snapshot.blobs = HashMap::from([("../escape.png".to_string(), LOGO.to_vec())]);
+        let errors =
+            read_archive(Cursor::new(build_archive(&snapshot, 0.0).unwrap()), LIMITS).unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("is not a content hash")));
+
+        // Content that does not match the hash its id is named after
+        let mut snapshot = sample();
+        snapshot.blobs.insert(logo_id(), b"gif".to_vec());
+        let errors =
+            read_archive(Cursor::new(build_archive(&snapshot, 0.0).unwrap()), LIMITS).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![format!(
+                "Asset file assets/{} does not match its hash",
+                logo_id()
+            )]
+        );
+    }
+
+    /// Store whose writes of the listed records fail
+    #[derive(Default)]
+    struct FlakyStore {
+        records: tokio::sync::RwLock<Vec<u32>>,
+        disk: std::sync::Mutex<Option<Vec<u32>>>,
+        failing: Vec<Vec<u32>>,
+    }
+
+    impl PersistentStore for FlakyStore {
+        type Records = Vec<u32>;
+        const NAME: &'static str = "flaky";
+
+        fn lock(&self) -> &tokio::sync::RwLock<Self::Records> {
+            &self.records
+        }
+
+        async fn persist(&self, records: &Self::Records) -> std::io::Result<()> {
+            if self.failing.contains(records) {
+                return Err(std::io::Error::other("disk full"));
+            }
+            *self.disk.lock().unwrap() = Some(records.clone());
+            Ok(())
+        }
+    }
+
+    fn flaky(failing: &[&[u32]]) -> FlakyStore {
+        FlakyStore {
+            failing: failing.iter().map(|r| r.to_vec()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_persist_rolls_back_written_stores() {
+        let (restored, previous) = (vec![1], vec![0]);
+        let (a, b, c) = (flaky(&[]), flaky(&[&[1]]), flaky(&[]));
+        let errors = persist_in_turn(vec![
+            persist_step(&a, &restored, &previous),
+            persist_step(&b, &restored, &previous),
+            persist_step(&c, &restored, &previous),
+        ])
+        .await;
+        assert_eq!(errors, vec!["Failed to persist flaky: disk full"]);
+        assert_eq!(*a.disk.lock().unwrap(), Some(vec![0]));
+        assert_eq!(*b.disk.lock().unwrap(), Some(vec![0]));
+        assert_eq!(*c.disk.lock().unwrap(), None);
+
+        // A rollback that fails too is reported
+        let (a, b) = (flaky(&[&[0]]), flaky(&[&[1]]));
+        let errors = persist_in_turn(vec![
+            persist_step(&a, &restored, &previous),
+            persist_step(&b, &restored, &previous),
+        ])
+        .await;
+        assert_eq!(errors.len(), 2);
+        assert!(errors[1].starts_with("Failed to roll back flaky"));
+        assert_eq!(*a.disk.lock().unwrap(), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_unreferenced_files() {
+        let snapshot = sample();
+        let logo = logo_id();
+        let ids = [logo.as_str(), "0123456789abcdef0123456789abcdef.png"];
+        assert_eq!(
+            unreferenced(ids.into_iter(), &snapshot.assets),
+            vec!["0123456789abcdef0123456789abcdef.png"]
+        );
+    }
+
+    #[test]
+    fn test_plan_restore_modes() {
+        let current = sample();
+        let mut incoming = Snapshot::default();
+        incoming
+            .projects
+            .insert("p1".to_string(), serde_json::json!({ "name": "Renamed" }));
+        incoming
+            .projects
+            .insert("p2".to_string(), serde_json::json!({ "name": "Two" }));
+
+        let merge = plan_restore(&current, &incoming, RestoreMode::Merge);
+        assert_eq!(
+            merge.projects,
+            StoreChanges {
+                added: 1,
+                updated: 1,
+                removed: 0,
+                unchanged: 0
+            }
+        );
+        assert_eq!(merge.assets.removed, 0);
+
+        let replace = plan_restore(&current, &incoming, RestoreMode::Replace);
+        assert_eq!(replace.assets.removed, 1);
+        assert_eq!(replace.commit_histories.removed, 1);
+    }
+}
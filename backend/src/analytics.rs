@@ -3,18 +3,21 @@ use serde::{Deserialize, Serialize};
 use std::{path::Path as FilePath, sync::Arc};
 use tokio::sync::RwLock;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+use crate::{metrics, storage::PersistentStore};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct AnalyticsData {
     pub session_id: String,
     pub timestamp: f64,
     pub event_type: String,
+    #[schema(value_type = Object)]
     pub payload: serde_json::Value,
 }
 
 // We append to a list, simpler store than map for logs
 pub type AnalyticsStore = Arc<RwLock<Vec<AnalyticsData>>>;
 
-fn get_data_file() -> String {
+pub(crate) fn get_data_file() -> String {
     std::env::var("ANALYTICS_DATA_FILE").unwrap_or_else(|_| "analytics.json".to_string())
 }
 
@@ -36,22 +39,47 @@ pub fn load_analytics() -> Vec<AnalyticsData> {
 async fn save_store(store: &Vec<AnalyticsData>) -> std::io::Result<()> {
     let path = get_data_file();
     let data = serde_json::to_vec_pretty(store)?;
-    tokio::fs::write(&path, data).await
+    tokio::fs::write(&path, data)
+        .await
+        .inspect_err(|_| metrics::record_persistence_failure("analytics"))
+}
+
+impl PersistentStore for AnalyticsStore {
+    type Records = Vec<AnalyticsData>;
+    const NAME: &'static str = "analytics";
+
+    fn lock(&self) -> &RwLock<Self::Records> {
+        self
+    }
+
+    async fn persist(&self, records: &Self::Records) -> std::io::Result<()> {
+        save_store(records).await
+    }
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, utoipa::ToSchema)]
 pub struct AnalyticsBatch {
     pub events: Vec<AnalyticsData>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/analytics",
+    tag = "analytics",
+    request_body = AnalyticsBatch,
+    responses(
+        (status = 200, description = "Events appended"),
+        (status = 500, description = "Events could not be persisted")
+    )
+)]
 pub async fn post_analytics(
     State(store): State<AnalyticsStore>,
     Json(batch): Json<AnalyticsBatch>,
 ) -> StatusCode {
-    let mut guard = store.write().await;
+    let mut guard = store.records_mut().await;
     guard.extend(batch.events);
 
-    if let Err(e) = save_store(&guard).await {
+    if let Err(e) = store.persist(&guard).await {
         tracing::error!("Failed to save analytics: {}", e);
         return StatusCode::INTERNAL_SERVER_ERROR;
     }
@@ -0,0 +1,882 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::{Multipart, Path, State},
+    http::{StatusCode, header},
+    response::Response,
+};
+use quick_xml::{Reader, XmlVersion, escape::escape, events::Event};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, path::Path as FilePath, sync::Arc, sync::LazyLock};
+use tokio::sync::RwLock;
+
+use crate::{
+    imaging, metrics,
+    storage::{BlobStore, PersistentStore},
+};
+
+/// Default upload limit: 10 MiB
+const DEFAULT_MAX_ASSET_BYTES: usize = 10 * 1024 * 1024;
+
+/// Stored media asset.
+///
+/// The id is derived from the content hash plus the file extension, so
+/// uploading the same bytes twice yields the same asset.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Asset {
+    pub id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size: usize,
+    /// Hex-encoded SHA-256 of the stored bytes
+    pub hash: String,
+    pub created_at: f64, // JS timestamp
+    /// Intrinsic size in pixels, when it could be determined
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// Most common color as `#rrggbb`, for placeholders
+    #[serde(default)]
+    pub dominant_color: Option<String>,
+    /// Resized copies, narrowest first
+    #[serde(default)]
+    pub variants: Vec<AssetVariant>,
+}
+
+/// A resized copy of an asset, served from `/api/assets/{id}` like the original
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct AssetVariant {
+    pub id: String,
+    pub width: u32,
+    pub height: u32,
+    pub mime_type: String,
+    pub size: usize,
+}
+
+impl Asset {
+    /// Ids of every stored file: the original followed by its variants
+    pub fn file_ids(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.id.as_str()).chain(self.variants.iter().map(|v| v.id.as_str()))
+    }
+}
+
+pub type AssetStore = Arc<RwLock<HashMap<String, Asset>>>;
+
+pub(crate) fn get_data_file() -> String {
+    std::env::var("ASSETS_DATA_FILE").unwrap_or_else(|_| "assets.json".to_string())
+}
+
+pub(crate) fn get_assets_dir() -> String {
+    std::env::var("ASSETS_DIR").unwrap_or_else(|_| "assets".to_string())
+}
+
+pub(crate) fn max_asset_bytes() -> usize {
+    std::env::var("MAX_ASSET_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_ASSET_BYTES)
+}
+
+static FILE_ID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[0-9a-f]{32}(\.(png|jpg|gif|webp|svg)|-[0-9]+w\.(webp|jpg))$")
+        .expect("valid file id pattern")
+});
+
+/// Whether `id` has the form uploads give stored files: the content hash
+/// stem with an extension, or a resized variant of it. Ids become file
+/// names, so nothing else may reach the asset dir.
+pub(crate) fn is_file_id(id: &str) -> bool {
+    FILE_ID.is_match(id)
+}
+
+fn blob_path(id: &str) -> std::io::Result<std::path::PathBuf> {
+    if !is_file_id(id) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{:?} is not an asset file id", id),
+        ));
+    }
+    Ok(FilePath::new(&get_assets_dir()).join(id))
+}
+
+pub fn load_assets() -> HashMap<String, Asset> {
+    let path = get_data_file();
+    if FilePath::new(&path).exists() {
+        if let Ok(file) = std::fs::File::open(&path) {
+            let reader = std::io::BufReader::new(file);
+            if let Ok(map) = serde_json::from_reader(reader) {
+                tracing::info!("Loaded assets from {}", path);
+                return map;
+            }
+        }
+        tracing::error!("Failed to load assets from {}", path);
+    }
+    HashMap::new()
+}
+
+async fn save_store(store: &HashMap<String, Asset>) -> std::io::Result<()> {
+    let path = get_data_file();
+    let data = serde_json::to_vec_pretty(store)?;
+    tokio::fs::write(&path, data)
+        .await
+        .inspect_err(|_| metrics::record_persistence_failure("assets"))
+}
+
+impl PersistentStore for AssetStore {
+    type Records = HashMap<String, Asset>;
+    const NAME: &'static str = "assets";
+
+    fn lock(&self) -> &RwLock<Self::Records> {
+        self
+    }
+
+    async fn persist(&self, records: &Self::Records) -> std::io::Result<()> {
+        save_store(records).await
+    }
+}
+
+/// Asset files live in `ASSETS_DIR`, named by their id
+impl BlobStore for AssetStore {
+    async fn get_blob(&self, id: &str) -> std::io::Result<Vec<u8>> {
+        tokio::fs::read(blob_path(id)?).await
+    }
+
+    async fn put_blob(&self, id: &str, data: &[u8]) -> std::io::Result<()> {
+        let path = blob_path(id)?;
+        tokio::fs::create_dir_all(get_assets_dir()).await?;
+        tokio::fs::write(path, data)
+            .await
+            .inspect_err(|_| metrics::record_persistence_failure("assets"))
+    }
+
+    async fn delete_blob(&self, id: &str) -> std::io::Result<()> {
+        tokio::fs::remove_file(blob_path(id)?).await
+    }
+}
+
+/// Detect the MIME type from the file contents; the client-supplied type is
+/// never trusted. Returns `None` for unsupported formats.
+pub fn sniff_mime(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some("image/png");
+    }
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    if svg_root(bytes) {
+        return Some("image/svg+xml");
+    }
+    None
+}
+
+/// Whether the first element of an XML document is `<svg>`, skipping the
+/// prolog: declaration, comments, processing instructions and a DOCTYPE.
+/// HTML that embeds an SVG does not count.
+fn svg_root(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(4096)];
+    let head = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        // The cut may fall inside a multi-byte character
+        Err(e) => std::str::from_utf8(&head[..e.valid_up_to()]).unwrap_or_default(),
+    };
+    let mut rest = head.trim_start_matches('\u{feff}');
+    loop {
+        rest = rest.trim_start();
+        let skip_to = |end: &str| rest.find(end).map(|i| &rest[i + end.len()..]);
+        let next = if rest.starts_with("<?") {
+            skip_to("?>")
+        } else if rest.starts_with("<!--") {
+            skip_to("-->")
+        } else if rest.starts_with("<!DOCTYPE") {
+            // An internal subset in `[...]` may itself contain `>`
+            let subset_end = match (rest.find('['), rest.find('>')) {
+                (Some(open), Some(close)) if open < close => rest.find(']'),
+                _ => Some(0),
+            };
+            subset_end.and_then(|i| rest[i..].find('>').map(|j| &rest[i + j + 1..]))
+        } else {
+            let Some(tag) = rest.strip_prefix("<svg") else {
+                return false;
+            };
+            return tag.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/');
+        };
+        match next {
+            Some(next) => rest = next,
+            None => return false,
+        }
+    }
+}
+
+fn extension_for(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        _ => "bin",
+    }
+}
+
+/// SVG elements kept by `sanitize_svg`: shapes, text, paint servers,
+/// clipping, masking and filters. Anything else, including `script`,
+/// `foreignObject`, `animate`, `set` and `style`, is dropped with its
+/// contents. Stylesheets are not filtered but refused outright: CSS
+/// escapes and comments make URL and `@import` checks easy to slip past.
+const SVG_ELEMENTS: &[&str] = &[
+    "svg",
+    "g",
+    "defs",
+    "symbol",
+    "use",
+    "title",
+    "desc",
+    "path",
+    "rect",
+    "circle",
+    "ellipse",
+    "line",
+    "polyline",
+    "polygon",
+    "text",
+    "tspan",
+    "textPath",
+    "image",
+    "a",
+    "linearGradient",
+    "radialGradient",
+    "stop",
+    "pattern",
+    "clipPath",
+    "mask",
+    "marker",
+    "filter",
+    "feBlend",
+    "feColorMatrix",
+    "feComponentTransfer",
+    "feComposite",
+    "feConvolveMatrix",
+    "feDiffuseLighting",
+    "feDisplacementMap",
+    "feDistantLight",
+    "feDropShadow",
+    "feFlood",
+    "feFuncA",
+    "feFuncB",
+    "feFuncG",
+    "feFuncR",
+    "feGaussianBlur",
+    "feMerge",
+    "feMergeNode",
+    "feMorphology",
+    "feOffset",
+    "fePointLight",
+    "feSpecularLighting",
+    "feSpotLight",
+    "feTile",
+    "feTurbulence",
+];
+
+/// Attributes kept by `sanitize_svg`: geometry, presentation and filter
+/// parameters. Event handlers, `style` and foreign namespaces are not
+/// listed.
+const SVG_ATTRIBUTES: &[&str] = &[
+    "xmlns",
+    "xmlns:xlink",
+    "version",
+    "baseProfile",
+    "id",
+    "class",
+    "xml:space",
+    "x",
+    "y",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "r",
+    "rx",
+    "ry",
+    "fx",
+    "fy",
+    "fr",
+    "width",
+    "height",
+    "d",
+    "points",
+    "pathLength",
+    "viewBox",
+    "preserveAspectRatio",
+    "transform",
+    "transform-origin",
+    "href",
+    "xlink:href",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "stroke",
+    "stroke-width",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-opacity",
+    "opacity",
+    "color",
+    "display",
+    "visibility",
+    "overflow",
+    "clip-path",
+    "clip-rule",
+    "mask",
+    "filter",
+    "paint-order",
+    "vector-effect",
+    "mix-blend-mode",
+    "shape-rendering",
+    "text-rendering",
+    "image-rendering",
+    "font-family",
+    "font-size",
+    "font-weight",
+    "font-style",
+    "font-variant",
+    "text-anchor",
+    "dominant-baseline",
+    "alignment-baseline",
+    "baseline-shift",
+    "letter-spacing",
+    "word-spacing",
+    "text-decoration",
+    "writing-mode",
+    "dx",
+    "dy",
+    "rotate",
+    "textLength",
+    "lengthAdjust",
+    "startOffset",
+    "method",
+    "spacing",
+    "offset",
+    "stop-color",
+    "stop-opacity",
+    "gradientUnits",
+    "gradientTransform",
+    "spreadMethod",
+    "patternUnits",
+    "patternContentUnits",
+    "patternTransform",
+    "clipPathUnits",
+    "maskUnits",
+    "maskContentUnits",
+    "markerWidth",
+    "markerHeight",
+    "markerUnits",
+    "refX",
+    "refY",
+    "orient",
+    "marker-start",
+    "marker-mid",
+    "marker-end",
+    "filterUnits",
+    "primitiveUnits",
+    "color-interpolation-filters",
+    "in",
+    "in2",
+    "result",
+    "stdDeviation",
+    "mode",
+    "operator",
+    "k1",
+    "k2",
+    "k3",
+    "k4",
+    "values",
+    "type",
+    "tableValues",
+    "slope",
+    "intercept",
+    "amplitude",
+    "exponent",
+    "flood-color",
+    "flood-opacity",
+    "lighting-color",
+    "baseFrequency",
+    "numOctaves",
+    "seed",
+    "stitchTiles",
+    "scale",
+    "xChannelSelector",
+    "yChannelSelector",
+    "radius",
+    "order",
+    "kernelMatrix",
+    "divisor",
+    "bias",
+    "targetX",
+    "targetY",
+    "edgeMode",
+    "preserveAlpha",
+    "surfaceScale",
+    "diffuseConstant",
+    "specularConstant",
+    "specularExponent",
+    "azimuth",
+    "elevation",
+    "z",
+    "pointsAtX",
+    "pointsAtY",
+    "pointsAtZ",
+    "limitingConeAngle",
+];
+
+/// Inline images an `<image>` may embed; everything else it could load is
+/// refused, like every non-fragment link
+const SVG_IMAGE_DATA: &[&str] = &[
+    "data:image/png;base64,",
+    "data:image/jpeg;base64,",
+    "data:image/gif;base64,",
+    "data:image/webp;base64,",
+];
+
+/// Whether an attribute may be kept, judged on its unescaped value so
+/// that entity-encoded schemes are caught
+fn svg_attribute_allowed(element: &str, name: &str, value: &str) -> bool {
+    if !SVG_ATTRIBUTES.contains(&name) {
+        return false;
+    }
+    // Browsers ignore whitespace and control characters inside URLs
+    let compact: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if name == "href" || name == "xlink:href" {
+        return compact.starts_with('#')
+            || (element == "image" && SVG_IMAGE_DATA.iter().any(|p| compact.starts_with(p)));
+    }
+    // Presentation attributes are parsed as CSS, where `u\72l(` is `url(`
+    !compact.contains('\\')
+        && compact.match_indices("url(").all(|(i, _)| {
+            compact[i + 4..]
+                .trim_start_matches(['"', '\''])
+                .starts_with('#')
+        })
+}
+
+/// Rebuild an SVG document from allowlisted elements and attributes.
+///
+/// The document is parsed, so markup tricks that slip past patterns
+/// (missing whitespace, unquoted or entity-encoded values) do not apply.
+/// DTDs, comments, processing instructions and unknown entities are
+/// dropped. Returns `None` unless the document is well-formed XML with an
+/// `<svg>` root.
+pub fn sanitize_svg(svg: &str) -> Option<String> {
+    let mut reader = Reader::from_str(svg);
+    let mut out = String::with_capacity(svg.len());
+    // Open kept elements, and how deep inside a dropped one the reader is
+    let mut open: Vec<String> = Vec::new();
+    let mut skipping = 0usize;
+    let mut seen_root = false;
+
+    loop {
+        let event = reader.read_event().ok()?;
+        match event {
+            Event::Start(_) | Event::Empty(_) if skipping > 0 => {
+                if matches!(event, Event::Start(_)) {
+                    skipping += 1;
+                }
+            }
+            Event::End(_) if skipping > 0 => skipping -= 1,
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let name = e.name().as_ref().to_string();
+                if !seen_root && name != "svg" {
+                    return None;
+                }
+                seen_root = true;
+                let empty = matches!(event, Event::Empty(_));
+                if !SVG_ELEMENTS.contains(&name.as_str()) {
+                    if !empty {
+                        skipping = 1;
+                    }
+                    continue;
+                }
+                out.push('<');
+                out.push_str(&name);
+                for attribute in e.attributes() {
+                    let attribute = attribute.ok()?;
+                    let key = attribute.key.as_ref();
+                    let Ok(value) = attribute.normalized_value(XmlVersion::Implicit1_0) else {
+                        continue;
+                    };
+                    if svg_attribute_allowed(&name, key, &value) {
+                        out.push_str(&format!(" {}=\"{}\"", key, escape(value.as_ref())));
+                    }
+                }
+                if empty {
+                    out.push_str("/>");
+                } else {
+                    out.push('>');
+                    open.push(name);
+                }
+            }
+            Event::End(_) => {
+                let name = open.pop()?;
+                out.push_str(&format!("</{}>", name));
+            }
+            Event::Text(_) | Event::CData(_) | Event::GeneralRef(_) if skipping > 0 => {}
+            Event::Text(ref t) => out.push_str(&escape(t.xml10_content())),
+            Event::CData(ref t) => out.push_str(&escape(t.xml10_content())),
+            Event::GeneralRef(ref r) => match r.resolve_char_ref() {
+                Ok(Some(c)) => out.push_str(&escape(&*c.encode_utf8(&mut [0; 4]))),
+                Ok(None) => {
+                    let name = r.xml10_content();
+                    if matches!(name.as_ref(), "lt" | "gt" | "amp" | "quot" | "apos") {
+                        out.push_str(&format!("&{};", name));
+                    }
+                }
+                Err(_) => return None,
+            },
+            Event::Eof => break,
+            // Declarations, DTDs, comments and processing instructions
+            _ => {}
+        }
+    }
+    (seen_root && open.is_empty()).then_some(out)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/assets",
+    tag = "assets",
+    responses((status = 200, description = "Assets, newest first", body = [Asset]))
+)]
+pub async fn list_assets(State(store): State<AssetStore>) -> Json<Vec<Asset>> {
+    let store = store.records().await;
+    let mut assets: Vec<Asset> = store.values().cloned().collect();
+    assets.sort_by(|a, b| {
+        b.created_at
+            .partial_cmp(&a.created_at)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Json(assets)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/assets",
+    tag = "assets",
+    request_body(content_type = "multipart/form-data", description = "A single `file` field"),
+    responses(
+        (status = 200, description = "Asset stored, or the existing asset with identical content", body = Asset),
+        (status = 400, description = "No file in the request"),
+        (status = 413, description = "File exceeds the size limit"),
+        (status = 415, description = "Not a supported image format"),
+        (status = 500, description = "Asset could not be persisted")
+    )
+)]
+pub async fn upload_asset(
+    State(store): State<AssetStore>,
+    mut multipart: Multipart,
+) -> Result<Json<Asset>, StatusCode> {
+    let mut upload = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+    {
+        if field.name() == Some("file") {
+            let filename = field.file_name().unwrap_or("upload").to_string();
+            let bytes = field.bytes().await.map_err(|e| {
+                tracing::warn!("Failed to read upload: {}", e);
+                e.status()
+            })?;
+            upload = Some((filename, bytes));
+            break;
+        }
+    }
+    let (filename, bytes) = upload.ok_or(StatusCode::BAD_REQUEST)?;
+
+    if bytes.len() > max_asset_bytes() {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+    let mime = sniff_mime(&bytes).ok_or(StatusCode::UNSUPPORTED_MEDIA_TYPE)?;
+
+    let data = if mime == "image/svg+xml" {
+        let text = std::str::from_utf8(&bytes).map_err(|_| StatusCode::UNSUPPORTED_MEDIA_TYPE)?;
+        sanitize_svg(text)
+            .ok_or(StatusCode::UNSUPPORTED_MEDIA_TYPE)?
+            .into_bytes()
+    } else {
+        bytes.to_vec()
+    };
+
+    let hash = hex(&Sha256::digest(&data));
+    let stem = hash[..32].to_string();
+    let id = format!("{}.{}", stem, extension_for(mime));
+
+    if let Some(existing) = store.records().await.get(&id) {
+        return Ok(Json(existing.clone()));
+    }
+
+    // Decoding and resizing is CPU-bound; keep it off the async workers
+    let (data, info) = tokio::task::spawn_blocking(move || {
+        let info = imaging::process(&data, mime, &imaging::variant_widths());
+        (data, info)
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let info = info.map_err(|e| {
+        tracing::warn!("Rejected undecodable image {}: {}", filename, e);
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    })?;
+
+    let mut guard = store.records_mut().await;
+    // A concurrent upload of the same content may have finished first
+    if let Some(existing) = guard.get(&id) {
+        return Ok(Json(existing.clone()));
+    }
+
+    let mut files = vec![(id.clone(), data.as_slice())];
+    let variants: Vec<AssetVariant> = info
+        .variants
+        .iter()
+        .map(|v| AssetVariant {
+            id: format!("{}-{}w.{}", stem, v.width, v.extension()),
+            width: v.width,
+            height: v.height,
+            mime_type: v.mime_type.to_string(),
+            size: v.data.len(),
+        })
+        .collect();
+    files.extend(
+        variants
+            .iter()
+            .zip(&info.variants)
+            .map(|(v, encoded)| (v.id.clone(), encoded.data.as_slice())),
+    );
+
+    for (written, (file_id, bytes)) in files.iter().enumerate() {
+        if let Err(e) = store.put_blob(file_id, bytes).await {
+            tracing::error!("Failed to write asset {}: {}", file_id, e);
+            store
+                .delete_blobs(files[..written].iter().map(|(f, _)| f.as_str()))
+                .await;
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    let asset = Asset {
+        id: id.clone(),
+        filename,
+        mime_type: mime.to_string(),
+        size: data.len(),
+        hash,
+        created_at: crate::now_millis(),
+        width: info.width,
+        height: info.height,
+        dominant_color: info.dominant_color,
+        variants,
+    };
+    guard.insert(id.clone(), asset.clone());
+
+    if let Err(e) = store.persist(&guard).await {
+        tracing::error!("Failed to save assets: {}", e);
+        guard.remove(&id);
+        store
+            .delete_blobs(files.iter().map(|(f, _)| f.as_str()))
+            .await;
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    Ok(Json(asset))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/assets/{id}",
+    tag = "assets",
+    params(("id" = String, Path, description = "Asset or variant id")),
+    responses(
+        (status = 200, description = "Asset bytes with their MIME type"),
+        (status = 404, description = "Asset not found")
+    )
+)]
+pub async fn get_asset(
+    Path(id): Path<String>,
+    State(store): State<AssetStore>,
+) -> Result<Response, StatusCode> {
+    let mime_type = {
+        let store = store.records().await;
+        match store.get(&id) {
+            Some(asset) => asset.mime_type.clone(),
+            None => store
+                .values()
+                .flat_map(|a| &a.variants)
+                .find(|v| v.id == id)
+                .map(|v| v.mime_type.clone())
+                .ok_or(StatusCode::NOT_FOUND)?,
+        }
+    };
+
+    let data = store.get_blob(&id).await.map_err(|e| {
+        tracing::error!("Asset {} is missing its data: {}", id, e);
+        StatusCode::NOT_FOUND
+    })?;
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, mime_type)
+        // Content-addressed, so the bytes behind an id never change
+        .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
+        .header(header::CONTENT_SECURITY_POLICY, "script-src 'none'")
+        .body(Body::from(data))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/assets/{id}",
+    tag = "assets",
+    params(("id" = String, Path, description = "Asset id")),
+    responses(
+        (status = 204, description = "Asset deleted"),
+        (status = 404, description = "Asset not found"),
+        (status = 500, description = "Deletion could not be persisted")
+    )
+)]
+pub async fn delete_asset(Path(id): Path<String>, State(store): State<AssetStore>) -> StatusCode {
+    let mut guard = store.records_mut().await;
+
+    if let Some(removed) = guard.remove(&id) {
+        if let Err(e) = store.persist(&guard).await {
+            tracing::error!("Failed to save assets after delete: {}", e);
+            guard.insert(id, removed);
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+        store.delete_blobs(removed.file_ids()).await;
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_mime() {
+        assert_eq!(sniff_mime(b"\x89PNG\r\n\x1a\nrest"), Some("image/png"));
+        assert_eq!(sniff_mime(&[0xFF, 0xD8, 0xFF, 0xE0]), Some("image/jpeg"));
+        assert_eq!(sniff_mime(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(
+            sniff_mime(b"<?xml version=\"1.0\"?><svg></svg>"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(
+            sniff_mime(b"\xef\xbb\xbf<!-- logo --><!DOCTYPE svg [<!ENTITY a \">\">]>\n<svg/>"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(sniff_mime(b"<html><body>hi</body></html>"), None);
+        assert_eq!(
+            sniff_mime(b"<html><body><svg></svg><script>x()</script></body></html>"),
+            None
+        );
+        assert_eq!(sniff_mime(b"<svgfoo></svgfoo>"), None);
+        assert_eq!(sniff_mime(b"MZ\x90\x00"), None);
+    }
+
+    #[test]
+    fn test_file_ids_are_plain_hash_names() {
+        let stem = "0123456789abcdef0123456789abcdef";
+        assert!(is_file_id(&format!("{}.png", stem)));
+        assert!(is_file_id(&format!("{}-480w.webp", stem)));
+        assert!(is_file_id(&format!("{}-480w.jpg", stem)));
+        assert!(!is_file_id(&format!("../{}.png", stem)));
+        assert!(!is_file_id(&format!("/etc/{}.png", stem)));
+        assert!(!is_file_id(&format!("{}.png/..", stem)));
+        assert!(!is_file_id("abc.png"));
+        assert!(blob_path("../../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_sanitize_svg() {
+        let svg = r#"<!DOCTYPE svg [<!ENTITY x "y">]><svg onload="alert(1)"><script>alert(2)</script><a xlink:href="javascript:alert(3)"><rect width="10" onclick='x()'/></a><foreignObject><div>hi</div></foreignObject></svg>"#;
+        let clean = sanitize_svg(svg).unwrap();
+        assert!(!clean.contains("script"));
+        assert!(!clean.contains("onload"));
+        assert!(!clean.contains("onclick"));
+        assert!(!clean.contains("javascript:"));
+        assert!(!clean.contains("foreignObject"));
+        assert!(!clean.contains("ENTITY"));
+        assert!(clean.contains(r#"<rect width="10"/>"#));
+    }
+
+    #[test]
+    fn test_sanitize_svg_parses_instead_of_matching() {
+        let cases = [
+            r#"<svg/onload=alert(1)><rect/></svg>"#,
+            r#"<svg><a href=javascript:alert(1)><rect/></a></svg>"#,
+            r#"<svg><a href="java&#x09;script&#58;alert(1)"><rect/></a></svg>"#,
+            r##"<svg><a href="#x"><set attributeName="href" to="javascript:alert(1)"/><animate attributeName="href" values="javascript:alert(1)"/></a></svg>"##,
+            r#"<svg><rect fill="url(https://evil.test/x.svg#p)"/><style>@import url(//evil.test/x.css);</style></svg>"#,
+        ];
+        for svg in cases {
+            match sanitize_svg(svg) {
+                None => {}
+                Some(clean) => {
+                    let lower = clean.to_ascii_lowercase();
+                    for bad in ["onload", "javascript", "<set", "<animate", "evil"] {
+                        assert!(!lower.contains(bad), "{} kept {:?} in {}", svg, bad, clean);
+                    }
+                }
+            }
+        }
+
+        let kept = sanitize_svg(
+            r##"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><defs><linearGradient id="g"><stop offset="0" stop-color="#fff"/></linearGradient></defs><rect fill="url(#g)" width="10" height="10"/><text x="1">a &amp; b</text></svg>"##,
+        )
+        .unwrap();
+        assert_eq!(
+            kept,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10"><defs><linearGradient id="g"><stop offset="0" stop-color="#fff"/></linearGradient></defs><rect fill="url(#g)" width="10" height="10"/><text x="1">a &amp; b</text></svg>"##
+        );
+        assert_eq!(sanitize_svg("<html><svg/></html>"), None);
+        assert_eq!(sanitize_svg("<svg><g></svg>"), None);
+    }
+
+    #[test]
+    fn test_sanitize_svg_drops_stylesheets() {
+        let cases = [
+            r#"<svg><style>@imp&#111;rt "//evil.test/x.css";</style><rect/></svg>"#,
+            r#"<svg><style>@\69mport "//evil.test/x.css";</style><rect/></svg>"#,
+            r#"<svg><style>rect { fill: u\72l(//evil.test/x.svg#p) }</style><rect/></svg>"#,
+            r#"<svg><style><![CDATA[@import url(//evil.test/x.css);]]></style><rect/></svg>"#,
+            r#"<svg><rect style="fill: u\72l(//evil.test/x.svg#p)"/></svg>"#,
+            r#"<svg><rect style="background: url(&#47;/evil.test/x.png)"/></svg>"#,
+            r#"<svg><rect fill="u\72l(//evil.test/x.svg#p)"/></svg>"#,
+        ];
+        for svg in cases {
+            let clean = sanitize_svg(svg).unwrap();
+            assert!(!clean.contains("evil"), "{} kept {}", svg, clean);
+            assert!(!clean.contains("style"), "{} kept {}", svg, clean);
+            assert!(clean.contains("<rect"), "{} lost the shape: {}", svg, clean);
+        }
+    }
+}
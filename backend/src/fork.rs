@@ -0,0 +1,333 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::git::GitCommit;
+use crate::{ProjectMetadata, Stores, now_millis, storage::PersistentStore, trashed_at};
+
+/// Project field recording where a fork came from. It is owned by the
+/// server: saves that omit it keep the stored value.
+pub(crate) const ORIGIN_FIELD: &str = "forked_from";
+
+/// The project a fork was copied from
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ForkOrigin {
+    pub project_id: String,
+    /// Name of the source project at the time of the fork
+    pub name: String,
+    /// Latest commit of the source project, if it had any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_id: Option<String>,
+    /// JS timestamp
+    pub forked_at: f64,
+}
+
+impl ForkOrigin {
+    pub fn of(project: &serde_json::Value) -> Option<Self> {
+        project
+            .get(ORIGIN_FIELD)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+}
+
+/// What happens to the source project's commit history
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryMode {
+    /// Copy every commit
+    #[default]
+    Copy,
+    /// Replace the history with a single commit of the current state
+    Squash,
+    /// Start without history
+    None,
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct ForkRequest {
+    /// Name of the fork, defaults to "<source name> (Fork)"
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub history: HistoryMode,
+}
+
+/// Collect the id of every component in a project layout, recursing into
+/// container children.
+///
+/// Components are serialized externally tagged (`{"Button": {"id": ..}}`).
+fn collect_component_ids(layout: &serde_json::Value, ids: &mut Vec<String>) {
+    let Some(components) = layout.as_array() else {
+        return;
+    };
+    for component in components {
+        let Some(body) = component
+            .as_object()
+            .filter(|o| o.len() == 1)
+            .and_then(|o| o.values().next())
+        else {
+            continue;
+        };
+        if let Some(id) = body.get("id").and_then(|v| v.as_str()) {
+            ids.push(id.to_string());
+        }
+        if let Some(children) = body.get("children") {
+            collect_component_ids(children, ids);
+        }
+    }
+}
+
+/// Replace every string equal to a mapped id, wherever it appears, so
+/// references to components (selection, bindings) follow the new ids.
+fn remap_ids(value: &mut serde_json::Value, map: &HashMap<String, String>) {
+    match value {
+        serde_json::Value::String(s) => {
+            if let Some(new_id) = map.get(s.as_str()) {
+                *s = new_id.clone();
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| remap_ids(v, map)),
+        serde_json::Value::Object(fields) => fields.values_mut().for_each(|v| remap_ids(v, map)),
+        _ => {}
+    }
+}
+
+/// Give every component in the project and the given snapshots a fresh id.
+///
+/// A component keeps the same new id across the project and all snapshots,
+/// so the fork's history stays consistent with its layout.
+fn rewrite_component_ids(project: &mut serde_json::Value, snapshots: &mut [serde_json::Value]) {
+    let mut ids = Vec::new();
+    for doc in std::iter::once(&*project).chain(snapshots.iter()) {
+        if let Some(layout) = doc.get("layout") {
+            collect_component_ids(layout, &mut ids);
+        }
+    }
+    let map: HashMap<String, String> = ids
+        .into_iter()
+        .map(|id| (id, uuid::Uuid::new_v4().to_string()))
+        .collect();
+
+    remap_ids(project, &map);
+    for snapshot in snapshots {
+        remap_ids(snapshot, &map);
+    }
+}
+
+/// Build the fork's project document and commit history
+fn build_fork(
+    source_id: &str,
+    source: &serde_json::Value,
+    commits: &[GitCommit],
+    fork_id: &str,
+    request: &ForkRequest,
+    now: f64,
+) -> (serde_json::Value, Vec<GitCommit>) {
+    let source_name = source
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Untitled");
+    let name = request
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} (Fork)", source_name));
+    let origin = ForkOrigin {
+        project_id: source_id.to_string(),
+        name: source_name.to_string(),
+        commit_id: commits.last().map(|c| c.id.clone()),
+        forked_at: now,
+    };
+
+    let mut project = source.clone();
+    let mut snapshots: Vec<serde_json::Value> = match request.history {
+        HistoryMode::Copy => commits.iter().map(|c| c.snapshot.clone()).collect(),
+        HistoryMode::Squash | HistoryMode::None => Vec::new(),
+    };
+    rewrite_component_ids(&mut project, &mut snapshots);
+
+    if let Some(obj) = project.as_object_mut() {
+        obj.insert("id".to_string(), serde_json::Value::from(fork_id));
+        obj.insert("name".to_string(), serde_json::Value::from(name));
+        obj.insert("last_modified".to_string(), serde_json::Value::from(now));
+        obj.insert(
+            ORIGIN_FIELD.to_string(),
+            serde_json::to_value(&origin).unwrap_or_default(),
+        );
+    }
+
+    let history = match request.history {
+        HistoryMode::Copy => commits
+            .iter()
+            .zip(snapshots)
+            .map(|(commit, snapshot)| GitCommit {
+                id: uuid::Uuid::new_v4().to_string(),
+                message: commit.message.clone(),
+                timestamp: commit.timestamp,
+                snapshot,
+            })
+            .collect(),
+        HistoryMode::Squash if !commits.is_empty() => vec![GitCommit {
+            id: uuid::Uuid::new_v4().to_string(),
+            message: format!("Forked from {}", source_name),
+            timestamp: now,
+            snapshot: project_snapshot(&project),
+        }],
+        HistoryMode::Squash | HistoryMode::None => Vec::new(),
+    };
+
+    (project, history)
+}
+
+/// A commit snapshot holds the project document without server-side fields
+fn project_snapshot(project: &serde_json::Value) -> serde_json::Value {
+    let mut snapshot = project.clone();
+    if let Some(obj) = snapshot.as_object_mut() {
+        for field in ["id", "last_modified", crate::thumbnail::THUMBNAIL_FIELD]
+            .into_iter()
+            .chain(crate::SERVER_MANAGED_FIELDS)
+        {
+            obj.remove(field);
+        }
+    }
+    snapshot
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/projects/{id}/fork",
+    tag = "projects",
+    params(("id" = String, Path, description = "Id of the project to fork")),
+    request_body(content = Option<ForkRequest>, description = "Fork options; the body may be omitted"),
+    responses(
+        (status = 201, description = "Fork created", body = ProjectMetadata),
+        (status = 404, description = "Project not found or in the trash"),
+        (status = 500, description = "Fork could not be persisted")
+    )
+)]
+pub async fn fork_project(
+    Path(id): Path<String>,
+    State(stores): State<Stores>,
+    request: Option<Json<ForkRequest>>,
+) -> Result<(StatusCode, Json<ProjectMetadata>), StatusCode> {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+
+    let mut projects = stores.projects.records_mut().await;
+    let mut git = stores.git.records_mut().await;
+
+    let source = projects
+        .get(&id)
+        .filter(|p| trashed_at(p).is_none())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let commits = git.get(&id).map(Vec::as_slice).unwrap_or_default();
+
+    let fork_id = uuid::Uuid::new_v4().to_string();
+    let (project, history) = build_fork(&id, source, commits, &fork_id, &request, now_millis());
+    let metadata = ProjectMetadata::from_project(&project);
+    let has_history = !history.is_empty();
+
+    projects.insert(fork_id.clone(), project);
+    if has_history {
+        git.insert(fork_id.clone(), history);
+    }
+
+    let result = match stores.projects.persist(&projects).await {
+        Ok(()) if has_history => stores.git.persist(&git).await,
+        other => other,
+    };
+    if let Err(e) = result {
+        tracing::error!("Failed to save fork of {}: {}", id, e);
+        projects.remove(&fork_id);
+        git.remove(&fork_id);
+        // Best effort: bring the project file back in line with memory
+        if let Err(e) = stores.projects.persist(&projects).await {
+            tracing::error!("Failed to roll back fork of {}: {}", id, e);
+        }
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    Ok((StatusCode::CREATED, Json(metadata)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn source() -> serde_json::Value {
+        json!({
+            "id": "src",
+            "name": "Landing",
+            "last_modified": 1.0,
+            "layout": [
+                { "Container": { "id": "c1", "children": [ { "Button": { "id": "b1", "label": "Go" } } ] } },
+                { "Text": { "id": "t1", "content": "Hello" } }
+            ]
+        })
+    }
+
+    fn commit(id: &str, layout: serde_json::Value) -> GitCommit {
+        GitCommit {
+            id: id.to_string(),
+            message: format!("commit {}", id),
+            timestamp: 5.0,
+            snapshot: json!({ "name": "Landing", "layout": layout }),
+        }
+    }
+
+    #[test]
+    fn test_fork_rewrites_ids_consistently_across_history() {
+        let commits = vec![
+            commit("k1", json!([{ "Text": { "id": "gone" } }])),
+            commit("k2", source()["layout"].clone()),
+        ];
+        let request = ForkRequest::default();
+        let (project, history) = build_fork("src", &source(), &commits, "fork", &request, 9.0);
+
+        assert_eq!(project["id"], "fork");
+        assert_eq!(project["name"], "Landing (Fork)");
+        let origin = ForkOrigin::of(&project).unwrap();
+        assert_eq!(origin.project_id, "src");
+        assert_eq!(origin.commit_id.as_deref(), Some("k2"));
+
+        let mut ids = Vec::new();
+        collect_component_ids(&project["layout"], &mut ids);
+        assert_eq!(ids.len(), 3);
+        assert!(
+            ids.iter()
+                .all(|id| !["c1", "b1", "t1"].contains(&id.as_str()))
+        );
+
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|c| c.id != "k1" && c.id != "k2"));
+        assert_eq!(history[1].snapshot["layout"], project["layout"]);
+        assert_ne!(history[0].snapshot["layout"][0]["Text"]["id"], "gone");
+    }
+
+    #[test]
+    fn test_fork_squash_and_none() {
+        let commits = vec![commit("k1", json!([])), commit("k2", json!([]))];
+        let squash = ForkRequest {
+            name: Some("Copy".to_string()),
+            history: HistoryMode::Squash,
+        };
+        let (project, history) = build_fork("src", &source(), &commits, "fork", &squash, 9.0);
+        assert_eq!(project["name"], "Copy");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].snapshot["layout"], project["layout"]);
+        assert!(history[0].snapshot.get(ORIGIN_FIELD).is_none());
+
+        let none = ForkRequest {
+            name: None,
+            history: HistoryMode::None,
+        };
+        let (_, history) = build_fork("src", &source(), &commits, "fork", &none, 9.0);
+        assert!(history.is_empty());
+    }
+}
@@ -7,18 +7,21 @@ use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, path::Path as FilePath, sync::Arc};
 use tokio::sync::RwLock;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+use crate::{metrics, storage::PersistentStore};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct GitCommit {
     pub id: String,
     pub message: String,
     pub timestamp: f64, // JS timestamp
+    #[schema(value_type = Object)]
     pub snapshot: serde_json::Value,
 }
 
 // Map: ProjectID -> List of Commits
 pub type GitStore = Arc<RwLock<HashMap<String, Vec<GitCommit>>>>;
 
-fn get_data_file() -> String {
+pub(crate) fn get_data_file() -> String {
     std::env::var("GIT_DATA_FILE").unwrap_or_else(|_| "git_data.json".to_string())
 }
 
@@ -40,14 +43,36 @@ pub fn load_git_data() -> HashMap<String, Vec<GitCommit>> {
 async fn save_store(store: &HashMap<String, Vec<GitCommit>>) -> std::io::Result<()> {
     let path = get_data_file();
     let data = serde_json::to_vec_pretty(store)?;
-    tokio::fs::write(&path, data).await
+    tokio::fs::write(&path, data)
+        .await
+        .inspect_err(|_| metrics::record_persistence_failure("git"))
+}
+
+impl PersistentStore for GitStore {
+    type Records = HashMap<String, Vec<GitCommit>>;
+    const NAME: &'static str = "git";
+
+    fn lock(&self) -> &RwLock<Self::Records> {
+        self
+    }
+
+    async fn persist(&self, records: &Self::Records) -> std::io::Result<()> {
+        save_store(records).await
+    }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/projects/{id}/commits",
+    tag = "commits",
+    params(("id" = String, Path, description = "Project id")),
+    responses((status = 200, description = "Commits in chronological order", body = [GitCommit]))
+)]
 pub async fn get_log(
     Path(project_id): Path<String>,
     State(store): State<GitStore>,
 ) -> Json<Vec<GitCommit>> {
-    let store = store.read().await;
+    let store = store.records().await;
     let commits = store.get(&project_id).cloned().unwrap_or_default();
     // Assuming stored in append order (oldest first), we might want to return newest first?
     // Frontend usually handles sorting or expects specific order.
@@ -57,13 +82,25 @@ pub async fn get_log(
     Json(commits)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, utoipa::ToSchema)]
 pub struct CommitPayload {
     pub message: String,
     pub timestamp: f64,
+    #[schema(value_type = Object)]
     pub snapshot: serde_json::Value,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/projects/{id}/commits",
+    tag = "commits",
+    params(("id" = String, Path, description = "Project id")),
+    request_body = CommitPayload,
+    responses(
+        (status = 200, description = "Commit recorded", body = GitCommit),
+        (status = 500, description = "Commit could not be persisted")
+    )
+)]
 pub async fn post_commit(
     Path(project_id): Path<String>,
     State(store): State<GitStore>,
@@ -76,11 +113,11 @@ pub async fn post_commit(
         snapshot: payload.snapshot,
     };
 
-    let mut guard = store.write().await;
+    let mut guard = store.records_mut().await;
     let project_commits = guard.entry(project_id.clone()).or_insert_with(Vec::new);
     project_commits.push(commit.clone());
 
-    if let Err(e) = save_store(&guard).await {
+    if let Err(e) = store.persist(&guard).await {
         tracing::error!("Failed to save git data: {}", e);
         // Rollback: pop the commit we just added
         if let Some(commits) = guard.get_mut(&project_id) {
@@ -92,13 +129,24 @@ pub async fn post_commit(
     Ok(Json(commit))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/projects/{id}/commits",
+    tag = "commits",
+    params(("id" = String, Path, description = "Project id")),
+    responses(
+        (status = 204, description = "History deleted"),
+        (status = 404, description = "Project has no history"),
+        (status = 500, description = "Deletion could not be persisted")
+    )
+)]
 pub async fn delete_history(
     Path(project_id): Path<String>,
     State(store): State<GitStore>,
 ) -> StatusCode {
-    let mut guard = store.write().await;
+    let mut guard = store.records_mut().await;
     if let Some(removed) = guard.remove(&project_id) {
-        if let Err(e) = save_store(&guard).await {
+        if let Err(e) = store.persist(&guard).await {
             tracing::error!("Failed to save git data after delete: {}", e);
             // Rollback: put it back
             guard.insert(project_id, removed);
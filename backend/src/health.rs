@@ -0,0 +1,69 @@
+use axum::{Json, http::StatusCode};
+use serde::Serialize;
+use std::path::Path as FilePath;
+
+use crate::{analytics, assets, git, templates};
+
+#[derive(Debug, Serialize)]
+pub struct FileCheck {
+    pub file: String,
+    pub writable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<FileCheck>,
+}
+
+/// Liveness probe: the process is up and serving requests.
+pub async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Readiness probe: every data file can be written.
+pub async fn readyz() -> (StatusCode, Json<Readiness>) {
+    let mut checks = Vec::new();
+    for file in [
+        crate::get_data_file(),
+        templates::get_data_file(),
+        git::get_data_file(),
+        analytics::get_data_file(),
+        assets::get_data_file(),
+    ] {
+        let result = check_writable(&file).await;
+        checks.push(FileCheck {
+            file,
+            writable: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+
+    let ready = checks.iter().all(|c| c.writable);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(Readiness { ready, checks }))
+}
+
+/// Check that `path` can be written without modifying its contents.
+///
+/// Existing files are opened for appending; missing files are checked by
+/// creating and removing a probe file next to where they will live.
+async fn check_writable(path: &str) -> std::io::Result<()> {
+    if FilePath::new(path).exists() {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .await?;
+        return Ok(());
+    }
+
+    let probe = format!("{}.readyz", path);
+    tokio::fs::write(&probe, b"").await?;
+    tokio::fs::remove_file(&probe).await
+}
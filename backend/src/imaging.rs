@@ -0,0 +1,293 @@
+use image::{
+    DynamicImage, GenericImageView, ImageReader, Limits, codecs::jpeg::JpegEncoder,
+    imageops::FilterType,
+};
+use regex::Regex;
+use std::{io::Cursor, sync::LazyLock};
+
+/// Default widths (px) generated for raster uploads
+const DEFAULT_VARIANT_WIDTHS: [u32; 4] = [320, 640, 1024, 1920];
+
+/// JPEG quality of variants of opaque images
+const JPEG_QUALITY: u8 = 82;
+
+/// Decoding limits guarding against decompression bombs
+const MAX_DIMENSION: u32 = 16_384;
+const MAX_DECODE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// A resized copy of an image, encoded as WebP or JPEG
+pub struct EncodedVariant {
+    pub width: u32,
+    pub height: u32,
+    pub mime_type: &'static str,
+    pub data: Vec<u8>,
+}
+
+impl EncodedVariant {
+    pub fn extension(&self) -> &'static str {
+        match self.mime_type {
+            "image/jpeg" => "jpg",
+            _ => "webp",
+        }
+    }
+}
+
+/// What image processing learned about an upload
+#[derive(Default)]
+pub struct ImageInfo {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// `#rrggbb`
+    pub dominant_color: Option<String>,
+    pub variants: Vec<EncodedVariant>,
+}
+
+/// Widths to generate variants at, from `ASSET_VARIANT_WIDTHS`
+/// (comma-separated), sorted ascending.
+pub fn variant_widths() -> Vec<u32> {
+    let mut widths: Vec<u32> = std::env::var("ASSET_VARIANT_WIDTHS")
+        .ok()
+        .map(|v| {
+            v.split(',')
+                .filter_map(|w| w.trim().parse().ok())
+                .filter(|w| *w > 0)
+                .collect()
+        })
+        .unwrap_or_else(|| DEFAULT_VARIANT_WIDTHS.to_vec());
+    widths.sort_unstable();
+    widths.dedup();
+    widths
+}
+
+/// Inspect an upload and generate its resized variants.
+///
+/// Raster images get intrinsic dimensions, a dominant color and one variant
+/// per configured width narrower than the original, as long as it comes
+/// out smaller than the original (see `encode_variant`). GIFs are not
+/// resized so animations survive; SVGs only report the size declared on the
+/// root element.
+pub fn process(data: &[u8], mime: &str, widths: &[u32]) -> Result<ImageInfo, image::ImageError> {
+    if mime == "image/svg+xml" {
+        let (width, height) = svg_dimensions(&String::from_utf8_lossy(data));
+        return Ok(ImageInfo {
+            width,
+            height,
+            ..Default::default()
+        });
+    }
+
+    let image = decode(data)?;
+    let (width, height) = image.dimensions();
+
+    let mut variants = Vec::new();
+    if mime != "image/gif" {
+        for &target in widths.iter().filter(|w| **w < width) {
+            let target_height = ((height as u64 * target as u64) / width as u64).max(1) as u32;
+            let resized = image.resize_exact(target, target_height, FilterType::CatmullRom);
+            if let Some((mime_type, data)) = encode_variant(&resized, data.len())? {
+                variants.push(EncodedVariant {
+                    width: target,
+                    height: target_height,
+                    mime_type,
+                    data,
+                });
+            }
+        }
+    }
+
+    Ok(ImageInfo {
+        width: Some(width),
+        height: Some(height),
+        dominant_color: Some(dominant_color(&image)),
+        variants,
+    })
+}
+
+fn decode(data: &[u8]) -> Result<DynamicImage, image::ImageError> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+
+    let mut reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+    reader.limits(limits);
+    reader.decode()
+}
+
+/// Encode a resized copy as the smaller of WebP and, for opaque images,
+/// JPEG. The `image` crate only writes lossless WebP, which suits flat
+/// graphics but can outgrow the upload for photos. A variant that is not
+/// smaller than the original (`original_size` bytes) is not worth serving,
+/// so `None` is returned for it.
+fn encode_variant(
+    image: &DynamicImage,
+    original_size: usize,
+) -> Result<Option<(&'static str, Vec<u8>)>, image::ImageError> {
+    let mut best = ("image/webp", encode_webp(image)?);
+    if image.pixels().all(|(_, _, p)| p.0[3] == u8::MAX) {
+        let jpeg = encode_jpeg(image)?;
+        if jpeg.len() < best.1.len() {
+            best = ("image/jpeg", jpeg);
+        }
+    }
+    Ok((best.1.len() < original_size).then_some(best))
+}
+
+fn encode_webp(image: &DynamicImage) -> Result<Vec<u8>, image::ImageError> {
+    let mut out = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(image.to_rgba8()).write_to(&mut out, image::ImageFormat::WebP)?;
+    Ok(out.into_inner())
+}
+
+fn encode_jpeg(image: &DynamicImage) -> Result<Vec<u8>, image::ImageError> {
+    let mut out = Vec::new();
+    JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY)
+        .encode_image(&DynamicImage::ImageRgb8(image.to_rgb8()))?;
+    Ok(out)
+}
+
+/// Most common color, bucketed to 4 bits per channel on a thumbnail and
+/// averaged within the winning bucket. Mostly transparent pixels are ignored.
+pub fn dominant_color(image: &DynamicImage) -> String {
+    let thumb = image.thumbnail(64, 64).to_rgba8();
+    let mut buckets = vec![(0u32, [0u64; 3]); 4096];
+
+    for pixel in thumb.pixels() {
+        let [r, g, b, a] = pixel.0;
+        if a < 128 {
+            continue;
+        }
+        let key = ((r as usize >> 4) << 8) | ((g as usize >> 4) << 4) | (b as usize >> 4);
+        let bucket = &mut buckets[key];
+        bucket.0 += 1;
+        bucket.1[0] += r as u64;
+        bucket.1[1] += g as u64;
+        bucket.1[2] += b as u64;
+    }
+
+    match buckets.iter().filter(|b| b.0 > 0).max_by_key(|b| b.0) {
+        Some((count, sums)) => {
+            let n = *count as u64;
+            format!("#{:02x}{:02x}{:02x}", sums[0] / n, sums[1] / n, sums[2] / n)
+        }
+        // Fully transparent image
+        None => "#ffffff".to_string(),
+    }
+}
+
+static SVG_ROOT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<svg\b([^>]*)>").expect("valid svg root pattern"));
+static SVG_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(width|height|viewBox)\s*=\s*["']([^"']*)["']"#)
+        .expect("valid svg attribute pattern")
+});
+
+/// Size declared by `width`/`height` (plain or `px` values), falling back to
+/// the `viewBox`.
+pub fn svg_dimensions(svg: &str) -> (Option<u32>, Option<u32>) {
+    let Some(root) = SVG_ROOT.captures(svg) else {
+        return (None, None);
+    };
+
+    let (mut width, mut height, mut view_box) = (None, None, None);
+    for attr in SVG_ATTR.captures_iter(&root[1]) {
+        let value = attr[2].trim();
+        match attr[1].to_ascii_lowercase().as_str() {
+            "width" => width = parse_length(value),
+            "height" => height = parse_length(value),
+            _ => view_box = Some(value.to_string()),
+        }
+    }
+
+    if let (None, None, Some(view_box)) = (width, height, view_box) {
+        let parts: Vec<f64> = view_box
+            .split([' ', ','])
+            .filter(|p| !p.is_empty())
+            .filter_map(|p| p.parse().ok())
+            .collect();
+        if let [_, _, w, h] = parts[..] {
+            return (Some(w.round() as u32), Some(h.round() as u32));
+        }
+    }
+    (width, height)
+}
+
+fn parse_length(value: &str) -> Option<u32> {
+    value
+        .strip_suffix("px")
+        .unwrap_or(value)
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| *v > 0.0)
+        .map(|v| v.round() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut img = RgbaImage::from_pixel(width, height, Rgba([200, 30, 30, 255]));
+        for x in 0..width / 4 {
+            img.put_pixel(x, 0, Rgba([0, 0, 255, 255]));
+        }
+        let mut out = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(img)
+            .write_to(&mut out, image::ImageFormat::Png)
+            .unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn test_process_raster_generates_narrower_variants() {
+        let info = process(&png(800, 400), "image/png", &[320, 640, 1024]).unwrap();
+        assert_eq!((info.width, info.height), (Some(800), Some(400)));
+        assert_eq!(info.dominant_color.as_deref(), Some("#c81e1e"));
+
+        let sizes: Vec<(u32, u32)> = info.variants.iter().map(|v| (v.width, v.height)).collect();
+        assert_eq!(sizes, vec![(320, 160), (640, 320)]);
+        assert!(info.variants[0].data.starts_with(b"RIFF"));
+        assert_eq!(info.variants[0].extension(), "webp");
+    }
+
+    #[test]
+    fn test_photo_variants_fall_back_to_smaller_jpeg() {
+        // Noise stands in for a photo: lossless WebP barely compresses it
+        let mut seed = 7u32;
+        let photo = RgbaImage::from_fn(800, 400, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let [r, g, b, _] = seed.to_be_bytes();
+            Rgba([r, g, b, 255])
+        });
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, 95)
+            .encode_image(&DynamicImage::ImageRgba8(photo.clone()).to_rgb8())
+            .unwrap();
+
+        let info = process(&jpeg, "image/jpeg", &[320, 640]).unwrap();
+        assert_eq!(info.variants.len(), 2);
+        for variant in &info.variants {
+            assert_eq!(variant.mime_type, "image/jpeg");
+            assert!(variant.data.len() < jpeg.len());
+        }
+
+        // Nothing is kept when it would not be smaller than the upload
+        let resized = DynamicImage::ImageRgba8(photo).thumbnail(320, 160);
+        assert!(encode_variant(&resized, 100).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_svg_dimensions() {
+        assert_eq!(
+            svg_dimensions(r#"<svg width="120px" height="40">"#),
+            (Some(120), Some(40))
+        );
+        assert_eq!(
+            svg_dimensions(r#"<svg viewBox="0 0 24 12.4">"#),
+            (Some(24), Some(12))
+        );
+        assert_eq!(svg_dimensions(r#"<svg width="100%">"#), (None, None));
+    }
+}
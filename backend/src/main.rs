@@ -1,8 +1,8 @@
 use axum::{
     Json, Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{Method, StatusCode},
-    routing::{delete, get},
+    routing::{delete, get, patch, post},
 };
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, net::SocketAddr, path::Path as FilePath, sync::Arc};
@@ -10,20 +10,120 @@ use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::services::{ServeDir, ServeFile};
 
+use storage::PersistentStore;
+
+mod admin;
 mod analytics;
+mod assets;
+mod fork;
 mod git;
+mod health;
+mod imaging;
+mod metrics;
+mod openapi;
+mod organize;
+mod search;
+mod storage;
 mod templates;
+mod thumbnail;
+mod trash;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Summary of a stored project, as returned by the project list.
+#[derive(Clone, Debug, Serialize, Deserialize, utoipa::ToSchema)]
 struct ProjectMetadata {
     id: String,
     name: String,
     last_modified: f64,
     component_count: usize,
+    /// When the project was moved to the trash (JS timestamp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted_at: Option<f64>,
+    /// Set when the project was forked from another one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    forked_from: Option<fork::ForkOrigin>,
+    tags: Vec<String>,
+    favorite: bool,
+    /// Slash-separated folder path, absent at the root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    folder: Option<String>,
+    /// SVG wireframe of the top of the canvas, as a `data:` URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<String>,
+}
+
+impl ProjectMetadata {
+    fn from_project(p: &serde_json::Value) -> Self {
+        ProjectMetadata {
+            id: p
+                .get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            name: p
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Untitled")
+                .to_string(),
+            last_modified: p
+                .get("last_modified")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0),
+            component_count: p
+                .get("layout")
+                .and_then(|l| l.as_array())
+                .map(|a| a.len())
+                .unwrap_or(0),
+            deleted_at: trashed_at(p),
+            forked_from: fork::ForkOrigin::of(p),
+            tags: organize::project_tags(p),
+            favorite: organize::is_favorite(p),
+            folder: organize::project_folder(p),
+            thumbnail: p
+                .get(thumbnail::THUMBNAIL_FIELD)
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Project fields set through dedicated endpoints rather than by saving
+const SERVER_MANAGED_FIELDS: [&str; 4] = [
+    fork::ORIGIN_FIELD,
+    organize::TAGS_FIELD,
+    organize::FAVORITE_FIELD,
+    organize::FOLDER_FIELD,
+];
+
+/// Save field carrying the `last_modified` of the copy an edit started
+/// from. It is checked, not stored.
+const BASE_MODIFIED_FIELD: &str = "base_modified";
+
+/// Deletion timestamp of a project in the trash
+fn trashed_at(project: &serde_json::Value) -> Option<f64> {
+    project.get("deleted_at").and_then(|v| v.as_f64())
+}
+
+/// Current time as a JS timestamp
+pub(crate) fn now_millis() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
 }
 
 type Store = Arc<RwLock<HashMap<String, serde_json::Value>>>;
 
+/// Handles to every store, for features that span all of them
+/// (metrics, backup and restore).
+#[derive(Clone)]
+pub struct Stores {
+    pub projects: Store,
+    pub templates: templates::TemplateStore,
+    pub git: git::GitStore,
+    pub analytics: analytics::AnalyticsStore,
+    pub assets: assets::AssetStore,
+}
+
 fn get_data_file() -> String {
     std::env::var("DATA_FILE").unwrap_or_else(|_| "projects.json".to_string())
 }
@@ -34,8 +134,13 @@ fn load_store() -> HashMap<String, serde_json::Value> {
     if FilePath::new(&path).exists() {
         if let Ok(file) = std::fs::File::open(&path) {
             let reader = std::io::BufReader::new(file);
-            if let Ok(map) = serde_json::from_reader(reader) {
+            if let Ok(mut map) =
+                serde_json::from_reader::<_, HashMap<String, serde_json::Value>>(reader)
+            {
                 tracing::info!("Loaded projects from {}", path);
+                // Redraw thumbnails so older projects and renderer changes
+                // are covered
+                map.values_mut().for_each(thumbnail::refresh);
                 return map;
             }
         }
@@ -48,7 +153,22 @@ fn load_store() -> HashMap<String, serde_json::Value> {
 async fn save_store(store: &HashMap<String, serde_json::Value>) -> std::io::Result<()> {
     let path = get_data_file();
     let data = serde_json::to_vec_pretty(store)?;
-    tokio::fs::write(&path, data).await
+    tokio::fs::write(&path, data)
+        .await
+        .inspect_err(|_| metrics::record_persistence_failure("projects"))
+}
+
+impl PersistentStore for Store {
+    type Records = HashMap<String, serde_json::Value>;
+    const NAME: &'static str = "projects";
+
+    fn lock(&self) -> &RwLock<Self::Records> {
+        self
+    }
+
+    async fn persist(&self, records: &Self::Records) -> std::io::Result<()> {
+        save_store(records).await
+    }
 }
 
 #[tokio::main]
@@ -68,6 +188,19 @@ async fn main() {
     let initial_analytics = analytics::load_analytics();
     let analytics_store = Arc::new(RwLock::new(initial_analytics));
 
+    let initial_assets = assets::load_assets();
+    let asset_store = Arc::new(RwLock::new(initial_assets));
+
+    let stores = Stores {
+        projects: store.clone(),
+        templates: template_store.clone(),
+        git: git_store.clone(),
+        analytics: analytics_store.clone(),
+        assets: asset_store.clone(),
+    };
+
+    trash::spawn_purge_task(stores.clone());
+
     // CORS
     // Use CORS_ORIGIN env var if set, otherwise default to Any (for dev)
     let cors_origin = std::env::var("CORS_ORIGIN").ok();
@@ -79,13 +212,25 @@ async fn main() {
                     .parse::<axum::http::HeaderValue>()
                     .expect("Invalid CORS_ORIGIN value"),
             )
-            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+            ])
             .allow_headers(Any)
     } else {
         tracing::warn!("CORS allowing ANY origin (development mode)");
         CorsLayer::new()
             .allow_origin(Any)
-            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+            ])
             .allow_headers(Any)
     };
 
@@ -95,6 +240,11 @@ async fn main() {
             "/api/projects/{id}",
             get(get_project).delete(delete_project),
         )
+        .route("/api/projects/facets", get(organize::get_facets))
+        .route(
+            "/api/projects/{id}/organization",
+            patch(organize::patch_organization),
+        )
         .with_state(store);
 
     let template_routes = Router::new()
@@ -121,6 +271,46 @@ async fn main() {
         )
         .with_state(analytics_store);
 
+    let asset_routes = Router::new()
+        .route(
+            "/api/assets",
+            get(assets::list_assets).post(assets::upload_asset),
+        )
+        .route(
+            "/api/assets/{id}",
+            get(assets::get_asset).delete(assets::delete_asset),
+        )
+        // Leave headroom over the file limit for the multipart framing
+        .layer(axum::extract::DefaultBodyLimit::max(
+            assets::max_asset_bytes() + 64 * 1024,
+        ))
+        .with_state(asset_store);
+
+    let admin_routes = Router::new()
+        .route("/api/admin/backup", get(admin::get_backup))
+        .route(
+            "/api/admin/restore",
+            axum::routing::post(admin::post_restore),
+        )
+        .route_layer(axum::middleware::from_fn(admin::require_admin))
+        .with_state(stores.clone());
+
+    let fork_routes = Router::new()
+        .route("/api/projects/{id}/fork", post(fork::fork_project))
+        .with_state(stores.clone());
+
+    let trash_routes = Router::new()
+        .route("/api/trash", get(trash::list_trash))
+        .route("/api/trash/{id}", delete(trash::delete_permanently))
+        .route("/api/trash/{id}/restore", post(trash::restore_project))
+        .with_state(stores.clone());
+
+    let ops_routes = Router::new()
+        .route("/healthz", get(health::healthz))
+        .route("/readyz", get(health::readyz))
+        .route("/metrics", get(metrics::get_metrics))
+        .with_state(stores.clone());
+
     // Serve frontend static files
     // Fallback to index.html for SPA routing
     let static_files = ServeDir::new("dist").fallback(ServeFile::new("dist/index.html"));
@@ -130,7 +320,14 @@ async fn main() {
         .merge(template_routes)
         .merge(git_routes)
         .merge(analytics_routes)
+        .merge(asset_routes)
+        .merge(admin_routes)
+        .merge(fork_routes)
+        .merge(trash_routes)
+        .merge(ops_routes)
+        .route("/api/openapi.json", get(openapi::openapi_json))
         .fallback_service(static_files)
+        .layer(axum::middleware::from_fn(metrics::track_requests))
         .layer(cors);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
@@ -139,50 +336,38 @@ async fn main() {
     axum::serve(listener, app).await.unwrap();
 }
 
-async fn list_projects(State(store): State<Store>) -> Json<Vec<ProjectMetadata>> {
-    let store = store.read().await;
-    let mut projects: Vec<ProjectMetadata> = store
-        .values()
-        .map(|p| {
-            let id = p
-                .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            let name = p
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Untitled")
-                .to_string();
-            let last_modified = p
-                .get("last_modified")
-                .and_then(|v| v.as_f64())
-                .unwrap_or(0.0);
-            let component_count = p
-                .get("layout")
-                .and_then(|l| l.as_array())
-                .map(|a| a.len())
-                .unwrap_or(0);
-
-            ProjectMetadata {
-                id,
-                name,
-                last_modified,
-                component_count,
-            }
-        })
-        .collect();
-
-    // Sort by last modified desc
-    projects.sort_by(|a, b| {
-        b.last_modified
-            .partial_cmp(&a.last_modified)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-
-    Json(projects)
+#[utoipa::path(
+    get,
+    path = "/api/projects",
+    tag = "projects",
+    params(search::ProjectQuery),
+    responses(
+        (status = 200, description = "Matching projects, most recently modified first unless sorted otherwise", body = search::ProjectPage),
+        (status = 400, description = "Malformed cursor")
+    )
+)]
+async fn list_projects(
+    State(store): State<Store>,
+    Query(query): Query<search::ProjectQuery>,
+) -> Result<Json<search::ProjectPage>, StatusCode> {
+    let store = store.records().await;
+    search::list(&store, &query)
+        .map(Json)
+        .map_err(|_| StatusCode::BAD_REQUEST)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/projects",
+    tag = "projects",
+    request_body(content = Object, description = "Project document; an `id` is generated when missing. An optional `base_modified` makes the save fail unless the stored project still has that `last_modified`."),
+    responses(
+        (status = 200, description = "Project stored", body = ProjectMetadata),
+        (status = 409, description = "Project is in the trash"),
+        (status = 412, description = "Project changed since `base_modified`"),
+        (status = 500, description = "Project could not be persisted")
+    )
+)]
 async fn save_project(
     State(store): State<Store>,
     Json(mut payload): Json<serde_json::Value>,
@@ -195,37 +380,54 @@ async fn save_project(
         .map(|s| s.to_string())
         .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
+    let base_modified = payload
+        .as_object_mut()
+        .and_then(|obj| obj.remove(BASE_MODIFIED_FIELD))
+        .and_then(|v| v.as_f64());
+
     // Ensure ID is in payload
     if let Some(obj) = payload.as_object_mut() {
         obj.insert("id".to_string(), serde_json::Value::String(id.clone()));
+        // Trashing goes through DELETE, never through a save
+        obj.remove("deleted_at");
         // Ensure last_modified is updated if not present (though frontend should send it)
         if !obj.contains_key("last_modified") {
             obj.insert("last_modified".to_string(), serde_json::Value::from(0.0));
         }
     }
+    thumbnail::refresh(&mut payload);
+
+    let metadata = {
+        let mut guard = store.records_mut().await;
+        // Saving must not silently bring a project back out of the trash
+        if guard.get(&id).is_some_and(|p| trashed_at(p).is_some()) {
+            return Err(StatusCode::CONFLICT);
+        }
+        // Queued offline saves must not overwrite changes made in the meantime
+        if let (Some(base), Some(stored)) = (base_modified, guard.get(&id))
+            && stored.get("last_modified").and_then(|v| v.as_f64()) != Some(base)
+        {
+            return Err(StatusCode::PRECONDITION_FAILED);
+        }
+
+        // The editor doesn't round-trip dashboard-managed fields, so keep the
+        // stored values unless the payload sets them
+        if let Some(stored) = guard.get(&id).and_then(|p| p.as_object())
+            && let Some(obj) = payload.as_object_mut()
+        {
+            for field in SERVER_MANAGED_FIELDS {
+                if let Some(value) = stored.get(field) {
+                    obj.entry(field).or_insert_with(|| value.clone());
+                }
+            }
+        }
+        let metadata = ProjectMetadata::from_project(&payload);
 
-    let name = payload
-        .get("name")
-        .and_then(|v| v.as_str())
-        .unwrap_or("Untitled")
-        .to_string();
-    let last_modified = payload
-        .get("last_modified")
-        .and_then(|v| v.as_f64())
-        .unwrap_or(0.0);
-    let component_count = payload
-        .get("layout")
-        .and_then(|l| l.as_array())
-        .map(|a| a.len())
-        .unwrap_or(0);
-
-    {
-        let mut guard = store.write().await;
         // Insert into memory first, capture old value for rollback
         let old_value = guard.insert(id.clone(), payload);
 
         // Try to save to disk
-        if let Err(e) = save_store(&guard).await {
+        if let Err(e) = store.persist(&guard).await {
             tracing::error!("Failed to save store: {}", e);
             // Rollback: Restore old value or remove if it was a new insert
             if let Some(v) = old_value {
@@ -235,40 +437,67 @@ async fn save_project(
             }
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
-    }
+        metadata
+    };
 
-    Ok(Json(ProjectMetadata {
-        id,
-        name,
-        last_modified,
-        component_count,
-    }))
+    Ok(Json(metadata))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/projects/{id}",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project id")),
+    responses(
+        (status = 200, description = "Full project document", body = Object),
+        (status = 404, description = "Project not found or in the trash")
+    )
+)]
 async fn get_project(
     Path(id): Path<String>,
     State(store): State<Store>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let store = store.read().await;
-    if let Some(project) = store.get(&id) {
+    let store = store.records().await;
+    if let Some(project) = store.get(&id).filter(|p| trashed_at(p).is_none()) {
         Ok(Json(project.clone()))
     } else {
         Err(StatusCode::NOT_FOUND)
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/projects/{id}",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project id")),
+    responses(
+        (status = 204, description = "Project moved to the trash"),
+        (status = 404, description = "Project not found"),
+        (status = 500, description = "Deletion could not be persisted")
+    )
+)]
 async fn delete_project(Path(id): Path<String>, State(store): State<Store>) -> StatusCode {
-    let mut guard = store.write().await;
-
-    if let Some(removed_project) = guard.remove(&id) {
-        if let Err(e) = save_store(&guard).await {
-            tracing::error!("Failed to save store after delete: {}", e);
-            // Rollback: put it back
-            guard.insert(id, removed_project);
-            return StatusCode::INTERNAL_SERVER_ERROR;
+    let mut guard = store.records_mut().await;
+
+    let Some(project) = guard.get_mut(&id).filter(|p| trashed_at(p).is_none()) else {
+        return StatusCode::NOT_FOUND;
+    };
+    let Some(obj) = project.as_object_mut() else {
+        return StatusCode::NOT_FOUND;
+    };
+    // Soft delete: the document and its commit history stay until the trash is emptied
+    obj.insert(
+        "deleted_at".to_string(),
+        serde_json::Value::from(now_millis()),
+    );
+
+    if let Err(e) = store.persist(&guard).await {
+        tracing::error!("Failed to save store after delete: {}", e);
+        // Rollback: take it back out of the trash
+        if let Some(obj) = guard.get_mut(&id).and_then(|p| p.as_object_mut()) {
+            obj.remove("deleted_at");
         }
-        StatusCode::NO_CONTENT
-    } else {
-        StatusCode::NOT_FOUND
+        return StatusCode::INTERNAL_SERVER_ERROR;
     }
+    StatusCode::NO_CONTENT
 }
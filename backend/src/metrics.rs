@@ -0,0 +1,314 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    ops::{Deref, DerefMut},
+    sync::{LazyLock, Mutex},
+    time::Instant,
+};
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::Stores;
+
+/// Upper bounds (seconds) shared by all latency histograms
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+    counts: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bound, count) in BUCKETS.iter().zip(self.counts.iter_mut()) {
+            if seconds <= *bound {
+                *count += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        for (bound, count) in BUCKETS.iter().zip(self.counts.iter()) {
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{},le=\"{}\"}} {}",
+                name, labels, bound, count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{},le=\"+Inf\"}} {}",
+            name, labels, self.count
+        );
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, self.count);
+    }
+}
+
+/// Process-wide metrics registry.
+///
+/// Keys are kept in `BTreeMap`s so the exposition output is stable.
+#[derive(Default)]
+struct Registry {
+    /// (method, route, status) -> count
+    requests: BTreeMap<(String, String, u16), u64>,
+    /// (method, route) -> latency
+    latencies: BTreeMap<(String, String), Histogram>,
+    /// store -> failed `save_store` calls
+    persistence_failures: BTreeMap<&'static str, u64>,
+    /// (store, mode) -> time a lock guard was held
+    lock_hold: BTreeMap<(&'static str, &'static str), Histogram>,
+}
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| Mutex::new(Registry::default()));
+
+fn with_registry(f: impl FnOnce(&mut Registry)) {
+    // A poisoned registry only means a panic happened mid-update; counts are still usable.
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut registry);
+}
+
+/// Record a failed `save_store` call for the given store.
+pub fn record_persistence_failure(store: &'static str) {
+    with_registry(|r| *r.persistence_failures.entry(store).or_default() += 1);
+}
+
+/// Lock guard that records how long it was held when dropped.
+pub struct TimedGuard<G> {
+    guard: G,
+    store: &'static str,
+    mode: &'static str,
+    acquired: Instant,
+}
+
+impl<G> Deref for TimedGuard<G> {
+    type Target = G;
+
+    fn deref(&self) -> &G {
+        &self.guard
+    }
+}
+
+impl<G> DerefMut for TimedGuard<G> {
+    fn deref_mut(&mut self) -> &mut G {
+        &mut self.guard
+    }
+}
+
+impl<G> Drop for TimedGuard<G> {
+    fn drop(&mut self) {
+        let held = self.acquired.elapsed().as_secs_f64();
+        let key = (self.store, self.mode);
+        with_registry(|r| r.lock_hold.entry(key).or_default().observe(held));
+    }
+}
+
+/// Acquire a read lock on a store, timing how long it is held.
+pub async fn read<'a, T>(
+    lock: &'a RwLock<T>,
+    store: &'static str,
+) -> TimedGuard<RwLockReadGuard<'a, T>> {
+    let guard = lock.read().await;
+    TimedGuard {
+        guard,
+        store,
+        mode: "read",
+        acquired: Instant::now(),
+    }
+}
+
+/// Acquire a write lock on a store, timing how long it is held.
+pub async fn write<'a, T>(
+    lock: &'a RwLock<T>,
+    store: &'static str,
+) -> TimedGuard<RwLockWriteGuard<'a, T>> {
+    let guard = lock.write().await;
+    TimedGuard {
+        guard,
+        store,
+        mode: "write",
+        acquired: Instant::now(),
+    }
+}
+
+/// Middleware counting requests and their latency per matched route.
+pub async fn track_requests(req: Request, next: Next) -> Response {
+    // Unmatched paths (static files, SPA fallback) share one label to bound cardinality.
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let method = req.method().to_string();
+    let start = Instant::now();
+
+    let response = next.run(req).await;
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let status = response.status().as_u16();
+    with_registry(|r| {
+        *r.requests
+            .entry((method.clone(), route.clone(), status))
+            .or_default() += 1;
+        r.latencies
+            .entry((method, route))
+            .or_default()
+            .observe(elapsed);
+    });
+
+    response
+}
+
+/// Current sizes of the in-memory stores.
+#[derive(Debug, Default)]
+pub struct StoreSizes {
+    pub projects: usize,
+    pub templates: usize,
+    pub commits: usize,
+    pub analytics_events: usize,
+    pub assets: usize,
+}
+
+/// Render the registry in Prometheus text exposition format.
+pub fn render(sizes: &StoreSizes) -> String {
+    let mut out = String::new();
+    let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+
+    out.push_str("# HELP http_requests_total Total HTTP requests by method, route and status.\n");
+    out.push_str("# TYPE http_requests_total counter\n");
+    for ((method, route, status), count) in &registry.requests {
+        let _ = writeln!(
+            out,
+            "http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+            method, route, status, count
+        );
+    }
+
+    out.push_str(
+        "# HELP http_request_duration_seconds HTTP request latency by method and route.\n",
+    );
+    out.push_str("# TYPE http_request_duration_seconds histogram\n");
+    for ((method, route), histogram) in &registry.latencies {
+        let labels = format!("method=\"{}\",route=\"{}\"", method, route);
+        histogram.render(&mut out, "http_request_duration_seconds", &labels);
+    }
+
+    for (name, help, value) in [
+        (
+            "studio_projects",
+            "Number of stored projects.",
+            sizes.projects,
+        ),
+        (
+            "studio_templates",
+            "Number of stored templates.",
+            sizes.templates,
+        ),
+        (
+            "studio_commits",
+            "Number of commits across all projects.",
+            sizes.commits,
+        ),
+        (
+            "studio_analytics_events",
+            "Number of stored analytics events.",
+            sizes.analytics_events,
+        ),
+        (
+            "studio_assets",
+            "Number of stored media assets.",
+            sizes.assets,
+        ),
+    ] {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+
+    out.push_str(
+        "# HELP studio_persistence_failures_total Failed writes of a store to its data file.\n",
+    );
+    out.push_str("# TYPE studio_persistence_failures_total counter\n");
+    for (store, count) in &registry.persistence_failures {
+        let _ = writeln!(
+            out,
+            "studio_persistence_failures_total{{store=\"{}\"}} {}",
+            store, count
+        );
+    }
+
+    out.push_str("# HELP studio_lock_hold_seconds Time a store lock was held.\n");
+    out.push_str("# TYPE studio_lock_hold_seconds histogram\n");
+    for ((store, mode), histogram) in &registry.lock_hold {
+        let labels = format!("store=\"{}\",mode=\"{}\"", store, mode);
+        histogram.render(&mut out, "studio_lock_hold_seconds", &labels);
+    }
+
+    out
+}
+
+pub async fn get_metrics(State(state): State<Stores>) -> impl IntoResponse {
+    // Read the stores directly so scraping does not show up in the lock-hold histograms.
+    let sizes = StoreSizes {
+        projects: state.projects.read().await.len(),
+        templates: state.templates.read().await.len(),
+        commits: state.git.read().await.values().map(Vec::len).sum(),
+        analytics_events: state.analytics.read().await.len(),
+        assets: state.assets.read().await.len(),
+    };
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render(&sizes),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let mut histogram = Histogram::default();
+        histogram.observe(0.003);
+        histogram.observe(0.2);
+        histogram.observe(20.0);
+
+        assert_eq!(histogram.counts[0], 1);
+        assert_eq!(histogram.counts[5], 2);
+        assert_eq!(histogram.counts[BUCKETS.len() - 1], 2);
+        assert_eq!(histogram.count, 3);
+
+        let mut out = String::new();
+        histogram.render(&mut out, "test", "a=\"b\"");
+        assert!(out.contains("test_bucket{a=\"b\",le=\"+Inf\"} 3"));
+        assert!(out.contains("test_count{a=\"b\"} 3"));
+    }
+
+    #[test]
+    fn test_render_includes_store_sizes() {
+        record_persistence_failure("projects");
+        let out = render(&StoreSizes {
+            projects: 4,
+            templates: 1,
+            commits: 9,
+            analytics_events: 0,
+            assets: 2,
+        });
+        assert!(out.contains("studio_projects 4"));
+        assert!(out.contains("studio_commits 9"));
+        assert!(out.contains("studio_assets 2"));
+        assert!(out.contains("studio_persistence_failures_total{store=\"projects\"}"));
+    }
+}
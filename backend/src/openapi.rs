@@ -0,0 +1,111 @@
+use axum::Json;
+use utoipa::OpenApi;
+
+use crate::{admin, analytics, assets, fork, git, organize, search, templates, trash};
+
+/// OpenAPI 3 description of every `/api` route, generated from the handler
+/// annotations so it cannot drift from the router.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Leptos Studio API",
+        description = "Persistence API for projects, commit history, templates, media assets and analytics."
+    ),
+    paths(
+        crate::list_projects,
+        crate::save_project,
+        crate::get_project,
+        crate::delete_project,
+        fork::fork_project,
+        organize::get_facets,
+        organize::patch_organization,
+        git::get_log,
+        git::post_commit,
+        git::delete_history,
+        templates::list_templates,
+        templates::save_template,
+        templates::delete_template,
+        analytics::post_analytics,
+        assets::list_assets,
+        assets::upload_asset,
+        assets::get_asset,
+        assets::delete_asset,
+        admin::get_backup,
+        admin::post_restore,
+        trash::list_trash,
+        trash::restore_project,
+        trash::delete_permanently,
+    ),
+    components(schemas(
+        crate::ProjectMetadata,
+        search::ProjectPage,
+        organize::OrganizationPatch,
+        organize::ProjectFacets,
+        organize::FolderCount,
+        organize::TagCount,
+        search::SortField,
+        search::SortOrder,
+        fork::ForkOrigin,
+        fork::ForkRequest,
+        fork::HistoryMode,
+        git::GitCommit,
+        git::CommitPayload,
+        templates::Template,
+        templates::TemplateCategory,
+        analytics::AnalyticsData,
+        analytics::AnalyticsBatch,
+        assets::Asset,
+        assets::AssetVariant,
+        admin::Manifest,
+        admin::ManifestFile,
+        admin::BackupCounts,
+        admin::RestoreMode,
+        admin::RestoreReport,
+        admin::RestoreChanges,
+        admin::StoreChanges,
+    )),
+    tags(
+        (name = "projects", description = "Project documents"),
+        (name = "commits", description = "Per-project commit history"),
+        (name = "templates", description = "User-defined templates"),
+        (name = "analytics", description = "Usage analytics ingestion"),
+        (name = "assets", description = "Uploaded media assets"),
+        (name = "trash", description = "Deleted projects awaiting restore or purge"),
+        (name = "admin", description = "Backup and restore of every store")
+    )
+)]
+pub struct ApiDoc;
+
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_routes_documented() {
+        let doc = ApiDoc::openapi();
+        for path in [
+            "/api/projects",
+            "/api/projects/{id}",
+            "/api/projects/{id}/commits",
+            "/api/projects/{id}/fork",
+            "/api/projects/{id}/organization",
+            "/api/projects/facets",
+            "/api/templates",
+            "/api/templates/{id}",
+            "/api/analytics",
+            "/api/assets",
+            "/api/assets/{id}",
+            "/api/admin/backup",
+            "/api/admin/restore",
+            "/api/trash",
+            "/api/trash/{id}",
+            "/api/trash/{id}/restore",
+        ] {
+            assert!(doc.paths.paths.contains_key(path), "missing {}", path);
+        }
+    }
+}
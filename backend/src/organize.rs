@@ -0,0 +1,266 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{ProjectMetadata, Store, storage::PersistentStore, trashed_at};
+
+pub(crate) const TAGS_FIELD: &str = "tags";
+pub(crate) const FAVORITE_FIELD: &str = "favorite";
+pub(crate) const FOLDER_FIELD: &str = "folder";
+
+/// Longest tag accepted, in characters
+const MAX_TAG_LEN: usize = 40;
+/// Most tags a project can carry
+const MAX_TAGS: usize = 20;
+
+/// Dashboard organization of a project, changed through
+/// `PATCH /api/projects/{id}/organization`. Fields left out are unchanged.
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct OrganizationPatch {
+    pub tags: Option<Vec<String>>,
+    pub favorite: Option<bool>,
+    /// Slash-separated folder path; empty moves the project to the root
+    pub folder: Option<String>,
+}
+
+/// A folder and how many projects sit directly in it
+#[derive(Debug, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct FolderCount {
+    pub path: String,
+    pub count: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Folders, tags and favorites across all live projects, for the
+/// dashboard sidebar
+#[derive(Debug, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct ProjectFacets {
+    /// Sorted by path
+    pub folders: Vec<FolderCount>,
+    /// Sorted by tag
+    pub tags: Vec<TagCount>,
+    pub favorites: usize,
+    pub total: usize,
+}
+
+/// Trim tags, drop empty ones and duplicates, keep the first spelling
+pub(crate) fn normalize_tags(tags: &[String]) -> Result<Vec<String>, &'static str> {
+    let mut out: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.is_empty() || out.iter().any(|t| t == tag) {
+            continue;
+        }
+        if tag.chars().count() > MAX_TAG_LEN || tag.contains(',') {
+            return Err("tags must be at most 40 characters and cannot contain commas");
+        }
+        out.push(tag.to_string());
+    }
+    if out.len() > MAX_TAGS {
+        return Err("a project can have at most 20 tags");
+    }
+    Ok(out)
+}
+
+/// Canonical folder path: trimmed segments joined by `/`, no empty segments.
+/// `None` means the root.
+pub(crate) fn normalize_folder(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path
+        .split('/')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+pub(crate) fn project_tags(project: &serde_json::Value) -> Vec<String> {
+    project
+        .get(TAGS_FIELD)
+        .and_then(|v| v.as_array())
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|t| t.as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn is_favorite(project: &serde_json::Value) -> bool {
+    project
+        .get(FAVORITE_FIELD)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+pub(crate) fn project_folder(project: &serde_json::Value) -> Option<String> {
+    project
+        .get(FOLDER_FIELD)
+        .and_then(|v| v.as_str())
+        .and_then(normalize_folder)
+}
+
+fn facets(projects: &HashMap<String, serde_json::Value>) -> ProjectFacets {
+    let mut folders: BTreeMap<String, usize> = BTreeMap::new();
+    let mut tags: BTreeMap<String, usize> = BTreeMap::new();
+    let mut favorites = 0;
+    let mut total = 0;
+
+    for project in projects.values().filter(|p| trashed_at(p).is_none()) {
+        total += 1;
+        if is_favorite(project) {
+            favorites += 1;
+        }
+        if let Some(folder) = project_folder(project) {
+            *folders.entry(folder).or_default() += 1;
+        }
+        for tag in project_tags(project) {
+            *tags.entry(tag).or_default() += 1;
+        }
+    }
+
+    ProjectFacets {
+        folders: folders
+            .into_iter()
+            .map(|(path, count)| FolderCount { path, count })
+            .collect(),
+        tags: tags
+            .into_iter()
+            .map(|(tag, count)| TagCount { tag, count })
+            .collect(),
+        favorites,
+        total,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/projects/facets",
+    tag = "projects",
+    responses((status = 200, description = "Folders, tags and favorites of live projects", body = ProjectFacets))
+)]
+pub async fn get_facets(State(store): State<Store>) -> Json<ProjectFacets> {
+    let store = store.records().await;
+    Json(facets(&store))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/projects/{id}/organization",
+    tag = "projects",
+    params(("id" = String, Path, description = "Project id")),
+    request_body = OrganizationPatch,
+    responses(
+        (status = 200, description = "Updated project", body = ProjectMetadata),
+        (status = 400, description = "Invalid tags", body = String),
+        (status = 404, description = "Project not found or in the trash"),
+        (status = 500, description = "Change could not be persisted")
+    )
+)]
+pub async fn patch_organization(
+    Path(id): Path<String>,
+    State(store): State<Store>,
+    Json(patch): Json<OrganizationPatch>,
+) -> Result<Json<ProjectMetadata>, (StatusCode, String)> {
+    let tags = patch
+        .tags
+        .as_deref()
+        .map(normalize_tags)
+        .transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let mut guard = store.records_mut().await;
+    let Some(obj) = guard
+        .get_mut(&id)
+        .filter(|p| trashed_at(p).is_none())
+        .and_then(|p| p.as_object_mut())
+    else {
+        return Err((StatusCode::NOT_FOUND, String::new()));
+    };
+
+    let old = obj.clone();
+    if let Some(tags) = tags {
+        obj.insert(TAGS_FIELD.to_string(), serde_json::Value::from(tags));
+    }
+    if let Some(favorite) = patch.favorite {
+        obj.insert(
+            FAVORITE_FIELD.to_string(),
+            serde_json::Value::from(favorite),
+        );
+    }
+    if let Some(folder) = patch.folder {
+        match normalize_folder(&folder) {
+            Some(folder) => obj.insert(FOLDER_FIELD.to_string(), serde_json::Value::from(folder)),
+            None => obj.remove(FOLDER_FIELD),
+        };
+    }
+
+    if let Err(e) = store.persist(&guard).await {
+        tracing::error!("Failed to save store after organizing {}: {}", id, e);
+        guard.insert(id, serde_json::Value::Object(old));
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, String::new()));
+    }
+
+    Ok(Json(ProjectMetadata::from_project(&guard[&id])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalization() {
+        let tags = vec![" web ".into(), "".into(), "web".into(), "App".into()];
+        assert_eq!(normalize_tags(&tags).unwrap(), vec!["web", "App"]);
+        assert!(normalize_tags(&["a,b".to_string()]).is_err());
+
+        assert_eq!(
+            normalize_folder(" Clients / Acme//"),
+            Some("Clients/Acme".to_string())
+        );
+        assert_eq!(normalize_folder(" / "), None);
+    }
+
+    #[test]
+    fn test_facets_skip_trash() {
+        let projects = HashMap::from([
+            (
+                "a".to_string(),
+                json!({ "folder": "Clients/Acme", "tags": ["web"], "favorite": true }),
+            ),
+            (
+                "b".to_string(),
+                json!({ "folder": "Clients/Acme", "tags": ["web", "app"] }),
+            ),
+            (
+                "c".to_string(),
+                json!({ "folder": "Old", "tags": ["web"], "deleted_at": 1.0 }),
+            ),
+        ]);
+        let facets = facets(&projects);
+        assert_eq!(
+            facets.folders,
+            vec![FolderCount {
+                path: "Clients/Acme".to_string(),
+                count: 2
+            }]
+        );
+        let tags: Vec<(&str, usize)> = facets
+            .tags
+            .iter()
+            .map(|t| (t.tag.as_str(), t.count))
+            .collect();
+        assert_eq!(tags, vec![("app", 1), ("web", 2)]);
+        assert_eq!((facets.favorites, facets.total), (1, 2));
+    }
+}
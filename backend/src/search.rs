@@ -0,0 +1,394 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::organize::{is_favorite, normalize_folder, project_folder, project_tags};
+use crate::{ProjectMetadata, trashed_at};
+
+/// Page size when `limit` is not given
+const DEFAULT_PAGE_SIZE: usize = 50;
+/// Largest page a client may ask for
+const MAX_PAGE_SIZE: usize = 200;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    Name,
+    #[default]
+    Modified,
+    Components,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Query parameters of `GET /api/projects`
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProjectQuery {
+    /// Case-insensitive substring of the project name
+    pub q: Option<String>,
+    /// Full-text search over the name and the text inside the project
+    /// (button labels, text content, placeholders, custom component names).
+    /// Every word must match.
+    pub text: Option<String>,
+    /// Comma-separated tags; a project must have all of them
+    pub tags: Option<String>,
+    /// Only projects directly in this folder; empty for the root
+    pub folder: Option<String>,
+    /// Only favorites (`true`) or only non-favorites (`false`)
+    pub favorite: Option<bool>,
+    #[serde(default)]
+    #[param(inline)]
+    pub sort: SortField,
+    /// Defaults to ascending for `name`, descending otherwise
+    #[param(inline)]
+    pub order: Option<SortOrder>,
+    /// Page size, at most 200
+    pub limit: Option<usize>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+/// One page of the project list
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ProjectPage {
+    pub projects: Vec<ProjectMetadata>,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Number of projects matching the filters, across all pages
+    pub total: usize,
+}
+
+/// Error for a malformed `cursor`
+#[derive(Debug, PartialEq)]
+pub struct InvalidCursor;
+
+/// Position after the last item of a page. Keyset-based, so pages stay
+/// stable while projects are added or removed between requests.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Cursor {
+    key: SortKey,
+    id: String,
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(untagged)]
+enum SortKey {
+    Number(f64),
+    Text(String),
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        json.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn decode(cursor: &str) -> Result<Self, InvalidCursor> {
+        if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+            return Err(InvalidCursor);
+        }
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| InvalidCursor)?;
+        serde_json::from_slice(&bytes).map_err(|_| InvalidCursor)
+    }
+}
+
+fn sort_key(meta: &ProjectMetadata, field: SortField) -> SortKey {
+    match field {
+        SortField::Name => SortKey::Text(meta.name.to_lowercase()),
+        SortField::Modified => SortKey::Number(meta.last_modified),
+        SortField::Components => SortKey::Number(meta.component_count as f64),
+    }
+}
+
+/// Lowercased searchable text of a project: its name plus the text of every
+/// component, one entry per line.
+fn project_text(project: &serde_json::Value) -> String {
+    fn walk(layout: &serde_json::Value, out: &mut String) {
+        let Some(components) = layout.as_array() else {
+            return;
+        };
+        for component in components {
+            let Some((kind, body)) = component
+                .as_object()
+                .filter(|o| o.len() == 1)
+                .and_then(|o| o.iter().next())
+            else {
+                continue;
+            };
+            let fields: &[&str] = match kind.as_str() {
+                "Button" => &["label"],
+                "Text" => &["content"],
+                "Input" | "Select" => &["placeholder"],
+                "Custom" => &["name"],
+                _ => &[],
+            };
+            for field in fields {
+                if let Some(text) = body.get(*field).and_then(|v| v.as_str()) {
+                    out.push_str(text);
+                    out.push('\n');
+                }
+            }
+            if let Some(children) = body.get("children") {
+                walk(children, out);
+            }
+        }
+    }
+
+    let mut text = String::new();
+    if let Some(name) = project.get("name").and_then(|v| v.as_str()) {
+        text.push_str(name);
+        text.push('\n');
+    }
+    if let Some(layout) = project.get("layout") {
+        walk(layout, &mut text);
+    }
+    text.to_lowercase()
+}
+
+fn matches(project: &serde_json::Value, meta: &ProjectMetadata, query: &ProjectQuery) -> bool {
+    if let Some(q) = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty())
+        && !meta.name.to_lowercase().contains(&q.to_lowercase())
+    {
+        return false;
+    }
+
+    if let Some(folder) = query.folder.as_deref()
+        && project_folder(project) != normalize_folder(folder)
+    {
+        return false;
+    }
+
+    if let Some(favorite) = query.favorite
+        && is_favorite(project) != favorite
+    {
+        return false;
+    }
+
+    if let Some(tags) = query.tags.as_deref() {
+        let wanted: Vec<&str> = tags
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect();
+        if !wanted.is_empty() {
+            let have = project_tags(project);
+            if !wanted.iter().all(|t| have.iter().any(|h| h == t)) {
+                return false;
+            }
+        }
+    }
+
+    if let Some(text) = query.text.as_deref() {
+        let words: Vec<String> = text.split_whitespace().map(str::to_lowercase).collect();
+        if !words.is_empty() {
+            let haystack = project_text(project);
+            if !words.iter().all(|w| haystack.contains(w.as_str())) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Filter, sort and paginate the live (non-trashed) projects
+pub fn list(
+    projects: &HashMap<String, serde_json::Value>,
+    query: &ProjectQuery,
+) -> Result<ProjectPage, InvalidCursor> {
+    let after = query.cursor.as_deref().map(Cursor::decode).transpose()?;
+    let order = query.order.unwrap_or(match query.sort {
+        SortField::Name => SortOrder::Asc,
+        SortField::Modified | SortField::Components => SortOrder::Desc,
+    });
+
+    let mut entries: Vec<(SortKey, ProjectMetadata)> = projects
+        .values()
+        .filter(|p| trashed_at(p).is_none())
+        .map(|p| (p, ProjectMetadata::from_project(p)))
+        .filter(|(p, meta)| matches(p, meta, query))
+        .map(|(_, meta)| (sort_key(&meta, query.sort), meta))
+        .collect();
+
+    // Ties are broken by id so the order, and therefore the cursor, is total
+    let compare = |a: (&SortKey, &str), b: (&SortKey, &str)| {
+        let ord =
+            a.0.partial_cmp(b.0)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.1.cmp(b.1));
+        match order {
+            SortOrder::Asc => ord,
+            SortOrder::Desc => ord.reverse(),
+        }
+    };
+    entries.sort_by(|a, b| compare((&a.0, &a.1.id), (&b.0, &b.1.id)));
+
+    let total = entries.len();
+    let start = match &after {
+        Some(cursor) => entries.partition_point(|(key, meta)| {
+            compare((key, &meta.id), (&cursor.key, &cursor.id)) != Ordering::Greater
+        }),
+        None => 0,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let has_more = total - start > limit;
+    let page: Vec<(SortKey, ProjectMetadata)> =
+        entries.into_iter().skip(start).take(limit).collect();
+
+    let next_cursor = page.last().filter(|_| has_more).map(|(key, meta)| {
+        Cursor {
+            key: key.clone(),
+            id: meta.id.clone(),
+        }
+        .encode()
+    });
+
+    Ok(ProjectPage {
+        projects: page.into_iter().map(|(_, meta)| meta).collect(),
+        next_cursor,
+        total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn store() -> HashMap<String, serde_json::Value> {
+        let projects = [
+            json!({ "id": "a", "name": "Alpha", "last_modified": 3.0, "tags": ["web"], "folder": "Work",
+                    "layout": [{ "Button": { "id": "1", "label": "Sign up" } }] }),
+            json!({ "id": "b", "name": "beta", "last_modified": 1.0, "tags": ["web", "app"],
+                    "layout": [{ "Container": { "id": "2", "children": [
+                        { "Input": { "id": "3", "placeholder": "Email address" } },
+                        { "Custom": { "id": "4", "name": "PricingTable" } }
+                    ] } }] }),
+            json!({ "id": "c", "name": "Gamma", "last_modified": 2.0, "favorite": true,
+                    "layout": [{ "Text": { "id": "5", "content": "Welcome back" } }, { "Text": { "id": "6", "content": "" } }] }),
+            json!({ "id": "d", "name": "Deleted", "last_modified": 4.0, "deleted_at": 5.0, "layout": [] }),
+        ];
+        projects
+            .into_iter()
+            .map(|p| (p["id"].as_str().unwrap().to_string(), p))
+            .collect()
+    }
+
+    fn ids(page: &ProjectPage) -> Vec<&str> {
+        page.projects.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_and_filters() {
+        let store = store();
+        let page = list(&store, &ProjectQuery::default()).unwrap();
+        assert_eq!(ids(&page), vec!["a", "c", "b"]);
+        assert_eq!(page.total, 3);
+        assert_eq!(page.next_cursor, None);
+
+        let by_name = ProjectQuery {
+            sort: SortField::Name,
+            ..Default::default()
+        };
+        assert_eq!(ids(&list(&store, &by_name).unwrap()), vec!["a", "b", "c"]);
+
+        let by_components = ProjectQuery {
+            sort: SortField::Components,
+            order: Some(SortOrder::Asc),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&list(&store, &by_components).unwrap()),
+            vec!["a", "b", "c"]
+        );
+
+        let tagged = ProjectQuery {
+            tags: Some("web, app".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&list(&store, &tagged).unwrap()), vec!["b"]);
+
+        let favorites = ProjectQuery {
+            favorite: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(ids(&list(&store, &favorites).unwrap()), vec!["c"]);
+
+        let in_folder = ProjectQuery {
+            folder: Some("Work/".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&list(&store, &in_folder).unwrap()), vec!["a"]);
+        let at_root = ProjectQuery {
+            folder: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&list(&store, &at_root).unwrap()), vec!["c", "b"]);
+
+        let named = ProjectQuery {
+            q: Some("ETA".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&list(&store, &named).unwrap()), vec!["b"]);
+    }
+
+    #[test]
+    fn test_full_text_search_walks_children() {
+        let store = store();
+        let search = |text: &str| {
+            let query = ProjectQuery {
+                text: Some(text.to_string()),
+                ..Default::default()
+            };
+            list(&store, &query)
+                .unwrap()
+                .projects
+                .into_iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(search("email pricingtable"), vec!["b"]);
+        assert_eq!(search("sign"), vec!["a"]);
+        assert_eq!(search("welcome gamma"), vec!["c"]);
+        assert!(search("deleted").is_empty());
+    }
+
+    #[test]
+    fn test_cursor_pagination() {
+        let mut store = store();
+        let mut query = ProjectQuery {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let first = list(&store, &query).unwrap();
+        assert_eq!(ids(&first), vec!["a", "c"]);
+
+        // A project added between requests does not shift the next page
+        store.insert(
+            "z".to_string(),
+            json!({ "id": "z", "name": "New", "last_modified": 10.0 }),
+        );
+        query.cursor = first.next_cursor.clone();
+        let second = list(&store, &query).unwrap();
+        assert_eq!(ids(&second), vec!["b"]);
+        assert_eq!(second.next_cursor, None);
+
+        query.cursor = Some("zz".to_string());
+        assert_eq!(list(&store, &query).unwrap_err(), InvalidCursor);
+    }
+}
@@ -0,0 +1,61 @@
+//! Persistence behind the stores.
+//!
+//! Each store is a lock around its records plus a way to persist them, and
+//! the asset store also keeps files (blobs) next to its records. Handlers
+//! only lock, persist and reach blobs through `PersistentStore` and
+//! `BlobStore`, so they keep working whatever a store is kept in.
+
+use std::future::Future;
+
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::metrics::{self, TimedGuard};
+
+pub trait PersistentStore: Send + Sync {
+    type Records: Clone + Send + Sync + 'static;
+
+    /// Store name, as used in metrics
+    const NAME: &'static str;
+
+    fn lock(&self) -> &RwLock<Self::Records>;
+
+    /// Write the records to durable storage
+    fn persist(&self, records: &Self::Records) -> impl Future<Output = std::io::Result<()>> + Send;
+
+    /// Read access to the records, timed like every other lock
+    fn records(
+        &self,
+    ) -> impl Future<Output = TimedGuard<RwLockReadGuard<'_, Self::Records>>> + Send {
+        metrics::read(self.lock(), Self::NAME)
+    }
+
+    /// Write access to the records; changes are only durable once persisted
+    fn records_mut(
+        &self,
+    ) -> impl Future<Output = TimedGuard<RwLockWriteGuard<'_, Self::Records>>> + Send {
+        metrics::write(self.lock(), Self::NAME)
+    }
+}
+
+/// A store that also keeps files, addressed by id, next to its records
+pub trait BlobStore: PersistentStore {
+    fn get_blob(&self, id: &str) -> impl Future<Output = std::io::Result<Vec<u8>>> + Send;
+
+    fn put_blob(&self, id: &str, data: &[u8]) -> impl Future<Output = std::io::Result<()>> + Send;
+
+    fn delete_blob(&self, id: &str) -> impl Future<Output = std::io::Result<()>> + Send;
+
+    /// Best-effort removal of several blobs; failures are only logged
+    fn delete_blobs<'a>(
+        &self,
+        ids: impl Iterator<Item = &'a str> + Send,
+    ) -> impl Future<Output = ()> + Send {
+        async move {
+            for id in ids {
+                if let Err(e) = self.delete_blob(id).await {
+                    tracing::warn!("Failed to remove {} blob {}: {}", Self::NAME, id, e);
+                }
+            }
+        }
+    }
+}
@@ -7,8 +7,10 @@ use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, path::Path as FilePath, sync::Arc};
 use tokio::sync::RwLock;
 
+use crate::{metrics, storage::PersistentStore};
+
 // Match frontend TemplateCategory enum
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 pub enum TemplateCategory {
     LandingPage,
     Dashboard,
@@ -20,20 +22,21 @@ pub enum TemplateCategory {
     Custom,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct Template {
     pub id: String,
     pub name: String,
     pub description: String,
     pub category: TemplateCategory,
     pub thumbnail: Option<String>,
+    #[schema(value_type = Vec<Object>)]
     pub components: Vec<serde_json::Value>,
     pub tags: Vec<String>,
 }
 
 pub type TemplateStore = Arc<RwLock<HashMap<String, Template>>>;
 
-fn get_data_file() -> String {
+pub(crate) fn get_data_file() -> String {
     std::env::var("TEMPLATES_FILE").unwrap_or_else(|_| "templates.json".to_string())
 }
 
@@ -55,17 +58,48 @@ pub fn load_templates() -> HashMap<String, Template> {
 async fn save_store(store: &HashMap<String, Template>) -> std::io::Result<()> {
     let path = get_data_file();
     let data = serde_json::to_vec_pretty(store)?;
-    tokio::fs::write(&path, data).await
+    tokio::fs::write(&path, data)
+        .await
+        .inspect_err(|_| metrics::record_persistence_failure("templates"))
+}
+
+impl PersistentStore for TemplateStore {
+    type Records = HashMap<String, Template>;
+    const NAME: &'static str = "templates";
+
+    fn lock(&self) -> &RwLock<Self::Records> {
+        self
+    }
+
+    async fn persist(&self, records: &Self::Records) -> std::io::Result<()> {
+        save_store(records).await
+    }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/templates",
+    tag = "templates",
+    responses((status = 200, description = "Custom templates sorted by name", body = [Template]))
+)]
 pub async fn list_templates(State(store): State<TemplateStore>) -> Json<Vec<Template>> {
-    let store = store.read().await;
+    let store = store.records().await;
     let mut templates: Vec<Template> = store.values().cloned().collect();
     // Sort by name
     templates.sort_by(|a, b| a.name.cmp(&b.name));
     Json(templates)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/templates",
+    tag = "templates",
+    request_body = Template,
+    responses(
+        (status = 200, description = "Template stored; an empty id is replaced", body = Template),
+        (status = 500, description = "Template could not be persisted")
+    )
+)]
 pub async fn save_template(
     State(store): State<TemplateStore>,
     Json(mut payload): Json<Template>,
@@ -73,11 +107,14 @@ pub async fn save_template(
     if payload.id.is_empty() {
         payload.id = uuid::Uuid::new_v4().to_string();
     }
+    if payload.thumbnail.is_none() {
+        payload.thumbnail = Some(studio_layout::wireframe::render(&payload.components));
+    }
 
-    let mut guard = store.write().await;
+    let mut guard = store.records_mut().await;
     let old_value = guard.insert(payload.id.clone(), payload.clone());
 
-    if let Err(e) = save_store(&guard).await {
+    if let Err(e) = store.persist(&guard).await {
         tracing::error!("Failed to save templates: {}", e);
         if let Some(v) = old_value {
             guard.insert(payload.id.clone(), v);
@@ -90,14 +127,25 @@ pub async fn save_template(
     Ok(Json(payload))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/templates/{id}",
+    tag = "templates",
+    params(("id" = String, Path, description = "Template id")),
+    responses(
+        (status = 204, description = "Template deleted"),
+        (status = 404, description = "Template not found"),
+        (status = 500, description = "Deletion could not be persisted")
+    )
+)]
 pub async fn delete_template(
     Path(id): Path<String>,
     State(store): State<TemplateStore>,
 ) -> StatusCode {
-    let mut guard = store.write().await;
+    let mut guard = store.records_mut().await;
 
     if let Some(removed) = guard.remove(&id) {
-        if let Err(e) = save_store(&guard).await {
+        if let Err(e) = store.persist(&guard).await {
             tracing::error!("Failed to save templates after delete: {}", e);
             guard.insert(id, removed);
             return StatusCode::INTERNAL_SERVER_ERROR;
@@ -0,0 +1,24 @@
+//! Project thumbnails: SVG wireframes of the top of the canvas, drawn by
+//! `studio_layout::wireframe` and stored with each project.
+
+use serde_json::Value;
+
+pub(crate) const THUMBNAIL_FIELD: &str = "thumbnail";
+
+/// Render the thumbnail of a project document as an SVG `data:` URL
+pub(crate) fn render_project(project: &Value) -> String {
+    let layout = project
+        .get("layout")
+        .and_then(|l| l.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    studio_layout::wireframe::render(layout)
+}
+
+/// Store a fresh thumbnail in a project document
+pub(crate) fn refresh(project: &mut Value) {
+    let thumbnail = render_project(project);
+    if let Some(obj) = project.as_object_mut() {
+        obj.insert(THUMBNAIL_FIELD.to_string(), Value::String(thumbnail));
+    }
+}
@@ -0,0 +1,222 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use std::{collections::HashMap, time::Duration};
+
+use crate::{ProjectMetadata, Stores, now_millis, storage::PersistentStore, trashed_at};
+
+/// Trashed projects are purged after this many days unless
+/// `TRASH_RETENTION_DAYS` says otherwise
+const DEFAULT_RETENTION_DAYS: u64 = 30;
+
+/// How often the background task looks for expired projects
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const DAY_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// Retention period from `TRASH_RETENTION_DAYS`. `0` keeps trashed projects
+/// until they are deleted by hand.
+pub fn retention_days() -> u64 {
+    std::env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+/// Ids of trashed projects deleted more than `retention_ms` before `now`
+fn expired_ids(
+    projects: &HashMap<String, serde_json::Value>,
+    now: f64,
+    retention_ms: f64,
+) -> Vec<String> {
+    projects
+        .iter()
+        .filter(|(_, p)| trashed_at(p).is_some_and(|at| now - at >= retention_ms))
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+/// Permanently remove trashed projects together with their commit history.
+/// Ids of projects that are not in the trash are ignored.
+///
+/// Both stores are saved; if either write fails, both are rolled back.
+async fn remove_permanently(stores: &Stores, ids: &[String]) -> Result<usize, StatusCode> {
+    let mut projects = stores.projects.records_mut().await;
+    let mut git = stores.git.records_mut().await;
+
+    let trashed: Vec<&String> = ids
+        .iter()
+        .filter(|id| projects.get(*id).is_some_and(|p| trashed_at(p).is_some()))
+        .collect();
+    let removed_projects: Vec<(String, serde_json::Value)> = trashed
+        .into_iter()
+        .filter_map(|id| projects.remove_entry(id))
+        .collect();
+    if removed_projects.is_empty() {
+        return Ok(0);
+    }
+    let removed_commits: Vec<(String, Vec<crate::git::GitCommit>)> = removed_projects
+        .iter()
+        .filter_map(|(id, _)| git.remove(id).map(|c| (id.clone(), c)))
+        .collect();
+
+    let result = match stores.projects.persist(&projects).await {
+        Ok(()) if removed_commits.is_empty() => Ok(()),
+        Ok(()) => stores.git.persist(&git).await,
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        tracing::error!("Failed to purge projects from the trash: {}", e);
+        let count = removed_projects.len();
+        projects.extend(removed_projects);
+        git.extend(removed_commits);
+        // Best effort: bring the project file back in line with memory
+        if let Err(e) = stores.projects.persist(&projects).await {
+            tracing::error!("Failed to restore projects after purge failure: {}", e);
+        }
+        tracing::warn!("Rolled back purge of {} project(s)", count);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    Ok(removed_projects.len())
+}
+
+/// Purge every project that has been in the trash longer than the retention period
+pub async fn purge_expired(stores: &Stores) {
+    let days = retention_days();
+    if days == 0 {
+        return;
+    }
+    let ids = {
+        let projects = stores.projects.records().await;
+        expired_ids(&projects, now_millis(), days as f64 * DAY_MS)
+    };
+    if ids.is_empty() {
+        return;
+    }
+    if let Ok(count) = remove_permanently(stores, &ids).await {
+        tracing::info!("Purged {} project(s) from the trash", count);
+    }
+}
+
+/// Run [`purge_expired`] at startup and then periodically
+pub fn spawn_purge_task(stores: Stores) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            purge_expired(&stores).await;
+        }
+    });
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/trash",
+    tag = "trash",
+    responses((status = 200, description = "Trashed projects, most recently deleted first", body = [ProjectMetadata]))
+)]
+pub async fn list_trash(State(stores): State<Stores>) -> Json<Vec<ProjectMetadata>> {
+    let projects = stores.projects.records().await;
+    let mut trashed: Vec<ProjectMetadata> = projects
+        .values()
+        .filter(|p| trashed_at(p).is_some())
+        .map(ProjectMetadata::from_project)
+        .collect();
+
+    trashed.sort_by(|a, b| {
+        b.deleted_at
+            .partial_cmp(&a.deleted_at)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    Json(trashed)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/trash/{id}/restore",
+    tag = "trash",
+    params(("id" = String, Path, description = "Project id")),
+    responses(
+        (status = 200, description = "Project restored", body = ProjectMetadata),
+        (status = 404, description = "Project is not in the trash"),
+        (status = 500, description = "Restore could not be persisted")
+    )
+)]
+pub async fn restore_project(
+    Path(id): Path<String>,
+    State(stores): State<Stores>,
+) -> Result<Json<ProjectMetadata>, StatusCode> {
+    let mut guard = stores.projects.records_mut().await;
+
+    let Some(obj) = guard
+        .get_mut(&id)
+        .filter(|p| trashed_at(p).is_some())
+        .and_then(|p| p.as_object_mut())
+    else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    let deleted_at = obj.remove("deleted_at");
+
+    if let Err(e) = stores.projects.persist(&guard).await {
+        tracing::error!("Failed to save store after restore: {}", e);
+        // Rollback: put it back in the trash
+        if let (Some(obj), Some(deleted_at)) = (
+            guard.get_mut(&id).and_then(|p| p.as_object_mut()),
+            deleted_at,
+        ) {
+            obj.insert("deleted_at".to_string(), deleted_at);
+        }
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    Ok(Json(ProjectMetadata::from_project(&guard[&id])))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/trash/{id}",
+    tag = "trash",
+    params(("id" = String, Path, description = "Project id")),
+    responses(
+        (status = 204, description = "Project and its commit history permanently deleted"),
+        (status = 404, description = "Project is not in the trash"),
+        (status = 500, description = "Deletion could not be persisted")
+    )
+)]
+pub async fn delete_permanently(
+    Path(id): Path<String>,
+    State(stores): State<Stores>,
+) -> StatusCode {
+    match remove_permanently(&stores, &[id]).await {
+        Ok(0) => StatusCode::NOT_FOUND,
+        Ok(_) => StatusCode::NO_CONTENT,
+        Err(status) => status,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_expired_ids_only_returns_old_trash() {
+        let projects = HashMap::from([
+            ("live".to_string(), json!({ "id": "live" })),
+            (
+                "old".to_string(),
+                json!({ "id": "old", "deleted_at": 1_000.0 }),
+            ),
+            (
+                "recent".to_string(),
+                json!({ "id": "recent", "deleted_at": 9_500.0 }),
+            ),
+        ]);
+
+        assert_eq!(expired_ids(&projects, 10_000.0, 1_000.0), vec!["old"]);
+    }
+}
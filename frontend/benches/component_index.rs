@@ -0,0 +1,130 @@
+//! Component index benchmark
+//!
+//! Compares looking components up through `ComponentIndex` with walking the
+//! nested tree, and keeping the index current operation by operation with
+//! rebuilding it, on a design of about ten thousand components. Run with
+//! `cargo bench -p leptos_studio --bench component_index`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use leptos_studio::domain::{
+    ButtonComponent, CanvasComponent, ComponentId, ContainerComponent, TextComponent,
+};
+use leptos_studio::state::history::{ComponentTree, Operation, Slot, children_of, find, locate};
+use leptos_studio::state::{ComponentIndex, IndexedTree};
+
+/// `sections` containers, each holding a container of ten texts and ten
+/// buttons. Returns the design, the inner containers and every leaf.
+fn design(sections: usize) -> (Vec<CanvasComponent>, Vec<ComponentId>, Vec<ComponentId>) {
+    let mut components = Vec::new();
+    let (mut groups, mut leaves) = (Vec::new(), Vec::new());
+    for s in 0..sections {
+        let mut inner = ContainerComponent::new();
+        for i in 0..10 {
+            let text = TextComponent::new(format!("Section {} paragraph {}", s, i));
+            let button = ButtonComponent::new(format!("Action {}", i));
+            leaves.extend([text.id, button.id]);
+            inner.children.push(CanvasComponent::Text(text));
+            inner.children.push(CanvasComponent::Button(button));
+        }
+        groups.push(inner.id);
+        let mut outer = ContainerComponent::new();
+        outer.children.push(CanvasComponent::Container(inner));
+        components.push(CanvasComponent::Container(outer));
+    }
+    (components, groups, leaves)
+}
+
+/// Average nanoseconds per call of `f` over `ids`
+fn per_call(ids: &[ComponentId], mut f: impl FnMut(&ComponentId)) -> f64 {
+    let start = Instant::now();
+    for id in ids {
+        f(id);
+    }
+    start.elapsed().as_secs_f64() * 1e9 / ids.len() as f64
+}
+
+/// The recursive check the index replaced
+fn is_descendant(components: &[CanvasComponent], ancestor: &ComponentId, id: &ComponentId) -> bool {
+    find(components, ancestor)
+        .and_then(children_of)
+        .is_some_and(|children| find(children, id).is_some())
+}
+
+/// Moves of each leaf into the next section
+fn moves(groups: &[ComponentId], leaves: &[ComponentId]) -> Vec<Operation> {
+    leaves
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let group = i / 20;
+            Operation::Move {
+                id: *id,
+                from: Slot::child(groups[group], 0),
+                to: Slot::child(groups[(group + 1) % groups.len()], 20),
+            }
+        })
+        .collect()
+}
+
+fn main() {
+    let sections = 455;
+    let (components, groups, leaves) = design(sections);
+    let index = ComponentIndex::new(&components);
+    let sample: Vec<ComponentId> = leaves.iter().step_by(7).copied().collect();
+    println!("{} components\n", index.len());
+
+    println!("{:<12} {:>14} {:>14}", "query", "tree (ns)", "index (ns)");
+    let walk = per_call(&sample, |id| {
+        black_box(find(&components, id));
+    });
+    let indexed = per_call(&sample, |id| {
+        black_box(index.get(&components, id));
+    });
+    println!("{:<12} {:>14.0} {:>14.0}", "get", walk, indexed);
+    let walk = per_call(&sample, |id| {
+        black_box(locate(&components, id));
+    });
+    let indexed = per_call(&sample, |id| {
+        black_box(index.slot(id));
+    });
+    println!("{:<12} {:>14.0} {:>14.0}", "parent", walk, indexed);
+    let outer = *components[sections - 1].id();
+    let walk = per_call(&sample, |id| {
+        black_box(is_descendant(&components, &outer, id));
+    });
+    let indexed = per_call(&sample, |id| {
+        black_box(index.is_ancestor(&outer, id));
+    });
+    println!("{:<12} {:>14.0} {:>14.0}\n", "ancestor", walk, indexed);
+
+    // Keeping the index current while every leaf moves to another section
+    let operations = moves(&groups, &leaves[..2000]);
+    let mut rebuilt = components.clone();
+    let start = Instant::now();
+    for operation in &operations {
+        assert!(operation.apply(&mut rebuilt));
+        black_box(ComponentIndex::new(&rebuilt));
+    }
+    let rebuild = start.elapsed().as_secs_f64() * 1e6 / operations.len() as f64;
+
+    let (mut incremental, mut index) = (components.clone(), index);
+    let start = Instant::now();
+    let mut tree = IndexedTree {
+        components: &mut incremental,
+        index: &mut index,
+    };
+    for operation in &operations {
+        assert!(tree.run(operation, false));
+    }
+    let maintain = start.elapsed().as_secs_f64() * 1e6 / operations.len() as f64;
+    assert_eq!(incremental, rebuilt);
+    assert_eq!(index, ComponentIndex::new(&incremental));
+
+    println!(
+        "{:<12} {:>14} {:>14}",
+        "per move", "rebuild (µs)", "update (µs)"
+    );
+    println!("{:<12} {:>14.1} {:>14.1}", "", rebuild, maintain);
+}
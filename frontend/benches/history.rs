@@ -0,0 +1,140 @@
+//! Undo history memory benchmark
+//!
+//! Compares the operation-based `History` with the full-tree snapshots it
+//! replaced, on a large design edited one keystroke at a time. Run with
+//! `cargo bench -p leptos_studio --bench history`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::time::Instant;
+
+use leptos_studio::constants::MAX_HISTORY_SIZE;
+use leptos_studio::domain::{
+    ButtonComponent, CanvasComponent, ComponentId, ContainerComponent, TextComponent,
+};
+use leptos_studio::state::history::{History, HistoryEntry, Operation, find_mut};
+
+/// Counts bytes currently allocated
+struct Counting;
+
+static LIVE: AtomicIsize = AtomicIsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(layout.size() as isize, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size() as isize, Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn live_bytes() -> isize {
+    LIVE.load(Ordering::Relaxed)
+}
+
+/// `sections` containers of ten texts and ten buttons each
+fn design(sections: usize) -> (Vec<CanvasComponent>, ComponentId) {
+    let mut components = Vec::new();
+    let mut first_text = None;
+    for s in 0..sections {
+        let mut container = ContainerComponent::new();
+        for i in 0..10 {
+            let text = TextComponent::new(format!("Section {} paragraph {}", s, i));
+            first_text.get_or_insert(text.id);
+            container.children.push(CanvasComponent::Text(text));
+            container
+                .children
+                .push(CanvasComponent::Button(ButtonComponent::new(format!(
+                    "Action {}",
+                    i
+                ))));
+        }
+        components.push(CanvasComponent::Container(container));
+    }
+    (components, first_text.expect("at least one section"))
+}
+
+fn type_into(components: &mut [CanvasComponent], id: &ComponentId, key: usize) {
+    if let Some(CanvasComponent::Text(text)) = find_mut(components, id) {
+        text.content.push(char::from(b'a' + (key % 26) as u8));
+    }
+}
+
+struct Measurement {
+    bytes: isize,
+    micros_per_edit: f64,
+}
+
+/// The approach `History` replaced: a copy of the whole tree per edit
+fn snapshots(sections: usize, edits: usize) -> Measurement {
+    let (mut components, id) = design(sections);
+    let before = live_bytes();
+    let start = Instant::now();
+    let mut stack: VecDeque<Vec<CanvasComponent>> = VecDeque::new();
+    for key in 0..edits {
+        stack.push_back(components.clone());
+        if stack.len() > MAX_HISTORY_SIZE {
+            stack.pop_front();
+        }
+        type_into(&mut components, &id, key);
+    }
+    let elapsed = start.elapsed();
+    let bytes = live_bytes() - before;
+    std::hint::black_box(&stack);
+    Measurement {
+        bytes,
+        micros_per_edit: elapsed.as_secs_f64() * 1e6 / edits as f64,
+    }
+}
+
+fn operations(sections: usize, edits: usize) -> Measurement {
+    let (mut components, id) = design(sections);
+    let before = live_bytes();
+    let start = Instant::now();
+    let mut history = History::new();
+    for key in 0..edits {
+        let Some(old) = find_mut(&mut components, &id).map(|c| c.clone()) else {
+            break;
+        };
+        type_into(&mut components, &id, key);
+        let Some(new) = find_mut(&mut components, &id).map(|c| c.clone()) else {
+            break;
+        };
+        let changes = Operation::set_fields(&old, &new).unwrap_or_default();
+        history.push(HistoryEntry::new("Update Text".to_string(), changes));
+    }
+    let elapsed = start.elapsed();
+    let bytes = live_bytes() - before;
+    std::hint::black_box(&history);
+    Measurement {
+        bytes,
+        micros_per_edit: elapsed.as_secs_f64() * 1e6 / edits as f64,
+    }
+}
+
+fn main() {
+    let edits = MAX_HISTORY_SIZE * 4;
+    println!(
+        "{:>10} {:>16} {:>16} {:>12} {:>12}",
+        "components", "snapshots (KiB)", "operations (KiB)", "snap µs/edit", "ops µs/edit"
+    );
+    for sections in [10, 100, 500] {
+        let snap = snapshots(sections, edits);
+        let ops = operations(sections, edits);
+        println!(
+            "{:>10} {:>16.1} {:>16.1} {:>12.1} {:>12.1}",
+            sections * 21,
+            snap.bytes as f64 / 1024.0,
+            ops.bytes as f64 / 1024.0,
+            snap.micros_per_edit,
+            ops.micros_per_edit
+        );
+    }
+}
@@ -44,6 +44,9 @@ fn component_type_str(component: &CanvasComponent) -> String {
 }
 
 fn component_name(component: &CanvasComponent) -> String {
+    if let Some(name) = component.name() {
+        return name.to_string();
+    }
     match component {
         CanvasComponent::Button(btn) => btn.label.clone(),
         CanvasComponent::Text(txt) => txt.content.clone(),
@@ -62,55 +65,6 @@ fn component_name(component: &CanvasComponent) -> String {
     }
 }
 
-// Recursive search for path
-fn find_path(
-    components: &[CanvasComponent],
-    target_id: ComponentId,
-    current_path: &mut Vec<BreadcrumbItem>,
-) -> bool {
-    for (i, comp) in components.iter().enumerate() {
-        if *comp.id() == target_id {
-            current_path.push(BreadcrumbItem {
-                id: Some(*comp.id()),
-                name: component_name(comp),
-                component_type: component_type_str(comp),
-                index: Some(i),
-            });
-            return true;
-        }
-
-        // Search children
-        match comp {
-            CanvasComponent::Container(c) => {
-                current_path.push(BreadcrumbItem {
-                    id: Some(*comp.id()),
-                    name: component_name(comp),
-                    component_type: component_type_str(comp),
-                    index: Some(i),
-                });
-                if find_path(&c.children, target_id, current_path) {
-                    return true;
-                }
-                current_path.pop();
-            }
-            CanvasComponent::Card(c) => {
-                current_path.push(BreadcrumbItem {
-                    id: Some(*comp.id()),
-                    name: component_name(comp),
-                    component_type: component_type_str(comp),
-                    index: Some(i),
-                });
-                if find_path(&c.children, target_id, current_path) {
-                    return true;
-                }
-                current_path.pop();
-            }
-            _ => {}
-        }
-    }
-    false
-}
-
 #[component]
 pub fn BreadcrumbNavigation() -> impl IntoView {
     let app_state = AppState::expect_context();
@@ -123,19 +77,29 @@ pub fn BreadcrumbNavigation() -> impl IntoView {
             index: None,
         }];
 
-        if let Some(selected_id) = app_state.canvas.selected.get() {
-            let components = app_state.canvas.components.get();
-            let mut path = Vec::new();
-            if find_path(&components, selected_id, &mut path) {
-                items.extend(path);
-            }
+        if let Some(selected_id) = app_state.canvas.selected() {
+            app_state.canvas.with_index(|index, components| {
+                let mut path = index.ancestors(&selected_id);
+                path.reverse();
+                path.push(selected_id);
+                items.extend(path.into_iter().filter_map(|id| {
+                    let component = index.get(components, &id)?;
+                    Some(BreadcrumbItem {
+                        id: Some(id),
+                        name: component_name(component),
+                        component_type: component_type_str(component),
+                        index: index.slot(&id).map(|slot| slot.index),
+                    })
+                }));
+            });
         }
 
         items
     });
 
-    let navigate_to = move |item: BreadcrumbItem| {
-        app_state.canvas.selected.set(item.id);
+    let navigate_to = move |item: BreadcrumbItem| match item.id {
+        Some(id) => app_state.canvas.select(id),
+        None => app_state.canvas.clear_selection(),
     };
 
     view! {
@@ -2,6 +2,7 @@ use crate::builder::breadcrumb::BreadcrumbNavigation;
 use crate::builder::canvas::renderer::ComponentRenderer;
 use crate::builder::component_library::create_canvas_component;
 use crate::builder::context_menu::ContextMenu;
+use crate::builder::hooks::use_keyboard_actions::{group_selection, ungroup_selection};
 use crate::domain::ComponentId;
 use crate::state::app_state::AppState;
 use leptos::{ev, html, prelude::*};
@@ -17,6 +18,15 @@ pub fn handle_drop(ev: ev::DragEvent, _target_id: Option<ComponentId>, app_state
     ev.prevent_default();
     ev.stop_propagation();
 
+    // Nothing can be dropped into a locked component
+    if _target_id.is_some_and(|target| app_state.canvas.is_locked(&target)) {
+        app_state
+            .canvas
+            .drag_state
+            .set(crate::builder::drag_drop::DragState::NotDragging);
+        return;
+    }
+
     let drag_ev = ev.unchecked_into::<web_sys::DragEvent>();
 
     // Check for "component" (New component from palette)
@@ -102,12 +112,77 @@ pub fn Canvas() -> impl IntoView {
         }
     };
 
+    // Rubber-band selection: dragging across the canvas background selects
+    // the components entirely inside the band. Corners in client coordinates.
+    let band = RwSignal::new(None::<((f64, f64), (f64, f64))>);
+    let band_dragged = StoredValue::new(false);
+    let on_pointer_down = move |ev: ev::PointerEvent| {
+        let target = event_target::<web_sys::HtmlElement>(&ev);
+        if ev.button() == 0 && target.id() == "main-canvas" && !app_state.ui.preview_mode.get() {
+            let start = (ev.client_x() as f64, ev.client_y() as f64);
+            band.set(Some((start, start)));
+            band_dragged.set_value(false);
+        }
+    };
+    let _ = window_event_listener(ev::pointermove, move |ev| {
+        if band.with_untracked(Option::is_none) {
+            return;
+        }
+        let end = (ev.client_x() as f64, ev.client_y() as f64);
+        band.update(|band| {
+            if let Some((start, current)) = band {
+                *current = end;
+                if (end.0 - start.0).abs() + (end.1 - start.1).abs() > BAND_THRESHOLD_PX {
+                    band_dragged.set_value(true);
+                }
+            }
+        });
+    });
+    let _ = window_event_listener(ev::pointerup, move |ev| {
+        let Some((start, end)) = band.get_untracked() else {
+            return;
+        };
+        band.set(None);
+        if band_dragged.get_value()
+            && let Some(canvas) = canvas_ref.get_untracked()
+        {
+            let mut ids = components_in_band(&canvas, start, end);
+            ids.retain(|id| !app_state.canvas.is_locked(id));
+            // Children go along with a selected container
+            let ids = app_state.canvas.with_index(|index, _| {
+                ids.iter()
+                    .filter(|id| !ids.iter().any(|other| index.is_ancestor(other, id)))
+                    .copied()
+                    .collect()
+            });
+            app_state.canvas.select_many(ids, ev.shift_key());
+        }
+    });
+    let band_style = move || {
+        band.get()
+            .filter(|_| band_dragged.get_value())
+            .map(|((x1, y1), (x2, y2))| {
+                format!(
+                    "left: {}px; top: {}px; width: {}px; height: {}px;",
+                    x1.min(x2),
+                    y1.min(y2),
+                    (x2 - x1).abs(),
+                    (y2 - y1).abs()
+                )
+            })
+    };
+
     // Handle background click to deselect
     let on_canvas_click = move |ev: ev::MouseEvent| {
+        // The click ending a rubber band drag keeps what it selected
+        if band_dragged.get_value() {
+            band_dragged.set_value(false);
+            return;
+        }
         // Only deselect if clicking the canvas background directly
         let target = event_target::<web_sys::HtmlElement>(&ev);
         if target.id() == "main-canvas" {
-            app_state.canvas.selected.set(None);
+            app_state.canvas.clear_selection();
         }
     };
 
@@ -120,16 +195,21 @@ pub fn Canvas() -> impl IntoView {
         if let Some(closest) = target.closest("[data-component-id]").ok().flatten()
             && let Some(id_str) = closest.get_attribute("data-component-id")
         {
-            let components = app_state.canvas.components.get_untracked();
-            let found_id = find_component_id_by_string(&components, &id_str);
+            let found_id = uuid::Uuid::parse_str(&id_str)
+                .ok()
+                .map(ComponentId::from)
+                .filter(|id| app_state.canvas.with_index(|index, _| index.contains(id)))
+                .filter(|id| !app_state.canvas.is_locked(id));
 
             if let Some(id) = found_id {
                 set_cm_target_id.set(Some(id));
                 set_cm_position.set((ev.client_x() as f64, ev.client_y() as f64));
                 set_cm_visible.set(true);
 
-                // Also select it
-                app_state.canvas.selected.set(Some(id));
+                // Also select it, keeping a selection it is part of
+                if !app_state.canvas.is_selected(&id) {
+                    app_state.canvas.select(id);
+                }
                 return;
             }
         }
@@ -158,6 +238,7 @@ pub fn Canvas() -> impl IntoView {
             <div
                 class="flex-1 relative overflow-auto flex flex-col items-center justify-center p-8 canvas-area"
                 on:click=on_canvas_click
+                on:pointerdown=on_pointer_down
                 on:dragover=handle_drag_over
                 on:drop=move |ev| handle_drop(ev, None, app_state)
             >
@@ -209,6 +290,8 @@ pub fn Canvas() -> impl IntoView {
                 </div>
             </div>
 
+            {move || band_style().map(|style| view! { <div class="selection-band" style=style></div> })}
+
             // Breadcrumbs at the bottom
             <BreadcrumbNavigation />
 
@@ -218,21 +301,20 @@ pub fn Canvas() -> impl IntoView {
                 position=cm_position
                 component_id=cm_target_id
                 on_close=Callback::new(move |_| set_cm_visible.set(false))
-                on_delete=Callback::new(move |id| {
-                    app_state.canvas.remove_component(&id);
+                // The target is always selected, so these act on the whole selection
+                on_delete=Callback::new(move |_| {
+                    app_state.canvas.remove_selected();
                 })
-                on_duplicate=Callback::new(move |id| {
-                    // Use the new duplicate_with_new_id method
-                    if let Some(comp) = app_state.canvas.get_component(&id) {
-                         let new_comp = comp.duplicate_with_new_id();
-                         app_state.canvas.add_component(new_comp);
-                    }
+                on_duplicate=Callback::new(move |_| {
+                    app_state.canvas.duplicate_selected();
                 })
                 on_select_parent=Callback::new(move |id| {
-                     if let Some(parent_id) = find_parent_id(&app_state.canvas.components.get_untracked(), id) {
-                         app_state.canvas.selected.set(Some(parent_id));
+                     if let Some(parent_id) = app_state.canvas.parent_of(&id) {
+                         app_state.canvas.select(parent_id);
                      }
                 })
+                on_group=Callback::new(move |kind| group_selection(app_state, kind))
+                on_ungroup=Callback::new(move |keep_spacing| ungroup_selection(app_state, keep_spacing))
                 on_save_custom=Callback::new(move |id| {
                      save_custom_component(id);
                 })
@@ -241,67 +323,33 @@ pub fn Canvas() -> impl IntoView {
     }
 }
 
-// Helper to find ID from string
-fn find_component_id_by_string(
-    components: &[crate::domain::CanvasComponent],
-    id_str: &str,
-) -> Option<ComponentId> {
-    for comp in components {
-        if comp.id().to_string() == id_str {
-            return Some(*comp.id());
-        }
+/// Distance a drag must cover before it draws a rubber band
+const BAND_THRESHOLD_PX: f64 = 4.0;
 
-        match comp {
-            crate::domain::CanvasComponent::Container(c) => {
-                if let Some(found) = find_component_id_by_string(&c.children, id_str) {
-                    return Some(found);
-                }
-            }
-            crate::domain::CanvasComponent::Card(c) => {
-                if let Some(found) = find_component_id_by_string(&c.children, id_str) {
-                    return Some(found);
-                }
-            }
-            _ => {}
-        }
-    }
-    None
-}
-
-// Helper to find parent ID
-fn find_parent_id(
-    components: &[crate::domain::CanvasComponent],
-    target_id: ComponentId,
-) -> Option<ComponentId> {
-    for comp in components {
-        let is_parent = match comp {
-            crate::domain::CanvasComponent::Container(c) => {
-                c.children.iter().any(|child| *child.id() == target_id)
-            }
-            crate::domain::CanvasComponent::Card(c) => {
-                c.children.iter().any(|child| *child.id() == target_id)
-            }
-            _ => false,
-        };
-
-        if is_parent {
-            return Some(*comp.id());
-        }
-
-        // Recurse
-        match comp {
-            crate::domain::CanvasComponent::Container(c) => {
-                if let Some(found) = find_parent_id(&c.children, target_id) {
-                    return Some(found);
-                }
-            }
-            crate::domain::CanvasComponent::Card(c) => {
-                if let Some(found) = find_parent_id(&c.children, target_id) {
-                    return Some(found);
-                }
-            }
-            _ => {}
-        }
-    }
-    None
+/// Components rendered in `canvas` that lie entirely inside the rectangle
+/// spanned by `start` and `end`
+fn components_in_band(
+    canvas: &web_sys::HtmlElement,
+    start: (f64, f64),
+    end: (f64, f64),
+) -> Vec<ComponentId> {
+    let (left, right) = (start.0.min(end.0), start.0.max(end.0));
+    let (top, bottom) = (start.1.min(end.1), start.1.max(end.1));
+    let Ok(nodes) = canvas.query_selector_all("[data-component-id]") else {
+        return Vec::new();
+    };
+    (0..nodes.length())
+        .filter_map(|i| nodes.item(i)?.dyn_into::<web_sys::Element>().ok())
+        .filter(|element| {
+            let rect = element.get_bounding_client_rect();
+            rect.left() >= left
+                && rect.right() <= right
+                && rect.top() >= top
+                && rect.bottom() <= bottom
+        })
+        .filter_map(|element| {
+            let id = element.get_attribute("data-component-id")?;
+            uuid::Uuid::parse_str(&id).ok().map(ComponentId::from)
+        })
+        .collect()
 }
@@ -4,7 +4,8 @@ use crate::domain::{
     Animation, ButtonComponent, CanvasComponent, CardComponent, ContainerComponent,
     CustomComponent, ImageComponent, InputComponent, SelectComponent, TextComponent,
 };
-use crate::state::{AppState, CanvasState};
+use crate::services::ApiClient;
+use crate::state::{AppState, CanvasState, SelectMode};
 use leptos::prelude::*;
 
 fn get_animation_style(animation: &Option<Animation>) -> String {
@@ -26,29 +27,30 @@ pub fn ComponentRenderer(
     let component_id = *component.id();
     let preview_mode = app_state.ui.preview_mode;
 
-    let is_selected = Memo::new(move |_| {
-        !preview_mode.get()
-            && canvas_state
-                .selected
-                .get()
-                .as_ref()
-                .map(|id| id == &component_id)
-                .unwrap_or(false)
-    });
+    // Hidden components stay in the tree but are not drawn
+    if component.editor().hidden {
+        return ().into_any();
+    }
+
+    let is_selected =
+        Memo::new(move |_| !preview_mode.get() && canvas_state.is_selected(&component_id));
 
     let on_click = move |ev: leptos::ev::MouseEvent| {
+        // A click on a locked component falls through to what is around it
+        if canvas_state.is_locked(&component_id) {
+            return;
+        }
         ev.stop_propagation();
         if !preview_mode.get() {
-            canvas_state.selected.set(Some(component_id));
+            canvas_state.click_select(component_id, SelectMode::from_mouse(&ev));
         }
     };
 
-    let class = move || {
-        if is_selected.get() {
-            "canvas-component selected"
-        } else {
-            "canvas-component"
-        }
+    let locked = Memo::new(move |_| canvas_state.is_locked(&component_id));
+    let class = move || match (is_selected.get(), locked.get()) {
+        (true, _) => "canvas-component selected",
+        (false, true) => "canvas-component locked",
+        (false, false) => "canvas-component",
     };
 
     let component_type_label = match component.component_type() {
@@ -84,7 +86,7 @@ pub fn ComponentRenderer(
                 CanvasComponent::Custom(custom) => render_custom(custom).into_any(),
             }}
         </div>
-    }
+    }.into_any()
 }
 
 fn render_button(button: ButtonComponent) -> impl IntoView {
@@ -358,10 +360,16 @@ fn render_container(container: ContainerComponent, canvas_state: CanvasState) ->
 fn render_image(image: ImageComponent) -> impl IntoView {
     let anim_style = get_animation_style(&image.animation);
     let custom_style = image.style.to_css_string();
+    let client = ApiClient::new();
+    let src = client.resolve_src(&image.src);
+    let srcset = image.srcset_attr(|s| client.resolve_src(s));
+    let sizes = srcset.as_ref().map(|_| image.sizes_attr());
 
     view! {
         <img
-            src=image.src
+            src=src
+            srcset=srcset
+            sizes=sizes
             alt=image.alt
             class="canvas-image"
             style:width=image.width
@@ -5,6 +5,7 @@ use crate::services::export_advanced::{
 use crate::services::export_service::{
     CodeGenerator, HtmlCodeGenerator, LeptosCodeGenerator, MarkdownCodeGenerator,
 };
+use crate::services::localize_asset_urls;
 use crate::state::app_state::{AppState, Notification};
 use crate::utils::copy_to_clipboard;
 use crate::utils::file::download_file;
@@ -18,11 +19,11 @@ pub fn CodePanel() -> impl IntoView {
 
     // Memoize code generation to avoid constant re-rendering
     let code = Memo::new(move |_| {
-        let comps = app_state.canvas.components.get();
+        let comps = app_state.export_components();
         let variables = app_state.variables.get();
         let selected_format = format.get();
 
-        match selected_format.as_str() {
+        let code = match selected_format.as_str() {
             "leptos" => {
                 let generator = LeptosCodeGenerator::new(crate::state::ExportPreset::Plain);
                 generator
@@ -86,7 +87,8 @@ pub fn CodePanel() -> impl IntoView {
                     .unwrap_or_else(|e| e.user_message())
             }
             _ => "Unknown format".to_string(),
-        }
+        };
+        localize_asset_urls(&code)
     });
 
     let copy_handler = move |_| {
@@ -84,6 +84,12 @@ fn get_commands() -> Vec<Command> {
         Command::new("copy", "Copy", "Edit", KeyboardAction::Copy),
         // Paste
         Command::new("paste", "Paste", "Edit", KeyboardAction::Paste),
+        Command::new(
+            "find_replace",
+            "Find and Replace",
+            "Edit",
+            KeyboardAction::FindReplace,
+        ),
         // Selection
         Command::new(
             "select_all",
@@ -154,6 +160,25 @@ fn get_commands() -> Vec<Command> {
             "Arrange",
             KeyboardAction::MoveDown,
         ),
+        Command::new(
+            "group_container",
+            "Group into Container",
+            "Arrange",
+            KeyboardAction::GroupIntoContainer,
+        ),
+        Command::new(
+            "group_card",
+            "Group into Card",
+            "Arrange",
+            KeyboardAction::GroupIntoCard,
+        ),
+        Command::new("ungroup", "Ungroup", "Arrange", KeyboardAction::Ungroup),
+        Command::new(
+            "ungroup_keep_spacing",
+            "Ungroup, Keep Spacing",
+            "Arrange",
+            KeyboardAction::UngroupKeepSpacing,
+        ),
     ]
 }
 
@@ -1,4 +1,5 @@
 use crate::domain::ComponentId;
+use crate::state::GroupKind;
 use leptos::prelude::*;
 
 #[component]
@@ -11,6 +12,12 @@ pub fn ContextMenu(
     on_duplicate: Callback<ComponentId>,
     on_select_parent: Callback<ComponentId>,
     #[prop(optional)] on_save_custom: Option<Callback<ComponentId>>,
+    /// Group the selection; hidden when not given
+    #[prop(optional)]
+    on_group: Option<Callback<GroupKind>>,
+    /// Ungroup the selection, with whether to keep its spacing
+    #[prop(optional)]
+    on_ungroup: Option<Callback<bool>>,
 ) -> impl IntoView {
     // We attach a click listener to the window to close the menu when clicking outside
     // This is handled by the parent component or via a global listener, but usually
@@ -63,6 +70,34 @@ pub fn ContextMenu(
                 <span>"📋"</span> "Duplicate"
             </button>
 
+            {on_group.map(|on_group| {
+                [GroupKind::Container, GroupKind::Card].map(|kind| view! {
+                    <button
+                        class="w-full text-left px-4 py-2 hover:bg-blue-50 text-sm text-gray-700 flex items-center gap-2 transition-colors"
+                        on:click=move |_| {
+                            on_group.run(kind);
+                            on_close.run(());
+                        }
+                    >
+                        <span>"📦"</span> {format!("Group into {}", kind.label())}
+                    </button>
+                })
+            })}
+
+            {on_ungroup.map(|on_ungroup| {
+                [(false, "Ungroup"), (true, "Ungroup, Keep Spacing")].map(|(keep_spacing, label)| view! {
+                    <button
+                        class="w-full text-left px-4 py-2 hover:bg-blue-50 text-sm text-gray-700 flex items-center gap-2 transition-colors"
+                        on:click=move |_| {
+                            on_ungroup.run(keep_spacing);
+                            on_close.run(());
+                        }
+                    >
+                        <span>"📤"</span> {label}
+                    </button>
+                })
+            })}
+
             <Show when=move || on_save_custom.is_some()>
                 <button
                     class="w-full text-left px-4 py-2 hover:bg-purple-50 text-sm text-gray-700 flex items-center gap-2 transition-colors"
@@ -19,9 +19,10 @@ pub fn DebugPanel() -> impl IntoView {
                         .notify(Notification::success("Analytics synced".to_string()));
                 }
                 Err(e) => {
-                    app_state
-                        .ui
-                        .notify(Notification::error(format!("Sync failed: {}", e)));
+                    app_state.ui.notify(Notification::error(format!(
+                        "Sync failed: {}",
+                        e.user_message()
+                    )));
                 }
             }
             sync_loading.set(false);
@@ -1,7 +1,47 @@
-use crate::state::app_state::Notification;
+use crate::builder::breakpoint_editor::{BreakpointState, default_breakpoints};
+use crate::builder::hooks::use_export_actions::{DEFAULT_EXPORT_BREAKPOINT, generate_export};
+use crate::domain::AppResult;
+use crate::services::{
+    ApiClient, AssetData, BUNDLE_ASSET_DIR, build_zip, localize_asset_urls, referenced_assets,
+};
+use crate::state::app_state::{AppState, Notification};
 use crate::utils::copy_to_clipboard;
+use crate::utils::file::{download_bytes, download_file};
 use leptos::prelude::*;
 
+/// File extension and MIME type for an export format
+fn format_file_type(format: &str) -> (&'static str, &'static str) {
+    match format {
+        "leptos" => ("rs", "text/plain"),
+        "react" => ("tsx", "text/plain"),
+        "vue" => ("vue", "text/plain"),
+        "svelte" => ("svelte", "text/plain"),
+        "html" | "tailwind" => ("html", "text/html"),
+        "json" | "jsonschema" => ("json", "application/json"),
+        "typescript" => ("ts", "text/plain"),
+        "markdown" => ("md", "text/markdown"),
+        "svg" => ("svg", "image/svg+xml"),
+        "pdf" => ("pdf", "application/pdf"),
+        _ => ("txt", "text/plain"),
+    }
+}
+
+/// Formats drawn from the computed layout rather than generated as code
+fn is_visual(format: &str) -> bool {
+    matches!(format, "svg" | "pdf")
+}
+
+/// Download the given assets from the backend
+async fn fetch_assets(ids: Vec<String>) -> AppResult<AssetData> {
+    let client = ApiClient::new();
+    let mut assets = AssetData::new();
+    for id in ids {
+        let bytes = client.fetch_asset(&id).await?;
+        assets.insert(id, bytes);
+    }
+    Ok(assets)
+}
+
 #[component]
 pub fn ExportModal(
     show: RwSignal<bool>,
@@ -31,42 +71,105 @@ pub fn ExportModal(
         });
     };
 
-    let download_handler = move |_| {
-        let code_text = code.get();
-        let selected_format = format.get();
-        let mime = match selected_format.as_str() {
-            "html" => "text/html",
-            "markdown" => "text/markdown",
-            "json" => "application/json",
-            _ => "text/plain",
-        };
+    let app_state = AppState::expect_context();
+    // Uploaded images referenced by the canvas; exported code points at `assets/<id>`
+    let asset_ids = Memo::new(move |_| referenced_assets(&app_state.export_components()));
 
-        // Use the shared download utility instead of window.open data URI
-        use crate::utils::file::download_file;
-        let ext = match selected_format.as_str() {
-            "leptos" => "rs",
-            "react" => "tsx",
-            "vue" => "vue",
-            "svelte" => "svelte",
-            "html" | "tailwind" => "html",
-            "json" | "jsonschema" => "json",
-            "typescript" => "ts",
-            "markdown" => "md",
-            _ => "txt",
-        };
+    // SVG and PDF exports are laid out at the editor's breakpoints
+    let breakpoints = use_context::<BreakpointState>()
+        .map(|state| state.breakpoints)
+        .unwrap_or_else(|| RwSignal::new(default_breakpoints()));
+    let breakpoint = RwSignal::new(DEFAULT_EXPORT_BREAKPOINT.to_string());
+
+    let render = move |assets: &AssetData| {
+        generate_export(
+            &format.get_untracked(),
+            &untrack(|| app_state.export_components()),
+            &app_state.variables.get_untracked(),
+            &breakpoints.get_untracked(),
+            &breakpoint.get_untracked(),
+            assets,
+        )
+    };
+
+    // Regenerate when the format, breakpoint or design changes
+    Effect::new(move |_| {
+        format.track();
+        breakpoint.track();
+        breakpoints.track();
+        app_state.canvas.components.track();
+        app_state.variables.track();
+        code.set(localize_asset_urls(&render(&AssetData::new())));
+    });
 
+    let download_handler = move |_| {
+        let selected = format.get();
+        let (ext, mime) = format_file_type(&selected);
         let filename = format!("leptos-export.{}", ext);
-        if let Err(e) = download_file(&code_text, &filename, mime) {
-            notification_signal.set(Some(Notification::error(format!(
-                "❌ Download failed: {}",
-                e.user_message()
-            ))));
-        } else {
-            notification_signal.set(Some(Notification::success(format!(
+        let notif_signal = notification_signal;
+        let save = move |content: &str| match download_file(content, &filename, mime) {
+            Ok(()) => notif_signal.set(Some(Notification::success(format!(
                 "⬇️ Downloaded {}",
                 filename
-            ))));
+            )))),
+            Err(e) => notif_signal.set(Some(Notification::error(format!(
+                "❌ Download failed: {}",
+                e.user_message()
+            )))),
+        };
+
+        let ids = asset_ids.get();
+        if !is_visual(&selected) || ids.is_empty() {
+            save(&code.get());
+            return;
         }
+
+        // Visual exports are standalone files, so uploaded images go inside them
+        wasm_bindgen_futures::spawn_local(async move {
+            match fetch_assets(ids).await {
+                Ok(assets) => save(&render(&assets)),
+                Err(e) => notif_signal.set(Some(Notification::error(format!(
+                    "❌ Failed to fetch assets: {}",
+                    e.user_message()
+                )))),
+            }
+        });
+    };
+
+    let bundle_handler = move |_| {
+        let code_text = code.get();
+        let (ext, _) = format_file_type(&format.get());
+        let ids = asset_ids.get();
+        let notif_signal = notification_signal;
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let client = ApiClient::new();
+            let mut entries = vec![(format!("leptos-export.{}", ext), code_text.into_bytes())];
+            for id in ids {
+                match client.fetch_asset(&id).await {
+                    Ok(bytes) => entries.push((format!("{}{}", BUNDLE_ASSET_DIR, id), bytes)),
+                    Err(e) => {
+                        notif_signal.set(Some(Notification::error(format!(
+                            "❌ Failed to fetch asset {}: {}",
+                            id,
+                            e.user_message()
+                        ))));
+                        return;
+                    }
+                }
+            }
+
+            let zip = build_zip(&entries);
+            match download_bytes(&zip, "leptos-export.zip", "application/zip") {
+                Ok(()) => notif_signal.set(Some(Notification::success(
+                    "📦 Downloaded leptos-export.zip".to_string(),
+                ))),
+                Err(e) => notif_signal.set(Some(Notification::error(format!(
+                    "❌ Download failed: {}",
+                    e.user_message()
+                )))),
+            }
+        });
     };
 
     view! {
@@ -103,7 +206,36 @@ pub fn ExportModal(
                         <optgroup label="Documentation">
                             <option value="markdown">{"Markdown"}</option>
                         </optgroup>
+                        <optgroup label="Design Review">
+                            <option value="svg">{"SVG Image"}</option>
+                            <option value="pdf">{"PDF (one page per breakpoint)"}</option>
+                        </optgroup>
                     </select>
+                    <Show when=move || format.get() == "svg">
+                        <label for="export-breakpoint">{"Breakpoint"}</label>
+                        <select
+                            id="export-breakpoint"
+                            prop:value=breakpoint
+                            on:input=move |ev| breakpoint.set(event_target_value(&ev))
+                            style="margin-bottom:1em;width:100%;padding:0.5rem;"
+                        >
+                            {move || {
+                                breakpoints
+                                    .get()
+                                    .into_iter()
+                                    .map(|bp| {
+                                        let label = format!("{} ({}px)", bp.name, bp.min_width);
+                                        view! { <option value=bp.id>{label}</option> }
+                                    })
+                                    .collect_view()
+                            }}
+                        </select>
+                    </Show>
+                    <Show when=move || format.get() == "pdf">
+                        <p style="margin-bottom:1em;font-size:0.875rem;">
+                            {"JPEG and PNG images are embedded. GIF, WebP and SVG images show as placeholders."}
+                        </p>
+                    </Show>
                     <textarea
                         style="width:100%;height:300px;margin-bottom:0.75rem;font-family:monospace;font-size:0.875rem;"
                         readonly
@@ -114,6 +246,11 @@ pub fn ExportModal(
                     <div style="display:flex;justify-content:flex-end;gap:0.5rem;">
                         <button on:click=copy_handler class="btn btn-secondary">{"📋 Copy"}</button>
                         <button on:click=download_handler class="btn btn-secondary">{"⬇️ Download"}</button>
+                        <Show when=move || !asset_ids.get().is_empty()>
+                            <button on:click=bundle_handler class="btn btn-secondary">
+                                {"📦 Download with Assets"}
+                            </button>
+                        </Show>
                         <button on:click=move |_| on_close.run(()) class="btn btn-outline">{"Close"}</button>
                     </div>
                 </div>
@@ -0,0 +1,232 @@
+use leptos::prelude::*;
+
+use crate::domain::ComponentId;
+use crate::state::app_state::{AppState, Notification};
+use crate::state::find_replace::{Matcher, SearchMatch, SearchOptions, SearchScope};
+
+/// Matches listed at most; Replace All still covers the rest
+const MAX_LISTED_MATCHES: usize = 200;
+
+/// Characters of context shown around each match
+const EXCERPT_CONTEXT: usize = 24;
+
+/// Scroll the canvas to a component
+fn scroll_to(id: &ComponentId) {
+    let selector = format!("[data-component-id=\"{}\"]", id);
+    if let Ok(Some(element)) = document().query_selector(&selector) {
+        element.scroll_into_view();
+    }
+}
+
+/// Find and replace across component text
+#[component]
+pub fn FindReplacePanel() -> impl IntoView {
+    let app_state = AppState::expect_context();
+    let show = app_state.ui.show_find_replace;
+
+    let query = RwSignal::new(String::new());
+    let replacement = RwSignal::new(String::new());
+    let options = RwSignal::new(SearchOptions::default());
+    let scope = RwSignal::new(SearchScope::Project);
+
+    let matcher = move || Matcher::new(&query.get(), options.get());
+    let matches = Memo::new(move |_| {
+        matcher()
+            .map(|matcher| app_state.canvas.find(&matcher, scope.get()))
+            .unwrap_or_default()
+    });
+    let error = move || {
+        matcher()
+            .err()
+            .filter(|_| !query.with(String::is_empty))
+            .map(|e| e.to_string())
+    };
+
+    let close = move || show.set(false);
+
+    // In selection scope the selection is what is being searched, so a
+    // jump only scrolls
+    let jump = move |id: ComponentId| {
+        if scope.get_untracked() == SearchScope::Project {
+            app_state.canvas.select(id);
+        }
+        scroll_to(&id);
+    };
+
+    let replace_one = move |found: SearchMatch| {
+        let Ok(matcher) = Matcher::new(&query.get_untracked(), options.get_untracked()) else {
+            return;
+        };
+        if !app_state
+            .canvas
+            .replace_match(&found, &matcher, &replacement.get_untracked())
+        {
+            app_state.ui.notify(Notification::warning(
+                "⚠️ That text has changed since the search".to_string(),
+            ));
+        }
+    };
+
+    let replace_all = move |_| {
+        let Ok(matcher) = Matcher::new(&query.get_untracked(), options.get_untracked()) else {
+            return;
+        };
+        let count = app_state.canvas.replace_all(
+            &matcher,
+            &replacement.get_untracked(),
+            scope.get_untracked(),
+        );
+        let notification = match count {
+            0 => Notification::info("No matches to replace".to_string()),
+            1 => Notification::success("🔁 Replaced 1 match".to_string()),
+            count => Notification::success(format!("🔁 Replaced {} matches", count)),
+        };
+        app_state.ui.notify(notification);
+    };
+
+    let toggle = move |label: &'static str,
+                       title: &'static str,
+                       get: fn(&SearchOptions) -> bool,
+                       set: fn(&mut SearchOptions)| {
+        view! {
+            <button
+                class=move || if options.with(get) { "find-option active" } else { "find-option" }
+                title=title
+                aria-label=title
+                aria-pressed=move || options.with(get).to_string()
+                on:click=move |_| options.update(set)
+            >
+                {label}
+            </button>
+        }
+    };
+
+    view! {
+        <Show when=move || show.get()>
+            <div
+                class="find-replace-panel"
+                role="dialog"
+                aria-label="Find and replace"
+                on:keydown=move |ev: leptos::ev::KeyboardEvent| {
+                    if ev.key() == "Escape" {
+                        ev.stop_propagation();
+                        close();
+                    }
+                }
+            >
+                <div class="find-replace-header">
+                    <h4>"Find and Replace"</h4>
+                    <button class="close-btn" aria-label="Close" on:click=move |_| close()>"×"</button>
+                </div>
+
+                <div class="find-replace-row">
+                    <input
+                        type="text"
+                        placeholder="Find"
+                        aria-label="Find"
+                        autofocus=true
+                        prop:value=move || query.get()
+                        on:input=move |ev| query.set(event_target_value(&ev))
+                    />
+                    {toggle("Aa", "Match case", |o| o.case_sensitive, |o| o.case_sensitive = !o.case_sensitive)}
+                    {toggle("W", "Whole word", |o| o.whole_word, |o| o.whole_word = !o.whole_word)}
+                    {toggle(".*", "Regular expression", |o| o.regex, |o| o.regex = !o.regex)}
+                </div>
+                <div class="find-replace-row">
+                    <input
+                        type="text"
+                        placeholder="Replace"
+                        aria-label="Replace"
+                        prop:value=move || replacement.get()
+                        on:input=move |ev| replacement.set(event_target_value(&ev))
+                    />
+                    <button
+                        class="btn btn-primary"
+                        disabled=move || matches.with(Vec::is_empty)
+                        on:click=replace_all
+                    >
+                        "Replace All"
+                    </button>
+                </div>
+                <div class="find-replace-row">
+                    <select
+                        aria-label="Search in"
+                        on:change=move |ev| {
+                            scope.set(if event_target_value(&ev) == "selection" {
+                                SearchScope::Selection
+                            } else {
+                                SearchScope::Project
+                            })
+                        }
+                    >
+                        <option value="project" selected=move || scope.get() == SearchScope::Project>"Whole project"</option>
+                        <option value="selection" selected=move || scope.get() == SearchScope::Selection>"Selection"</option>
+                    </select>
+                    <span class="find-replace-count" role="status">
+                        {move || match error() {
+                            Some(message) => message,
+                            None => match matches.with(Vec::len) {
+                                1 => "1 match".to_string(),
+                                count => format!("{} matches", count),
+                            },
+                        }}
+                    </span>
+                </div>
+
+                <ul class="find-replace-results">
+                    {move || {
+                        let replacement = replacement.get();
+                        let Ok(matcher) = matcher() else {
+                            return Vec::new();
+                        };
+                        matches
+                            .get()
+                            .into_iter()
+                            .take(MAX_LISTED_MATCHES)
+                            .map(|found| {
+                                let (before, matched, after) = found.excerpt(EXCERPT_CONTEXT);
+                                let matched = matched.to_string();
+                                let replaced = (!replacement.is_empty()).then(|| {
+                                    matcher.replacement_for(&found.text, found.range.clone(), &replacement)
+                                });
+                                let id = found.id;
+                                view! {
+                                    <li class="find-replace-result">
+                                        <button
+                                            class="find-replace-jump"
+                                            title="Go to component"
+                                            on:click=move |_| jump(id)
+                                        >
+                                            <span class="find-replace-where">
+                                                {format!("{} · {}", found.kind, found.field.label())}
+                                            </span>
+                                            <span class="find-replace-excerpt">
+                                                {before}
+                                                {match replaced {
+                                                    Some(replaced) => view! {
+                                                        <del>{matched}</del>
+                                                        <ins>{replaced}</ins>
+                                                    }.into_any(),
+                                                    None => view! { <mark>{matched}</mark> }.into_any(),
+                                                }}
+                                                {after}
+                                            </span>
+                                        </button>
+                                        <button
+                                            class="find-replace-one"
+                                            title="Replace"
+                                            aria-label="Replace this match"
+                                            on:click=move |_| replace_one(found.clone())
+                                        >
+                                            "⇄"
+                                        </button>
+                                    </li>
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    }}
+                </ul>
+            </div>
+        </Show>
+    }
+}
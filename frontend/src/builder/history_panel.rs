@@ -1,4 +1,5 @@
 use crate::state::app_state::AppState;
+use crate::state::history::{HistoryNodeState, NodeId};
 use leptos::prelude::*;
 
 #[component]
@@ -17,26 +18,12 @@ pub fn HistoryPanel() -> impl IntoView {
         )
     };
 
-    // Memoize the history list to avoid issues inside view macro
-    let history_list = Memo::new(move |_| {
-        // get_undo_stack returns reversed list (Newest -> Oldest)
-        let stack = history.get().get_undo_stack();
-        let len = stack.len();
+    // Memoize the history tree to avoid issues inside view macro
+    let history_rows = Memo::new(move |_| history.with(|h| h.rows()));
+    let at_start = Memo::new(move |_| history.with(|h| h.current().is_none()));
 
-        // Map to (internal_index, snapshot)
-        // stack[0] is newest. internal index should be len - 1.
-        // stack[len-1] is oldest. internal index should be 0.
-        stack
-            .into_iter()
-            .enumerate()
-            .map(|(i, snap)| (len - 1 - i, snap))
-            .collect::<Vec<_>>()
-    });
-
-    let restore = move |index: usize| {
-        // Index comes from the enumeration of the original stack order
-        if let Some(snapshot) = history.write().restore_to_index(index) {
-            app_state.canvas.apply_snapshot(&snapshot);
+    let jump = move |node: Option<NodeId>| {
+        if app_state.canvas.jump_to(node) {
             app_state
                 .ui
                 .notify(crate::state::app_state::Notification::info(
@@ -48,27 +35,41 @@ pub fn HistoryPanel() -> impl IntoView {
     view! {
         <div class="history-panel">
             <h4 class="panel-title">"History"</h4>
-            <div class="history-list">
+            <div class="history-list history-tree">
+                <div
+                    class="history-item clickable"
+                    class:history-current=move || at_start.get()
+                    on:click=move |_| jump(None)
+                    title="Click to go back to the start"
+                >
+                    <span class="history-desc">"Start"</span>
+                </div>
                 <For
-                    each=move || history_list.get()
-                    key=|(i, _)| *i
-                    children=move |(i, snapshot)| {
+                    each=move || history_rows.get()
+                    key=|row| (row.id, row.depth, row.state)
+                    children=move |row| {
+                        let state_class = match row.state {
+                            HistoryNodeState::Current => "history-current",
+                            HistoryNodeState::Applied => "history-applied",
+                            HistoryNodeState::Redo => "history-redo",
+                            HistoryNodeState::Branch => "history-branch",
+                        };
                         view! {
                             <div
-                                class="history-item clickable"
-                                on:click=move |_| restore(i)
+                                class=format!("history-item clickable {}", state_class)
+                                style=format!("--history-depth: {}", row.depth)
+                                on:click=move |_| jump(Some(row.id))
                                 title="Click to restore this state"
                             >
-                                <span class="history-time">{format_time(snapshot.timestamp)}</span>
-                                <span class="history-desc">{snapshot.description}</span>
+                                <span class="history-time">{format_time(row.timestamp)}</span>
+                                <span class="history-desc">{row.description}</span>
                             </div>
                         }
                     }
                 />
             </div>
             {move || {
-                let h = history.get();
-                if !h.can_undo() && !h.can_redo() {
+                if history.with(|h| h.is_empty()) {
                     view! { <div class="history-empty">"No history yet"</div> }.into_any()
                 } else {
                     view! { <div></div> }.into_any()
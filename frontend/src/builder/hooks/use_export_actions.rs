@@ -1,3 +1,5 @@
+use crate::builder::breakpoint_editor::{Breakpoint, default_breakpoints};
+use crate::domain::{CanvasComponent, Variable};
 use crate::services::export_advanced::{
     JsonSchemaGenerator, ReactGenerator, SvelteGenerator, TailwindHtmlGenerator,
     TypeScriptGenerator,
@@ -5,9 +7,107 @@ use crate::services::export_advanced::{
 use crate::services::export_service::{
     CodeGenerator, HtmlCodeGenerator, LeptosCodeGenerator, MarkdownCodeGenerator,
 };
+use crate::services::{AssetData, ExportPage, PdfGenerator, SvgGenerator, localize_asset_urls};
 use crate::state::app_state::AppState;
 use leptos::prelude::*;
 
+/// Page width for SVG exports when the breakpoint is unknown
+const DEFAULT_EXPORT_WIDTH: u32 = 1024;
+
+/// Breakpoint the SVG export is laid out at by default
+pub const DEFAULT_EXPORT_BREAKPOINT: &str = "lg";
+
+/// Generate the export output for `format`.
+///
+/// SVG exports are laid out at `breakpoint`; PDF exports get a page for each
+/// of `breakpoints`. Both embed the images in `assets`.
+pub fn generate_export(
+    format: &str,
+    components: &[CanvasComponent],
+    variables: &[Variable],
+    breakpoints: &[Breakpoint],
+    breakpoint: &str,
+    assets: &AssetData,
+) -> String {
+    match format {
+        "leptos" => {
+            let generator = LeptosCodeGenerator::new(crate::state::ExportPreset::Plain);
+            generator
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        "html" => {
+            let generator = HtmlCodeGenerator;
+            generator
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        "markdown" => {
+            let generator = MarkdownCodeGenerator;
+            generator
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        "json" => serde_json::to_string_pretty(components)
+            .unwrap_or_else(|e| format!("Error serializing JSON: {}", e)),
+        "jsonschema" => {
+            let generator = JsonSchemaGenerator;
+            generator
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        "typescript" => {
+            let generator = TypeScriptGenerator;
+            generator
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        "react" => {
+            let generator = ReactGenerator;
+            generator
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        "tailwind" => {
+            let generator = TailwindHtmlGenerator;
+            generator
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        "svelte" => {
+            let generator = SvelteGenerator;
+            generator
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        "svg" => {
+            let width = breakpoints
+                .iter()
+                .find(|b| b.id == breakpoint)
+                .map_or(DEFAULT_EXPORT_WIDTH, |b| b.min_width);
+            SvgGenerator::new(width)
+                .with_assets(assets.clone())
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        "pdf" => {
+            let mut pages: Vec<ExportPage> = breakpoints
+                .iter()
+                .map(|b| ExportPage {
+                    title: format!("{} · {}px", b.name, b.min_width),
+                    width: b.min_width,
+                })
+                .collect();
+            pages.sort_by_key(|p| p.width);
+            PdfGenerator::new(pages)
+                .with_assets(assets.clone())
+                .generate(components, variables)
+                .unwrap_or_else(|e| e.user_message())
+        }
+        _ => "Unknown template".to_string(),
+    }
+}
+
 pub fn use_export_actions(
     show_export: WriteSignal<bool>,
     export_code: WriteSignal<String>,
@@ -16,64 +116,16 @@ pub fn use_export_actions(
     let app_state = AppState::expect_context();
 
     move |_| {
-        let comps = app_state.canvas.components.get();
-        let variables = app_state.variables.get();
-
-        let code = match export_template.get().as_str() {
-            "leptos" => {
-                let generator = LeptosCodeGenerator::new(crate::state::ExportPreset::Plain);
-                generator
-                    .generate(&comps, &variables)
-                    .unwrap_or_else(|e| e.user_message())
-            }
-            "html" => {
-                let generator = HtmlCodeGenerator;
-                generator
-                    .generate(&comps, &variables)
-                    .unwrap_or_else(|e| e.user_message())
-            }
-            "markdown" => {
-                let generator = MarkdownCodeGenerator;
-                generator
-                    .generate(&comps, &variables)
-                    .unwrap_or_else(|e| e.user_message())
-            }
-            "json" => serde_json::to_string_pretty(&comps)
-                .unwrap_or_else(|e| format!("Error serializing JSON: {}", e)),
-            "jsonschema" => {
-                let generator = JsonSchemaGenerator;
-                generator
-                    .generate(&comps, &variables)
-                    .unwrap_or_else(|e| e.user_message())
-            }
-            "typescript" => {
-                let generator = TypeScriptGenerator;
-                generator
-                    .generate(&comps, &variables)
-                    .unwrap_or_else(|e| e.user_message())
-            }
-            "react" => {
-                let generator = ReactGenerator;
-                generator
-                    .generate(&comps, &variables)
-                    .unwrap_or_else(|e| e.user_message())
-            }
-            "tailwind" => {
-                let generator = TailwindHtmlGenerator;
-                generator
-                    .generate(&comps, &variables)
-                    .unwrap_or_else(|e| e.user_message())
-            }
-            "svelte" => {
-                let generator = SvelteGenerator;
-                generator
-                    .generate(&comps, &variables)
-                    .unwrap_or_else(|e| e.user_message())
-            }
-            _ => "Unknown template".to_string(),
-        };
+        let code = generate_export(
+            &export_template.get(),
+            &app_state.export_components(),
+            &app_state.variables.get(),
+            &default_breakpoints(),
+            DEFAULT_EXPORT_BREAKPOINT,
+            &AssetData::new(),
+        );
 
-        export_code.set(code);
+        export_code.set(localize_asset_urls(&code));
         show_export.set(true);
     }
 }
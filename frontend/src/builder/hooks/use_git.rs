@@ -1,3 +1,4 @@
+use crate::domain::AppError;
 use crate::services::git_factory::get_git_backend;
 use crate::services::{CommitInfo, RepoStatus};
 use crate::state::{AppState, Notification};
@@ -97,12 +98,23 @@ pub fn use_git() -> UseGitReturn {
 
         is_committing.set(true);
         let project_id = app_state.current_project_id.get();
-        let backend = get_git_backend(project_id);
+        let backend = get_git_backend(project_id.clone());
         let project = app_state.to_project();
 
         wasm_bindgen_futures::spawn_local(async move {
-            match backend.commit(&project, &message).await {
-                Ok(()) => {
+            match (
+                backend.commit(&project, &message).await,
+                project_id.as_deref(),
+            ) {
+                // Recorded once the backend is reachable again
+                (Err(AppError::Offline(_)), Some(id)) => {
+                    app_state.sync.queue_commit(id, &message, project);
+                    app_state.ui.notify(Notification::info(format!(
+                        "Offline: commit \"{}\" will be recorded when the backend is back",
+                        message
+                    )));
+                }
+                (Ok(()), _) => {
                     app_state.ui.notify(Notification::success(format!(
                         "Commit recorded: {}",
                         message
@@ -120,7 +132,7 @@ pub fn use_git() -> UseGitReturn {
                         log_data.set(logs);
                     }
                 }
-                Err(e) => app_state.ui.notify(Notification::error(e.user_message())),
+                (Err(e), _) => app_state.ui.notify(Notification::error(e.user_message())),
             }
             is_committing.set(false);
         });
@@ -2,8 +2,8 @@ use crate::builder::component_library::create_canvas_component;
 use crate::builder::keyboard::KeyboardAction;
 use crate::domain::CanvasComponent;
 use crate::services::export_service::{CodeGenerator, LeptosCodeGenerator};
-use crate::state::ExportPreset;
 use crate::state::app_state::{AppState, Notification};
+use crate::state::{ExportPreset, GroupKind};
 use crate::utils::{copy_to_clipboard, read_from_clipboard};
 use leptos::prelude::*;
 
@@ -17,8 +17,7 @@ pub fn use_keyboard_actions(
     move |action: KeyboardAction| {
         match action {
             KeyboardAction::Undo => {
-                if let Some(snapshot) = app_state.canvas.history.write().undo() {
-                    app_state.canvas.apply_snapshot(&snapshot);
+                if app_state.canvas.undo() {
                     app_state
                         .ui
                         .notification
@@ -30,8 +29,7 @@ pub fn use_keyboard_actions(
                 }
             }
             KeyboardAction::Redo => {
-                if let Some(snapshot) = app_state.canvas.history.write().redo() {
-                    app_state.canvas.apply_snapshot(&snapshot);
+                if app_state.canvas.redo() {
                     app_state
                         .ui
                         .notification
@@ -45,82 +43,57 @@ pub fn use_keyboard_actions(
             KeyboardAction::Save => {
                 app_state.save();
             }
-            KeyboardAction::Delete => {
-                if let Some(selected_id) = app_state.canvas.selected.get() {
-                    // remove_component already records snapshot
-                    app_state.canvas.remove_component(&selected_id);
-                    app_state.canvas.selected.set(None);
-                    app_state.ui.notification.set(Some(Notification::success(
-                        "🗑️ Component deleted".to_string(),
-                    )));
-                } else {
+            KeyboardAction::Delete => match app_state.canvas.remove_selected() {
+                0 => {
                     app_state.ui.notification.set(Some(Notification::warning(
                         "⚠️ No component selected".to_string(),
                     )));
                 }
-            }
-            KeyboardAction::Copy => {
-                if let Some(selected_id) = app_state.canvas.selected.get() {
-                    if let Some(comp) = app_state.canvas.get_component(&selected_id) {
-                        match serde_json::to_string(&comp) {
-                            Ok(json) => {
-                                let app_state_clone = app_state;
-                                wasm_bindgen_futures::spawn_local(async move {
-                                    match copy_to_clipboard(&json).await {
-                                        Ok(()) => {
-                                            app_state_clone.ui.notification.set(Some(
-                                                Notification::success(
-                                                    "📋 Component copied!".to_string(),
-                                                ),
-                                            ));
-                                        }
-                                        Err(e) => {
-                                            app_state_clone.ui.notification.set(Some(
-                                                Notification::error(format!(
-                                                    "❌ {}",
-                                                    e.user_message()
-                                                )),
-                                            ));
-                                        }
-                                    }
-                                });
-                            }
-                            Err(_) => {
-                                app_state.ui.notification.set(Some(Notification::error(
-                                    "❌ Failed to serialize component".to_string(),
-                                )));
-                            }
-                        }
-                    }
-                } else {
-                    app_state.ui.notification.set(Some(Notification::warning(
-                        "⚠️ No component selected".to_string(),
-                    )));
+                count => {
+                    app_state
+                        .ui
+                        .notification
+                        .set(Some(Notification::success(format!(
+                            "🗑️ {} deleted",
+                            components_label(count)
+                        ))));
                 }
+            },
+            KeyboardAction::Copy => {
+                copy_selection(app_state, false);
             }
             KeyboardAction::Paste => {
                 let app_state_clone = app_state;
                 wasm_bindgen_futures::spawn_local(async move {
                     match read_from_clipboard().await {
-                        Ok(text) => {
-                            match serde_json::from_str::<CanvasComponent>(&text) {
-                                Ok(comp) => {
-                                    // add_component already records snapshot
-                                    app_state_clone.canvas.add_component(comp);
-                                    app_state_clone.ui.notification.set(Some(
-                                        Notification::success("📋 Component pasted!".to_string()),
-                                    ));
-                                }
-                                Err(_) => {
-                                    app_state_clone
-                                        .ui
-                                        .notification
-                                        .set(Some(Notification::error(
-                                            "⚠️ Invalid clipboard content".to_string(),
-                                        )));
-                                }
+                        Ok(text) => match parse_clipboard(&text) {
+                            Some(components) => {
+                                let count = components.len();
+                                // Fresh ids, so pasting next to the originals keeps ids unique
+                                let copies = components
+                                    .iter()
+                                    .map(CanvasComponent::duplicate_with_new_id)
+                                    .collect();
+                                app_state_clone
+                                    .canvas
+                                    .add_components(&describe_paste(count), copies);
+                                app_state_clone
+                                    .ui
+                                    .notification
+                                    .set(Some(Notification::success(format!(
+                                        "📋 {} pasted!",
+                                        components_label(count)
+                                    ))));
                             }
-                        }
+                            None => {
+                                app_state_clone
+                                    .ui
+                                    .notification
+                                    .set(Some(Notification::error(
+                                        "⚠️ Invalid clipboard content".to_string(),
+                                    )));
+                            }
+                        },
                         Err(e) => {
                             app_state_clone
                                 .ui
@@ -133,21 +106,22 @@ pub fn use_keyboard_actions(
                     }
                 });
             }
-            KeyboardAction::Duplicate => {
-                if let Some(selected_id) = app_state.canvas.selected.get() {
-                    if let Some(comp) = app_state.canvas.get_component(&selected_id) {
-                        // add_component already records snapshot
-                        app_state.canvas.add_component(comp);
-                        app_state.ui.notification.set(Some(Notification::success(
-                            "🔄 Component duplicated!".to_string(),
-                        )));
-                    }
-                } else {
+            KeyboardAction::Duplicate => match app_state.canvas.duplicate_selected() {
+                0 => {
                     app_state.ui.notification.set(Some(Notification::warning(
                         "⚠️ No component selected".to_string(),
                     )));
                 }
-            }
+                count => {
+                    app_state
+                        .ui
+                        .notification
+                        .set(Some(Notification::success(format!(
+                            "🔄 {} duplicated!",
+                            components_label(count)
+                        ))));
+                }
+            },
             KeyboardAction::NewComponent => {
                 app_state.ui.notification.set(Some(Notification::info(
                     "ℹ️ Drag component from sidebar to add".to_string(),
@@ -155,7 +129,7 @@ pub fn use_keyboard_actions(
             }
             KeyboardAction::AddComponent(type_name) => {
                 if let Some(comp) = create_canvas_component(&type_name) {
-                    if let Some(selected_id) = app_state.canvas.selected.get() {
+                    if let Some(selected_id) = app_state.canvas.selected() {
                         // Try to add as child if selected is container
                         if app_state
                             .canvas
@@ -190,11 +164,14 @@ pub fn use_keyboard_actions(
             KeyboardAction::OpenCommandPalette => {
                 app_state.ui.show_command_palette.set(true);
             }
+            KeyboardAction::SelectAll => {
+                app_state.canvas.select_all();
+            }
             KeyboardAction::Deselect => {
-                app_state.canvas.selected.set(None);
+                app_state.canvas.clear_selection();
             }
             KeyboardAction::Export => {
-                let comps = app_state.canvas.components.get();
+                let comps = app_state.export_components();
                 let variables = app_state.variables.get();
                 let generator = LeptosCodeGenerator::new(ExportPreset::Plain);
 
@@ -215,61 +192,138 @@ pub fn use_keyboard_actions(
                 }
             }
             KeyboardAction::Cut => {
-                if let Some(selected_id) = app_state.canvas.selected.get() {
-                    if let Some(comp) = app_state.canvas.get_component(&selected_id) {
-                        match serde_json::to_string(&comp) {
-                            Ok(json) => {
-                                let app_state_clone = app_state;
-                                wasm_bindgen_futures::spawn_local(async move {
-                                    match copy_to_clipboard(&json).await {
-                                        Ok(()) => {
-                                            app_state_clone.canvas.remove_component(&selected_id);
-                                            app_state_clone.canvas.selected.set(None);
-                                            app_state_clone.ui.notification.set(Some(
-                                                Notification::success(
-                                                    "✂️ Component cut!".to_string(),
-                                                ),
-                                            ));
-                                        }
-                                        Err(e) => {
-                                            app_state_clone.ui.notification.set(Some(
-                                                Notification::error(format!(
-                                                    "❌ {}",
-                                                    e.user_message()
-                                                )),
-                                            ));
-                                        }
-                                    }
-                                });
-                            }
-                            Err(_) => {
-                                app_state.ui.notification.set(Some(Notification::error(
-                                    "❌ Failed to serialize component".to_string(),
-                                )));
-                            }
-                        }
-                    }
-                } else {
-                    app_state.ui.notification.set(Some(Notification::warning(
-                        "⚠️ No component selected".to_string(),
-                    )));
-                }
+                copy_selection(app_state, true);
             }
             KeyboardAction::ShowShortcuts => {
                 app_state.ui.show_shortcuts_modal.set(true);
             }
             KeyboardAction::MoveUp => {
-                if let Some(id) = app_state.canvas.selected.get() {
-                    app_state.canvas.move_component_up(&id);
-                }
+                app_state.canvas.move_selected(-1);
             }
             KeyboardAction::MoveDown => {
-                if let Some(id) = app_state.canvas.selected.get() {
-                    app_state.canvas.move_component_down(&id);
-                }
+                app_state.canvas.move_selected(1);
+            }
+            KeyboardAction::GroupIntoContainer => group_selection(app_state, GroupKind::Container),
+            KeyboardAction::GroupIntoCard => group_selection(app_state, GroupKind::Card),
+            KeyboardAction::Ungroup => ungroup_selection(app_state, false),
+            KeyboardAction::UngroupKeepSpacing => ungroup_selection(app_state, true),
+            KeyboardAction::FindReplace => {
+                app_state.ui.show_find_replace.set(true);
             }
-            // Add other cases if any
-            _ => {}
         }
     }
 }
+
+/// "Component", or "3 components" for several
+fn components_label(count: usize) -> String {
+    if count == 1 {
+        "Component".to_string()
+    } else {
+        format!("{} components", count)
+    }
+}
+
+fn describe_paste(count: usize) -> String {
+    if count == 1 {
+        "Paste Component".to_string()
+    } else {
+        format!("Paste {} Components", count)
+    }
+}
+
+pub fn group_selection(app_state: AppState, kind: GroupKind) {
+    let notification = if app_state.canvas.group_selected(kind).is_some() {
+        Notification::success(format!("📦 Grouped into {}", kind.label()))
+    } else {
+        Notification::warning("⚠️ Select components that share a parent to group".to_string())
+    };
+    app_state.ui.notification.set(Some(notification));
+}
+
+pub fn ungroup_selection(app_state: AppState, keep_spacing: bool) {
+    let notification = match app_state.canvas.ungroup_selected(keep_spacing) {
+        0 => Notification::warning("⚠️ Select a Container or Card to ungroup".to_string()),
+        1 => Notification::success("📤 Ungrouped".to_string()),
+        count => Notification::success(format!("📤 Ungrouped {} groups", count)),
+    };
+    app_state.ui.notification.set(Some(notification));
+}
+
+/// Components on the clipboard: a list, or a single component as copied
+/// before multi-selection
+fn parse_clipboard(text: &str) -> Option<Vec<CanvasComponent>> {
+    serde_json::from_str::<Vec<CanvasComponent>>(text)
+        .ok()
+        .or_else(|| {
+            serde_json::from_str::<CanvasComponent>(text)
+                .ok()
+                .map(|c| vec![c])
+        })
+        .filter(|components| !components.is_empty())
+}
+
+/// Copy the selection to the clipboard, removing it afterwards for `cut`.
+/// A single component is copied on its own so older builds can paste it.
+fn copy_selection(app_state: AppState, cut: bool) {
+    let components = app_state.canvas.selected_components();
+    let json = match components.as_slice() {
+        [] => {
+            app_state.ui.notification.set(Some(Notification::warning(
+                "⚠️ No component selected".to_string(),
+            )));
+            return;
+        }
+        [component] => serde_json::to_string(component),
+        components => serde_json::to_string(components),
+    };
+    let Ok(json) = json else {
+        app_state.ui.notification.set(Some(Notification::error(
+            "❌ Failed to serialize component".to_string(),
+        )));
+        return;
+    };
+    let count = components.len();
+    wasm_bindgen_futures::spawn_local(async move {
+        match copy_to_clipboard(&json).await {
+            Ok(()) => {
+                let message = if cut {
+                    app_state.canvas.remove_selected();
+                    format!("✂️ {} cut!", components_label(count))
+                } else {
+                    format!("📋 {} copied!", components_label(count))
+                };
+                app_state
+                    .ui
+                    .notification
+                    .set(Some(Notification::success(message)));
+            }
+            Err(e) => {
+                app_state
+                    .ui
+                    .notification
+                    .set(Some(Notification::error(format!(
+                        "❌ {}",
+                        e.user_message()
+                    ))));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ButtonComponent, TextComponent};
+
+    #[test]
+    fn test_parse_clipboard_accepts_lists_and_single_components() {
+        let button = CanvasComponent::Button(ButtonComponent::new("A".to_string()));
+        let text = CanvasComponent::Text(TextComponent::new("B".to_string()));
+        let single = serde_json::to_string(&button).unwrap();
+        assert_eq!(parse_clipboard(&single), Some(vec![button.clone()]));
+        let list = serde_json::to_string(&vec![button.clone(), text.clone()]).unwrap();
+        assert_eq!(parse_clipboard(&list), Some(vec![button, text]));
+        assert_eq!(parse_clipboard("[]"), None);
+        assert_eq!(parse_clipboard("hello"), None);
+    }
+}
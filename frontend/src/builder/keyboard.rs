@@ -33,6 +33,12 @@ pub enum KeyboardAction {
     ShowShortcuts,
     MoveUp,
     MoveDown,
+    GroupIntoContainer,
+    GroupIntoCard,
+    Ungroup,
+    /// Ungroup, turning the group's padding and gap into margins
+    UngroupKeepSpacing,
+    FindReplace,
 }
 
 /// Defines a keyboard shortcut with modifiers and action
@@ -136,6 +142,9 @@ impl KeyboardShortcut {
 /// * **Ctrl+S**: Save
 /// * **Ctrl+E**: Export
 /// * **Ctrl+N**: New component
+/// * **Ctrl+G / Ctrl+Alt+G**: Group into container / card
+/// * **Ctrl+Shift+G**: Ungroup
+/// * **Ctrl+H**: Find and replace
 pub fn get_default_shortcuts() -> Vec<KeyboardShortcut> {
     vec![
         KeyboardShortcut::new(
@@ -300,6 +309,42 @@ pub fn get_default_shortcuts() -> Vec<KeyboardShortcut> {
             KeyboardAction::MoveDown,
             "Move component down",
         ),
+        KeyboardShortcut::new(
+            "g",
+            true,
+            false,
+            false,
+            false,
+            KeyboardAction::GroupIntoContainer,
+            "Group selected into a container",
+        ),
+        KeyboardShortcut::new(
+            "g",
+            true,
+            false,
+            true,
+            false,
+            KeyboardAction::GroupIntoCard,
+            "Group selected into a card",
+        ),
+        KeyboardShortcut::new(
+            "g",
+            true,
+            true,
+            false,
+            false,
+            KeyboardAction::Ungroup,
+            "Ungroup selected container",
+        ),
+        KeyboardShortcut::new(
+            "h",
+            true,
+            false,
+            false,
+            false,
+            KeyboardAction::FindReplace,
+            "Find and replace",
+        ),
     ]
 }
 
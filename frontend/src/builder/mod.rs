@@ -12,6 +12,7 @@ pub mod context_menu;
 pub mod design_tokens;
 pub mod drag_drop;
 pub mod export_modal;
+pub mod find_replace_panel;
 pub mod git_panel;
 pub mod history_panel;
 pub mod hooks;
@@ -21,6 +22,7 @@ pub mod project;
 pub mod property_editor;
 pub mod property_editors;
 pub mod property_inputs;
+pub mod recovery_dialog;
 pub mod responsive_preview;
 pub mod save_template_modal;
 pub mod settings_modal;
@@ -1,4 +1,5 @@
 use crate::domain::CanvasComponent;
+use crate::services::ApiClient;
 use crate::state::app_state::{AppState, ResponsiveMode, Theme};
 use leptos::prelude::*;
 
@@ -76,7 +77,7 @@ fn PreviewNode(component: CanvasComponent) -> impl IntoView {
             </div>
         }.into_any(),
         CanvasComponent::Image(img) => view! {
-            <div><img src=img.src alt=img.alt style="max-width: 100px" /></div>
+            <div><img src=ApiClient::new().resolve_src(&img.src) alt=img.alt style="max-width: 100px" /></div>
         }.into_any(),
         CanvasComponent::Card(card) => view! {
             <div class="card preview-inline-margin">
@@ -1,9 +1,11 @@
 use super::property_editors::{
-    ButtonPropertyEditor, CardPropertyEditor, ContainerPropertyEditor, CustomPropertyEditor,
-    ImagePropertyEditor, InputPropertyEditor, SelectPropertyEditor, TextPropertyEditor,
+    BulkPropertyEditor, ButtonPropertyEditor, CardPropertyEditor, ComponentNameEditor,
+    ContainerPropertyEditor, CustomPropertyEditor, ImagePropertyEditor, InputPropertyEditor,
+    SelectPropertyEditor, TextPropertyEditor,
 };
 use crate::domain::CanvasComponent;
 use crate::state::AppState;
+use leptos::ev;
 use leptos::prelude::*;
 
 #[component]
@@ -13,20 +15,37 @@ pub fn PropertyEditor() -> impl IntoView {
     let canvas_state = app_state.canvas;
 
     let delete_selected = move |_| {
-        if let Some(id) = canvas_state.selected.get() {
-            canvas_state.remove_component(&id);
-            canvas_state.selected.set(None);
-            app_state.ui.notify(crate::state::Notification::info(
-                "Component removed".to_string(),
-            ));
+        let message = match canvas_state.remove_selected() {
+            0 => return,
+            1 => "Component removed".to_string(),
+            count => format!("{} components removed", count),
+        };
+        app_state
+            .ui
+            .notify(crate::state::Notification::info(message));
+    };
+
+    // Everything a single drag changes, like holding a number spinner or
+    // sweeping a color picker, is one undo step
+    let dragging = StoredValue::new(false);
+    let start_drag = move |_| {
+        if !canvas_state.in_transaction() {
+            canvas_state.begin_transaction("Adjust Properties");
+            dragging.set_value(true);
         }
     };
+    let _ = window_event_listener(ev::pointerup, move |_| {
+        if dragging.get_value() {
+            dragging.set_value(false);
+            canvas_state.commit_transaction();
+        }
+    });
 
     view! {
-        <section class="property-editor">
+        <section class="property-editor" on:pointerdown=start_drag>
             <div style="display: flex; justify-content: space-between; align-items: center; border-bottom: 2px solid #e2e8f0; margin-bottom: 16px; padding-bottom: 12px;">
                 <h3 style="margin: 0; border: none; padding: 0;">{"Property Editor"}</h3>
-                {move || if canvas_state.selected.get().is_some() {
+                {move || if canvas_state.selected().is_some() {
                     view! {
                         <button
                             class="btn btn-danger btn-sm"
@@ -42,9 +61,18 @@ pub fn PropertyEditor() -> impl IntoView {
             </div>
 
             {move || {
-                if let Some(selected_id) = canvas_state.selected.get() {
+                let ids = canvas_state.selected_ids();
+                if ids.len() > 1 {
+                    let components: Vec<CanvasComponent> = ids
+                        .iter()
+                        .filter_map(|id| canvas_state.get_component(id))
+                        .collect();
+                    return view! { <BulkPropertyEditor ids=ids components=components /> }.into_any();
+                }
+                if let Some(selected_id) = canvas_state.selected() {
                     if let Some(comp) = canvas_state.get_component(&selected_id) {
-                        match comp {
+                        let name = comp.name().map(str::to_string);
+                        let editor = match comp {
                             CanvasComponent::Button(btn) => {
                                 view! {
                                     <ButtonPropertyEditor id=selected_id button=btn />
@@ -85,7 +113,11 @@ pub fn PropertyEditor() -> impl IntoView {
                                     <CustomPropertyEditor id=selected_id custom=custom />
                                 }.into_any()
                             },
-                        }
+                        };
+                        view! {
+                            <ComponentNameEditor id=selected_id name=name />
+                            {editor}
+                        }.into_any()
                     } else {
                         view! { <div><p>{"Component not found"}</p></div> }.into_any()
                     }
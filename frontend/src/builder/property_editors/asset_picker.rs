@@ -0,0 +1,210 @@
+//! Asset Picker
+//!
+//! Lists images uploaded to the backend asset store and lets the user upload,
+//! delete or pick one as an image source.
+
+use crate::services::{ApiClient, AssetInfo};
+use crate::state::app_state::{AppState, Notification};
+use crate::utils::format_file_size;
+use leptos::prelude::*;
+
+#[component]
+pub fn AssetPicker(
+    /// Current image `src`, used to highlight the selected asset
+    selected: String,
+    /// Called with the asset that was picked or uploaded
+    #[prop(into)]
+    on_select: Callback<AssetInfo>,
+) -> impl IntoView {
+    let app_state = AppState::expect_context();
+    let client = ApiClient::new();
+
+    let open = RwSignal::new(false);
+    let assets = RwSignal::new(Vec::<AssetInfo>::new());
+    let loading = RwSignal::new(false);
+    let uploading = RwSignal::new(false);
+    let selected = RwSignal::new(selected);
+    let file_input_ref = NodeRef::<leptos::html::Input>::new();
+
+    let refresh_assets = {
+        let client = client.clone();
+        move || {
+            let client = client.clone();
+            loading.set(true);
+            leptos::task::spawn_local(async move {
+                match client.list_assets().await {
+                    Ok(list) => assets.set(list),
+                    Err(e) => app_state.ui.notify(Notification::error(format!(
+                        "Failed to load assets: {}",
+                        e.user_message()
+                    ))),
+                }
+                loading.set(false);
+            });
+        }
+    };
+
+    let toggle = {
+        let refresh_assets = refresh_assets.clone();
+        move |_| {
+            let now_open = !open.get_untracked();
+            open.set(now_open);
+            if now_open {
+                refresh_assets();
+            }
+        }
+    };
+
+    let on_upload_click = move |_| {
+        if let Some(input) = file_input_ref.get() {
+            input.click();
+        }
+    };
+
+    let on_file_change = {
+        let client = client.clone();
+        move |ev: leptos::ev::Event| {
+            let target = event_target::<web_sys::HtmlInputElement>(&ev);
+            let Some(file) = target.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            // Allow re-selecting the same file later
+            target.set_value("");
+
+            let client = client.clone();
+            uploading.set(true);
+            leptos::task::spawn_local(async move {
+                match client.upload_asset(&file).await {
+                    Ok(asset) => {
+                        assets.update(|list| {
+                            if !list.iter().any(|a| a.id == asset.id) {
+                                list.insert(0, asset.clone());
+                            }
+                        });
+                        selected.set(asset.src());
+                        on_select.run(asset);
+                    }
+                    Err(e) => app_state.ui.notify(Notification::error(format!(
+                        "Upload failed: {}",
+                        e.user_message()
+                    ))),
+                }
+                uploading.set(false);
+            });
+        }
+    };
+
+    let delete_asset = {
+        let client = client.clone();
+        Callback::new(move |id: String| {
+            if !window()
+                .confirm_with_message("Delete this asset? Images using it will break.")
+                .unwrap_or(false)
+            {
+                return;
+            }
+
+            let client = client.clone();
+            leptos::task::spawn_local(async move {
+                match client.delete_asset(&id).await {
+                    Ok(()) => assets.update(|list| list.retain(|a| a.id != id)),
+                    Err(e) => app_state.ui.notify(Notification::error(format!(
+                        "Failed to delete asset: {}",
+                        e.user_message()
+                    ))),
+                }
+            });
+        })
+    };
+
+    view! {
+        <div class="asset-picker">
+            <div class="flex-row gap-1">
+                <button class="btn btn-secondary btn-sm" on:click=toggle>
+                    {move || if open.get() { "Hide Assets" } else { "🖼️ Choose Asset" }}
+                </button>
+                <button
+                    class="btn btn-secondary btn-sm"
+                    on:click=on_upload_click
+                    disabled=move || uploading.get()
+                >
+                    {move || if uploading.get() { "Uploading..." } else { "⬆️ Upload" }}
+                </button>
+                <input
+                    type="file"
+                    accept="image/png,image/jpeg,image/gif,image/webp,image/svg+xml"
+                    node_ref=file_input_ref
+                    style="display: none"
+                    on:change=on_file_change
+                />
+            </div>
+
+            <Show when=move || open.get()>
+                <div class="asset-grid">
+                    {move || {
+                        if loading.get() {
+                            return view! { <div class="asset-empty">"Loading..."</div> }.into_any();
+                        }
+                        let list = assets.get();
+                        if list.is_empty() {
+                            return view! { <div class="asset-empty">"No assets uploaded yet"</div> }
+                                .into_any();
+                        }
+                        let client = ApiClient::new();
+                        list.into_iter()
+                            .map(|asset| {
+                                let src = asset.src();
+                                let id = asset.id.clone();
+                                let placeholder = asset
+                                    .dominant_color
+                                    .as_ref()
+                                    .map(|c| format!("background-color: {};", c))
+                                    .unwrap_or_default();
+                                let title = match (asset.width, asset.height) {
+                                    (Some(w), Some(h)) => format!(
+                                        "{} ({}×{}, {})",
+                                        asset.filename,
+                                        w,
+                                        h,
+                                        format_file_size(asset.size)
+                                    ),
+                                    _ => format!("{} ({})", asset.filename, format_file_size(asset.size)),
+                                };
+                                let img_url = client.asset_url(&asset.id);
+                                let alt = asset.filename.clone();
+                                view! {
+                                    <div
+                                        class=move || if selected.get() == src {
+                                            "asset-tile selected"
+                                        } else {
+                                            "asset-tile"
+                                        }
+                                        style=placeholder
+                                        title=title
+                                        on:click=move |_| {
+                                            selected.set(asset.src());
+                                            on_select.run(asset.clone());
+                                        }
+                                    >
+                                        <img src=img_url alt=alt loading="lazy" />
+                                        <button
+                                            class="asset-delete"
+                                            aria-label="Delete asset"
+                                            on:click=move |ev| {
+                                                ev.stop_propagation();
+                                                delete_asset.run(id.clone());
+                                            }
+                                        >
+                                            "×"
+                                        </button>
+                                    </div>
+                                }
+                            })
+                            .collect_view()
+                            .into_any()
+                    }}
+                </div>
+            </Show>
+        </div>
+    }
+}
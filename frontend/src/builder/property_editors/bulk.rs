@@ -0,0 +1,229 @@
+use crate::builder::component_library::{LibraryComponent, PropSchema, PropType};
+use crate::builder::property_inputs::{BoolCheckbox, EnumSelect, NumberInput, StringInput};
+use crate::domain::{CanvasComponent, ComponentId};
+use crate::state::history::{field, set_field};
+use crate::state::{AppState, Notification};
+use leptos::prelude::*;
+use serde_json::Value;
+
+/// A property every selected component has
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommonProp {
+    pub schema: PropSchema,
+    /// The shared value, `None` when the components differ
+    pub value: Option<Value>,
+}
+
+/// Properties the library declares, with the same type, for every one of
+/// `components`. Only props stored as a plain field of the component are
+/// offered, so an edit is a field change on each of them.
+pub fn common_props(
+    components: &[CanvasComponent],
+    library: &[LibraryComponent],
+) -> Vec<CommonProp> {
+    let schema_of = |component: &CanvasComponent| {
+        let kind = component.component_type().to_string();
+        library
+            .iter()
+            .find(|entry| entry.kind == kind)
+            .and_then(|entry| entry.props_schema.clone())
+            .unwrap_or_default()
+    };
+    let Some(first) = components.first() else {
+        return Vec::new();
+    };
+    let schemas: Vec<Vec<PropSchema>> = components.iter().map(schema_of).collect();
+    schema_of(first)
+        .into_iter()
+        .filter_map(|schema| {
+            let mut values = Vec::with_capacity(components.len());
+            for (component, props) in components.iter().zip(&schemas) {
+                if !props
+                    .iter()
+                    .any(|p| p.name == schema.name && p.prop_type == schema.prop_type)
+                {
+                    return None;
+                }
+                match field(component, &schema.name)? {
+                    Value::Array(_) | Value::Object(_) => return None,
+                    value => values.push(value),
+                }
+            }
+            let value = values
+                .iter()
+                .all(|v| *v == values[0])
+                .then(|| values[0].clone());
+            Some(CommonProp { schema, value })
+        })
+        .collect()
+}
+
+/// Edits the properties shared by several selected components at once
+#[component]
+pub fn BulkPropertyEditor(
+    ids: Vec<ComponentId>,
+    components: Vec<CanvasComponent>,
+) -> impl IntoView {
+    let app_state = AppState::expect_context();
+    let canvas_state = app_state.canvas;
+    let ui_state = app_state.ui;
+
+    let props = common_props(&components, &ui_state.component_library.get_untracked());
+    let count = ids.len();
+    let shared = !props.is_empty();
+    let ids = StoredValue::new(ids);
+
+    // Set `name` on every component as one step, unless that makes any of
+    // them invalid
+    let apply = move |name: String, value: Value| {
+        let ids = ids.get_value();
+        let invalid = ids.iter().find_map(|id| {
+            let mut updated = canvas_state.get_component(id)?;
+            if !set_field(&mut updated, &name, Some(&value)) {
+                return Some(format!("Invalid value for {}", name));
+            }
+            updated.validate().err().map(|e| e.user_message())
+        });
+        match invalid {
+            Some(message) => ui_state.notify(Notification::error(message)),
+            None => canvas_state.edit_components(
+                &ids,
+                &format!("Edit {} Components", ids.len()),
+                |component| {
+                    set_field(component, &name, Some(&value));
+                },
+            ),
+        }
+    };
+
+    let fields = props
+        .into_iter()
+        .map(|prop| {
+            let name = prop.schema.name.clone();
+            let label = match prop.value {
+                Some(_) => name.clone(),
+                None => format!("{} (mixed)", name),
+            };
+            match prop.schema.prop_type {
+                PropType::String => {
+                    let value = prop
+                        .value
+                        .as_ref()
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    view! {
+                        <StringInput
+                            value=value
+                            label=label
+                            on_change=move |new_val| apply(name.clone(), Value::String(new_val))
+                        />
+                    }
+                    .into_any()
+                }
+                PropType::Number => {
+                    let value = prop.value.as_ref().and_then(Value::as_f64).unwrap_or(0.0);
+                    view! {
+                        <NumberInput
+                            value=value
+                            label=label
+                            min_value=0.0
+                            on_change=move |new_val: f64| {
+                                if new_val.is_finite() && new_val >= 0.0 {
+                                    apply(name.clone(), Value::from(new_val.round() as u64));
+                                }
+                            }
+                        />
+                    }
+                    .into_any()
+                }
+                PropType::Bool => {
+                    let checked = prop
+                        .value
+                        .as_ref()
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false);
+                    view! {
+                        <BoolCheckbox
+                            checked=checked
+                            label=label
+                            on_change=move |new_val| apply(name.clone(), Value::Bool(new_val))
+                        />
+                    }
+                    .into_any()
+                }
+                PropType::Enum { mut options } => {
+                    let value = prop
+                        .value
+                        .as_ref()
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string();
+                    // Nothing matches a mixed value, so offer a blank first choice
+                    if value.is_empty() {
+                        options.insert(0, String::new());
+                    }
+                    view! {
+                        <EnumSelect
+                            value=value
+                            label=label
+                            options=options
+                            on_change=move |new_val: String| {
+                                if !new_val.is_empty() {
+                                    apply(name.clone(), Value::String(new_val));
+                                }
+                            }
+                        />
+                    }
+                    .into_any()
+                }
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div class="property-group">
+            <div class="group-title">{format!("{} components selected", count)}</div>
+            {shared.then_some(fields)}
+            {(!shared).then(|| view! { <p>{"These components have no properties in common"}</p> })}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::component_library::builtin_library_components;
+    use crate::domain::{ButtonComponent, InputComponent};
+
+    #[test]
+    fn test_common_props_across_kinds() {
+        let library = builtin_library_components();
+        let mut disabled = ButtonComponent::new("A".to_string());
+        disabled.disabled = true;
+        let buttons = vec![
+            CanvasComponent::Button(disabled),
+            CanvasComponent::Button(ButtonComponent::new("A".to_string())),
+        ];
+        let props = common_props(&buttons, &library);
+        let value = |name: &str| {
+            props
+                .iter()
+                .find(|p| p.schema.name == name)
+                .map(|p| p.value.clone())
+        };
+        assert_eq!(value("label"), Some(Some(Value::from("A"))));
+        assert_eq!(value("disabled"), Some(None));
+
+        // A button and an input only share `disabled`
+        let mixed = vec![
+            buttons[0].clone(),
+            CanvasComponent::Input(InputComponent::new()),
+        ];
+        let names: Vec<String> = common_props(&mixed, &library)
+            .into_iter()
+            .map(|p| p.schema.name)
+            .collect();
+        assert_eq!(names, vec!["disabled".to_string()]);
+    }
+}
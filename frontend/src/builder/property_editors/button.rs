@@ -33,11 +33,7 @@ pub fn ButtonPropertyEditor(
             ui_state.notify(crate::state::Notification::error(e.user_message()));
         } else {
             // We need to overwrite the component
-            canvas_state.update_component_with_snapshot(
-                &id,
-                updated,
-                &format!("Update Button {}", prop_name),
-            );
+            canvas_state.replace_component(&id, updated, &format!("Update Button {}", prop_name));
         }
     };
 
@@ -11,8 +11,7 @@ pub fn CardPropertyEditor(id: crate::domain::ComponentId, card: CardComponent) -
     let canvas_state = app_state.canvas;
 
     let update_padding = move |val: f64| {
-        canvas_state.record_snapshot("Update Card Padding");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Card Padding", |c| {
             if let crate::domain::CanvasComponent::Card(card) = c {
                 card.padding = val as u32;
             }
@@ -20,8 +19,7 @@ pub fn CardPropertyEditor(id: crate::domain::ComponentId, card: CardComponent) -
     };
 
     let update_radius = move |val: f64| {
-        canvas_state.record_snapshot("Update Card Radius");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Card Radius", |c| {
             if let crate::domain::CanvasComponent::Card(card) = c {
                 card.border_radius = val as u32;
             }
@@ -29,8 +27,7 @@ pub fn CardPropertyEditor(id: crate::domain::ComponentId, card: CardComponent) -
     };
 
     let update_shadow = move |val: bool| {
-        canvas_state.record_snapshot("Update Card Shadow");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Card Shadow", |c| {
             if let crate::domain::CanvasComponent::Card(card) = c {
                 card.shadow = val;
             }
@@ -38,8 +35,7 @@ pub fn CardPropertyEditor(id: crate::domain::ComponentId, card: CardComponent) -
     };
 
     let update_border = move |val: bool| {
-        canvas_state.record_snapshot("Update Card Border");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Card Border", |c| {
             if let crate::domain::CanvasComponent::Card(card) = c {
                 card.border = val;
             }
@@ -97,8 +93,7 @@ pub fn CardPropertyEditor(id: crate::domain::ComponentId, card: CardComponent) -
             event_name="On Click".to_string()
             handler_name=card_clone2.on_click.clone()
             on_change=Callback::new(move |val: String| {
-                canvas_state.record_snapshot("Update Card Event");
-                canvas_state.update_component(&id_clone2, |c| {
+                canvas_state.edit_component(&id_clone2, "Update Card Event", |c| {
                     if let crate::domain::CanvasComponent::Card(card) = c {
                         card.on_click = if val.is_empty() { None } else { Some(val) };
                     }
@@ -109,8 +104,7 @@ pub fn CardPropertyEditor(id: crate::domain::ComponentId, card: CardComponent) -
         <StyleEditor
             style=card_style.style
             on_change=move |new_style| {
-                canvas_state.record_snapshot("Update Card Style");
-                canvas_state.update_component(&id_style, |c| {
+                canvas_state.edit_component(&id_style, "Update Card Style", |c| {
                      if let crate::domain::CanvasComponent::Card(card) = c {
                         card.style = new_style;
                     }
@@ -122,8 +116,7 @@ pub fn CardPropertyEditor(id: crate::domain::ComponentId, card: CardComponent) -
             _id=id_clone
             animation=card_clone.animation
             on_change=move |new_anim| {
-                canvas_state.record_snapshot("Update Card Animation");
-                canvas_state.update_component(&id_clone, |c| {
+                canvas_state.edit_component(&id_clone, "Update Card Animation", |c| {
                     if let crate::domain::CanvasComponent::Card(card) = c {
                         card.animation = new_anim;
                     }
@@ -23,7 +23,7 @@ pub fn ContainerPropertyEditor(
             ui_state.notify(crate::state::Notification::error(e.user_message()));
         } else {
             // Overwrite using new signature (closure that assigns the value)
-            canvas_state.update_component_with_snapshot(
+            canvas_state.replace_component(
                 &id,
                 updated,
                 &format!("Update Container {}", prop_name),
@@ -16,18 +16,14 @@ pub fn CustomPropertyEditor(
     let update_name = move |new_name: String| {
         if let Some(CanvasComponent::Custom(mut c)) = canvas_state.get_component(&id) {
             c.name = new_name;
-            canvas_state.update_component_with_snapshot(
-                &id,
-                CanvasComponent::Custom(c),
-                "Update Custom Name",
-            );
+            canvas_state.replace_component(&id, CanvasComponent::Custom(c), "Update Custom Name");
         }
     };
 
     let update_template = move |new_template: String| {
         if let Some(CanvasComponent::Custom(mut c)) = canvas_state.get_component(&id) {
             c.template = new_template;
-            canvas_state.update_component_with_snapshot(
+            canvas_state.replace_component(
                 &id,
                 CanvasComponent::Custom(c),
                 "Update Custom Template",
@@ -38,11 +34,7 @@ pub fn CustomPropertyEditor(
     let update_style = move |new_style| {
         if let Some(CanvasComponent::Custom(mut c)) = canvas_state.get_component(&id) {
             c.style = new_style;
-            canvas_state.update_component_with_snapshot(
-                &id,
-                CanvasComponent::Custom(c),
-                "Update Custom Style",
-            );
+            canvas_state.replace_component(&id, CanvasComponent::Custom(c), "Update Custom Style");
         }
     };
 
@@ -1,8 +1,9 @@
 use super::VariableBinding;
-use super::{AnimationPropertyEditor, EventPropertyEditor};
+use super::{AnimationPropertyEditor, AssetPicker, EventPropertyEditor};
 use crate::builder::property_inputs::StringInput;
 use crate::builder::styling_system::StyleEditor;
 use crate::domain::ImageComponent;
+use crate::services::AssetInfo;
 use crate::state::AppState;
 use leptos::prelude::*;
 
@@ -12,17 +13,33 @@ pub fn ImagePropertyEditor(id: crate::domain::ComponentId, image: ImageComponent
     let canvas_state = app_state.canvas;
 
     let update_src = move |new_src: String| {
-        canvas_state.record_snapshot("Update Image Source");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Image Source", |c| {
             if let crate::domain::CanvasComponent::Image(img) = c {
+                // Responsive candidates belong to the previous source
+                if img.src != new_src {
+                    img.srcset.clear();
+                }
                 img.src = new_src;
             }
         });
     };
 
+    let choose_asset = move |asset: AssetInfo| {
+        canvas_state.edit_component(&id, "Choose Image Asset", |c| {
+            if let crate::domain::CanvasComponent::Image(img) = c {
+                img.src = asset.src();
+                img.srcset = asset.srcset();
+                // Prefill the intrinsic size unless the user already chose one
+                if img.width.is_none() && img.height.is_none() {
+                    img.width = asset.width.map(|w| format!("{}px", w));
+                    img.height = asset.height.map(|h| format!("{}px", h));
+                }
+            }
+        });
+    };
+
     let update_alt = move |new_alt: String| {
-        canvas_state.record_snapshot("Update Image Alt");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Image Alt", |c| {
             if let crate::domain::CanvasComponent::Image(img) = c {
                 img.alt = new_alt;
             }
@@ -30,8 +47,7 @@ pub fn ImagePropertyEditor(id: crate::domain::ComponentId, image: ImageComponent
     };
 
     let update_width = move |new_width: String| {
-        canvas_state.record_snapshot("Update Image Width");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Image Width", |c| {
             if let crate::domain::CanvasComponent::Image(img) = c {
                 if new_width.is_empty() {
                     img.width = None;
@@ -43,8 +59,7 @@ pub fn ImagePropertyEditor(id: crate::domain::ComponentId, image: ImageComponent
     };
 
     let update_height = move |new_height: String| {
-        canvas_state.record_snapshot("Update Image Height");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Image Height", |c| {
             if let crate::domain::CanvasComponent::Image(img) = c {
                 if new_height.is_empty() {
                     img.height = None;
@@ -93,6 +108,10 @@ pub fn ImagePropertyEditor(id: crate::domain::ComponentId, image: ImageComponent
                     current_binding=image.bindings.get("src").cloned().unwrap_or_default()
                 />
             </div>
+            <AssetPicker
+                selected=image.src.clone()
+                on_select=Callback::new(choose_asset)
+            />
             <div class="flex-row items-end gap-1">
                 <div class="flex-grow">
                     <StringInput
@@ -124,8 +143,7 @@ pub fn ImagePropertyEditor(id: crate::domain::ComponentId, image: ImageComponent
             event_name="On Click".to_string()
             handler_name=img_clone2.on_click.clone()
             on_change=Callback::new(move |val: String| {
-                canvas_state.record_snapshot("Update Image Event");
-                canvas_state.update_component(&id_clone2, |c| {
+                canvas_state.edit_component(&id_clone2, "Update Image Event", |c| {
                     if let crate::domain::CanvasComponent::Image(img) = c {
                         img.on_click = if val.is_empty() { None } else { Some(val) };
                     }
@@ -136,8 +154,7 @@ pub fn ImagePropertyEditor(id: crate::domain::ComponentId, image: ImageComponent
         <StyleEditor
             style=img_style.style
             on_change=move |new_style| {
-                canvas_state.record_snapshot("Update Image Style");
-                canvas_state.update_component(&id_style, |c| {
+                canvas_state.edit_component(&id_style, "Update Image Style", |c| {
                      if let crate::domain::CanvasComponent::Image(img) = c {
                         img.style = new_style;
                     }
@@ -149,8 +166,7 @@ pub fn ImagePropertyEditor(id: crate::domain::ComponentId, image: ImageComponent
             _id=id_clone
             animation=img_clone.animation
             on_change=move |new_anim| {
-                canvas_state.record_snapshot("Update Image Animation");
-                canvas_state.update_component(&id_clone, |c| {
+                canvas_state.edit_component(&id_clone, "Update Image Animation", |c| {
                     if let crate::domain::CanvasComponent::Image(img) = c {
                         img.animation = new_anim;
                     }
@@ -32,11 +32,7 @@ pub fn InputPropertyEditor(
             ui_state.notify(crate::state::Notification::error(e.user_message()));
         } else {
             // Overwrite using new signature (closure that assigns the value)
-            canvas_state.update_component_with_snapshot(
-                &id,
-                updated,
-                &format!("Update Input {}", prop_name),
-            );
+            canvas_state.replace_component(&id, updated, &format!("Update Input {}", prop_name));
         }
     };
 
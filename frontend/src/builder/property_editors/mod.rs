@@ -1,4 +1,6 @@
 pub mod animation;
+pub mod asset_picker;
+pub mod bulk;
 pub mod button;
 pub mod card;
 pub mod container;
@@ -6,11 +8,14 @@ pub mod custom;
 pub mod event;
 pub mod image;
 pub mod input;
+pub mod name;
 pub mod select_property_editor;
 pub mod text;
 pub mod variable_binding;
 
 pub use animation::AnimationPropertyEditor;
+pub use asset_picker::AssetPicker;
+pub use bulk::BulkPropertyEditor;
 pub use button::ButtonPropertyEditor;
 pub use card::CardPropertyEditor;
 pub use container::ContainerPropertyEditor;
@@ -18,6 +23,7 @@ pub use custom::CustomPropertyEditor;
 pub use event::EventPropertyEditor;
 pub use image::ImagePropertyEditor;
 pub use input::InputPropertyEditor;
+pub use name::ComponentNameEditor;
 pub use select_property_editor::SelectPropertyEditor;
 pub use text::TextPropertyEditor;
 pub use variable_binding::VariableBinding;
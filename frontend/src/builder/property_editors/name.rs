@@ -0,0 +1,42 @@
+use crate::domain::ComponentId;
+use crate::state::AppState;
+use leptos::prelude::*;
+
+/// Unique name of a component, used by the exporters for ids, classes and
+/// refs. Applied when the field is left, since a half-typed name is often
+/// invalid.
+#[component]
+pub fn ComponentNameEditor(id: ComponentId, #[prop(into)] name: Option<String>) -> impl IntoView {
+    let canvas_state = AppState::expect_context().canvas;
+    let error = RwSignal::new(None::<String>);
+
+    let on_change = move |ev| {
+        let value = event_target_value(&ev);
+        error.set(
+            canvas_state
+                .set_name(&id, &value)
+                .err()
+                .map(|e| e.to_string()),
+        );
+    };
+
+    view! {
+        <div class="property-group">
+            <div class="property-field">
+                <label>
+                    "Name"
+                    <input
+                        type="text"
+                        prop:value=name.unwrap_or_default()
+                        placeholder="e.g. submit_button"
+                        aria-invalid=move || error.with(Option::is_some).to_string()
+                        on:change=on_change
+                    />
+                </label>
+                {move || error.get().map(|message| view! {
+                    <div class="property-error" role="alert">{message}</div>
+                })}
+            </div>
+        </div>
+    }
+}
@@ -12,8 +12,7 @@ pub fn SelectPropertyEditor(id: ComponentId, select: SelectComponent) -> impl In
     let canvas_state = app_state.canvas;
 
     let update_options = move |val: String| {
-        canvas_state.record_snapshot("Update Select Options");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Select Options", |c| {
             if let crate::domain::CanvasComponent::Select(sel) = c {
                 sel.options = val;
             }
@@ -21,8 +20,7 @@ pub fn SelectPropertyEditor(id: ComponentId, select: SelectComponent) -> impl In
     };
 
     let update_placeholder = move |val: String| {
-        canvas_state.record_snapshot("Update Select Placeholder");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Select Placeholder", |c| {
             if let crate::domain::CanvasComponent::Select(sel) = c {
                 sel.placeholder = val;
             }
@@ -30,8 +28,7 @@ pub fn SelectPropertyEditor(id: ComponentId, select: SelectComponent) -> impl In
     };
 
     let update_disabled = move |val: bool| {
-        canvas_state.record_snapshot("Update Select Disabled");
-        canvas_state.update_component(&id, |c| {
+        canvas_state.edit_component(&id, "Update Select Disabled", |c| {
             if let crate::domain::CanvasComponent::Select(sel) = c {
                 sel.disabled = val;
             }
@@ -111,8 +108,7 @@ pub fn SelectPropertyEditor(id: ComponentId, select: SelectComponent) -> impl In
             event_name="On Change".to_string()
             handler_name=select_clone2.on_change.clone()
             on_change=Callback::new(move |val: String| {
-                canvas_state.record_snapshot("Update Select Event");
-                canvas_state.update_component(&id_clone2, |c| {
+                canvas_state.edit_component(&id_clone2, "Update Select Event", |c| {
                     if let crate::domain::CanvasComponent::Select(sel) = c {
                         sel.on_change = if val.is_empty() { None } else { Some(val) };
                     }
@@ -123,8 +119,7 @@ pub fn SelectPropertyEditor(id: ComponentId, select: SelectComponent) -> impl In
         <StyleEditor
             style=select_style.style
             on_change=move |new_style| {
-                canvas_state.record_snapshot("Update Select Style");
-                canvas_state.update_component(&id_style, |c| {
+                canvas_state.edit_component(&id_style, "Update Select Style", |c| {
                      if let crate::domain::CanvasComponent::Select(sel) = c {
                         sel.style = new_style;
                     }
@@ -136,8 +131,7 @@ pub fn SelectPropertyEditor(id: ComponentId, select: SelectComponent) -> impl In
             _id=id_clone
             animation=select_clone.animation
             on_change=move |new_anim| {
-                canvas_state.record_snapshot("Update Select Animation");
-                canvas_state.update_component(&id_clone, |c| {
+                canvas_state.edit_component(&id_clone, "Update Select Animation", |c| {
                     if let crate::domain::CanvasComponent::Select(sel) = c {
                         sel.animation = new_anim;
                     }
@@ -21,8 +21,7 @@ pub fn VariableBinding(
                     let binding = if val.is_empty() { None } else { Some(val) };
 
                     if let (Some(id), Some(prop)) = (component_id, property_name.clone()) {
-                        canvas_state.record_snapshot(&format!("Update binding for {}", prop));
-                        canvas_state.update_component(&id, |c| {
+                        canvas_state.edit_component(&id, &format!("Update binding for {}", prop), |c| {
                             match c {
                                 crate::domain::CanvasComponent::Button(btn) => {
                                     if let Some(b) = binding.clone() { btn.bindings.insert(prop, b); } else { btn.bindings.remove(&prop); }
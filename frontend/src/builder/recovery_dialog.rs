@@ -0,0 +1,66 @@
+use leptos::prelude::*;
+
+use crate::state::AppState;
+
+/// Offers to restore unsaved edits of the open project left behind by a
+/// closed or crashed tab
+#[component]
+pub fn RecoveryDialog() -> impl IntoView {
+    let app_state = AppState::expect_context();
+
+    // Only for the project on screen
+    let offer = move || {
+        app_state.recovery.get().filter(|offer| {
+            app_state.current_project_id.get().as_deref() == Some(offer.project_id.as_str())
+        })
+    };
+
+    move || {
+        offer().map(|offer| {
+            let written_at = js_sys::Date::new(&offer.journal.written_at.into())
+                .to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)
+                .as_string()
+                .unwrap_or_default();
+            let changes = offer
+                .summary
+                .describe()
+                .into_iter()
+                .map(|line| view! { <li>{line}</li> })
+                .collect_view();
+
+            view! {
+                <div
+                    class="modal-overlay"
+                    role="dialog"
+                    aria-modal="true"
+                    aria-labelledby="recovery-title"
+                >
+                    <div class="modal-content recovery-dialog">
+                        <h3 id="recovery-title">{"Restore unsaved changes?"}</h3>
+                        <p>
+                            {format!(
+                                "This project has changes from {} that were never saved.",
+                                written_at
+                            )}
+                        </p>
+                        <ul class="recovery-changes">{changes}</ul>
+                        <div class="recovery-actions">
+                            <button
+                                class="btn btn-secondary"
+                                on:click=move |_| app_state.discard_journal()
+                            >
+                                {"Discard"}
+                            </button>
+                            <button
+                                class="btn btn-primary"
+                                on:click=move |_| app_state.restore_journal()
+                            >
+                                {"Restore"}
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            }
+        })
+    }
+}
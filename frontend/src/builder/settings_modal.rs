@@ -62,6 +62,26 @@ pub fn SettingsModal() -> impl IntoView {
                             </p>
                         </div>
 
+                        <div class="setting-item">
+                            <label>"Exclude Hidden Components"</label>
+                            <div class="toggle-switch">
+                                <input
+                                    type="checkbox"
+                                    id="exclude-hidden-toggle"
+                                    prop:checked=move || settings.get().exclude_hidden_from_export
+                                    on:change=move |ev| {
+                                        let checked = event_target_checked(&ev);
+                                        settings.update(|s| s.exclude_hidden_from_export = checked);
+                                        app_state.save_settings();
+                                    }
+                                />
+                                <label for="exclude-hidden-toggle" class="toggle-slider"></label>
+                            </div>
+                            <p class="setting-hint">
+                                "Leave components hidden in the editor out of exported code."
+                            </p>
+                        </div>
+
                         <div class="setting-item">
                             <label>"Export Preset"</label>
                             <select
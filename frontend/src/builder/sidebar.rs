@@ -171,7 +171,7 @@ pub fn Sidebar() -> impl IntoView {
     };
 
     let export_code = move |_| {
-        let components = app_state.canvas.components.get();
+        let components = app_state.export_components();
         let preset = app_state.settings.with(|s| s.export_preset.clone());
         let variables = app_state.variables.get();
         let generator = LeptosCodeGenerator::new(preset);
@@ -1,11 +1,13 @@
 //! Status Bar Component
 //!
 //! A status bar at the bottom of the application showing current state,
-//! component count, responsive mode, and other useful information.
+//! component count, sync status, responsive mode, and other useful information.
 
 use leptos::prelude::*;
 
-use crate::state::{AppState, DerivedState};
+use crate::state::{
+    AppState, ConflictResolution, ConflictResolved, DerivedState, Notification, SyncStatus,
+};
 
 /// Status bar component
 #[component]
@@ -69,9 +71,7 @@ pub fn StatusBar() -> impl IntoView {
                         disabled=move || !derived.can_undo.get()
                         title="Undo (Ctrl+Z)"
                         on:click=move |_| {
-                            if let Some(snapshot) = app_state.canvas.history.write().undo() {
-                                app_state.canvas.apply_snapshot(&snapshot);
-                            }
+                            app_state.canvas.undo();
                         }
                     >
                         "↩️"
@@ -81,9 +81,7 @@ pub fn StatusBar() -> impl IntoView {
                         disabled=move || !derived.can_redo.get()
                         title="Redo (Ctrl+Y)"
                         on:click=move |_| {
-                            if let Some(snapshot) = app_state.canvas.history.write().redo() {
-                                app_state.canvas.apply_snapshot(&snapshot);
-                            }
+                            app_state.canvas.redo();
                         }
                     >
                         "↪️"
@@ -92,6 +90,8 @@ pub fn StatusBar() -> impl IntoView {
             </div>
 
             <div class="status-bar-right">
+                <SyncIndicator />
+
                 // Responsive mode indicator
                 <span class="status-item" title="Current responsive mode">
                     <span class="status-icon">
@@ -132,6 +132,204 @@ pub fn StatusBar() -> impl IntoView {
     }
 }
 
+/// Whether saves and commits reached the backend, with the conflict and
+/// failure dialogs
+#[component]
+pub fn SyncIndicator() -> impl IntoView {
+    let app_state = AppState::expect_context();
+    let sync = app_state.sync;
+    let show_dialog = RwSignal::new(false);
+
+    let label = move || {
+        let pending = sync.pending();
+        match sync.status.get() {
+            SyncStatus::Synced => ("✓", "Synced".to_string()),
+            SyncStatus::Syncing => ("⟳", "Syncing…".to_string()),
+            SyncStatus::Offline => ("⚡", format!("Offline · {} pending", pending)),
+            SyncStatus::Conflict => ("⚠", "Sync conflict".to_string()),
+            SyncStatus::Failed => ("⚠", format!("Sync failed · {} pending", pending)),
+        }
+    };
+    let title = move || match sync.status.get() {
+        SyncStatus::Synced => "All changes are saved to the backend",
+        SyncStatus::Syncing => "Uploading queued changes",
+        SyncStatus::Offline => {
+            "Backend unreachable. Changes are kept on this device and sync when it is back"
+        }
+        SyncStatus::Conflict => "The server copy changed. Click to choose which version to keep",
+        SyncStatus::Failed => "The backend rejected a change. Click to retry or discard it",
+    };
+
+    view! {
+        <button
+            class=move || format!("status-item status-sync status-sync-{:?}", sync.status.get()).to_lowercase()
+            title=title
+            disabled=move || {
+                !matches!(sync.status.get(), SyncStatus::Conflict | SyncStatus::Failed)
+            }
+            on:click=move |_| show_dialog.set(true)
+        >
+            <span class="status-icon">{move || label().0}</span>
+            <span class="status-text">{move || label().1}</span>
+        </button>
+        <Show when=move || show_dialog.get() && sync.conflict.get().is_some()>
+            <SyncConflictDialog on_close=Callback::new(move |_| show_dialog.set(false)) />
+        </Show>
+        <Show when=move || show_dialog.get() && sync.failure.get().is_some()>
+            <SyncFailureDialog on_close=Callback::new(move |_| show_dialog.set(false)) />
+        </Show>
+    }
+}
+
+/// Retry or discard a write the backend rejected
+#[component]
+fn SyncFailureDialog(on_close: Callback<(), ()>) -> impl IntoView {
+    let app_state = AppState::expect_context();
+    let sync = app_state.sync;
+    let busy = RwSignal::new(false);
+
+    let retry = move |_| {
+        busy.set(true);
+        leptos::task::spawn_local(async move {
+            sync.retry().await;
+            busy.set(false);
+            on_close.run(());
+        });
+    };
+    let discard = move |_| {
+        sync.discard_failed();
+        on_close.run(());
+    };
+
+    let failure = move || sync.failure.get();
+    let name = move || failure().map(|f| f.project_name).unwrap_or_default();
+    let message = move || failure().map(|f| f.message).unwrap_or_default();
+
+    view! {
+        <div
+            class="modal-overlay"
+            role="dialog"
+            aria-modal="true"
+            aria-labelledby="sync-failure-title"
+        >
+            <div class="modal-content sync-conflict">
+                <h3 id="sync-failure-title">{"Sync failed"}</h3>
+                <p>
+                    {move || format!(
+                        "A change to \"{}\" was rejected by the server: {}. It stays on this device until you retry or discard it.",
+                        name(),
+                        message()
+                    )}
+                </p>
+                <div class="sync-conflict-actions">
+                    <button class="btn btn-primary" disabled=busy on:click=retry>
+                        {"Retry"}
+                    </button>
+                    <button class="btn btn-secondary" disabled=busy on:click=discard>
+                        {"Discard change"}
+                    </button>
+                    <button class="btn btn-outline" on:click=move |_| on_close.run(())>
+                        {"Later"}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Choose between the local and server copies of a project
+#[component]
+fn SyncConflictDialog(on_close: Callback<(), ()>) -> impl IntoView {
+    let app_state = AppState::expect_context();
+    let sync = app_state.sync;
+    let navigate = leptos_router::hooks::use_navigate();
+    let busy = RwSignal::new(false);
+
+    let resolve = move |resolution: ConflictResolution| {
+        let navigate = navigate.clone();
+        let conflict_id = sync.conflict.get_untracked().map(|c| c.project_id);
+        busy.set(true);
+        leptos::task::spawn_local(async move {
+            let is_current = conflict_id.is_some()
+                && app_state.current_project_id.get_untracked() == conflict_id;
+            match sync.resolve(resolution).await {
+                Ok(ConflictResolved::Reload(project)) if is_current => {
+                    app_state.apply_project(project);
+                }
+                Ok(ConflictResolved::Copied(id)) if is_current => {
+                    navigate(&format!("/editor/{}", id), Default::default());
+                }
+                Ok(_) => {}
+                Err(e) => app_state.ui.notify(Notification::error(e.user_message())),
+            }
+            busy.set(false);
+            on_close.run(());
+        });
+    };
+    let keep_local = resolve.clone();
+    let use_server = resolve.clone();
+
+    let conflict = move || sync.conflict.get();
+    let name = move || conflict().map(|c| c.project_name).unwrap_or_default();
+    let server_time = move || {
+        conflict()
+            .and_then(|c| c.server_modified)
+            .map(|ms| {
+                js_sys::Date::new(&ms.into())
+                    .to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)
+                    .as_string()
+                    .unwrap_or_default()
+            })
+            .unwrap_or_else(|| "an unknown time".to_string())
+    };
+
+    view! {
+        <div
+            class="modal-overlay"
+            role="dialog"
+            aria-modal="true"
+            aria-labelledby="sync-conflict-title"
+        >
+            <div class="modal-content sync-conflict">
+                <h3 id="sync-conflict-title">{"Sync conflict"}</h3>
+                <p>
+                    {move || format!(
+                        "\"{}\" was changed on the server ({}) while your edits were waiting to sync.",
+                        name(),
+                        server_time()
+                    )}
+                </p>
+                <div class="sync-conflict-actions">
+                    <button
+                        class="btn btn-primary"
+                        disabled=busy
+                        on:click=move |_| keep_local(ConflictResolution::KeepLocal)
+                    >
+                        {"Keep my version"}
+                    </button>
+                    <button
+                        class="btn btn-secondary"
+                        disabled=busy
+                        on:click=move |_| use_server(ConflictResolution::UseServer)
+                    >
+                        {"Use server version"}
+                    </button>
+                    <button
+                        class="btn btn-secondary"
+                        disabled=busy
+                        on:click=move |_| resolve(ConflictResolution::SaveAsCopy)
+                    >
+                        {"Save mine as a copy"}
+                    </button>
+                    <button class="btn btn-outline" on:click=move |_| on_close.run(())>
+                        {"Later"}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
 /// Mini status indicator for inline use
 #[component]
 pub fn StatusIndicator(
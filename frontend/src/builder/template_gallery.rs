@@ -5,7 +5,7 @@
 
 use leptos::prelude::*;
 
-use crate::services::{Template, TemplateCategory, TemplateService};
+use crate::services::{Template, TemplateCategory, TemplateService, render_thumbnail};
 use crate::state::app_state::{AppState, Notification};
 
 /// Template gallery panel component with callbacks
@@ -153,6 +153,10 @@ pub fn TemplateGallery(
                                     let template_tags = template.tags.clone();
                                     let is_custom = template.category == TemplateCategory::Custom;
                                     let id_for_delete = template.id.clone();
+                                    let thumbnail = template
+                                        .thumbnail
+                                        .clone()
+                                        .unwrap_or_else(|| render_thumbnail(&template.components));
 
                                     view! {
                                         <div
@@ -161,6 +165,7 @@ pub fn TemplateGallery(
                                             on:mouseleave=move |_| preview_template.set(None)
                                         >
                                             <div class="template-card-preview">
+                                                <img class="template-thumbnail" src=thumbnail alt="" />
                                                 <div class="template-icon">
                                                     {category_icon(&template.category)}
                                                 </div>
@@ -249,10 +254,10 @@ pub fn TemplateGalleryToggle() -> impl IntoView {
                         <TemplateGallery
                             on_close=move || show_gallery.set(false)
                             on_apply=move |template: Template| {
-                                app_state.canvas.record_snapshot(&format!("Apply Template: {}", template.name));
-                                for component in template.components {
-                                    app_state.canvas.add_component_without_snapshot(component);
-                                }
+                                app_state.canvas.add_components(
+                                    &format!("Apply Template: {}", template.name),
+                                    template.components,
+                                );
                                 app_state.ui.notification.set(Some(Notification::success(
                                     format!("✅ Template '{}' applied!", template.name),
                                 )));
@@ -23,8 +23,7 @@ pub fn Toolbar(
 
     // Undo/Redo handlers
     let do_undo = move |_| {
-        if let Some(snapshot) = app_state.canvas.history.write().undo() {
-            app_state.canvas.apply_snapshot(&snapshot);
+        if app_state.canvas.undo() {
             app_state
                 .ui
                 .notify(Notification::info("↪️ Undo".to_string()));
@@ -32,8 +31,7 @@ pub fn Toolbar(
     };
 
     let do_redo = move |_| {
-        if let Some(snapshot) = app_state.canvas.history.write().redo() {
-            app_state.canvas.apply_snapshot(&snapshot);
+        if app_state.canvas.redo() {
             app_state
                 .ui
                 .notify(Notification::info("↪️ Redo".to_string()));
@@ -52,7 +50,7 @@ pub fn Toolbar(
             app_state
                 .ui
                 .notify(Notification::info("👁️ Preview Mode On".to_string()));
-            app_state.canvas.selected.set(None); // Clear selection
+            app_state.canvas.clear_selection(); // Clear selection
         } else {
             app_state
                 .ui
@@ -1,6 +1,6 @@
 use crate::builder::component_library::create_canvas_component;
 use crate::domain::{CanvasComponent, ComponentId};
-use crate::state::AppState;
+use crate::state::{AppState, SelectMode};
 use leptos::prelude::*;
 use std::collections::HashMap;
 use wasm_bindgen::JsCast;
@@ -71,8 +71,7 @@ fn TreeNode(
     // Reactively look up the component. If not found (deleted), return None (view! {} handles it)
     let component = Memo::new(move |_| component_map.with(|m| m.get(&id).cloned()));
 
-    let selected_id = app_state.canvas.selected;
-    let is_selected = move || selected_id.get() == Some(id);
+    let is_selected = move || app_state.canvas.is_selected(&id);
 
     view! {
         {move || {
@@ -99,16 +98,28 @@ fn TreeNode(
                     CanvasComponent::Custom(_) => "⚙️",
                 };
 
+                let name = comp.name().map(str::to_string);
+                let (locked, hidden) = (comp.editor().locked, comp.editor().hidden);
+                let toggle_lock = move |ev: leptos::ev::MouseEvent| {
+                    ev.stop_propagation();
+                    app_state.canvas.set_locked(&id, !locked);
+                };
+                let toggle_hidden = move |ev: leptos::ev::MouseEvent| {
+                    ev.stop_propagation();
+                    app_state.canvas.set_hidden(&id, !hidden);
+                };
+
                 let on_click = move |ev: leptos::ev::MouseEvent| {
                     ev.stop_propagation();
-                    app_state.canvas.selected.set(Some(id));
+                    app_state.canvas.click_select(id, SelectMode::from_mouse(&ev));
                 };
 
                 let on_keydown = move |ev: leptos::ev::KeyboardEvent| {
                      if ev.key() == "Enter" || ev.key() == " " {
                         ev.prevent_default();
                         ev.stop_propagation();
-                        app_state.canvas.selected.set(Some(id));
+                        let mode = SelectMode::from_modifiers(ev.shift_key(), ev.ctrl_key() || ev.meta_key());
+                        app_state.canvas.click_select(id, mode);
                     }
                 };
 
@@ -227,11 +238,20 @@ fn TreeNode(
                 view! {
                     <div class="tree-node-wrapper" role="presentation">
                         <div
-                            class=move || if is_selected() { "tree-node selected" } else { "tree-node" }
+                            class=move || {
+                                let mut class = String::from("tree-node");
+                                if is_selected() {
+                                    class.push_str(" selected");
+                                }
+                                if hidden {
+                                    class.push_str(" hidden");
+                                }
+                                class
+                            }
                             style=format!("padding-left: {}px", level * 12 + 12)
                             on:click=on_click
                             on:keydown=on_keydown
-                            draggable="true"
+                            draggable=(!locked).to_string()
                             on:dragstart=on_drag_start
                             on:dragover=on_drag_over
                             on:drop=on_drop
@@ -240,7 +260,34 @@ fn TreeNode(
                             tabindex="0"
                         >
                             <span class="tree-node-icon" aria-hidden="true">{icon}</span>
-                            <span class="tree-node-label">{label}</span>
+                            {match name {
+                                Some(name) => view! {
+                                    <span class="tree-node-label">
+                                        {name}
+                                        " "
+                                        <span class="tree-node-type">{label}</span>
+                                    </span>
+                                }.into_any(),
+                                None => view! { <span class="tree-node-label">{label}</span> }.into_any(),
+                            }}
+                            <button
+                                class=if locked { "tree-node-toggle active" } else { "tree-node-toggle" }
+                                title=if locked { "Unlock" } else { "Lock" }
+                                aria-label=if locked { "Unlock component" } else { "Lock component" }
+                                aria-pressed=locked.to_string()
+                                on:click=toggle_lock
+                            >
+                                {if locked { "🔒" } else { "🔓" }}
+                            </button>
+                            <button
+                                class=if hidden { "tree-node-toggle active" } else { "tree-node-toggle" }
+                                title=if hidden { "Show" } else { "Hide" }
+                                aria-label=if hidden { "Show component" } else { "Hide component" }
+                                aria-pressed=hidden.to_string()
+                                on:click=toggle_hidden
+                            >
+                                {if hidden { "🙈" } else { "👁" }}
+                            </button>
                         </div>
                         {if !children.is_empty() {
                             Some(view! {
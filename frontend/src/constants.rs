@@ -7,11 +7,20 @@
 // History & Undo/Redo
 // ============================================================================
 
-/// Maximum number of snapshots to keep in the undo/redo history
+/// Maximum number of actions that can be undone in a row
 pub const MAX_HISTORY_SIZE: usize = 50;
 
-/// Minimum time (ms) between history snapshots to prevent excessive recording
-pub const HISTORY_DEBOUNCE_MS: u32 = 100;
+/// Maximum number of history entries kept across all branches of the undo tree
+pub const MAX_HISTORY_NODES: usize = 200;
+
+/// Edits to the same fields less than this many ms apart share a history entry
+pub const HISTORY_COALESCE_MS: f64 = 1000.0;
+
+/// Delay (ms) after the last change before a project's undo history is stored
+pub const HISTORY_PERSIST_DEBOUNCE_MS: u32 = 1000;
+
+/// Maximum size of a project's stored undo history, compressed
+pub const MAX_PERSISTED_HISTORY_BYTES: usize = 512 * 1024;
 
 // ============================================================================
 // UI Timing
@@ -38,6 +47,9 @@ pub const DRAG_PREVIEW_THROTTLE_MS: u32 = 16; // ~60fps
 /// Auto-save debounce delay (ms)
 pub const AUTO_SAVE_DEBOUNCE_MS: u32 = 1000;
 
+/// Crash recovery journal debounce delay (ms)
+pub const RECOVERY_JOURNAL_DEBOUNCE_MS: u32 = 500;
+
 // ============================================================================
 // Canvas & Layout
 // ============================================================================
@@ -126,6 +126,24 @@ pub trait Component: Clone {
     fn validate(&self) -> Result<(), ValidationError>;
 }
 
+/// Editor-only flags of a component, never part of the design. Every
+/// component has them; they are left out of saved JSON while unset.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditorFlags {
+    /// Cannot be selected on the canvas, dragged, or dropped into
+    #[serde(default)]
+    pub locked: bool,
+    /// Left out of the canvas, and of exports if the settings say so
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+impl EditorFlags {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// Button variants
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ButtonVariant {
@@ -158,6 +176,10 @@ pub struct ButtonComponent {
     pub bindings: HashMap<String, String>,
     #[serde(default)]
     pub style: ComponentStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "EditorFlags::is_default")]
+    pub editor: EditorFlags,
 }
 
 impl ButtonComponent {
@@ -172,6 +194,8 @@ impl ButtonComponent {
             animation: None,
             bindings: HashMap::new(),
             style: ComponentStyle::default(),
+            name: None,
+            editor: EditorFlags::default(),
         }
     }
 }
@@ -229,6 +253,10 @@ pub struct TextComponent {
     pub bindings: HashMap<String, String>,
     #[serde(default)]
     pub custom_style: ComponentStyle, // renamed to avoid conflict with existing 'style' field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "EditorFlags::is_default")]
+    pub editor: EditorFlags,
 }
 
 impl TextComponent {
@@ -241,6 +269,8 @@ impl TextComponent {
             animation: None,
             bindings: HashMap::new(),
             custom_style: ComponentStyle::default(),
+            name: None,
+            editor: EditorFlags::default(),
         }
     }
 }
@@ -286,6 +316,10 @@ pub struct InputComponent {
     pub bindings: HashMap<String, String>,
     #[serde(default)]
     pub style: ComponentStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "EditorFlags::is_default")]
+    pub editor: EditorFlags,
 }
 
 impl InputComponent {
@@ -301,6 +335,8 @@ impl InputComponent {
             animation: None,
             bindings: HashMap::new(),
             style: ComponentStyle::default(),
+            name: None,
+            editor: EditorFlags::default(),
         }
     }
 }
@@ -339,6 +375,10 @@ pub struct SelectComponent {
     pub bindings: HashMap<String, String>,
     #[serde(default)]
     pub style: ComponentStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "EditorFlags::is_default")]
+    pub editor: EditorFlags,
 }
 
 impl SelectComponent {
@@ -352,6 +392,8 @@ impl SelectComponent {
             animation: None,
             bindings: HashMap::new(),
             style: ComponentStyle::default(),
+            name: None,
+            editor: EditorFlags::default(),
         }
     }
 }
@@ -445,6 +487,10 @@ pub struct ContainerComponent {
     pub bindings: HashMap<String, String>,
     #[serde(default)]
     pub style: ComponentStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "EditorFlags::is_default")]
+    pub editor: EditorFlags,
 }
 
 impl ContainerComponent {
@@ -464,6 +510,8 @@ impl ContainerComponent {
             animation: None,
             bindings: HashMap::new(),
             style: ComponentStyle::default(),
+            name: None,
+            editor: EditorFlags::default(),
         }
     }
 }
@@ -507,8 +555,25 @@ pub struct ImageComponent {
     pub bindings: HashMap<String, String>,
     #[serde(default)]
     pub style: ComponentStyle,
+    /// Responsive candidates for `srcset`, narrowest first
+    #[serde(default)]
+    pub srcset: Vec<ImageSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "EditorFlags::is_default")]
+    pub editor: EditorFlags,
 }
 
+/// A `srcset` candidate: an image URL and its width in pixels
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageSource {
+    pub src: String,
+    pub width: u32,
+}
+
+/// URL scheme for images stored in the backend asset store (`asset://<id>`)
+pub const ASSET_SCHEME: &str = "asset://";
+
 impl ImageComponent {
     pub fn new(src: String, alt: String) -> Self {
         Self {
@@ -521,8 +586,40 @@ impl ImageComponent {
             animation: None,
             bindings: HashMap::new(),
             style: ComponentStyle::default(),
+            srcset: Vec::new(),
+            name: None,
+            editor: EditorFlags::default(),
         }
     }
+
+    /// `srcset` attribute value, with each URL passed through `resolve`
+    pub fn srcset_attr(&self, resolve: impl Fn(&str) -> String) -> Option<String> {
+        if self.srcset.is_empty() {
+            return None;
+        }
+        let candidates: Vec<String> = self
+            .srcset
+            .iter()
+            .map(|s| format!("{} {}w", resolve(&s.src), s.width))
+            .collect();
+        Some(candidates.join(", "))
+    }
+
+    /// `sizes` attribute value: a fixed pixel width when one is set, else the
+    /// full viewport width
+    pub fn sizes_attr(&self) -> String {
+        match self.width.as_deref() {
+            Some(w) if w.trim().ends_with("px") => w.trim().to_string(),
+            _ => "100vw".to_string(),
+        }
+    }
+
+    /// Id of the uploaded asset this image shows, if `src` is an asset reference
+    pub fn asset_id(&self) -> Option<&str> {
+        self.src
+            .strip_prefix(ASSET_SCHEME)
+            .filter(|id| !id.is_empty())
+    }
 }
 
 impl Component for ImageComponent {
@@ -561,6 +658,10 @@ pub struct CardComponent {
     pub bindings: HashMap<String, String>,
     #[serde(default)]
     pub style: ComponentStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "EditorFlags::is_default")]
+    pub editor: EditorFlags,
 }
 
 impl CardComponent {
@@ -576,6 +677,8 @@ impl CardComponent {
             animation: None,
             bindings: HashMap::new(),
             style: ComponentStyle::default(),
+            name: None,
+            editor: EditorFlags::default(),
         }
     }
 }
@@ -623,6 +726,11 @@ pub struct CustomComponent {
     pub bindings: HashMap<String, String>,
     #[serde(default)]
     pub style: ComponentStyle,
+    /// `name` is the component type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_name: Option<String>,
+    #[serde(default, skip_serializing_if = "EditorFlags::is_default")]
+    pub editor: EditorFlags,
 }
 
 impl CustomComponent {
@@ -634,6 +742,8 @@ impl CustomComponent {
             props: HashMap::new(),
             bindings: HashMap::new(),
             style: ComponentStyle::default(),
+            instance_name: None,
+            editor: EditorFlags::default(),
         }
     }
 }
@@ -675,6 +785,73 @@ pub enum CanvasComponent {
     Custom(CustomComponent),
 }
 
+/// `components` without the hidden ones and everything inside them
+pub fn without_hidden(components: &[CanvasComponent]) -> Vec<CanvasComponent> {
+    components
+        .iter()
+        .filter(|component| !component.editor().hidden)
+        .map(|component| {
+            let mut component = component.clone();
+            match &mut component {
+                CanvasComponent::Container(c) => c.children = without_hidden(&c.children),
+                CanvasComponent::Card(c) => c.children = without_hidden(&c.children),
+                _ => {}
+            }
+            component
+        })
+        .collect()
+}
+
+/// The lowercase words of a component name. `submit_button`,
+/// `submitButton` and `SubmitButton` are all "submit" and "button".
+pub fn name_words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if c == '_' {
+            previous = None;
+            continue;
+        }
+        let boundary =
+            c.is_uppercase() && previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit());
+        match words.last_mut() {
+            Some(word) if previous.is_some() && !boundary => word.extend(c.to_lowercase()),
+            _ => words.push(c.to_lowercase().collect()),
+        }
+        previous = Some(c);
+    }
+    words
+}
+
+/// A component name in snake_case. Generated code spells names with the
+/// same key alike, so they clash there.
+pub fn name_key(name: &str) -> String {
+    name_words(name).join("_")
+}
+
+/// The component whose name has the same key as `name`, anywhere in
+/// `components`
+pub fn find_named<'a>(
+    components: &'a [CanvasComponent],
+    name: &str,
+) -> Option<&'a CanvasComponent> {
+    let key = name_key(name);
+    find_keyed(components, &key)
+}
+
+fn find_keyed<'a>(components: &'a [CanvasComponent], key: &str) -> Option<&'a CanvasComponent> {
+    components.iter().find_map(|component| {
+        if component.name().is_some_and(|name| name_key(name) == key) {
+            return Some(component);
+        }
+        match component {
+            CanvasComponent::Container(c) => find_keyed(&c.children, key),
+            CanvasComponent::Card(c) => find_keyed(&c.children, key),
+            _ => None,
+        }
+    })
+}
+
 impl CanvasComponent {
     pub fn id(&self) -> &ComponentId {
         match self {
@@ -703,6 +880,9 @@ impl CanvasComponent {
     }
 
     pub fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(name) = self.name() {
+            super::validation::ComponentNameValidator.validate(&name.to_string())?;
+        }
         match self {
             CanvasComponent::Button(c) => c.validate(),
             CanvasComponent::Text(c) => c.validate(),
@@ -715,8 +895,78 @@ impl CanvasComponent {
         }
     }
 
-    pub fn duplicate_with_new_id(&self) -> Self {
+    /// The name given to this component, such as `submit_button`. It is
+    /// unique on the canvas and used for ids, classes and refs in generated
+    /// code.
+    pub fn name(&self) -> Option<&str> {
         match self {
+            CanvasComponent::Button(c) => c.name.as_deref(),
+            CanvasComponent::Text(c) => c.name.as_deref(),
+            CanvasComponent::Input(c) => c.name.as_deref(),
+            CanvasComponent::Container(c) => c.name.as_deref(),
+            CanvasComponent::Image(c) => c.name.as_deref(),
+            CanvasComponent::Card(c) => c.name.as_deref(),
+            CanvasComponent::Select(c) => c.name.as_deref(),
+            CanvasComponent::Custom(c) => c.instance_name.as_deref(),
+        }
+    }
+
+    pub fn set_name(&mut self, name: Option<String>) {
+        match self {
+            CanvasComponent::Button(c) => c.name = name,
+            CanvasComponent::Text(c) => c.name = name,
+            CanvasComponent::Input(c) => c.name = name,
+            CanvasComponent::Container(c) => c.name = name,
+            CanvasComponent::Image(c) => c.name = name,
+            CanvasComponent::Card(c) => c.name = name,
+            CanvasComponent::Select(c) => c.name = name,
+            CanvasComponent::Custom(c) => c.instance_name = name,
+        }
+    }
+
+    pub fn editor(&self) -> &EditorFlags {
+        match self {
+            CanvasComponent::Button(c) => &c.editor,
+            CanvasComponent::Text(c) => &c.editor,
+            CanvasComponent::Input(c) => &c.editor,
+            CanvasComponent::Container(c) => &c.editor,
+            CanvasComponent::Image(c) => &c.editor,
+            CanvasComponent::Card(c) => &c.editor,
+            CanvasComponent::Select(c) => &c.editor,
+            CanvasComponent::Custom(c) => &c.editor,
+        }
+    }
+
+    pub fn editor_mut(&mut self) -> &mut EditorFlags {
+        match self {
+            CanvasComponent::Button(c) => &mut c.editor,
+            CanvasComponent::Text(c) => &mut c.editor,
+            CanvasComponent::Input(c) => &mut c.editor,
+            CanvasComponent::Container(c) => &mut c.editor,
+            CanvasComponent::Image(c) => &mut c.editor,
+            CanvasComponent::Card(c) => &mut c.editor,
+            CanvasComponent::Select(c) => &mut c.editor,
+            CanvasComponent::Custom(c) => &mut c.editor,
+        }
+    }
+
+    pub fn style_mut(&mut self) -> &mut ComponentStyle {
+        match self {
+            CanvasComponent::Button(c) => &mut c.style,
+            CanvasComponent::Text(c) => &mut c.custom_style,
+            CanvasComponent::Input(c) => &mut c.style,
+            CanvasComponent::Container(c) => &mut c.style,
+            CanvasComponent::Image(c) => &mut c.style,
+            CanvasComponent::Card(c) => &mut c.style,
+            CanvasComponent::Select(c) => &mut c.style,
+            CanvasComponent::Custom(c) => &mut c.style,
+        }
+    }
+
+    /// A copy with new ids throughout. Names must stay unique, so the copy
+    /// has none.
+    pub fn duplicate_with_new_id(&self) -> Self {
+        let mut copy = match self {
             CanvasComponent::Button(c) => {
                 let mut new_c = c.clone();
                 new_c.id = ComponentId::new();
@@ -767,7 +1017,9 @@ impl CanvasComponent {
                 new_c.id = ComponentId::new();
                 CanvasComponent::Custom(new_c)
             }
-        }
+        };
+        copy.set_name(None);
+        copy
     }
 }
 
@@ -775,6 +1027,33 @@ impl CanvasComponent {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_image_srcset_attributes() {
+        let mut image = ImageComponent::new("asset://a.png".to_string(), String::new());
+        assert_eq!(image.srcset_attr(str::to_string), None);
+
+        image.srcset = vec![
+            ImageSource {
+                src: "asset://a-320w.webp".to_string(),
+                width: 320,
+            },
+            ImageSource {
+                src: "asset://a.png".to_string(),
+                width: 800,
+            },
+        ];
+        assert_eq!(
+            image
+                .srcset_attr(|s| s.replace("asset://", "/img/"))
+                .as_deref(),
+            Some("/img/a-320w.webp 320w, /img/a.png 800w")
+        );
+        assert_eq!(image.sizes_attr(), "100vw");
+
+        image.width = Some("400px".to_string());
+        assert_eq!(image.sizes_attr(), "400px");
+    }
+
     #[test]
     fn test_component_id_creation() {
         let id1 = ComponentId::new();
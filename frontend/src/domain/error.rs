@@ -37,6 +37,8 @@ pub enum ErrorCode {
 
     // Network errors (7xxx)
     NetworkFailed = 7001,
+    NetworkOffline = 7002,
+    SyncConflict = 7003,
 
     // General errors (9xxx)
     Unknown = 9999,
@@ -67,6 +69,8 @@ impl ErrorCode {
             ErrorCode::ExportFailed => "E6001",
             ErrorCode::ExportFormatUnsupported => "E6002",
             ErrorCode::NetworkFailed => "E7001",
+            ErrorCode::NetworkOffline => "E7002",
+            ErrorCode::SyncConflict => "E7003",
             ErrorCode::Unknown => "E9999",
         }
     }
@@ -157,6 +161,14 @@ pub enum AppError {
 
     #[error("Network error: {0}")]
     Network(String),
+
+    /// The request never reached the backend
+    #[error("Offline: {0}")]
+    Offline(String),
+
+    /// The server copy changed since the version an edit started from
+    #[error("Conflict: {0}")]
+    Conflict(String),
 }
 
 pub type AppResult<T> = Result<T, AppError>;
@@ -174,6 +186,8 @@ impl AppError {
             AppError::NestingTooDeep(_) => ErrorCode::ComponentNestingTooDeep,
             AppError::Export(_) => ErrorCode::ExportFailed,
             AppError::Network(_) => ErrorCode::NetworkFailed,
+            AppError::Offline(_) => ErrorCode::NetworkOffline,
+            AppError::Conflict(_) => ErrorCode::SyncConflict,
         }
     }
 
@@ -199,6 +213,8 @@ impl AppError {
             }
             AppError::Export(msg) => format!("Export failed: {}", msg),
             AppError::Network(msg) => format!("Network error: {}", msg),
+            AppError::Offline(msg) => format!("Backend unreachable: {}", msg),
+            AppError::Conflict(msg) => format!("Sync conflict: {}", msg),
         }
     }
 
@@ -239,6 +255,8 @@ impl AppError {
             AppError::NestingTooDeep(_) => true,
             AppError::Export(_) => true,
             AppError::Network(_) => true,
+            AppError::Offline(_) => true,
+            AppError::Conflict(_) => true,
         }
     }
 }
@@ -1,39 +1,271 @@
 use crate::builder::snackbar::Snackbar;
-use crate::services::project_manager::{ProjectManager, ProjectMetadata};
+use crate::domain::AppError;
+use crate::pages::dashboard_sidebar::{DashboardSidebar, DashboardView, PROJECT_DRAG_TYPE};
+use crate::services::project_manager::{
+    ForkHistory, ForkOptions, OrganizationPatch, ProjectFacets, ProjectManager, ProjectMetadata,
+    ProjectQuery, ProjectSort,
+};
 use crate::state::app_state::{AppState, Notification};
 use crate::state::project::Project;
 use leptos::prelude::*;
 use leptos_router::components::A;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+
+/// Projects fetched per page while scrolling
+const PAGE_SIZE: usize = 24;
+/// Favorites listed in the sidebar
+const SIDEBAR_FAVORITES: usize = 20;
 
 #[component]
 pub fn DashboardPage() -> impl IntoView {
     let app_state = AppState::expect_context();
     let projects = RwSignal::new(Vec::<ProjectMetadata>::new());
     let loading = RwSignal::new(true);
+    let loading_more = RwSignal::new(false);
+    let next_cursor = RwSignal::new(None::<String>);
+    let total = RwSignal::new(0usize);
+    let search = RwSignal::new(String::new());
+    let search_contents = RwSignal::new(false);
+    let sort = RwSignal::new(ProjectSort::Modified);
+    // Backend unreachable: the list shows projects cached on this device
+    let offline = RwSignal::new(false);
+    // Responses to superseded queries are dropped
+    let request_seq = StoredValue::new(0u32);
+    let sentinel_ref = NodeRef::<leptos::html::Div>::new();
+    let observer = StoredValue::new_local(None::<web_sys::IntersectionObserver>);
+    let trash = RwSignal::new(Vec::<ProjectMetadata>::new());
+    let view = RwSignal::new(DashboardView::All);
+    let show_trash = Memo::new(move |_| view.get() == DashboardView::Trash);
+    let active_tags = RwSignal::new(Vec::<String>::new());
+    let facets = RwSignal::new(ProjectFacets::default());
+    let favorites = RwSignal::new(Vec::<ProjectMetadata>::new());
+    let tagging_id = RwSignal::new(None::<String>);
+    let tag_input = RwSignal::new(String::new());
     let editing_id = RwSignal::new(None::<String>);
     let edit_name = RwSignal::new(String::new());
+    let forking_id = RwSignal::new(None::<String>);
+    let fork_name = RwSignal::new(String::new());
+    let fork_history = RwSignal::new(ForkHistory::Copy);
     let import_input_ref = NodeRef::<leptos::html::Input>::new();
     let navigate = leptos_router::hooks::use_navigate();
 
+    let current_query = move || {
+        let term = search.get_untracked();
+        let (name, text) = if search_contents.get_untracked() {
+            (String::new(), term)
+        } else {
+            (term, String::new())
+        };
+        let (folder, favorite) = match view.get_untracked() {
+            DashboardView::Favorites => (None, Some(true)),
+            DashboardView::Folder(path) => (Some(path), None),
+            DashboardView::All | DashboardView::Trash => (None, None),
+        };
+        ProjectQuery {
+            name,
+            text,
+            tags: active_tags.get_untracked(),
+            folder,
+            favorite,
+            sort: sort.get_untracked(),
+            limit: Some(PAGE_SIZE),
+            ..Default::default()
+        }
+    };
+
     let refresh_projects = move || {
+        let seq = request_seq.get_value() + 1;
+        request_seq.set_value(seq);
         loading.set(true);
+        let query = current_query();
         leptos::task::spawn_local(async move {
-            match ProjectManager::list_projects().await {
-                Ok(list) => projects.set(list),
+            let result = ProjectManager::list_projects(&query).await;
+            if request_seq.get_value() != seq {
+                return;
+            }
+            offline.set(matches!(result, Err(AppError::Offline(_))));
+            match result {
+                Ok(page) => {
+                    total.set(page.total);
+                    next_cursor.set(page.next_cursor);
+                    projects.set(page.projects);
+                }
+                Err(AppError::Offline(_)) => {
+                    // Only one of the two is set
+                    let needle = format!("{}{}", query.name, query.text).to_lowercase();
+                    let cached: Vec<ProjectMetadata> = app_state
+                        .sync
+                        .cached_projects()
+                        .into_iter()
+                        .filter(|p| p.name.to_lowercase().contains(&needle))
+                        .collect();
+                    total.set(cached.len());
+                    next_cursor.set(None);
+                    projects.set(cached);
+                }
                 Err(e) => app_state.ui.notify(Notification::error(e.user_message())),
             }
             loading.set(false);
         });
     };
 
-    // Load on mount
-    Effect::new(move |_| {
+    let refresh_sidebar = move || {
+        leptos::task::spawn_local(async move {
+            match ProjectManager::project_facets().await {
+                Ok(f) => facets.set(f),
+                // The project list already says so
+                Err(AppError::Offline(_)) => {}
+                Err(e) => app_state.ui.notify(Notification::error(e.user_message())),
+            }
+            let query = ProjectQuery {
+                favorite: Some(true),
+                sort: ProjectSort::Name,
+                limit: Some(SIDEBAR_FAVORITES),
+                ..Default::default()
+            };
+            if let Ok(page) = ProjectManager::list_projects(&query).await {
+                favorites.set(page.projects);
+            }
+        });
+    };
+
+    // Project changes can touch the sidebar's folders, tags and favorites
+    let refresh_all = move || {
         refresh_projects();
+        refresh_sidebar();
+    };
+
+    let organize = move |id: String, patch: OrganizationPatch| {
+        leptos::task::spawn_local(async move {
+            match ProjectManager::organize_project(&id, &patch).await {
+                Ok(_) => refresh_all(),
+                Err(e) => app_state.ui.notify(Notification::error(e.user_message())),
+            }
+        });
+    };
+
+    let on_move = Callback::new(move |(id, folder): (String, String)| {
+        organize(
+            id,
+            OrganizationPatch {
+                folder: Some(folder),
+                ..Default::default()
+            },
+        );
+    });
+
+    let start_tagging = move |id: String, tags: Vec<String>| {
+        tagging_id.set(Some(id));
+        tag_input.set(tags.join(", "));
+    };
+
+    let save_tags = move |id: String| {
+        let tags = tag_input
+            .get_untracked()
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        tagging_id.set(None);
+        organize(
+            id,
+            OrganizationPatch {
+                tags: Some(tags),
+                ..Default::default()
+            },
+        );
+    };
+
+    let load_more = move || {
+        if loading.get_untracked() || loading_more.get_untracked() {
+            return;
+        }
+        let Some(cursor) = next_cursor.get_untracked() else {
+            return;
+        };
+        let seq = request_seq.get_value();
+        loading_more.set(true);
+        let query = ProjectQuery {
+            cursor: Some(cursor),
+            ..current_query()
+        };
+        leptos::task::spawn_local(async move {
+            let result = ProjectManager::list_projects(&query).await;
+            if request_seq.get_value() == seq {
+                match result {
+                    Ok(page) => {
+                        total.set(page.total);
+                        next_cursor.set(page.next_cursor);
+                        projects.update(|list| list.extend(page.projects));
+                    }
+                    Err(e) => app_state.ui.notify(Notification::error(e.user_message())),
+                }
+            }
+            loading_more.set(false);
+        });
+    };
+
+    // Infinite scroll: fetch the next page when the sentinel below the grid
+    // comes into view
+    Effect::new(move |_| {
+        let Some(sentinel) = sentinel_ref.get() else {
+            return;
+        };
+        let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+            let visible = entries.iter().any(|entry| {
+                entry
+                    .unchecked_into::<web_sys::IntersectionObserverEntry>()
+                    .is_intersecting()
+            });
+            if visible {
+                load_more();
+            }
+        });
+        if let Ok(obs) = web_sys::IntersectionObserver::new(callback.as_ref().unchecked_ref()) {
+            obs.observe(&sentinel);
+            observer.set_value(Some(obs));
+        }
+        callback.forget();
+    });
+    on_cleanup(move || {
+        observer.try_update_value(|obs| {
+            if let Some(obs) = obs.take() {
+                obs.disconnect();
+            }
+        });
+    });
+
+    let refresh_trash = move || {
+        loading.set(true);
+        leptos::task::spawn_local(async move {
+            match ProjectManager::list_trash().await {
+                Ok(list) => trash.set(list),
+                Err(e) => app_state.ui.notify(Notification::error(e.user_message())),
+            }
+            loading.set(false);
+        });
+    };
+
+    Effect::new(move |_| refresh_sidebar());
+
+    // Load on mount and whenever the view or the query changes
+    Effect::new(move |_| {
+        search.track();
+        search_contents.track();
+        sort.track();
+        active_tags.track();
+        view.track();
+        if show_trash.get() {
+            refresh_trash();
+        } else {
+            refresh_projects();
+        }
     });
 
     let on_delete = move |id: String| {
         if !window()
-            .confirm_with_message("Are you sure you want to delete this project?")
+            .confirm_with_message("Move this project to the trash?")
             .unwrap_or(false)
         {
             return;
@@ -42,11 +274,47 @@ pub fn DashboardPage() -> impl IntoView {
         leptos::task::spawn_local(async move {
             if let Err(e) = ProjectManager::delete_project(&id).await {
                 app_state.ui.notify(Notification::error(e.user_message()));
+            } else {
+                app_state
+                    .ui
+                    .notify(Notification::success("Project moved to trash".to_string()));
+                refresh_all();
+            }
+        });
+    };
+
+    let on_restore = move |id: String| {
+        leptos::task::spawn_local(async move {
+            if let Err(e) = ProjectManager::restore_project(&id).await {
+                app_state.ui.notify(Notification::error(e.user_message()));
+            } else {
+                app_state
+                    .ui
+                    .notify(Notification::success("Project restored".to_string()));
+                trash.update(|list| list.retain(|p| p.id != id));
+                refresh_sidebar();
+            }
+        });
+    };
+
+    let on_delete_forever = move |id: String| {
+        if !window()
+            .confirm_with_message(
+                "Permanently delete this project and its history? This cannot be undone.",
+            )
+            .unwrap_or(false)
+        {
+            return;
+        }
+
+        leptos::task::spawn_local(async move {
+            if let Err(e) = ProjectManager::delete_project_permanently(&id).await {
+                app_state.ui.notify(Notification::error(e.user_message()));
             } else {
                 app_state
                     .ui
                     .notify(Notification::success("Project deleted".to_string()));
-                refresh_projects();
+                trash.update(|list| list.retain(|p| p.id != id));
             }
         });
     };
@@ -80,7 +348,7 @@ pub fn DashboardPage() -> impl IntoView {
                                 app_state.ui.notify(Notification::success(
                                     "Project imported successfully".to_string(),
                                 ));
-                                refresh_projects();
+                                refresh_all();
                             }
                             Err(e) => {
                                 app_state.ui.notify(Notification::error(format!(
@@ -155,6 +423,34 @@ pub fn DashboardPage() -> impl IntoView {
         editing_id.set(None);
     };
 
+    let start_fork = move |id: String, current_name: String| {
+        editing_id.set(None);
+        forking_id.set(Some(id));
+        fork_name.set(format!("{} (Fork)", current_name));
+        fork_history.set(ForkHistory::Copy);
+    };
+
+    let save_fork = move |id: String| {
+        let name = fork_name.get_untracked().trim().to_string();
+        let options = ForkOptions {
+            name: (!name.is_empty()).then_some(name),
+            history: fork_history.get_untracked(),
+        };
+
+        leptos::task::spawn_local(async move {
+            match ProjectManager::fork_project(&id, &options).await {
+                Ok(_) => {
+                    app_state
+                        .ui
+                        .notify(Notification::success("Project forked".to_string()));
+                    forking_id.set(None);
+                    refresh_all();
+                }
+                Err(e) => app_state.ui.notify(Notification::error(e.user_message())),
+            }
+        });
+    };
+
     let on_new_header = on_new.clone();
 
     view! {
@@ -180,18 +476,128 @@ pub fn DashboardPage() -> impl IntoView {
                 </div>
             </header>
 
-            <main class="dashboard-content">
+            <main class="dashboard-content dashboard-layout">
                 <Snackbar notification=app_state.ui.notification />
+                <DashboardSidebar
+                    view=view
+                    active_tags=active_tags
+                    facets=facets
+                    favorites=favorites
+                    on_move=on_move
+                />
                 <div class="projects-section">
                     <div class="section-header">
-                        <h2>"My Projects"</h2>
+                        <h2>
+                            {move || match view.get() {
+                                DashboardView::All => "My Projects".to_string(),
+                                DashboardView::Favorites => "Favorites".to_string(),
+                                DashboardView::Folder(path) if path.is_empty() => "Unfiled".to_string(),
+                                DashboardView::Folder(path) => path.replace('/', " / "),
+                                DashboardView::Trash => "Trash".to_string(),
+                            }}
+                        </h2>
                         <span class="text-sm text-gray-500">
-                            {move || format!("{} Projects", projects.get().len())}
+                            {move || if show_trash.get() {
+                                format!("{} in Trash", trash.get().len())
+                            } else {
+                                format!("{} Projects", total.get())
+                            }}
                         </span>
                     </div>
 
-                    {move || if loading.get() {
-                        view! { <div class="loading-state">"Loading projects..."</div> }.into_any()
+                    <Show when=move || offline.get()>
+                        <div class="offline-banner" role="status">
+                            "Backend unreachable. Showing projects saved on this device; changes sync when it is back."
+                        </div>
+                    </Show>
+
+                    <Show when=move || !show_trash.get()>
+                        <div class="project-toolbar">
+                            <input
+                                type="search"
+                                class="project-search"
+                                placeholder="Search projects..."
+                                aria-label="Search projects"
+                                prop:value=search
+                                on:input=move |ev| search.set(event_target_value(&ev))
+                            />
+                            <label class="project-search-contents">
+                                <input
+                                    type="checkbox"
+                                    prop:checked=search_contents
+                                    on:change=move |ev| search_contents.set(event_target_checked(&ev))
+                                />
+                                "Search inside projects"
+                            </label>
+                            <select
+                                class="project-sort"
+                                aria-label="Sort projects"
+                                on:change=move |ev| sort.set(match event_target_value(&ev).as_str() {
+                                    "name" => ProjectSort::Name,
+                                    "components" => ProjectSort::Components,
+                                    _ => ProjectSort::Modified,
+                                })
+                            >
+                                <option value="modified" selected=true>"Last modified"</option>
+                                <option value="name">"Name"</option>
+                                <option value="components">"Component count"</option>
+                            </select>
+                        </div>
+                    </Show>
+
+                    {move || if loading.get() && (show_trash.get() || projects.get().is_empty()) {
+                        view! { <div class="loading-state">"Loading..."</div> }.into_any()
+                    } else if show_trash.get() {
+                        let list = trash.get();
+                        if list.is_empty() {
+                            return view! {
+                                <div class="empty-projects">
+                                    <div style="font-size: 48px; margin-bottom: 16px;">"🗑️"</div>
+                                    <h3>"Trash is empty"</h3>
+                                    <p>"Deleted projects stay here until you restore them or they expire."</p>
+                                </div>
+                            }.into_any();
+                        }
+                        view! {
+                            <div class="project-grid">
+                                {list.into_iter().map(|p| {
+                                    let id_restore = p.id.clone();
+                                    let id_forever = p.id.clone();
+                                    let deleted = p.deleted_at.map(format_date).unwrap_or_default();
+                                    view! {
+                                        <div class="project-card trashed">
+                                            <div class="project-card-header">
+                                                <h3 title=p.name.clone()>{p.name.clone()}</h3>
+                                            </div>
+                                            <div class="project-meta">
+                                                <div>{format!("{} components", p.component_count)}</div>
+                                                <div>{format!("Deleted: {}", deleted)}</div>
+                                            </div>
+                                            <div class="project-actions">
+                                                <button class="btn btn-sm btn-outline" on:click=move |_| on_restore(id_restore.clone())>
+                                                    "Restore"
+                                                </button>
+                                                <button class="btn btn-sm btn-ghost" style="color: var(--color-error-500);" on:click=move |_| on_delete_forever(id_forever.clone())>
+                                                    "Delete Forever"
+                                                </button>
+                                            </div>
+                                        </div>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </div>
+                        }.into_any()
+                    } else if projects.get().is_empty()
+                        && (!search.get().trim().is_empty()
+                            || !active_tags.get().is_empty()
+                            || view.get() != DashboardView::All)
+                    {
+                        view! {
+                            <div class="empty-projects">
+                                <div style="font-size: 48px; margin-bottom: 16px;">"🔍"</div>
+                                <h3>"No matching projects"</h3>
+                                <p>"Try a different search, folder or tag."</p>
+                            </div>
+                        }.into_any()
                     } else if projects.get().is_empty() {
                         view! {
                             <div class="empty-projects">
@@ -202,12 +608,66 @@ pub fn DashboardPage() -> impl IntoView {
                             </div>
                         }.into_any()
                     } else {
+                        let list = projects.get();
+                        let live_ids: std::collections::HashSet<String> =
+                            list.iter().map(|p| p.id.clone()).collect();
                         view! {
                             <div class="project-grid">
-                                {projects.get().into_iter().map(|p| {
+                                {list.into_iter().map(|p| {
                                     let id = p.id.clone();
                                     let id_ren = p.id.clone();
                                     let id_del = p.id.clone();
+                                    let id_fork = p.id.clone();
+                                    let id_fork_save = p.id.clone();
+                                    let name_fork = p.name.clone();
+                                    let is_forking = forking_id.get().as_deref() == Some(&id);
+                                    let is_tagging = tagging_id.get().as_deref() == Some(&id);
+                                    let id_drag = p.id.clone();
+                                    let id_fav = p.id.clone();
+                                    let id_tags = p.id.clone();
+                                    let id_tags_key = p.id.clone();
+                                    let id_tags_save = p.id.clone();
+                                    let favorite = p.favorite;
+                                    let tags_edit = p.tags.clone();
+                                    let thumbnail = p.thumbnail.clone();
+
+                                    let folder = p.folder.clone().map(|folder| view! {
+                                        <div class="project-folder">{format!("📁 {}", folder.replace('/', " / "))}</div>
+                                    });
+                                    let tag_chips = (!p.tags.is_empty()).then(|| view! {
+                                        <div class="tag-chips">
+                                            {p.tags.iter().cloned().map(|tag| {
+                                                let label = tag.clone();
+                                                view! {
+                                                    <button
+                                                        class="tag-chip"
+                                                        title="Filter by this tag"
+                                                        on:click=move |_| active_tags.update(|tags| {
+                                                            if !tags.contains(&tag) {
+                                                                tags.push(tag.clone());
+                                                            }
+                                                        })
+                                                    >
+                                                        {label}
+                                                    </button>
+                                                }
+                                            }).collect_view()}
+                                        </div>
+                                    });
+
+                                    // Link to the origin while it is still around
+                                    let origin = p.forked_from.clone().map(|origin| {
+                                        let label = format!("Forked from {}", origin.name);
+                                        if live_ids.contains(&origin.project_id) {
+                                            view! {
+                                                <div class="project-origin">
+                                                    <A href=format!("/editor/{}", origin.project_id)>{label}</A>
+                                                </div>
+                                            }.into_any()
+                                        } else {
+                                            view! { <div class="project-origin">{label}</div> }.into_any()
+                                        }
+                                    });
 
                                     let id_save_key = p.id.clone();
                                     let id_save_btn = p.id.clone();
@@ -218,17 +678,20 @@ pub fn DashboardPage() -> impl IntoView {
 
                                     let is_editing = editing_id.get().as_deref() == Some(&id);
 
-                                    let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(p.last_modified));
-                                    let date_str = format!("{}/{}/{}",
-                                        date.get_month() + 1,
-                                        date.get_date(),
-                                        date.get_full_year()
-                                    );
+                                    let date_str = format_date(p.last_modified);
 
                                     let editor_url = format!("/editor/{}", id);
 
                                     view! {
-                                        <div class="project-card">
+                                        <div
+                                            class="project-card"
+                                            draggable="true"
+                                            on:dragstart=move |ev: leptos::ev::DragEvent| {
+                                                if let Some(dt) = ev.data_transfer() {
+                                                    let _ = dt.set_data(PROJECT_DRAG_TYPE, &id_drag);
+                                                }
+                                            }
+                                        >
                                             {if is_editing {
                                                 view! {
                                                     <div class="rename-container" style="margin-bottom: 8px;">
@@ -257,27 +720,103 @@ pub fn DashboardPage() -> impl IntoView {
                                                 view! {
                                                     <div class="project-card-header">
                                                         <h3 title=name_title>{name_display}</h3>
+                                                        <button
+                                                            class=if favorite { "favorite-toggle active" } else { "favorite-toggle" }
+                                                            title=if favorite { "Remove from favorites" } else { "Add to favorites" }
+                                                            on:click=move |_| organize(id_fav.clone(), OrganizationPatch {
+                                                                favorite: Some(!favorite),
+                                                                ..Default::default()
+                                                            })
+                                                        >
+                                                            {if favorite { "★" } else { "☆" }}
+                                                        </button>
                                                     </div>
                                                 }.into_any()
                                             }}
 
                                             <A href=editor_url attr:class="project-card-link">
                                                 <div class="project-preview">
-                                                    // Placeholder for preview image
-                                                    <div class="preview-placeholder">"UI"</div>
+                                                    {match thumbnail {
+                                                        Some(src) => view! {
+                                                            <img class="project-thumbnail" src=src alt="" loading="lazy" />
+                                                        }.into_any(),
+                                                        None => view! { <div class="preview-placeholder">"UI"</div> }.into_any(),
+                                                    }}
                                                 </div>
                                             </A>
 
                                             <div class="project-meta">
                                                 <div>{format!("{} components", p.component_count)}</div>
                                                 <div>{format!("Last modified: {}", date_str)}</div>
+                                                {folder}
+                                                {origin}
                                             </div>
+                                            {tag_chips}
+
+                                            {is_tagging.then(|| view! {
+                                                <div class="fork-panel">
+                                                    <input
+                                                        type="text"
+                                                        class="rename-input"
+                                                        aria-label="Tags"
+                                                        placeholder="Comma-separated tags"
+                                                        prop:value=tag_input
+                                                        on:input=move |ev| tag_input.set(event_target_value(&ev))
+                                                        on:keydown=move |ev| {
+                                                            if ev.key() == "Enter" {
+                                                                save_tags(id_tags_key.clone());
+                                                            } else if ev.key() == "Escape" {
+                                                                tagging_id.set(None);
+                                                            }
+                                                        }
+                                                    />
+                                                    <div class="btn-group btn-group-sm">
+                                                        <button class="btn btn-sm btn-primary" on:click=move |_| save_tags(id_tags_save.clone())>"Save"</button>
+                                                        <button class="btn btn-sm btn-ghost" on:click=move |_| tagging_id.set(None)>"Cancel"</button>
+                                                    </div>
+                                                </div>
+                                            })}
+
+                                            {is_forking.then(|| view! {
+                                                <div class="fork-panel">
+                                                    <input
+                                                        type="text"
+                                                        class="rename-input"
+                                                        aria-label="Fork name"
+                                                        prop:value=fork_name
+                                                        on:input=move |ev| fork_name.set(event_target_value(&ev))
+                                                    />
+                                                    <select
+                                                        class="fork-history"
+                                                        aria-label="Commit history"
+                                                        on:change=move |ev| fork_history.set(match event_target_value(&ev).as_str() {
+                                                            "squash" => ForkHistory::Squash,
+                                                            "none" => ForkHistory::None,
+                                                            _ => ForkHistory::Copy,
+                                                        })
+                                                    >
+                                                        <option value="copy" selected=true>"Copy full history"</option>
+                                                        <option value="squash">"Squash into one commit"</option>
+                                                        <option value="none">"No history"</option>
+                                                    </select>
+                                                    <div class="btn-group btn-group-sm">
+                                                        <button class="btn btn-sm btn-primary" on:click=move |_| save_fork(id_fork_save.clone())>"Fork"</button>
+                                                        <button class="btn btn-sm btn-ghost" on:click=move |_| forking_id.set(None)>"Cancel"</button>
+                                                    </div>
+                                                </div>
+                                            })}
 
                                             <div class="project-actions">
                                                 <A href=format!("/editor/{}", id) attr:class="btn btn-sm btn-outline">"Open"</A>
                                                 <button class="btn btn-sm btn-ghost" on:click=move |_| start_rename(id_ren.clone(), name_ren.clone())>
                                                     "Rename"
                                                 </button>
+                                                <button class="btn btn-sm btn-ghost" on:click=move |_| start_fork(id_fork.clone(), name_fork.clone())>
+                                                    "Fork"
+                                                </button>
+                                                <button class="btn btn-sm btn-ghost" on:click=move |_| start_tagging(id_tags.clone(), tags_edit.clone())>
+                                                    "Tags"
+                                                </button>
                                                 <button class="btn btn-sm btn-ghost" style="color: var(--color-error-500);" on:click=move |_| on_delete(id_del.clone())>
                                                     "Delete"
                                                 </button>
@@ -288,8 +827,23 @@ pub fn DashboardPage() -> impl IntoView {
                             </div>
                         }.into_any()
                     }}
+
+                    <div node_ref=sentinel_ref class="scroll-sentinel" aria-hidden="true">
+                        {move || (loading_more.get() && !show_trash.get()).then_some("Loading more...")}
+                    </div>
                 </div>
             </main>
         </div>
     }
 }
+
+/// Format a JS timestamp as `month/day/year`
+fn format_date(timestamp: f64) -> String {
+    let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(timestamp));
+    format!(
+        "{}/{}/{}",
+        date.get_month() + 1,
+        date.get_date(),
+        date.get_full_year()
+    )
+}
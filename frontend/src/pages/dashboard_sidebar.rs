@@ -0,0 +1,358 @@
+//! Dashboard Sidebar
+//!
+//! Navigation for the project dashboard: all projects, favorites, the folder
+//! tree, tag filters and the trash. Project cards can be dragged onto a
+//! folder to move them there.
+
+use crate::services::project_manager::{FolderCount, ProjectFacets, ProjectMetadata};
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+/// Drag payload type carrying a project id
+pub const PROJECT_DRAG_TYPE: &str = "application/x-leptos-studio-project";
+
+/// Which projects the dashboard grid shows
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum DashboardView {
+    #[default]
+    All,
+    Favorites,
+    /// Projects directly in a folder; the empty path is the root
+    Folder(String),
+    Trash,
+}
+
+/// A node of the folder tree
+#[derive(Clone, Debug, PartialEq)]
+pub struct FolderNode {
+    pub name: String,
+    pub path: String,
+    /// Projects directly in this folder
+    pub count: usize,
+    /// Projects in this folder and all subfolders
+    pub total: usize,
+    pub children: Vec<FolderNode>,
+}
+
+/// Build the folder tree from folder paths. Intermediate folders that hold
+/// no projects themselves are created, and `extra` adds empty folders that
+/// only exist on the client so far. Siblings are sorted by name.
+pub fn build_folder_tree(folders: &[FolderCount], extra: &[String]) -> Vec<FolderNode> {
+    fn insert(nodes: &mut Vec<FolderNode>, segments: &[&str], prefix: &str, count: usize) {
+        let Some((name, rest)) = segments.split_first() else {
+            return;
+        };
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let index = match nodes.iter().position(|n| n.name == *name) {
+            Some(index) => index,
+            None => {
+                nodes.push(FolderNode {
+                    name: name.to_string(),
+                    path: path.clone(),
+                    count: 0,
+                    total: 0,
+                    children: Vec::new(),
+                });
+                nodes.len() - 1
+            }
+        };
+        let node = &mut nodes[index];
+        node.total += count;
+        if rest.is_empty() {
+            node.count += count;
+        } else {
+            insert(&mut node.children, rest, &path, count);
+        }
+    }
+
+    fn sort(nodes: &mut [FolderNode]) {
+        nodes.sort_by_key(|n| n.name.to_lowercase());
+        for node in nodes {
+            sort(&mut node.children);
+        }
+    }
+
+    let mut roots = Vec::new();
+    let entries = folders
+        .iter()
+        .map(|f| (f.path.as_str(), f.count))
+        .chain(extra.iter().map(|p| (p.as_str(), 0)));
+    for (path, count) in entries {
+        let segments: Vec<&str> = path
+            .split('/')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+        insert(&mut roots, &segments, "", count);
+    }
+    sort(&mut roots);
+    roots
+}
+
+/// Read the project id from a drop event, if a project card is being dragged
+fn dragged_project(ev: &leptos::ev::DragEvent) -> Option<String> {
+    ev.data_transfer()
+        .and_then(|dt| dt.get_data(PROJECT_DRAG_TYPE).ok())
+        .filter(|id| !id.is_empty())
+}
+
+#[component]
+pub fn DashboardSidebar(
+    view: RwSignal<DashboardView>,
+    /// Tags every listed project must carry
+    active_tags: RwSignal<Vec<String>>,
+    #[prop(into)] facets: Signal<ProjectFacets>,
+    /// Favorite projects, for quick access
+    #[prop(into)]
+    favorites: Signal<Vec<ProjectMetadata>>,
+    /// Called with `(project id, folder path)` when a card is dropped on a folder
+    #[prop(into)]
+    on_move: Callback<(String, String)>,
+) -> impl IntoView {
+    // Folders created here stay client-side until a project is moved in
+    let new_folders = RwSignal::new(Vec::<String>::new());
+    let drop_target = RwSignal::new(None::<String>);
+
+    let tree = Memo::new(move |_| build_folder_tree(&facets.get().folders, &new_folders.get()));
+
+    let create_folder = move |_| {
+        let parent = match view.get_untracked() {
+            DashboardView::Folder(path) => path,
+            _ => String::new(),
+        };
+        let Some(name) = window()
+            .prompt_with_message("Folder name")
+            .ok()
+            .flatten()
+            .map(|n| n.trim().replace('/', "-"))
+            .filter(|n| !n.is_empty())
+        else {
+            return;
+        };
+        let path = if parent.is_empty() {
+            name
+        } else {
+            format!("{}/{}", parent, name)
+        };
+        new_folders.update(|f| f.push(path.clone()));
+        view.set(DashboardView::Folder(path));
+    };
+
+    let nav_item = move |label: &'static str,
+                         target: DashboardView,
+                         count: Option<Signal<usize>>| {
+        let target_class = target.clone();
+        view! {
+            <button
+                class=move || if view.get() == target_class { "sidebar-nav-item active" } else { "sidebar-nav-item" }
+                on:click=move |_| view.set(target.clone())
+            >
+                <span>{label}</span>
+                {count.map(|count| view! { <span class="sidebar-count">{move || count.get()}</span> })}
+            </button>
+        }
+    };
+
+    let total = Signal::derive(move || facets.get().total);
+    let favorite_count = Signal::derive(move || facets.get().favorites);
+    let unfiled = Signal::derive(move || {
+        let facets = facets.get();
+        facets.total - facets.folders.iter().map(|f| f.count).sum::<usize>()
+    });
+
+    view! {
+        <aside class="dashboard-sidebar">
+            <nav class="sidebar-group">
+                {nav_item("📂 All Projects", DashboardView::All, Some(total))}
+                {nav_item("⭐ Favorites", DashboardView::Favorites, Some(favorite_count))}
+            </nav>
+
+            <Show when=move || !favorites.get().is_empty()>
+                <div class="sidebar-group sidebar-favorites">
+                    {move || favorites.get().into_iter().map(|p| {
+                        let title = p.name.clone();
+                        view! {
+                            <A href=format!("/editor/{}", p.id) attr:class="sidebar-favorite" attr:title=title>
+                                {p.name}
+                            </A>
+                        }
+                    }).collect_view()}
+                </div>
+            </Show>
+
+            <div class="sidebar-group">
+                <div class="sidebar-heading">
+                    <span>"Folders"</span>
+                    <button class="btn btn-sm btn-ghost" title="New folder" on:click=create_folder>"+"</button>
+                </div>
+                <FolderItem
+                    node=FolderNode {
+                        name: "Unfiled".to_string(),
+                        path: String::new(),
+                        count: unfiled.get_untracked(),
+                        total: unfiled.get_untracked(),
+                        children: Vec::new(),
+                    }
+                    depth=0
+                    view=view
+                    drop_target=drop_target
+                    on_move=on_move
+                    count_override=unfiled
+                />
+                {move || tree.get().into_iter().map(|node| view! {
+                    <FolderItem node=node depth=0 view=view drop_target=drop_target on_move=on_move />
+                }).collect_view()}
+            </div>
+
+            <Show when=move || !facets.get().tags.is_empty()>
+                <div class="sidebar-group">
+                    <div class="sidebar-heading"><span>"Tags"</span></div>
+                    <div class="tag-chips">
+                        {move || facets.get().tags.into_iter().map(|t| {
+                            let tag = t.tag.clone();
+                            let tag_class = t.tag.clone();
+                            view! {
+                                <button
+                                    class=move || if active_tags.get().contains(&tag_class) { "tag-chip active" } else { "tag-chip" }
+                                    on:click=move |_| active_tags.update(|tags| {
+                                        if let Some(i) = tags.iter().position(|x| *x == tag) {
+                                            tags.remove(i);
+                                        } else {
+                                            tags.push(tag.clone());
+                                        }
+                                    })
+                                >
+                                    {format!("{} ({})", t.tag, t.count)}
+                                </button>
+                            }
+                        }).collect_view()}
+                    </div>
+                </div>
+            </Show>
+
+            <nav class="sidebar-group">
+                {nav_item("🗑️ Trash", DashboardView::Trash, None)}
+            </nav>
+        </aside>
+    }
+}
+
+#[component]
+fn FolderItem(
+    node: FolderNode,
+    depth: usize,
+    view: RwSignal<DashboardView>,
+    drop_target: RwSignal<Option<String>>,
+    on_move: Callback<(String, String)>,
+    /// Live count for entries not backed by a tree node
+    #[prop(optional)]
+    count_override: Option<Signal<usize>>,
+) -> AnyView {
+    let path = node.path.clone();
+    let target = DashboardView::Folder(node.path.clone());
+    let is_active = {
+        let target = target.clone();
+        move || view.get() == target
+    };
+    let is_drop_target = {
+        let path = path.clone();
+        move || drop_target.get().as_deref() == Some(path.as_str())
+    };
+    let total = node.total;
+    let count = move || count_override.map(|c| c.get()).unwrap_or(total);
+
+    let on_drag_over = {
+        let path = path.clone();
+        move |ev: leptos::ev::DragEvent| {
+            ev.prevent_default();
+            drop_target.set(Some(path.clone()));
+        }
+    };
+    let on_drop = {
+        let path = path.clone();
+        move |ev: leptos::ev::DragEvent| {
+            ev.prevent_default();
+            drop_target.set(None);
+            if let Some(id) = dragged_project(&ev) {
+                on_move.run((id, path.clone()));
+            }
+        }
+    };
+
+    let children = node
+        .children
+        .into_iter()
+        .map(|child| {
+            view! { <FolderItem node=child depth=depth + 1 view=view drop_target=drop_target on_move=on_move /> }
+                .into_any()
+        })
+        .collect_view();
+
+    view! {
+        <button
+            class=move || {
+                let mut class = String::from("sidebar-nav-item folder-item");
+                if is_active() {
+                    class.push_str(" active");
+                }
+                if is_drop_target() {
+                    class.push_str(" drop-target");
+                }
+                class
+            }
+            style=format!("padding-left: {}px", 12 + depth * 14)
+            on:click=move |_| view.set(target.clone())
+            on:dragover=on_drag_over
+            on:dragleave=move |_| drop_target.set(None)
+            on:drop=on_drop
+        >
+            <span>{if depth == 0 && path.is_empty() { "🗂️ " } else { "📁 " }}{node.name}</span>
+            <span class="sidebar-count">{count}</span>
+        </button>
+        {children}
+    }
+    .into_any()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(path: &str, count: usize) -> FolderCount {
+        FolderCount {
+            path: path.to_string(),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_build_folder_tree() {
+        let tree = build_folder_tree(
+            &[
+                folder("Clients/Acme", 2),
+                folder("Clients", 1),
+                folder("archive", 3),
+            ],
+            &["Clients/Zeta".to_string()],
+        );
+
+        let names: Vec<&str> = tree.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["archive", "Clients"]);
+
+        let clients = &tree[1];
+        assert_eq!((clients.count, clients.total), (1, 3));
+        let children: Vec<(&str, &str, usize)> = clients
+            .children
+            .iter()
+            .map(|n| (n.name.as_str(), n.path.as_str(), n.total))
+            .collect();
+        assert_eq!(
+            children,
+            vec![("Acme", "Clients/Acme", 2), ("Zeta", "Clients/Zeta", 0)]
+        );
+    }
+}
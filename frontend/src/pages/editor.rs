@@ -11,6 +11,7 @@ use crate::builder::debug_panel::DebugPanel;
 use crate::builder::design_tokens::DesignTokenProvider;
 use crate::builder::drag_drop::DragPreview;
 use crate::builder::export_modal::ExportModal;
+use crate::builder::find_replace_panel::FindReplacePanel;
 use crate::builder::git_panel::GitPanel;
 use crate::builder::history_panel::HistoryPanel;
 use crate::builder::hooks::use_keyboard_actions::use_keyboard_actions;
@@ -18,6 +19,7 @@ use crate::builder::hooks::use_resize::use_resizable_sidebar;
 use crate::builder::keyboard::{KeyboardHandler, get_default_shortcuts};
 use crate::builder::preview::Preview;
 use crate::builder::property_editor::PropertyEditor;
+use crate::builder::recovery_dialog::RecoveryDialog;
 use crate::builder::responsive_preview::{CanvasViewport, ResponsivePreviewControls};
 use crate::builder::save_template_modal::SaveTemplateModal;
 use crate::builder::settings_modal::SettingsModal;
@@ -207,6 +209,7 @@ pub fn EditorPage() -> impl IntoView {
                                 <ResponsivePreviewControls />
                             </nav>
                             <div class="main-content">
+                                <FindReplacePanel />
                                 <section id="main-canvas" class="canvas-area" role="region" aria-label="Design canvas">
                                     <CanvasViewport>
                                         <Canvas />
@@ -285,12 +288,12 @@ pub fn EditorPage() -> impl IntoView {
                             <TemplateGallery
                                 on_close=move || show_template_gallery.set(false)
                                 on_apply=move |template: crate::services::Template| {
-                                    app_state.canvas.record_snapshot(&format!("Apply Template: {}", template.name));
                                     let comp_count = template.components.len();
                                     let template_name = template.name.clone();
-                                    for comp in template.components {
-                                        app_state.canvas.add_component_without_snapshot(comp);
-                                    }
+                                    app_state.canvas.add_components(
+                                        &format!("Apply Template: {}", template.name),
+                                        template.components,
+                                    );
                                     show_template_gallery.set(false);
                                     app_state.ui.notification.set(Some(Notification::success(
                                         format!("✨ Template '{}' applied!", template_name)
@@ -305,6 +308,7 @@ pub fn EditorPage() -> impl IntoView {
 
                 <SettingsModal />
                 <ShortcutsModal />
+                <RecoveryDialog />
 
                 {move || if show_save_template.get() {
                     view! {
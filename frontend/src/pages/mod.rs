@@ -1,2 +1,3 @@
 pub mod dashboard;
+pub mod dashboard_sidebar;
 pub mod editor;
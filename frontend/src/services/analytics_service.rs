@@ -3,7 +3,8 @@
 //! Provides application usage analytics, performance metrics,
 //! and session tracking for debugging and optimization.
 
-use gloo_net::http::Request;
+use crate::domain::AppResult;
+use crate::services::api_client::{AnalyticsBatch, AnalyticsEvent, ApiClient};
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -213,64 +214,32 @@ impl AnalyticsService {
         self.last_synced.get()
     }
 
-    fn get_api_base() -> String {
-        let runtime_base = window()
-            .get("LEPTOS_API_URL")
-            .and_then(|val| val.as_string());
-
-        let base = runtime_base
-            .or_else(|| option_env!("API_URL").map(|s| s.to_string()))
-            .unwrap_or_else(|| "http://localhost:3000".to_string());
-
-        format!("{}/api/analytics", base.trim_end_matches('/'))
-    }
-
     /// Flush metrics to backend
-    pub async fn flush_to_backend(&self) -> Result<(), String> {
+    pub async fn flush_to_backend(&self) -> AppResult<()> {
         let metrics = self.metrics.get();
         let session = self.session.get();
 
-        // Transform metrics to backend expected format (AnalyticsData)
-        // struct AnalyticsData { session_id, timestamp, event_type, payload }
-        // We will send a batch of events.
-
-        let events: Vec<serde_json::Value> = metrics
+        let mut events: Vec<AnalyticsEvent> = metrics
             .iter()
-            .map(|m| {
-                serde_json::json!({
-                    "session_id": session.session_id,
-                    "timestamp": m.timestamp,
-                    "event_type": format!("{:?}", m.metric_type),
-                    "payload": serde_json::to_value(m).unwrap_or_default(),
-                })
+            .map(|m| AnalyticsEvent {
+                session_id: session.session_id.clone(),
+                timestamp: m.timestamp,
+                event_type: format!("{:?}", m.metric_type),
+                payload: serde_json::to_value(m).unwrap_or_default(),
             })
             .collect();
 
         // Also add session info update as an event
-        let session_event = serde_json::json!({
-            "session_id": session.session_id,
-            "timestamp": js_sys::Date::now(),
-            "event_type": "SessionUpdate",
-            "payload": serde_json::to_value(&session).unwrap_or_default(),
-        });
-
-        let mut batch = events;
-        batch.push(session_event);
-
-        let body = serde_json::json!({
-            "events": batch
+        events.push(AnalyticsEvent {
+            session_id: session.session_id.clone(),
+            timestamp: js_sys::Date::now(),
+            event_type: "SessionUpdate".to_string(),
+            payload: serde_json::to_value(&session).unwrap_or_default(),
         });
 
-        let resp = Request::post(&Self::get_api_base())
-            .json(&body)
-            .map_err(|e| e.to_string())?
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if !resp.ok() {
-            return Err(format!("Server returned {}", resp.status()));
-        }
+        ApiClient::new()
+            .post_analytics(&AnalyticsBatch { events })
+            .await?;
 
         self.last_synced.set(js_sys::Date::now());
         // Clear metrics after successful flush to avoid duplication on next sync
@@ -0,0 +1,745 @@
+//! Backend API Client
+//!
+//! Typed client for the backend REST API described at `/api/openapi.json`.
+//! Every service that talks to the server goes through [`ApiClient`] so URL
+//! construction and error mapping into [`AppError`] live in one place.
+
+use crate::domain::{ASSET_SCHEME, AppError, AppResult, ImageSource};
+use crate::services::template_service::Template;
+use crate::state::project::Project;
+use gloo_net::http::{Request, Response};
+use leptos::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Fallback when neither `window.LEPTOS_API_URL` nor `API_URL` is set
+const DEFAULT_API_BASE: &str = "http://localhost:3000";
+
+/// Summary of a stored project, as returned by the project list
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProjectMetadata {
+    pub id: String,
+    pub name: String,
+    pub last_modified: f64,
+    pub component_count: usize,
+    /// When the project was moved to the trash (JS timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<f64>,
+    /// Set when the project was forked from another one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<ForkOrigin>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    /// Slash-separated folder path, `None` at the root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    /// SVG wireframe of the top of the canvas, as a `data:` URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+/// A project as stored by the backend, with its modification time
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct StoredProject {
+    #[serde(flatten)]
+    pub project: Project,
+    #[serde(default)]
+    pub last_modified: f64,
+}
+
+/// Change to a project's tags, favorite flag or folder; `None` fields are
+/// left unchanged
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+pub struct OrganizationPatch {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favorite: Option<bool>,
+    /// Empty moves the project to the root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+}
+
+/// A folder and how many projects sit directly in it
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FolderCount {
+    pub path: String,
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Folders, tags and favorites across all live projects
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProjectFacets {
+    pub folders: Vec<FolderCount>,
+    pub tags: Vec<TagCount>,
+    pub favorites: usize,
+    pub total: usize,
+}
+
+/// Sort field of the project list
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectSort {
+    Name,
+    #[default]
+    Modified,
+    Components,
+}
+
+impl ProjectSort {
+    fn as_str(self) -> &'static str {
+        match self {
+            ProjectSort::Name => "name",
+            ProjectSort::Modified => "modified",
+            ProjectSort::Components => "components",
+        }
+    }
+}
+
+/// Filters, sorting and paging for [`ApiClient::list_projects`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProjectQuery {
+    /// Substring of the project name
+    pub name: String,
+    /// Full-text search over the name and the text inside projects
+    pub text: String,
+    /// Projects must carry every tag
+    pub tags: Vec<String>,
+    /// Only projects directly in this folder; `Some("")` for the root
+    pub folder: Option<String>,
+    /// Only favorites (`true`) or only non-favorites (`false`)
+    pub favorite: Option<bool>,
+    pub sort: ProjectSort,
+    /// `None` uses the server default (ascending for name, else descending)
+    pub ascending: Option<bool>,
+    pub limit: Option<usize>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+impl ProjectQuery {
+    /// Encode as a URL query string, including the leading `?` when non-empty
+    pub fn to_query_string(&self) -> String {
+        let mut params: Vec<(&str, String)> = Vec::new();
+        if !self.name.trim().is_empty() {
+            params.push(("q", self.name.trim().to_string()));
+        }
+        if !self.text.trim().is_empty() {
+            params.push(("text", self.text.trim().to_string()));
+        }
+        if !self.tags.is_empty() {
+            params.push(("tags", self.tags.join(",")));
+        }
+        if let Some(folder) = &self.folder {
+            params.push(("folder", folder.clone()));
+        }
+        if let Some(favorite) = self.favorite {
+            params.push(("favorite", favorite.to_string()));
+        }
+        if self.sort != ProjectSort::default() {
+            params.push(("sort", self.sort.as_str().to_string()));
+        }
+        if let Some(ascending) = self.ascending {
+            params.push(("order", if ascending { "asc" } else { "desc" }.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(cursor) = &self.cursor {
+            params.push(("cursor", cursor.clone()));
+        }
+
+        if params.is_empty() {
+            return String::new();
+        }
+        let pairs: Vec<String> = params
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, encode_query_value(&value)))
+            .collect();
+        format!("?{}", pairs.join("&"))
+    }
+}
+
+/// One page of the project list
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProjectPage {
+    pub projects: Vec<ProjectMetadata>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    /// Matching projects across all pages
+    pub total: usize,
+}
+
+/// Percent-encode a query parameter value
+fn encode_query_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// The project a fork was copied from
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ForkOrigin {
+    pub project_id: String,
+    /// Name of the source project at the time of the fork
+    pub name: String,
+    #[serde(default)]
+    pub commit_id: Option<String>,
+    /// JS timestamp in milliseconds
+    pub forked_at: f64,
+}
+
+/// What a fork does with the source project's commit history
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForkHistory {
+    /// Copy every commit
+    #[default]
+    Copy,
+    /// Replace the history with a single commit
+    Squash,
+    /// Start without history
+    None,
+}
+
+/// Options for forking a project
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ForkOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub history: ForkHistory,
+}
+
+/// A commit as stored by the backend
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommitRecord {
+    pub id: String,
+    pub message: String,
+    /// JS timestamp in milliseconds
+    pub timestamp: f64,
+    pub snapshot: serde_json::Value,
+}
+
+/// Payload for creating a commit
+#[derive(Clone, Debug, Serialize)]
+pub struct NewCommit<'a> {
+    pub message: &'a str,
+    pub timestamp: f64,
+    pub snapshot: &'a Project,
+}
+
+/// A single analytics event accepted by the backend
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnalyticsEvent {
+    pub session_id: String,
+    pub timestamp: f64,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+}
+
+/// Batch of analytics events
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AnalyticsBatch {
+    pub events: Vec<AnalyticsEvent>,
+}
+
+/// An uploaded media asset
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AssetInfo {
+    pub id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size: usize,
+    pub hash: String,
+    pub created_at: f64,
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// `#rrggbb`
+    #[serde(default)]
+    pub dominant_color: Option<String>,
+    /// Resized WebP copies, narrowest first
+    #[serde(default)]
+    pub variants: Vec<AssetVariantInfo>,
+}
+
+/// A resized copy of an asset
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AssetVariantInfo {
+    pub id: String,
+    pub width: u32,
+    pub height: u32,
+    pub mime_type: String,
+    pub size: usize,
+}
+
+impl AssetInfo {
+    /// `asset://` reference for use as an image `src`
+    pub fn src(&self) -> String {
+        format!("{}{}", ASSET_SCHEME, self.id)
+    }
+
+    /// `srcset` candidates: every variant plus the original at its intrinsic width
+    pub fn srcset(&self) -> Vec<ImageSource> {
+        if self.variants.is_empty() {
+            return Vec::new();
+        }
+        let mut sources: Vec<ImageSource> = self
+            .variants
+            .iter()
+            .map(|v| ImageSource {
+                src: format!("{}{}", ASSET_SCHEME, v.id),
+                width: v.width,
+            })
+            .collect();
+        if let Some(width) = self.width {
+            sources.push(ImageSource {
+                src: self.src(),
+                width,
+            });
+        }
+        sources
+    }
+}
+
+/// Typed client for the backend API
+#[derive(Clone, Debug)]
+pub struct ApiClient {
+    base: String,
+}
+
+impl ApiClient {
+    /// Create a client for the configured API base URL
+    pub fn new() -> Self {
+        Self::with_base(&api_base())
+    }
+
+    /// Create a client for an explicit base URL (e.g. `http://localhost:3000`)
+    pub fn with_base(base: &str) -> Self {
+        Self {
+            base: base.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Build an absolute URL for an API path such as `/api/projects`
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base, path.trim_start_matches('/'))
+    }
+
+    // --- Projects ---
+
+    /// List metadata for all projects, most recently modified first
+    pub async fn list_projects(&self, query: &ProjectQuery) -> AppResult<ProjectPage> {
+        let url = self.url(&format!("/api/projects{}", query.to_query_string()));
+        let resp = send(Request::get(&url)).await?;
+        decode(resp).await
+    }
+
+    /// Fetch a full project document
+    pub async fn get_project(&self, id: &str) -> AppResult<Project> {
+        self.get_stored_project(id)
+            .await
+            .map(|stored| stored.project)
+    }
+
+    /// Fetch a project together with its server modification time
+    pub async fn get_stored_project(&self, id: &str) -> AppResult<StoredProject> {
+        let url = self.url(&format!("/api/projects/{}", id));
+        let resp = send(Request::get(&url)).await?;
+        decode(resp).await
+    }
+
+    /// Create or replace a project under `id`.
+    ///
+    /// With `base_modified`, the save fails with [`AppError::Conflict`]
+    /// unless the stored project is still at that version.
+    pub async fn save_project(
+        &self,
+        id: &str,
+        project: &Project,
+        base_modified: Option<f64>,
+    ) -> AppResult<ProjectMetadata> {
+        let mut json =
+            serde_json::to_value(project).map_err(|e| AppError::Serialization(e.to_string()))?;
+
+        if let Some(obj) = json.as_object_mut() {
+            obj.insert("id".to_string(), serde_json::Value::String(id.to_string()));
+            obj.insert(
+                "last_modified".to_string(),
+                serde_json::Value::from(js_sys::Date::now()),
+            );
+            if let Some(base) = base_modified {
+                obj.insert("base_modified".to_string(), serde_json::Value::from(base));
+            }
+        }
+
+        let resp = send_json(Request::post(&self.url("/api/projects")), &json).await?;
+        decode(resp).await
+    }
+
+    /// Move a project to the trash
+    pub async fn delete_project(&self, id: &str) -> AppResult<()> {
+        let url = self.url(&format!("/api/projects/{}", id));
+        send(Request::delete(&url)).await.map(drop)
+    }
+
+    /// Folders, tags and favorite count across all projects
+    pub async fn project_facets(&self) -> AppResult<ProjectFacets> {
+        let resp = send(Request::get(&self.url("/api/projects/facets"))).await?;
+        decode(resp).await
+    }
+
+    /// Change a project's tags, favorite flag or folder
+    pub async fn organize_project(
+        &self,
+        id: &str,
+        patch: &OrganizationPatch,
+    ) -> AppResult<ProjectMetadata> {
+        let url = self.url(&format!("/api/projects/{}/organization", id));
+        let resp = send_json(Request::patch(&url), patch).await?;
+        decode(resp).await
+    }
+
+    /// Copy a project under a new id, with fresh component ids
+    pub async fn fork_project(
+        &self,
+        id: &str,
+        options: &ForkOptions,
+    ) -> AppResult<ProjectMetadata> {
+        let url = self.url(&format!("/api/projects/{}/fork", id));
+        let resp = send_json(Request::post(&url), options).await?;
+        decode(resp).await
+    }
+
+    // --- Trash ---
+
+    /// List trashed projects, most recently deleted first
+    pub async fn list_trash(&self) -> AppResult<Vec<ProjectMetadata>> {
+        let resp = send(Request::get(&self.url("/api/trash"))).await?;
+        decode(resp).await
+    }
+
+    /// Take a project back out of the trash
+    pub async fn restore_project(&self, id: &str) -> AppResult<ProjectMetadata> {
+        let url = self.url(&format!("/api/trash/{}/restore", id));
+        let resp = send(Request::post(&url)).await?;
+        decode(resp).await
+    }
+
+    /// Permanently delete a trashed project and its commit history
+    pub async fn delete_project_permanently(&self, id: &str) -> AppResult<()> {
+        let url = self.url(&format!("/api/trash/{}", id));
+        send(Request::delete(&url)).await.map(drop)
+    }
+
+    // --- Commits ---
+
+    /// List a project's commits in chronological order
+    pub async fn list_commits(&self, project_id: &str) -> AppResult<Vec<CommitRecord>> {
+        let url = self.url(&format!("/api/projects/{}/commits", project_id));
+        let resp = send_unchecked(Request::get(&url)).await?;
+        // No history recorded yet
+        if resp.status() == 404 {
+            return Ok(Vec::new());
+        }
+        decode(check(resp)?).await
+    }
+
+    /// Append a commit to a project's history
+    pub async fn create_commit(
+        &self,
+        project_id: &str,
+        commit: &NewCommit<'_>,
+    ) -> AppResult<CommitRecord> {
+        let url = self.url(&format!("/api/projects/{}/commits", project_id));
+        let resp = send_json(Request::post(&url), commit).await?;
+        decode(resp).await
+    }
+
+    /// Delete a project's whole commit history
+    pub async fn delete_commits(&self, project_id: &str) -> AppResult<()> {
+        let url = self.url(&format!("/api/projects/{}/commits", project_id));
+        send(Request::delete(&url)).await.map(drop)
+    }
+
+    // --- Templates ---
+
+    /// List user-defined templates
+    pub async fn list_templates(&self) -> AppResult<Vec<Template>> {
+        let resp = send(Request::get(&self.url("/api/templates"))).await?;
+        decode(resp).await
+    }
+
+    /// Create or replace a template
+    pub async fn save_template(&self, template: &Template) -> AppResult<Template> {
+        let resp = send_json(Request::post(&self.url("/api/templates")), template).await?;
+        decode(resp).await
+    }
+
+    /// Delete a template
+    pub async fn delete_template(&self, id: &str) -> AppResult<()> {
+        let url = self.url(&format!("/api/templates/{}", id));
+        send(Request::delete(&url)).await.map(drop)
+    }
+
+    // --- Assets ---
+
+    /// List uploaded assets, newest first
+    pub async fn list_assets(&self) -> AppResult<Vec<AssetInfo>> {
+        let resp = send(Request::get(&self.url("/api/assets"))).await?;
+        decode(resp).await
+    }
+
+    /// Upload an image; identical content resolves to the existing asset
+    pub async fn upload_asset(&self, file: &web_sys::File) -> AppResult<AssetInfo> {
+        let form = web_sys::FormData::new()
+            .map_err(|_| AppError::Network("Failed to create form data".to_string()))?;
+        form.append_with_blob_and_filename("file", file, &file.name())
+            .map_err(|_| AppError::Network("Failed to attach file".to_string()))?;
+
+        let resp = Request::post(&self.url("/api/assets"))
+            .body(form)
+            .map_err(|e| AppError::Network(e.to_string()))?
+            .send()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        decode(check(resp)?).await
+    }
+
+    /// Delete an asset
+    pub async fn delete_asset(&self, id: &str) -> AppResult<()> {
+        send(Request::delete(&self.asset_url(id))).await.map(drop)
+    }
+
+    /// Fetch the raw bytes of an asset
+    pub async fn fetch_asset(&self, id: &str) -> AppResult<Vec<u8>> {
+        let resp = send(Request::get(&self.asset_url(id))).await?;
+        resp.binary()
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))
+    }
+
+    /// URL the asset is served from
+    pub fn asset_url(&self, id: &str) -> String {
+        self.url(&format!("/api/assets/{}", id))
+    }
+
+    /// Resolve an image `src` for display, mapping `asset://` references to
+    /// the asset endpoint and leaving other URLs untouched
+    pub fn resolve_src(&self, src: &str) -> String {
+        match src.strip_prefix(ASSET_SCHEME) {
+            Some(id) => self.asset_url(id),
+            None => src.to_string(),
+        }
+    }
+
+    // --- Analytics ---
+
+    /// Submit a batch of analytics events
+    pub async fn post_analytics(&self, batch: &AnalyticsBatch) -> AppResult<()> {
+        send_json(Request::post(&self.url("/api/analytics")), batch)
+            .await
+            .map(drop)
+    }
+}
+
+impl Default for ApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resolve the API base URL: runtime `window.LEPTOS_API_URL`, then the
+/// compile-time `API_URL`, then localhost.
+fn api_base() -> String {
+    let runtime_base = window()
+        .get("LEPTOS_API_URL")
+        .and_then(|val| val.as_string());
+
+    runtime_base
+        .or_else(|| option_env!("API_URL").map(|s| s.to_string()))
+        .unwrap_or_else(|| DEFAULT_API_BASE.to_string())
+}
+
+/// Map a non-success HTTP status to an [`AppError`]
+pub fn status_error(status: u16, status_text: &str) -> AppError {
+    let message = if status_text.is_empty() {
+        format!("Server returned {}", status)
+    } else {
+        format!("Server returned {}: {}", status, status_text)
+    };
+    match status {
+        412 => AppError::Conflict(message),
+        // A gateway in front of a backend that is down
+        502..=504 => AppError::Offline(message),
+        _ => AppError::Network(message),
+    }
+}
+
+async fn send(builder: gloo_net::http::RequestBuilder) -> AppResult<Response> {
+    check(send_unchecked(builder).await?)
+}
+
+async fn send_unchecked(builder: gloo_net::http::RequestBuilder) -> AppResult<Response> {
+    builder
+        .send()
+        .await
+        .map_err(|e| AppError::Offline(e.to_string()))
+}
+
+async fn send_json<T: Serialize + ?Sized>(
+    builder: gloo_net::http::RequestBuilder,
+    body: &T,
+) -> AppResult<Response> {
+    let resp = builder
+        .json(body)
+        .map_err(|e| AppError::Serialization(e.to_string()))?
+        .send()
+        .await
+        .map_err(|e| AppError::Offline(e.to_string()))?;
+    check(resp)
+}
+
+fn check(resp: Response) -> AppResult<Response> {
+    if resp.ok() {
+        Ok(resp)
+    } else {
+        Err(status_error(resp.status(), &resp.status_text()))
+    }
+}
+
+async fn decode<T: DeserializeOwned>(resp: Response) -> AppResult<T> {
+    resp.json()
+        .await
+        .map_err(|e| AppError::Serialization(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_joining() {
+        let client = ApiClient::with_base("http://example.com/");
+        assert_eq!(
+            client.url("/api/projects"),
+            "http://example.com/api/projects"
+        );
+        assert_eq!(
+            client.url("api/templates/1"),
+            "http://example.com/api/templates/1"
+        );
+        assert_eq!(
+            client.asset_url("abc.png"),
+            "http://example.com/api/assets/abc.png"
+        );
+        assert_eq!(
+            client.resolve_src("asset://abc.png"),
+            "http://example.com/api/assets/abc.png"
+        );
+        assert_eq!(client.resolve_src("/logo.svg"), "/logo.svg");
+    }
+
+    #[test]
+    fn test_asset_srcset_includes_original() {
+        let variant = |width: u32| AssetVariantInfo {
+            id: format!("abc-{}w.webp", width),
+            width,
+            height: width / 2,
+            mime_type: "image/webp".to_string(),
+            size: 1,
+        };
+        let mut asset = AssetInfo {
+            id: "abc.png".to_string(),
+            filename: "photo.png".to_string(),
+            mime_type: "image/png".to_string(),
+            size: 10,
+            hash: "abc".to_string(),
+            created_at: 0.0,
+            width: Some(1000),
+            height: Some(500),
+            dominant_color: None,
+            variants: vec![variant(320), variant(640)],
+        };
+
+        let widths: Vec<u32> = asset.srcset().iter().map(|s| s.width).collect();
+        assert_eq!(widths, vec![320, 640, 1000]);
+        assert_eq!(asset.srcset()[2].src, "asset://abc.png");
+
+        asset.variants.clear();
+        assert!(asset.srcset().is_empty());
+    }
+
+    #[test]
+    fn test_status_error_mapping() {
+        let err = status_error(404, "Not Found");
+        assert!(
+            matches!(err, AppError::Network(ref msg) if msg == "Server returned 404: Not Found")
+        );
+        assert_eq!(err.error_code(), crate::domain::ErrorCode::NetworkFailed);
+
+        let err = status_error(500, "");
+        assert!(matches!(err, AppError::Network(ref msg) if msg == "Server returned 500"));
+
+        assert!(matches!(status_error(412, ""), AppError::Conflict(_)));
+        assert!(matches!(status_error(503, ""), AppError::Offline(_)));
+    }
+
+    #[test]
+    fn test_fork_wire_format() {
+        let meta: ProjectMetadata = serde_json::from_str(
+            r#"{"id":"f","name":"A (Fork)","last_modified":1.0,"component_count":0,
+                "forked_from":{"project_id":"a","name":"A","forked_at":2.0}}"#,
+        )
+        .unwrap();
+        let origin = meta.forked_from.unwrap();
+        assert_eq!(origin.project_id, "a");
+        assert_eq!(origin.commit_id, None);
+
+        let options = ForkOptions {
+            name: None,
+            history: ForkHistory::Squash,
+        };
+        assert_eq!(
+            serde_json::to_string(&options).unwrap(),
+            r#"{"history":"squash"}"#
+        );
+    }
+
+    #[test]
+    fn test_project_query_string() {
+        assert_eq!(ProjectQuery::default().to_query_string(), "");
+
+        let query = ProjectQuery {
+            text: " sign up ".to_string(),
+            tags: vec!["web".to_string(), "a&b".to_string()],
+            folder: Some("Clients/Acme".to_string()),
+            favorite: Some(true),
+            sort: ProjectSort::Name,
+            ascending: Some(false),
+            limit: Some(24),
+            cursor: Some("7b22".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            query.to_query_string(),
+            "?text=sign%20up&tags=web%2Ca%26b&folder=Clients%2FAcme&favorite=true&sort=name&order=desc&limit=24&cursor=7b22"
+        );
+    }
+}
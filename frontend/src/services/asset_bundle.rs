@@ -0,0 +1,179 @@
+//! Asset Bundling
+//!
+//! Images uploaded to the backend are referenced as `asset://<id>`. Exported
+//! code cannot resolve that scheme, so exports rewrite references to a
+//! relative `assets/<id>` path and the export modal can package the code
+//! together with the referenced files as a ZIP archive.
+
+use crate::domain::{ASSET_SCHEME, CanvasComponent};
+use std::collections::BTreeSet;
+
+/// Directory that holds asset files inside an exported bundle
+pub const BUNDLE_ASSET_DIR: &str = "assets/";
+
+/// Collect the ids of every asset referenced in a component tree, sorted and
+/// deduplicated.
+pub fn referenced_assets(components: &[CanvasComponent]) -> Vec<String> {
+    fn walk(components: &[CanvasComponent], ids: &mut BTreeSet<String>) {
+        for component in components {
+            match component {
+                CanvasComponent::Image(img) => {
+                    if let Some(id) = img.asset_id() {
+                        ids.insert(id.to_string());
+                    }
+                    for source in &img.srcset {
+                        if let Some(id) = source.src.strip_prefix(ASSET_SCHEME) {
+                            ids.insert(id.to_string());
+                        }
+                    }
+                }
+                CanvasComponent::Container(c) => walk(&c.children, ids),
+                CanvasComponent::Card(c) => walk(&c.children, ids),
+                _ => {}
+            }
+        }
+    }
+
+    let mut ids = BTreeSet::new();
+    walk(components, &mut ids);
+    ids.into_iter().collect()
+}
+
+/// Rewrite `asset://<id>` references in generated code to bundle-relative paths
+pub fn localize_asset_urls(code: &str) -> String {
+    code.replace(ASSET_SCHEME, BUNDLE_ASSET_DIR)
+}
+
+/// Build an uncompressed ZIP archive from `(path, contents)` entries.
+///
+/// Images are already compressed, so entries are stored as-is.
+pub fn build_zip(entries: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+
+    for (name, data) in entries {
+        let offset = out.len() as u32;
+        let crc = crc32(data);
+        let size = data.len() as u32;
+
+        // Local file header
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        write_entry_fields(&mut out, name, crc, size);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        // Central directory record
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        write_entry_fields(&mut central, name, crc, size);
+        central.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        central.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        central.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    let central_size = central.len() as u32;
+    let count = entries.len() as u16;
+    out.extend_from_slice(&central);
+
+    // End of central directory
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // this disk
+    out.extend_from_slice(&0u16.to_le_bytes()); // central directory disk
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&central_size.to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+
+    out
+}
+
+/// Fields shared by local headers and central directory records
+fn write_entry_fields(out: &mut Vec<u8>, name: &str, crc: u32, size: u32) {
+    out.extend_from_slice(&20u16.to_le_bytes()); // version needed
+    out.extend_from_slice(&0x0800u16.to_le_bytes()); // UTF-8 names
+    out.extend_from_slice(&0u16.to_le_bytes()); // stored
+    out.extend_from_slice(&0u16.to_le_bytes()); // mod time
+    out.extend_from_slice(&0x0021u16.to_le_bytes()); // mod date: 1980-01-01
+    out.extend_from_slice(&crc.to_le_bytes());
+    out.extend_from_slice(&size.to_le_bytes()); // compressed
+    out.extend_from_slice(&size.to_le_bytes()); // uncompressed
+    out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // extra length
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ContainerComponent, ImageComponent, ImageSource};
+
+    #[test]
+    fn test_referenced_assets_walks_children() {
+        let mut container = ContainerComponent::new();
+        container
+            .children
+            .push(CanvasComponent::Image(ImageComponent::new(
+                "asset://b.png".to_string(),
+                String::new(),
+            )));
+        container
+            .children
+            .push(CanvasComponent::Image(ImageComponent::new(
+                "asset://a.svg".to_string(),
+                String::new(),
+            )));
+        let mut responsive = ImageComponent::new("asset://b.png".to_string(), String::new());
+        responsive.srcset.push(ImageSource {
+            src: "asset://b-320w.webp".to_string(),
+            width: 320,
+        });
+        let components = vec![
+            CanvasComponent::Container(container),
+            CanvasComponent::Image(responsive),
+            CanvasComponent::Image(ImageComponent::new(
+                "https://example.com/x.png".to_string(),
+                String::new(),
+            )),
+        ];
+
+        assert_eq!(
+            referenced_assets(&components),
+            vec!["a.svg", "b-320w.webp", "b.png"]
+        );
+    }
+
+    #[test]
+    fn test_localize_asset_urls() {
+        assert_eq!(
+            localize_asset_urls(r#"<img src="asset://abc.png" />"#),
+            r#"<img src="assets/abc.png" />"#
+        );
+    }
+
+    #[test]
+    fn test_build_zip_layout() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let zip = build_zip(&[("a.txt".to_string(), b"hello".to_vec())]);
+        assert_eq!(&zip[0..4], b"PK\x03\x04");
+        // Local header (30) + name (5) + data (5) + central record (46) + name (5) + end (22)
+        assert_eq!(zip.len(), 30 + 5 + 5 + 46 + 5 + 22);
+        assert_eq!(&zip[zip.len() - 22..zip.len() - 18], b"PK\x05\x06");
+    }
+}
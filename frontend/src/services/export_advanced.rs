@@ -3,9 +3,12 @@
 //! Additional export generators for JSON Schema, TypeScript types,
 //! React components, and other formats.
 
+use std::collections::HashMap;
+
 use crate::domain::{AppError, AppResult, CanvasComponent, Variable, VariableType};
 
 use super::CodeGenerator;
+use super::export_service::{ExportName, named_components};
 
 /// JSON Schema generator for component validation
 pub struct JsonSchemaGenerator;
@@ -473,9 +476,16 @@ impl CodeGenerator for ReactGenerator {
         };
 
         output.push_str(&format!(
-            "  const [vars, setVars] = React.useState({});\n\n",
+            "  const [vars, setVars] = React.useState({});\n",
             vars_init
         ));
+        for name in named_components(components) {
+            output.push_str(&format!(
+                "  const {}Ref = React.useRef(null);\n",
+                name.camel
+            ));
+        }
+        output.push('\n');
         output.push_str("  return (\n");
         output.push_str("    <>\n");
 
@@ -498,20 +508,43 @@ impl CodeGenerator for ReactGenerator {
 }
 
 impl ReactGenerator {
+    /// `id` from its binding or else the component name, and a ref for a
+    /// named component
+    fn id_attr(bindings: &HashMap<String, String>, name: Option<&ExportName>) -> String {
+        let mut attr = match (bindings.get("id"), name) {
+            (Some(bind), _) => format!(" id={{vars['{}']}}", bind),
+            (None, Some(name)) => format!(" id=\"{}\"", name.id),
+            (None, None) => String::new(),
+        };
+        if let Some(name) = name {
+            attr.push_str(&format!(" ref={{{}Ref}}", name.camel));
+        }
+        attr
+    }
+
+    /// The class of the component name, then any bound classes
+    fn class_attr(bindings: &HashMap<String, String>, name: Option<&ExportName>) -> String {
+        match (bindings.get("custom_css_classes"), name) {
+            (Some(bind), Some(name)) => {
+                format!(" className={{`{} ${{vars['{}']}}`}}", name.class, bind)
+            }
+            (Some(bind), None) => format!(" className={{vars['{}']}}", bind),
+            (None, Some(name)) => format!(" className=\"{}\"", name.class),
+            (None, None) => String::new(),
+        }
+    }
+
     fn generate_react(
         component: &CanvasComponent,
         output: &mut String,
         indent_level: usize,
     ) -> AppResult<()> {
         let indent = "  ".repeat(indent_level);
+        let name = ExportName::of(component);
 
         match component {
             CanvasComponent::Button(btn) => {
-                let id_attr = if let Some(bind) = btn.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&btn.bindings, name.as_ref());
 
                 let class_attr = if let Some(bind) = btn.bindings.get("custom_css_classes") {
                     format!(" className={{vars['{}']}}", bind)
@@ -545,28 +578,23 @@ impl ReactGenerator {
                 };
 
                 output.push_str(&format!(
-                    "{}<button{} {} className=\"{} {}\" disabled={{{}}}>{}</button>\n",
+                    "{}<button{} {} className=\"{} {}{}\" disabled={{{}}}>{}</button>\n",
                     indent,
                     id_attr,
                     class_attr,
                     variant_class,
                     size_class,
+                    name.as_ref()
+                        .map(|name| format!(" {}", name.class))
+                        .unwrap_or_default(),
                     disabled_expr,
                     label_expr
                 ));
             }
             CanvasComponent::Text(txt) => {
-                let id_attr = if let Some(bind) = txt.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&txt.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = txt.bindings.get("custom_css_classes") {
-                    format!(" className={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&txt.bindings, name.as_ref());
 
                 let tag = match txt.tag {
                     crate::domain::TextTag::H1 => "h1",
@@ -588,17 +616,9 @@ impl ReactGenerator {
                 ));
             }
             CanvasComponent::Input(inp) => {
-                let id_attr = if let Some(bind) = inp.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&inp.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = inp.bindings.get("custom_css_classes") {
-                    format!(" className={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&inp.bindings, name.as_ref());
 
                 let input_type = match inp.input_type {
                     crate::domain::InputType::Text => "text",
@@ -632,17 +652,9 @@ impl ReactGenerator {
                 ));
             }
             CanvasComponent::Select(sel) => {
-                let id_attr = if let Some(bind) = sel.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&sel.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = sel.bindings.get("custom_css_classes") {
-                    format!(" className={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&sel.bindings, name.as_ref());
 
                 let disabled_expr = if let Some(bind) = sel.bindings.get("disabled") {
                     format!("{{vars['{}']}}", bind)
@@ -735,17 +747,9 @@ impl ReactGenerator {
                     container.padding.left
                 );
 
-                let id_attr = if let Some(bind) = container.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&container.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = container.bindings.get("custom_css_classes") {
-                    format!(" className={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&container.bindings, name.as_ref());
 
                 output.push_str(&format!(
                     "{}<div{} {} style={{{}}}>\n",
@@ -759,17 +763,9 @@ impl ReactGenerator {
                 output.push_str(&format!("{}</div>\n", indent));
             }
             CanvasComponent::Image(img) => {
-                let id_attr = if let Some(bind) = img.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&img.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = img.bindings.get("custom_css_classes") {
-                    format!(" className={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&img.bindings, name.as_ref());
 
                 let width_attr = img
                     .width
@@ -809,17 +805,9 @@ impl ReactGenerator {
                 }
                 let style_str = style_parts.join(", ");
 
-                let id_attr = if let Some(bind) = card.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&card.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = card.bindings.get("custom_css_classes") {
-                    format!(" className={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&card.bindings, name.as_ref());
 
                 output.push_str(&format!(
                     "{}<div{} {} style={{{{ {} }}}}>\n",
@@ -831,17 +819,9 @@ impl ReactGenerator {
                 output.push_str(&format!("{}</div>\n", indent));
             }
             CanvasComponent::Custom(custom) => {
-                let id_attr = if let Some(bind) = custom.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&custom.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = custom.bindings.get("custom_css_classes") {
-                    format!(" className={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&custom.bindings, name.as_ref());
 
                 output.push_str(&format!("{}<!-- Custom: {} -->\n", indent, custom.name));
                 output.push_str(&format!(
@@ -897,6 +877,12 @@ impl CodeGenerator for VueGenerator {
             "const vars = ref<Record<string, any>>({});\n",
             vars_init
         ));
+        for name in named_components(components) {
+            template.push_str(&format!(
+                "const {} = ref<HTMLElement | null>(null);\n",
+                name.camel
+            ));
+        }
         template.push_str("</script>\n\n");
 
         // Style section
@@ -915,26 +901,45 @@ impl CodeGenerator for VueGenerator {
 }
 
 impl VueGenerator {
+    /// `id` from its binding or else the component name, and a template
+    /// ref for a named component
+    fn id_attr(bindings: &HashMap<String, String>, name: Option<&ExportName>) -> String {
+        let mut attr = match (bindings.get("id"), name) {
+            (Some(bind), _) => format!(" :id=\"vars['{}']\"", bind),
+            (None, Some(name)) => format!(" id=\"{}\"", name.id),
+            (None, None) => String::new(),
+        };
+        if let Some(name) = name {
+            attr.push_str(&format!(" ref=\"{}\"", name.camel));
+        }
+        attr
+    }
+
+    /// The class of the component name, which Vue merges with any bound
+    /// classes
+    fn class_attr(bindings: &HashMap<String, String>, name: Option<&ExportName>) -> String {
+        let mut attr = name
+            .map(|name| format!(" class=\"{}\"", name.class))
+            .unwrap_or_default();
+        if let Some(bind) = bindings.get("custom_css_classes") {
+            attr.push_str(&format!(" :class=\"vars['{}']\"", bind));
+        }
+        attr
+    }
+
     fn generate_vue(
         component: &CanvasComponent,
         output: &mut String,
         indent_level: usize,
     ) -> AppResult<()> {
         let indent = "  ".repeat(indent_level);
+        let name = ExportName::of(component);
 
         match component {
             CanvasComponent::Button(btn) => {
-                let id_attr = if let Some(bind) = btn.bindings.get("id") {
-                    format!(" :id=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&btn.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = btn.bindings.get("custom_css_classes") {
-                    format!(" :class=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&btn.bindings, name.as_ref());
 
                 let label_expr = if let Some(bind) = btn.bindings.get("label") {
                     format!("{{{{ vars['{}'] }}}}", bind)
@@ -954,17 +959,9 @@ impl VueGenerator {
                 ));
             }
             CanvasComponent::Text(txt) => {
-                let id_attr = if let Some(bind) = txt.bindings.get("id") {
-                    format!(" :id=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&txt.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = txt.bindings.get("custom_css_classes") {
-                    format!(" :class=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&txt.bindings, name.as_ref());
 
                 let tag = match txt.tag {
                     crate::domain::TextTag::H1 => "h1",
@@ -986,17 +983,9 @@ impl VueGenerator {
                 ));
             }
             CanvasComponent::Input(inp) => {
-                let id_attr = if let Some(bind) = inp.bindings.get("id") {
-                    format!(" :id=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&inp.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = inp.bindings.get("custom_css_classes") {
-                    format!(" :class=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&inp.bindings, name.as_ref());
 
                 let input_type = match inp.input_type {
                     crate::domain::InputType::Text => "text",
@@ -1030,17 +1019,9 @@ impl VueGenerator {
                 ));
             }
             CanvasComponent::Select(sel) => {
-                let id_attr = if let Some(bind) = sel.bindings.get("id") {
-                    format!(" :id=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&sel.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = sel.bindings.get("custom_css_classes") {
-                    format!(" :class=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&sel.bindings, name.as_ref());
 
                 let disabled_attr = if let Some(bind) = sel.bindings.get("disabled") {
                     format!(":disabled=\"vars['{}']\"", bind)
@@ -1081,17 +1062,9 @@ impl VueGenerator {
                 output.push_str(&format!("{}</select>\n", indent));
             }
             CanvasComponent::Container(container) => {
-                let id_attr = if let Some(bind) = container.bindings.get("id") {
-                    format!(" :id=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&container.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = container.bindings.get("custom_css_classes") {
-                    format!(" :class=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&container.bindings, name.as_ref());
 
                 output.push_str(&format!("{}<div{}{}>\n", indent, id_attr, class_attr));
                 for child in &container.children {
@@ -1100,17 +1073,9 @@ impl VueGenerator {
                 output.push_str(&format!("{}</div>\n", indent));
             }
             CanvasComponent::Image(img) => {
-                let id_attr = if let Some(bind) = img.bindings.get("id") {
-                    format!(" :id=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&img.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = img.bindings.get("custom_css_classes") {
-                    format!(" :class=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&img.bindings, name.as_ref());
 
                 let width_attr = img
                     .width
@@ -1141,16 +1106,19 @@ impl VueGenerator {
                 let shadow_class = if card.shadow { "shadow-md" } else { "" };
                 let border_class = if card.border { "border" } else { "" };
 
-                let id_attr = if let Some(bind) = card.bindings.get("id") {
-                    format!(" :id=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&card.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = card.bindings.get("custom_css_classes") {
-                    format!(" :class=\"vars['{}']\"", bind)
+                let class_attr = if card.bindings.contains_key("custom_css_classes") {
+                    Self::class_attr(&card.bindings, name.as_ref())
                 } else {
-                    format!(" class=\"card {} {}\"", shadow_class, border_class)
+                    format!(
+                        " class=\"card {} {}{}\"",
+                        shadow_class,
+                        border_class,
+                        name.as_ref()
+                            .map(|name| format!(" {}", name.class))
+                            .unwrap_or_default()
+                    )
                 };
 
                 output.push_str(&format!(
@@ -1163,17 +1131,9 @@ impl VueGenerator {
                 output.push_str(&format!("{}</div>\n", indent));
             }
             CanvasComponent::Custom(custom) => {
-                let id_attr = if let Some(bind) = custom.bindings.get("id") {
-                    format!(" :id=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&custom.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = custom.bindings.get("custom_css_classes") {
-                    format!(" :class=\"vars['{}']\"", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&custom.bindings, name.as_ref());
 
                 output.push_str(&format!("{}<!-- {} -->\n", indent, custom.name));
                 output.push_str(&format!(
@@ -1546,9 +1506,14 @@ impl TailwindHtmlGenerator {
                 output.push_str(&format!("{}</div>\n", indent));
             }
             CanvasComponent::Image(img) => {
+                let srcset_attr = img
+                    .srcset_attr(str::to_string)
+                    .map_or(String::new(), |srcset| {
+                        format!(" srcset=\"{}\" sizes=\"{}\"", srcset, img.sizes_attr())
+                    });
                 output.push_str(&format!(
-                    "{}<img src=\"{}\" alt=\"{}\" class=\"max-w-full h-auto\" />\n",
-                    indent, img.src, img.alt
+                    "{}<img src=\"{}\"{} alt=\"{}\" class=\"max-w-full h-auto\" />\n",
+                    indent, img.src, srcset_attr, img.alt
                 ));
             }
             CanvasComponent::Card(card) => {
@@ -1675,20 +1640,36 @@ impl CodeGenerator for SvelteGenerator {
 }
 
 impl SvelteGenerator {
+    /// `id` from its binding or else the component name
+    fn id_attr(bindings: &HashMap<String, String>, name: Option<&ExportName>) -> String {
+        match (bindings.get("id"), name) {
+            (Some(bind), _) => format!(" id={{vars['{}']}}", bind),
+            (None, Some(name)) => format!(" id=\"{}\"", name.id),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// The class of the component name, then any bound classes
+    fn class_attr(bindings: &HashMap<String, String>, name: Option<&ExportName>) -> String {
+        match (bindings.get("custom_css_classes"), name) {
+            (Some(bind), Some(name)) => format!(" class=\"{} {{vars['{}']}}\"", name.class, bind),
+            (Some(bind), None) => format!(" class={{vars['{}']}}", bind),
+            (None, Some(name)) => format!(" class=\"{}\"", name.class),
+            (None, None) => String::new(),
+        }
+    }
+
     fn generate_svelte(
         component: &CanvasComponent,
         output: &mut String,
         indent_level: usize,
     ) -> AppResult<()> {
         let indent = "  ".repeat(indent_level);
+        let name = ExportName::of(component);
 
         match component {
             CanvasComponent::Button(btn) => {
-                let id_attr = if let Some(bind) = btn.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&btn.bindings, name.as_ref());
 
                 let class_attr = if let Some(bind) = btn.bindings.get("custom_css_classes") {
                     format!(" class={{vars['{}']}}", bind)
@@ -1716,22 +1697,22 @@ impl SvelteGenerator {
                 };
 
                 output.push_str(&format!(
-                    "{}<button{}{} class=\"{}\" {}>{}</button>\n",
-                    indent, id_attr, class_attr, variant_class, disabled_expr, label_expr
+                    "{}<button{}{} class=\"{}{}\" {}>{}</button>\n",
+                    indent,
+                    id_attr,
+                    class_attr,
+                    variant_class,
+                    name.as_ref()
+                        .map(|name| format!(" {}", name.class))
+                        .unwrap_or_default(),
+                    disabled_expr,
+                    label_expr
                 ));
             }
             CanvasComponent::Text(txt) => {
-                let id_attr = if let Some(bind) = txt.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&txt.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = txt.bindings.get("custom_css_classes") {
-                    format!(" class={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&txt.bindings, name.as_ref());
 
                 let tag = match txt.tag {
                     crate::domain::TextTag::H1 => "h1",
@@ -1753,17 +1734,9 @@ impl SvelteGenerator {
                 ));
             }
             CanvasComponent::Input(inp) => {
-                let id_attr = if let Some(bind) = inp.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&inp.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = inp.bindings.get("custom_css_classes") {
-                    format!(" class={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&inp.bindings, name.as_ref());
 
                 let input_type = match inp.input_type {
                     crate::domain::InputType::Text => "text",
@@ -1797,17 +1770,9 @@ impl SvelteGenerator {
                 ));
             }
             CanvasComponent::Select(sel) => {
-                let id_attr = if let Some(bind) = sel.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&sel.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = sel.bindings.get("custom_css_classes") {
-                    format!(" class={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&sel.bindings, name.as_ref());
 
                 let disabled_attr = if let Some(bind) = sel.bindings.get("disabled") {
                     format!("disabled={{vars['{}']}}", bind)
@@ -1871,17 +1836,9 @@ impl SvelteGenerator {
                     container.padding.left
                 );
 
-                let id_attr = if let Some(bind) = container.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&container.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = container.bindings.get("custom_css_classes") {
-                    format!(" class={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&container.bindings, name.as_ref());
 
                 output.push_str(&format!(
                     "{}<div{}{} style=\"{}\">\n",
@@ -1895,17 +1852,9 @@ impl SvelteGenerator {
                 output.push_str(&format!("{}</div>\n", indent));
             }
             CanvasComponent::Image(img) => {
-                let id_attr = if let Some(bind) = img.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&img.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = img.bindings.get("custom_css_classes") {
-                    format!(" class={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&img.bindings, name.as_ref());
 
                 let src_val = if let Some(bind) = img.bindings.get("src") {
                     format!("src={{vars['{}']}}", bind)
@@ -1941,17 +1890,9 @@ impl SvelteGenerator {
                     }
                 );
 
-                let id_attr = if let Some(bind) = card.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&card.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = card.bindings.get("custom_css_classes") {
-                    format!(" class={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&card.bindings, name.as_ref());
 
                 output.push_str(&format!(
                     "{}<div{}{} style=\"{}\">\n",
@@ -1965,17 +1906,9 @@ impl SvelteGenerator {
                 output.push_str(&format!("{}</div>\n", indent));
             }
             CanvasComponent::Custom(custom) => {
-                let id_attr = if let Some(bind) = custom.bindings.get("id") {
-                    format!(" id={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = Self::id_attr(&custom.bindings, name.as_ref());
 
-                let class_attr = if let Some(bind) = custom.bindings.get("custom_css_classes") {
-                    format!(" class={{vars['{}']}}", bind)
-                } else {
-                    String::new()
-                };
+                let class_attr = Self::class_attr(&custom.bindings, name.as_ref());
 
                 output.push_str(&format!("{}<!-- Custom: {} -->\n", indent, custom.name));
                 output.push_str(&format!(
@@ -2026,6 +1959,24 @@ mod tests {
         assert!(code.contains("export function"));
     }
 
+    #[test]
+    fn test_react_and_vue_refs_for_named_components() {
+        let mut text = TextComponent::new("Hello".to_string());
+        text.name = Some("page_title".to_string());
+        let text = CanvasComponent::Text(text);
+
+        let react = ReactGenerator
+            .generate(std::slice::from_ref(&text), &[])
+            .unwrap();
+        assert!(react.contains("const pageTitleRef = React.useRef(null);"));
+        assert!(react.contains("id=\"page_title\" ref={pageTitleRef}"));
+        assert!(react.contains("className=\"page-title\""));
+
+        let vue = VueGenerator.generate(&[text], &[]).unwrap();
+        assert!(vue.contains("const pageTitle = ref<HTMLElement | null>(null);"));
+        assert!(vue.contains("id=\"page_title\" ref=\"pageTitle\" class=\"page-title\""));
+    }
+
     #[test]
     fn test_vue_generator() {
         let generator = VueGenerator;
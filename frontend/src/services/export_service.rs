@@ -1,6 +1,9 @@
-use crate::domain::{Animation, AppError, AppResult, CanvasComponent, Variable, VariableType};
+use crate::domain::{
+    Animation, AppError, AppResult, CanvasComponent, TextTag, Variable, VariableType, name_words,
+};
 use crate::state::ExportPreset;
 use std::cell::RefCell;
+use std::collections::HashMap;
 
 /// Helper to generate animation styles
 fn get_animation_css(animation: &Option<Animation>) -> String {
@@ -10,6 +13,70 @@ fn get_animation_css(animation: &Option<Animation>) -> String {
         .unwrap_or_default()
 }
 
+/// How a named component is referred to in generated code
+pub(crate) struct ExportName {
+    /// The name as given, for element ids
+    pub id: String,
+    /// kebab-case, for CSS classes
+    pub class: String,
+    /// camelCase, for JavaScript refs
+    pub camel: String,
+    /// snake_case, for Rust bindings
+    pub snake: String,
+}
+
+impl ExportName {
+    pub(crate) fn of(component: &CanvasComponent) -> Option<Self> {
+        let name = component.name()?;
+        let words = name_words(name);
+        if words.is_empty() {
+            return None;
+        }
+        let camel = words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) if i > 0 => first.to_uppercase().chain(chars).collect(),
+                    _ => word.clone(),
+                }
+            })
+            .collect();
+        Some(Self {
+            id: name.to_string(),
+            class: words.join("-"),
+            camel,
+            snake: words.join("_"),
+        })
+    }
+}
+
+/// Names of the named components in `components`, in document order
+pub(crate) fn named_components(components: &[CanvasComponent]) -> Vec<ExportName> {
+    let mut names = Vec::new();
+    for component in components {
+        names.extend(ExportName::of(component));
+        match component {
+            CanvasComponent::Container(c) => names.extend(named_components(&c.children)),
+            CanvasComponent::Card(c) => names.extend(named_components(&c.children)),
+            _ => {}
+        }
+    }
+    names
+}
+
+/// The `html::` element type of a text tag
+fn text_element(tag: &TextTag) -> &'static str {
+    match tag {
+        TextTag::H1 => "H1",
+        TextTag::H2 => "H2",
+        TextTag::H3 => "H3",
+        TextTag::P => "P",
+        TextTag::Span => "Span",
+    }
+}
+
 /// Code generator trait
 pub trait CodeGenerator {
     fn generate(&self, components: &[CanvasComponent], variables: &[Variable])
@@ -23,6 +90,8 @@ pub struct LeptosCodeGenerator {
     /// Tracks signals that need to be injected at the top of the component
     /// Format: (signal_name, default_value)
     required_signals: RefCell<Vec<(String, String)>>,
+    /// `NodeRef`s of named components, with their element type
+    node_refs: RefCell<Vec<(String, &'static str)>>,
 }
 
 impl LeptosCodeGenerator {
@@ -30,9 +99,32 @@ impl LeptosCodeGenerator {
         Self {
             preset,
             required_signals: RefCell::new(Vec::new()),
+            node_refs: RefCell::new(Vec::new()),
         }
     }
 
+    /// `id` from its binding or else the component name, and a `NodeRef`
+    /// for a named component
+    fn id_attr(
+        &self,
+        component: &CanvasComponent,
+        bindings: &HashMap<String, String>,
+        element: &'static str,
+    ) -> String {
+        let name = ExportName::of(component);
+        let mut attr = match (bindings.get("id"), &name) {
+            (Some(bind), _) => format!(" id=move || {}.get()", bind),
+            (None, Some(name)) => format!(" id=\"{}\"", name.id),
+            (None, None) => String::new(),
+        };
+        if let Some(name) = name {
+            let node_ref = format!("{}_ref", name.snake);
+            attr.push_str(&format!(" node_ref={}", node_ref));
+            self.node_refs.borrow_mut().push((node_ref, element));
+        }
+        attr
+    }
+
     fn generate_imports(&self) -> String {
         match self.preset {
             ExportPreset::Plain => "use leptos::*;\n".to_string(),
@@ -50,14 +142,13 @@ impl LeptosCodeGenerator {
         indent_level: usize,
     ) -> AppResult<()> {
         let indent = "    ".repeat(indent_level);
+        let name_class = ExportName::of(component)
+            .map(|name| format!(" {}", name.class))
+            .unwrap_or_default();
 
         match component {
             CanvasComponent::Button(btn) => {
-                let id_attr = if let Some(bind) = btn.bindings.get("id") {
-                    format!(" id=move || {}.get()", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = self.id_attr(component, &btn.bindings, "Button");
 
                 let class_attr = if let Some(bind) = btn.bindings.get("custom_css_classes") {
                     format!(" class=move || format!(\"{{}}\", {}.get())", bind)
@@ -106,12 +197,13 @@ impl LeptosCodeGenerator {
 
                 // Add interactive scaffolding
                 output.push_str(&format!(
-                    "{}        <button{}{} class=\"{} {}\" {} {}{}>{}</button>\n",
+                    "{}        <button{}{} class=\"{} {}{}\" {} {}{}>{}</button>\n",
                     indent,
                     id_attr,
                     class_attr,
                     variant_class,
                     size_class,
+                    name_class,
                     disabled_attr,
                     click_handler,
                     style_attr,
@@ -119,15 +211,12 @@ impl LeptosCodeGenerator {
                 ));
             }
             CanvasComponent::Text(txt) => {
-                let id_attr = if let Some(bind) = txt.bindings.get("id") {
-                    format!(" id=move || {}.get()", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = self.id_attr(component, &txt.bindings, text_element(&txt.tag));
 
                 let class_attr = if let Some(bind) = txt.bindings.get("custom_css_classes") {
                     format!(
-                        " class=move || format!(\"text-{{}} {{}}\", \"{}\", {}.get())",
+                        " class=move || format!(\"text-{{}}{} {{}}\", \"{}\", {}.get())",
+                        name_class,
                         match txt.style {
                             crate::domain::TextStyle::Heading1 => "heading1",
                             crate::domain::TextStyle::Heading2 => "heading2",
@@ -139,14 +228,15 @@ impl LeptosCodeGenerator {
                     )
                 } else {
                     format!(
-                        " class=\"text-{}\"",
+                        " class=\"text-{}{}\"",
                         match txt.style {
                             crate::domain::TextStyle::Heading1 => "heading1",
                             crate::domain::TextStyle::Heading2 => "heading2",
                             crate::domain::TextStyle::Heading3 => "heading3",
                             crate::domain::TextStyle::Body => "body",
                             crate::domain::TextStyle::Caption => "caption",
-                        }
+                        },
+                        name_class
                     )
                 };
 
@@ -177,14 +267,15 @@ impl LeptosCodeGenerator {
                 ));
             }
             CanvasComponent::Input(inp) => {
-                let id_attr = if let Some(bind) = inp.bindings.get("id") {
-                    format!(" id=move || {}.get()", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = self.id_attr(component, &inp.bindings, "Input");
 
                 let class_attr = if let Some(bind) = inp.bindings.get("custom_css_classes") {
-                    format!(" class=move || format!(\"{{}}\", {}.get())", bind)
+                    format!(
+                        " class=move || format!(\"{{}}{}\", {}.get())",
+                        name_class, bind
+                    )
+                } else if !name_class.is_empty() {
+                    format!(" class=\"{}\"", name_class.trim_start())
                 } else {
                     String::new()
                 };
@@ -252,14 +343,15 @@ impl LeptosCodeGenerator {
                 ));
             }
             CanvasComponent::Select(sel) => {
-                let id_attr = if let Some(bind) = sel.bindings.get("id") {
-                    format!(" id=move || {}.get()", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = self.id_attr(component, &sel.bindings, "Select");
 
                 let class_attr = if let Some(bind) = sel.bindings.get("custom_css_classes") {
-                    format!(" class=move || format!(\"{{}}\", {}.get())", bind)
+                    format!(
+                        " class=move || format!(\"{{}}{}\", {}.get())",
+                        name_class, bind
+                    )
+                } else if !name_class.is_empty() {
+                    format!(" class=\"{}\"", name_class.trim_start())
                 } else {
                     String::new()
                 };
@@ -389,19 +481,15 @@ impl LeptosCodeGenerator {
                     String::new()
                 };
 
-                let id_attr = if let Some(bind) = container.bindings.get("id") {
-                    format!(" id=move || {}.get()", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = self.id_attr(component, &container.bindings, "Div");
 
                 let class_attr = if let Some(bind) = container.bindings.get("custom_css_classes") {
                     format!(
-                        " class=move || format!(\"container {} {{}}\", {}.get())",
-                        layout_class, bind
+                        " class=move || format!(\"container {}{} {{}}\", {}.get())",
+                        layout_class, name_class, bind
                     )
                 } else {
-                    format!(" class=\"container {}\"", layout_class)
+                    format!(" class=\"container {}{}\"", layout_class, name_class)
                 };
 
                 output.push_str(&format!(
@@ -427,14 +515,15 @@ impl LeptosCodeGenerator {
                 output.push_str(&format!("{}        </div>\n", indent));
             }
             CanvasComponent::Image(img) => {
-                let id_attr = if let Some(bind) = img.bindings.get("id") {
-                    format!(" id=move || {}.get()", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = self.id_attr(component, &img.bindings, "Img");
 
                 let class_attr = if let Some(bind) = img.bindings.get("custom_css_classes") {
-                    format!(" class=move || format!(\"{{}}\", {}.get())", bind)
+                    format!(
+                        " class=move || format!(\"{{}}{}\", {}.get())",
+                        name_class, bind
+                    )
+                } else if !name_class.is_empty() {
+                    format!(" class=\"{}\"", name_class.trim_start())
                 } else {
                     String::new()
                 };
@@ -473,12 +562,21 @@ impl LeptosCodeGenerator {
                     format!("alt=\"{}\"", img.alt)
                 };
 
+                // A bound src replaces the image, so the stored candidates no longer apply
+                let srcset_attr = match img.srcset_attr(str::to_string) {
+                    Some(srcset) if !img.bindings.contains_key("src") => {
+                        format!(" srcset=\"{}\" sizes=\"{}\"", srcset, img.sizes_attr())
+                    }
+                    _ => String::new(),
+                };
+
                 output.push_str(&format!(
-                    "{}        <img{}{} {} {} {}{}{}{} />\n",
+                    "{}        <img{}{} {}{} {} {}{}{}{} />\n",
                     indent,
                     id_attr,
                     class_attr,
                     src_attr,
+                    srcset_attr,
                     alt_attr,
                     width_attr,
                     height_attr,
@@ -505,19 +603,15 @@ impl LeptosCodeGenerator {
                     String::new()
                 };
 
-                let id_attr = if let Some(bind) = card.bindings.get("id") {
-                    format!(" id=move || {}.get()", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = self.id_attr(component, &card.bindings, "Div");
 
                 let class_attr = if let Some(bind) = card.bindings.get("custom_css_classes") {
                     format!(
-                        " class=move || format!(\"card {} {{}}\", {}.get())",
-                        border_class, bind
+                        " class=move || format!(\"card {}{} {{}}\", {}.get())",
+                        border_class, name_class, bind
                     )
                 } else {
-                    format!(" class=\"card {}\"", border_class)
+                    format!(" class=\"card {}{}\"", border_class, name_class)
                 };
 
                 output.push_str(&format!(
@@ -537,14 +631,15 @@ impl LeptosCodeGenerator {
                 output.push_str(&format!("{}        </div>\n", indent));
             }
             CanvasComponent::Custom(custom) => {
-                let id_attr = if let Some(bind) = custom.bindings.get("id") {
-                    format!(" id=move || {}.get()", bind)
-                } else {
-                    String::new()
-                };
+                let id_attr = self.id_attr(component, &custom.bindings, "Div");
 
                 let class_attr = if let Some(bind) = custom.bindings.get("custom_css_classes") {
-                    format!(" class=move || format!(\"{{}}\", {}.get())", bind)
+                    format!(
+                        " class=move || format!(\"{{}}{}\", {}.get())",
+                        name_class, bind
+                    )
+                } else if !name_class.is_empty() {
+                    format!(" class=\"{}\"", name_class.trim_start())
                 } else {
                     String::new()
                 };
@@ -573,6 +668,7 @@ impl CodeGenerator for LeptosCodeGenerator {
         let mut output = String::new();
         // Reset required signals for each generation
         self.required_signals.borrow_mut().clear();
+        self.node_refs.borrow_mut().clear();
 
         // Add imports
         output.push_str(&self.generate_imports());
@@ -622,6 +718,18 @@ impl CodeGenerator for LeptosCodeGenerator {
             output.push('\n');
         }
 
+        let node_refs = self.node_refs.borrow();
+        if !node_refs.is_empty() {
+            output.push_str("    // Element refs\n");
+            for (name, element) in node_refs.iter() {
+                output.push_str(&format!(
+                    "    let {} = NodeRef::<html::{}>::new();\n",
+                    name, element
+                ));
+            }
+            output.push('\n');
+        }
+
         output.push_str(&view_body);
         output.push_str("}\n");
 
@@ -668,12 +776,16 @@ impl HtmlCodeGenerator {
         indent_level: usize,
     ) -> AppResult<()> {
         let indent = "    ".repeat(indent_level);
+        let name_attrs = ExportName::of(component)
+            .map(|name| format!(" id=\"{}\" class=\"{}\"", name.id, name.class))
+            .unwrap_or_default();
 
         match component {
             CanvasComponent::Button(btn) => {
                 output.push_str(&format!(
-                    "{}<button{}>{}</button>\n",
+                    "{}<button{}{}>{}</button>\n",
                     indent,
+                    name_attrs,
                     if btn.disabled { " disabled" } else { "" },
                     btn.label
                 ));
@@ -686,7 +798,10 @@ impl HtmlCodeGenerator {
                     crate::domain::TextTag::P => "p",
                     crate::domain::TextTag::Span => "span",
                 };
-                output.push_str(&format!("{}<{}>{}</{}>\n", indent, tag, txt.content, tag));
+                output.push_str(&format!(
+                    "{}<{}{}>{}</{}>\n",
+                    indent, tag, name_attrs, txt.content, tag
+                ));
             }
             CanvasComponent::Input(inp) => {
                 let input_type = match inp.input_type {
@@ -697,8 +812,9 @@ impl HtmlCodeGenerator {
                     crate::domain::InputType::Tel => "tel",
                 };
                 output.push_str(&format!(
-                    "{}<input type=\"{}\" placeholder=\"{}\"{}{}>\n",
+                    "{}<input{} type=\"{}\" placeholder=\"{}\"{}{}>\n",
                     indent,
+                    name_attrs,
                     input_type,
                     inp.placeholder,
                     if inp.required { " required" } else { "" },
@@ -707,8 +823,9 @@ impl HtmlCodeGenerator {
             }
             CanvasComponent::Select(sel) => {
                 output.push_str(&format!(
-                    "{}<select{}>\n",
+                    "{}<select{}{}>\n",
                     indent,
+                    name_attrs,
                     if sel.disabled { " disabled" } else { "" }
                 ));
                 if !sel.placeholder.is_empty() {
@@ -727,7 +844,7 @@ impl HtmlCodeGenerator {
                 output.push_str(&format!("{}</select>\n", indent));
             }
             CanvasComponent::Container(container) => {
-                output.push_str(&format!("{}<div>\n", indent));
+                output.push_str(&format!("{}<div{}>\n", indent, name_attrs));
                 for child in &container.children {
                     self.generate_html(child, output, indent_level + 1)?;
                 }
@@ -742,9 +859,14 @@ impl HtmlCodeGenerator {
                     .height
                     .as_ref()
                     .map_or(String::new(), |h| format!(" height=\"{}\"", h));
+                let srcset_attr = img
+                    .srcset_attr(str::to_string)
+                    .map_or(String::new(), |srcset| {
+                        format!(" srcset=\"{}\" sizes=\"{}\"", srcset, img.sizes_attr())
+                    });
                 output.push_str(&format!(
-                    "{}<img src=\"{}\" alt=\"{}\"{}{} />\n",
-                    indent, img.src, img.alt, width_attr, height_attr
+                    "{}<img{} src=\"{}\"{} alt=\"{}\"{}{} />\n",
+                    indent, name_attrs, img.src, srcset_attr, img.alt, width_attr, height_attr
                 ));
             }
             CanvasComponent::Card(card) => {
@@ -759,8 +881,13 @@ impl HtmlCodeGenerator {
                     ""
                 };
                 output.push_str(&format!(
-                    "{}<div style=\"padding: {}px; border-radius: {}px; {} {}\">\n",
-                    indent, card.padding, card.border_radius, shadow_style, border_style
+                    "{}<div{} style=\"padding: {}px; border-radius: {}px; {} {}\">\n",
+                    indent,
+                    name_attrs,
+                    card.padding,
+                    card.border_radius,
+                    shadow_style,
+                    border_style
                 ));
                 for child in &card.children {
                     self.generate_html(child, output, indent_level + 1)?;
@@ -769,7 +896,14 @@ impl HtmlCodeGenerator {
             }
             CanvasComponent::Custom(custom) => {
                 output.push_str(&format!("{}<!-- {} -->\n", indent, custom.name));
-                output.push_str(&format!("{}{}\n", indent, custom.template));
+                if name_attrs.is_empty() {
+                    output.push_str(&format!("{}{}\n", indent, custom.template));
+                } else {
+                    output.push_str(&format!(
+                        "{}<div{}>{}</div>\n",
+                        indent, name_attrs, custom.template
+                    ));
+                }
             }
         }
 
@@ -898,6 +1032,37 @@ mod tests {
         ButtonComponent, CanvasComponent, InputComponent, InputType, TextComponent,
     };
 
+    fn named_button() -> CanvasComponent {
+        let mut button = ButtonComponent::new("Send".to_string());
+        button.name = Some("submitButton".to_string());
+        CanvasComponent::Button(button)
+    }
+
+    #[test]
+    fn test_export_name_forms() {
+        let name = ExportName::of(&named_button()).unwrap();
+        assert_eq!(name.id, "submitButton");
+        assert_eq!(name.class, "submit-button");
+        assert_eq!(name.camel, "submitButton");
+        assert_eq!(name.snake, "submit_button");
+        let unnamed = CanvasComponent::Button(ButtonComponent::new("Send".to_string()));
+        assert!(ExportName::of(&unnamed).is_none());
+    }
+
+    #[test]
+    fn test_named_components_export_ids_classes_and_refs() {
+        let leptos = LeptosCodeGenerator::new(ExportPreset::Plain)
+            .generate(&[named_button()], &[])
+            .unwrap();
+        assert!(leptos.contains("let submit_button_ref = NodeRef::<html::Button>::new();"));
+        assert!(leptos.contains("id=\"submitButton\""));
+        assert!(leptos.contains("node_ref=submit_button_ref"));
+        assert!(leptos.contains("submit-button"));
+
+        let html = HtmlCodeGenerator.generate(&[named_button()], &[]).unwrap();
+        assert!(html.contains("id=\"submitButton\" class=\"submit-button\""));
+    }
+
     #[test]
     fn test_leptos_generator() {
         let generator = LeptosCodeGenerator::new(ExportPreset::Plain);
@@ -926,6 +1091,8 @@ mod tests {
             animation: None,
             bindings: Default::default(),
             style: Default::default(),
+            name: None,
+            editor: Default::default(),
         });
         let code = generator.generate(&[input], &variables).unwrap();
 
@@ -982,6 +1149,8 @@ mod tests {
             animation: None,
             bindings: Default::default(),
             style: Default::default(),
+            name: None,
+            editor: Default::default(),
         });
         let code = generator.generate(&[select], &variables).unwrap();
 
@@ -1005,6 +1174,8 @@ mod tests {
             animation: None,
             bindings: Default::default(),
             style: Default::default(),
+            name: None,
+            editor: Default::default(),
         });
         let code = generator.generate(&[select], &variables).unwrap();
 
@@ -1027,6 +1198,8 @@ mod tests {
             animation: None,
             bindings: Default::default(),
             style: Default::default(),
+            name: None,
+            editor: Default::default(),
         });
         let code = generator.generate(&[select], &variables).unwrap();
 
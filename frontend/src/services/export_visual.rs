@@ -0,0 +1,1316 @@
+//! Visual Export
+//!
+//! Renders a design as a static artifact for review outside the studio: an
+//! SVG of the page at one breakpoint, or a PDF with one page per breakpoint.
+//! Both are drawn from the computed layout, so they match the canvas rather
+//! than the generated code.
+//!
+//! Uploaded images are embedded when their bytes are supplied; otherwise
+//! the SVG links to them and the PDF shows a placeholder. The PDF embeds
+//! JPEG images as they are and 8-bit PNG images as deflated pixels; other
+//! formats keep the placeholder.
+
+use std::collections::HashMap;
+
+use studio_layout::typography::Typography;
+
+use super::export_service::CodeGenerator;
+use super::layout_service::{ComponentBox, compute_layout};
+use crate::domain::{
+    ASSET_SCHEME, AppResult, ButtonSize, ButtonVariant, CanvasComponent, ComponentStyle, TextStyle,
+    Variable,
+};
+
+/// Asset file contents by asset id
+pub type AssetData = HashMap<String, Vec<u8>>;
+
+const PAGE: &str = "#ffffff";
+const INK: &str = "#111827";
+const MUTED: &str = "#9ca3af";
+const OUTLINE: &str = "#d1d5db";
+const PRIMARY: &str = "#3b82f6";
+const SECONDARY: &str = "#e5e7eb";
+const PLACEHOLDER: &str = "#f3f4f6";
+const FONT_FAMILY: &str = "system-ui, -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif";
+
+/// Height of the breakpoint caption above each PDF page
+const CAPTION_HEIGHT: f32 = 28.0;
+
+/// Something to draw, in page coordinates
+#[derive(Clone, Debug, PartialEq)]
+enum Shape {
+    Rect {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        fill: Option<String>,
+        stroke: Option<String>,
+        dashed: bool,
+    },
+    Line {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        color: String,
+    },
+    /// A single line of text; `y` is the baseline
+    Text {
+        x: f32,
+        y: f32,
+        text: String,
+        size: f32,
+        bold: bool,
+        color: String,
+        align: Align,
+    },
+    Image {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        src: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Align {
+    Start,
+    Middle,
+    End,
+}
+
+/// A laid-out page, ready to be written as SVG or PDF
+struct Scene {
+    width: f32,
+    height: f32,
+    shapes: Vec<Shape>,
+}
+
+impl Scene {
+    fn build(components: &[CanvasComponent], width: f32) -> AppResult<Self> {
+        let layout = compute_layout(components, width)?;
+        let mut flat = Vec::new();
+        flatten(components, &mut flat);
+
+        let mut scene = Self {
+            width: layout.width,
+            height: layout.height,
+            shapes: Vec::new(),
+        };
+        // Boxes are in document order, parents before children
+        for (b, component) in layout.boxes.iter().zip(flat) {
+            scene.draw(b, component);
+        }
+        Ok(scene)
+    }
+
+    fn rect(&mut self, b: &ComponentBox, radius: f32, fill: Option<&str>, stroke: Option<&str>) {
+        self.shapes.push(Shape::Rect {
+            x: b.x,
+            y: b.y,
+            w: b.width,
+            h: b.height,
+            radius,
+            fill: fill.map(str::to_string),
+            stroke: stroke.map(str::to_string),
+            dashed: false,
+        });
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: &str) {
+        self.shapes.push(Shape::Line {
+            x1,
+            y1,
+            x2,
+            y2,
+            color: color.to_string(),
+        });
+    }
+
+    /// Wrapped text inside `b`, starting at its top edge
+    fn paragraph(&mut self, b: &ComponentBox, text: &str, font: Typography, ink: TextInk) {
+        let x = match ink.align {
+            Align::Start => b.x,
+            Align::Middle => b.x + b.width / 2.0,
+            Align::End => b.right(),
+        };
+        for (i, line) in font.wrap(text, b.width).into_iter().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            self.shapes.push(Shape::Text {
+                x,
+                y: b.y + i as f32 * font.line_height + baseline(font),
+                text: line,
+                size: font.font_size,
+                bold: ink.bold,
+                color: ink.color.clone(),
+                align: ink.align,
+            });
+        }
+    }
+
+    /// One line of text, vertically centered in `b`
+    fn label(&mut self, b: &ComponentBox, x: f32, text: &str, font: Typography, ink: TextInk) {
+        if text.is_empty() {
+            return;
+        }
+        self.shapes.push(Shape::Text {
+            x,
+            y: b.y + (b.height - font.line_height) / 2.0 + baseline(font),
+            text: text.to_string(),
+            size: font.font_size,
+            bold: ink.bold,
+            color: ink.color,
+            align: ink.align,
+        });
+    }
+
+    fn draw(&mut self, b: &ComponentBox, component: &CanvasComponent) {
+        match component {
+            CanvasComponent::Button(btn) => {
+                let (fill, stroke, text) = match btn.variant {
+                    ButtonVariant::Primary => (Some(PRIMARY), None, PAGE),
+                    ButtonVariant::Secondary => (Some(SECONDARY), None, INK),
+                    ButtonVariant::Outline => (None, Some(PRIMARY), PRIMARY),
+                    ButtonVariant::Ghost => (None, None, PRIMARY),
+                };
+                let style = &btn.style;
+                let fill = style.background_color.as_deref().or(fill);
+                let stroke = border(style).or(stroke);
+                self.rect(b, radius(style, 6.0), fill, stroke);
+
+                let font = font_size(
+                    style,
+                    match btn.size {
+                        ButtonSize::Small => Typography::XS,
+                        ButtonSize::Medium => Typography::SM,
+                        ButtonSize::Large => Typography::BASE,
+                    },
+                );
+                let ink = TextInk::new(style, text, Align::Middle).bold(true);
+                self.label(b, b.x + b.width / 2.0, &btn.label, font, ink);
+            }
+            CanvasComponent::Text(txt) => {
+                let style = &txt.custom_style;
+                self.background(b, style);
+                let font = font_size(style, Typography::for_text_style(text_style(&txt.style)));
+                let align = match style.text_align.as_deref() {
+                    Some("center") => Align::Middle,
+                    Some("right") => Align::End,
+                    _ => Align::Start,
+                };
+                let ink = TextInk::new(style, INK, align).bold(font.font_weight >= 700);
+                self.paragraph(b, &txt.content, font, ink);
+            }
+            CanvasComponent::Input(inp) => {
+                self.field(b, &inp.style, &inp.placeholder);
+            }
+            CanvasComponent::Select(sel) => {
+                let first = sel.options.split(',').next().unwrap_or("").trim();
+                let text = if sel.placeholder.is_empty() {
+                    first
+                } else {
+                    &sel.placeholder
+                };
+                self.field(b, &sel.style, text);
+                let (cx, cy) = (b.right() - 20.0, b.y + b.height / 2.0);
+                self.line(cx - 5.0, cy - 2.0, cx, cy + 3.0, MUTED);
+                self.line(cx, cy + 3.0, cx + 5.0, cy - 2.0, MUTED);
+            }
+            CanvasComponent::Image(img) => {
+                let style = &img.style;
+                self.rect(
+                    b,
+                    radius(style, 4.0),
+                    Some(style.background_color.as_deref().unwrap_or(PLACEHOLDER)),
+                    border(style),
+                );
+                self.line(b.x, b.y, b.right(), b.bottom(), OUTLINE);
+                self.line(b.right(), b.y, b.x, b.bottom(), OUTLINE);
+                if !img.src.is_empty() {
+                    self.shapes.push(Shape::Image {
+                        x: b.x,
+                        y: b.y,
+                        w: b.width,
+                        h: b.height,
+                        src: img.src.clone(),
+                    });
+                }
+            }
+            CanvasComponent::Container(container) => {
+                self.background(b, &container.style);
+            }
+            CanvasComponent::Card(card) => {
+                let style = &card.style;
+                let fill = style.background_color.as_deref().unwrap_or(PAGE);
+                let stroke = border(style).or(card.border.then_some(OUTLINE));
+                self.rect(
+                    b,
+                    radius(style, card.border_radius as f32),
+                    Some(fill),
+                    stroke,
+                );
+            }
+            CanvasComponent::Custom(custom) => {
+                let style = &custom.style;
+                self.shapes.push(Shape::Rect {
+                    x: b.x,
+                    y: b.y,
+                    w: b.width,
+                    h: b.height,
+                    radius: radius(style, 4.0),
+                    fill: Some(
+                        style
+                            .background_color
+                            .clone()
+                            .unwrap_or_else(|| PLACEHOLDER.to_string()),
+                    ),
+                    stroke: Some(MUTED.to_string()),
+                    dashed: true,
+                });
+                let ink = TextInk::new(style, MUTED, Align::Middle);
+                self.label(b, b.x + b.width / 2.0, &custom.name, Typography::SM, ink);
+            }
+        }
+    }
+
+    /// Input-like box with a line of muted text
+    fn field(&mut self, b: &ComponentBox, style: &ComponentStyle, text: &str) {
+        let fill = style.background_color.as_deref().unwrap_or(PAGE);
+        self.rect(
+            b,
+            radius(style, 4.0),
+            Some(fill),
+            Some(border(style).unwrap_or(OUTLINE)),
+        );
+        let ink = TextInk::new(style, MUTED, Align::Start);
+        self.label(b, b.x + 12.0, text, font_size(style, Typography::SM), ink);
+    }
+
+    /// Background and border set through `ComponentStyle`, if any
+    fn background(&mut self, b: &ComponentBox, style: &ComponentStyle) {
+        let fill = style.background_color.as_deref();
+        let stroke = border(style);
+        if fill.is_some() || stroke.is_some() {
+            self.rect(b, radius(style, 0.0), fill, stroke);
+        }
+    }
+}
+
+/// Color, weight and alignment of a run of text
+struct TextInk {
+    color: String,
+    bold: bool,
+    align: Align,
+}
+
+impl TextInk {
+    fn new(style: &ComponentStyle, default_color: &str, align: Align) -> Self {
+        Self {
+            color: style
+                .color
+                .clone()
+                .unwrap_or_else(|| default_color.to_string()),
+            bold: matches!(
+                style.font_weight.as_deref(),
+                Some("bold" | "bolder" | "600" | "700" | "800" | "900")
+            ),
+            align,
+        }
+    }
+
+    /// Use `bold` unless the style sets a weight
+    fn bold(mut self, bold: bool) -> Self {
+        self.bold |= bold;
+        self
+    }
+}
+
+/// Components in document order, matching the order of layout boxes
+fn flatten<'a>(components: &'a [CanvasComponent], out: &mut Vec<&'a CanvasComponent>) {
+    for component in components {
+        out.push(component);
+        match component {
+            CanvasComponent::Container(c) => flatten(&c.children, out),
+            CanvasComponent::Card(c) => flatten(&c.children, out),
+            _ => {}
+        }
+    }
+}
+
+fn text_style(style: &TextStyle) -> &'static str {
+    match style {
+        TextStyle::Heading1 => "Heading1",
+        TextStyle::Heading2 => "Heading2",
+        TextStyle::Heading3 => "Heading3",
+        TextStyle::Body => "Body",
+        TextStyle::Caption => "Caption",
+    }
+}
+
+fn font_size(style: &ComponentStyle, font: Typography) -> Typography {
+    match style.font_size {
+        Some(size) => font.with_font_size(size as f32),
+        None => font,
+    }
+}
+
+/// Distance from the top of a line box to the baseline
+fn baseline(font: Typography) -> f32 {
+    (font.line_height - font.font_size) / 2.0 + font.font_size * 0.8
+}
+
+fn radius(style: &ComponentStyle, default: f32) -> f32 {
+    style.border_radius.map(|r| r as f32).unwrap_or(default)
+}
+
+fn border(style: &ComponentStyle) -> Option<&str> {
+    match style.border_width {
+        Some(0) => None,
+        _ => style.border_color.as_deref(),
+    }
+}
+
+/// Renders the design as an SVG image of the page at one breakpoint
+pub struct SvgGenerator {
+    /// Page width in pixels
+    pub width: u32,
+    /// Uploaded images to embed instead of linking to
+    pub assets: AssetData,
+}
+
+impl SvgGenerator {
+    pub fn new(width: u32) -> Self {
+        Self {
+            width,
+            assets: AssetData::new(),
+        }
+    }
+
+    pub fn with_assets(mut self, assets: AssetData) -> Self {
+        self.assets = assets;
+        self
+    }
+
+    fn image_href(&self, src: &str) -> String {
+        match src.strip_prefix(ASSET_SCHEME) {
+            Some(id) => match self.assets.get(id) {
+                Some(bytes) => format!(
+                    "data:{};base64,{}",
+                    sniff_mime(bytes).unwrap_or("application/octet-stream"),
+                    base64(bytes)
+                ),
+                None => src.to_string(),
+            },
+            None => src.to_string(),
+        }
+    }
+}
+
+impl CodeGenerator for SvgGenerator {
+    fn generate(
+        &self,
+        components: &[CanvasComponent],
+        _variables: &[Variable],
+    ) -> AppResult<String> {
+        let scene = Scene::build(components, self.width as f32)?;
+        let (w, h) = (scene.width, scene.height);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"{FONT_FAMILY}\">\n\
+             <rect width=\"{w}\" height=\"{h}\" fill=\"{PAGE}\"/>\n"
+        );
+        for shape in &scene.shapes {
+            match shape {
+                Shape::Rect {
+                    x,
+                    y,
+                    w,
+                    h,
+                    radius,
+                    fill,
+                    stroke,
+                    dashed,
+                } => {
+                    svg.push_str(&format!(
+                        "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" rx=\"{radius}\" fill=\"{}\"",
+                        xml_escape(fill.as_deref().unwrap_or("none"))
+                    ));
+                    if let Some(stroke) = stroke {
+                        svg.push_str(&format!(" stroke=\"{}\"", xml_escape(stroke)));
+                    }
+                    if *dashed {
+                        svg.push_str(" stroke-dasharray=\"4 3\"");
+                    }
+                    svg.push_str("/>\n");
+                }
+                Shape::Line {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    color,
+                } => svg.push_str(&format!(
+                    "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{}\"/>\n",
+                    xml_escape(color)
+                )),
+                Shape::Text {
+                    x,
+                    y,
+                    text,
+                    size,
+                    bold,
+                    color,
+                    align,
+                } => {
+                    let anchor = match align {
+                        Align::Start => "start",
+                        Align::Middle => "middle",
+                        Align::End => "end",
+                    };
+                    svg.push_str(&format!(
+                        "<text x=\"{x}\" y=\"{y}\" font-size=\"{size}\" font-weight=\"{}\" fill=\"{}\" text-anchor=\"{anchor}\">{}</text>\n",
+                        if *bold { 700 } else { 400 },
+                        xml_escape(color),
+                        xml_escape(text)
+                    ));
+                }
+                Shape::Image { x, y, w, h, src } => svg.push_str(&format!(
+                    "<image x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" href=\"{}\" preserveAspectRatio=\"xMidYMid slice\"/>\n",
+                    xml_escape(&self.image_href(src))
+                )),
+            }
+        }
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+
+    fn file_extension(&self) -> &str {
+        "svg"
+    }
+}
+
+/// A PDF page: the design laid out at one breakpoint
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportPage {
+    /// Caption printed above the design
+    pub title: String,
+    /// Page width in pixels
+    pub width: u32,
+}
+
+/// Renders the design as a PDF with one page per breakpoint
+pub struct PdfGenerator {
+    pub pages: Vec<ExportPage>,
+    /// Uploaded images to embed; only JPEG and PNG files are used
+    pub assets: AssetData,
+}
+
+impl PdfGenerator {
+    pub fn new(pages: Vec<ExportPage>) -> Self {
+        Self {
+            pages,
+            assets: AssetData::new(),
+        }
+    }
+
+    pub fn with_assets(mut self, assets: AssetData) -> Self {
+        self.assets = assets;
+        self
+    }
+}
+
+impl CodeGenerator for PdfGenerator {
+    fn generate(
+        &self,
+        components: &[CanvasComponent],
+        _variables: &[Variable],
+    ) -> AppResult<String> {
+        let mut pdf = PdfWriter::default();
+        // Objects 1-4: catalog, page tree and the two fonts
+        let catalog = pdf.reserve();
+        let pages = pdf.reserve();
+        let regular = pdf.add(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        );
+        let bold = pdf.add(
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        );
+
+        let mut images: Vec<(String, usize, ImageSize)> = Vec::new();
+        for (id, bytes) in &self.assets {
+            if let Some(jpeg) = Jpeg::parse(bytes) {
+                let object = pdf.add_stream(
+                    &format!(
+                        "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{} /BitsPerComponent 8 /Filter [/ASCIIHexDecode /DCTDecode]",
+                        jpeg.width, jpeg.height, jpeg.color_space
+                    ),
+                    &hex(bytes),
+                );
+                let size = ImageSize {
+                    width: jpeg.width,
+                    height: jpeg.height,
+                };
+                images.push((id.clone(), object, size));
+            } else if let Some(png) = Png::decode(bytes) {
+                let size = ImageSize {
+                    width: png.width,
+                    height: png.height,
+                };
+                let image = |color_space: &str| {
+                    format!(
+                        "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{color_space} /BitsPerComponent 8 /Filter [/ASCIIHexDecode /FlateDecode]",
+                        png.width, png.height
+                    )
+                };
+                // Transparency goes into a separate grayscale soft mask
+                let mask = png.alpha.as_ref().map(|alpha| {
+                    let object = pdf.add_stream(&image("DeviceGray"), &hex(&deflate(alpha)));
+                    format!(" /SMask {object} 0 R")
+                });
+                let object = pdf.add_stream(
+                    &format!("{}{}", image(png.color_space), mask.unwrap_or_default()),
+                    &hex(&deflate(&png.pixels)),
+                );
+                images.push((id.clone(), object, size));
+            }
+        }
+        images.sort_by(|a, b| a.0.cmp(&b.0));
+        let xobjects: String = images
+            .iter()
+            .enumerate()
+            .map(|(i, (_, object, _))| format!("/Im{i} {object} 0 R "))
+            .collect();
+
+        let mut kids = Vec::new();
+        for page in &self.pages {
+            let scene = Scene::build(components, page.width as f32)?;
+            let content = page_content(&scene, &page.title, &images);
+            let stream = pdf.add_stream("", &content);
+            kids.push(pdf.add(format!(
+                "<< /Type /Page /Parent {pages} 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 {regular} 0 R /F2 {bold} 0 R >> /XObject << {xobjects}>> >> /Contents {stream} 0 R >>",
+                scene.width,
+                scene.height + CAPTION_HEIGHT
+            )));
+        }
+
+        let kids: Vec<String> = kids.iter().map(|k| format!("{k} 0 R")).collect();
+        pdf.set(
+            pages,
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                kids.len()
+            ),
+        );
+        pdf.set(catalog, format!("<< /Type /Catalog /Pages {pages} 0 R >>"));
+        Ok(pdf.finish(catalog))
+    }
+
+    fn file_extension(&self) -> &str {
+        "pdf"
+    }
+}
+
+/// Content stream of one page; y is flipped, PDF counts from the bottom
+fn page_content(scene: &Scene, title: &str, images: &[(String, usize, ImageSize)]) -> String {
+    let top = scene.height;
+    let mut out = format!(
+        "{} rg 0 {top} {} {CAPTION_HEIGHT} re f\nBT /F1 11 Tf {} rg 16 {} Td ({}) Tj ET\n",
+        rgb(SECONDARY),
+        scene.width,
+        rgb(INK),
+        top + 10.0,
+        pdf_text(title)
+    );
+    for shape in &scene.shapes {
+        match shape {
+            Shape::Rect {
+                x,
+                y,
+                w,
+                h,
+                radius,
+                fill,
+                stroke,
+                dashed,
+            } => {
+                let fill = fill.as_deref().and_then(parse_color);
+                let stroke = stroke.as_deref().and_then(parse_color);
+                let op = match (fill, stroke) {
+                    (Some(_), Some(_)) => "B",
+                    (Some(_), None) => "f",
+                    (None, Some(_)) => "S",
+                    (None, None) => continue,
+                };
+                if let Some(c) = fill {
+                    out.push_str(&format!("{} rg ", rgb_of(c)));
+                }
+                if let Some(c) = stroke {
+                    out.push_str(&format!("{} RG 1 w ", rgb_of(c)));
+                }
+                out.push_str(if *dashed { "[4 3] 0 d\n" } else { "[] 0 d\n" });
+                rounded_rect(&mut out, *x, top - y - h, *w, *h, *radius);
+                out.push_str(op);
+                out.push('\n');
+            }
+            Shape::Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                color,
+            } => {
+                let color = parse_color(color).map(rgb_of).unwrap_or_else(|| rgb(MUTED));
+                out.push_str(&format!(
+                    "{color} RG 1 w [] 0 d {x1} {} m {x2} {} l S\n",
+                    top - y1,
+                    top - y2
+                ));
+            }
+            Shape::Text {
+                x,
+                y,
+                text,
+                size,
+                bold,
+                color,
+                align,
+            } => {
+                let width =
+                    text.chars().count() as f32 * size * studio_layout::typography::CHAR_WIDTH;
+                let x = match align {
+                    Align::Start => *x,
+                    Align::Middle => x - width / 2.0,
+                    Align::End => x - width,
+                };
+                let color = parse_color(color).map(rgb_of).unwrap_or_else(|| rgb(INK));
+                out.push_str(&format!(
+                    "BT /{} {size} Tf {color} rg {x} {} Td ({}) Tj ET\n",
+                    if *bold { "F2" } else { "F1" },
+                    top - y,
+                    pdf_text(text)
+                ));
+            }
+            Shape::Image { x, y, w, h, src } => {
+                let found = src
+                    .strip_prefix(ASSET_SCHEME)
+                    .and_then(|id| images.iter().position(|(asset, _, _)| asset == id));
+                let Some(index) = found else { continue };
+                let size = &images[index].2;
+                // Cover the box, cropping the overflow like `object-fit: cover`
+                let scale = (w / size.width as f32).max(h / size.height as f32);
+                let (iw, ih) = (size.width as f32 * scale, size.height as f32 * scale);
+                let (ix, iy) = (x + (w - iw) / 2.0, top - y - h + (h - ih) / 2.0);
+                out.push_str(&format!(
+                    "q {x} {} {w} {h} re W n {iw} 0 0 {ih} {ix} {iy} cm /Im{index} Do Q\n",
+                    top - y - h
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// Rectangle path with corners rounded by cubic curves
+fn rounded_rect(out: &mut String, x: f32, y: f32, w: f32, h: f32, radius: f32) {
+    let r = radius.min(w / 2.0).min(h / 2.0);
+    if r <= 0.0 {
+        out.push_str(&format!("{x} {y} {w} {h} re "));
+        return;
+    }
+    // Control point distance for a quarter circle
+    let k = r * 0.552_284_8;
+    let (x2, y2) = (x + w, y + h);
+    out.push_str(&format!(
+        "{} {y} m {} {y} l {} {y} {x2} {} {x2} {} c {x2} {} l {x2} {} {} {y2} {} {y2} c {} {y2} l {} {y2} {x} {} {x} {} c {x} {} l {x} {} {} {y} {} {y} c h ",
+        x + r,
+        x2 - r,
+        x2 - r + k,
+        y + r - k,
+        y + r,
+        y2 - r,
+        y2 - r + k,
+        x2 - r + k,
+        x2 - r,
+        x + r,
+        x + r - k,
+        y2 - r + k,
+        y2 - r,
+        y + r,
+        y + r - k,
+        x + r - k,
+        x + r,
+    ));
+}
+
+/// Assembles numbered PDF objects and the cross-reference table
+#[derive(Default)]
+struct PdfWriter {
+    objects: Vec<String>,
+}
+
+impl PdfWriter {
+    /// Reserve an object number to fill in later with [`PdfWriter::set`]
+    fn reserve(&mut self) -> usize {
+        self.add(String::new())
+    }
+
+    fn add(&mut self, body: String) -> usize {
+        self.objects.push(body);
+        self.objects.len()
+    }
+
+    fn set(&mut self, object: usize, body: String) {
+        self.objects[object - 1] = body;
+    }
+
+    fn add_stream(&mut self, dict: &str, data: &str) -> usize {
+        self.add(format!(
+            "<< {dict} /Length {} >>\nstream\n{data}\nendstream",
+            data.len()
+        ))
+    }
+
+    /// The whole file. Every object is ASCII, so byte offsets are string
+    /// offsets.
+    fn finish(self, root: usize) -> String {
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (i, body) in self.objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.push_str(&format!("{} 0 obj\n{body}\nendobj\n", i + 1));
+        }
+        let xref = out.len();
+        out.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            self.objects.len() + 1
+        ));
+        for offset in offsets {
+            out.push_str(&format!("{offset:010} 00000 n \n"));
+        }
+        out.push_str(&format!(
+            "trailer\n<< /Size {} /Root {root} 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            self.objects.len() + 1
+        ));
+        out
+    }
+}
+
+/// Pixel size of an embedded image
+struct ImageSize {
+    width: u32,
+    height: u32,
+}
+
+/// Size and color space of a baseline or progressive JPEG
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Jpeg {
+    width: u32,
+    height: u32,
+    color_space: &'static str,
+}
+
+impl Jpeg {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        if !bytes.starts_with(&[0xFF, 0xD8]) {
+            return None;
+        }
+        let mut i = 2;
+        while i + 9 < bytes.len() {
+            if bytes[i] != 0xFF {
+                return None;
+            }
+            let marker = bytes[i + 1];
+            let length = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+            // Start-of-frame markers, except DHT (C4), JPG (C8) and DAC (CC)
+            if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+                let height = u16::from_be_bytes([bytes[i + 5], bytes[i + 6]]) as u32;
+                let width = u16::from_be_bytes([bytes[i + 7], bytes[i + 8]]) as u32;
+                let color_space = match bytes[i + 9] {
+                    1 => "DeviceGray",
+                    3 => "DeviceRGB",
+                    _ => return None,
+                };
+                return (width > 0 && height > 0).then_some(Self {
+                    width,
+                    height,
+                    color_space,
+                });
+            }
+            i += 2 + length;
+        }
+        None
+    }
+}
+
+/// Pixels of a non-interlaced 8-bit PNG, with transparency split off
+#[derive(Debug, PartialEq)]
+struct Png {
+    width: u32,
+    height: u32,
+    color_space: &'static str,
+    /// One byte per channel of `color_space`, row by row
+    pixels: Vec<u8>,
+    /// One byte per pixel, unless the image is fully opaque
+    alpha: Option<Vec<u8>>,
+}
+
+impl Png {
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut rest = bytes.strip_prefix(b"\x89PNG\r\n\x1a\n")?;
+        let (mut header, mut palette, mut transparency) = (None, None, None);
+        let mut data = Vec::new();
+        while rest.len() >= 12 {
+            let length = u32::from_be_bytes(rest[..4].try_into().ok()?) as usize;
+            let body = rest.get(8..8 + length)?;
+            match &rest[4..8] {
+                b"IHDR" => header = Some(body),
+                b"PLTE" => palette = Some(body),
+                b"tRNS" => transparency = Some(body),
+                b"IDAT" => data.extend_from_slice(body),
+                b"IEND" => break,
+                _ => {}
+            }
+            rest = rest.get(12 + length..)?;
+        }
+        let header = header.filter(|h| h.len() == 13)?;
+        let width = u32::from_be_bytes(header[0..4].try_into().ok()?);
+        let height = u32::from_be_bytes(header[4..8].try_into().ok()?);
+        let (depth, color_type, interlace) = (header[8], header[9], header[12]);
+        if depth != 8 || interlace != 0 || width == 0 || height == 0 {
+            return None;
+        }
+        let channels = match color_type {
+            0 | 3 => 1,
+            4 => 2,
+            2 => 3,
+            6 => 4,
+            _ => return None,
+        };
+        let stride = width as usize * channels;
+        let expected = (stride + 1).checked_mul(height as usize)?;
+        let raw = miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(&data, expected).ok()?;
+        if raw.len() != expected {
+            return None;
+        }
+        let rows = unfilter(&raw, stride, channels)?;
+
+        let (color_space, pixels, alpha) = match color_type {
+            0 => ("DeviceGray", rows, None),
+            2 => ("DeviceRGB", rows, None),
+            3 => {
+                let palette = palette?;
+                let transparency = transparency.unwrap_or_default();
+                let mut pixels = Vec::with_capacity(rows.len() * 3);
+                for &index in &rows {
+                    let index = index as usize;
+                    pixels.extend_from_slice(palette.get(index * 3..index * 3 + 3)?);
+                }
+                let alpha = rows
+                    .iter()
+                    .map(|&i| transparency.get(i as usize).copied().unwrap_or(u8::MAX))
+                    .collect();
+                ("DeviceRGB", pixels, Some(alpha))
+            }
+            _ => {
+                let color = channels - 1;
+                let pixels = rows
+                    .chunks_exact(channels)
+                    .flat_map(|p| &p[..color])
+                    .copied()
+                    .collect();
+                let alpha = rows.chunks_exact(channels).map(|p| p[color]).collect();
+                let space = if color == 1 {
+                    "DeviceGray"
+                } else {
+                    "DeviceRGB"
+                };
+                (space, pixels, Some(alpha))
+            }
+        };
+        Some(Self {
+            width,
+            height,
+            color_space,
+            pixels,
+            alpha: alpha.filter(|a: &Vec<u8>| a.iter().any(|&v| v < u8::MAX)),
+        })
+    }
+}
+
+/// Undo PNG's per-row filters; each row of `raw` starts with its filter
+fn unfilter(raw: &[u8], stride: usize, channels: usize) -> Option<Vec<u8>> {
+    let mut out: Vec<u8> = Vec::with_capacity(raw.len() - raw.len() / (stride + 1));
+    for (y, row) in raw.chunks_exact(stride + 1).enumerate() {
+        let start = y * stride;
+        for (x, &value) in row[1..].iter().enumerate() {
+            let left = if x >= channels {
+                out[start + x - channels]
+            } else {
+                0
+            };
+            let up = if y > 0 { out[start - stride + x] } else { 0 };
+            let up_left = if y > 0 && x >= channels {
+                out[start - stride + x - channels]
+            } else {
+                0
+            };
+            let predicted = match row[0] {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                _ => return None,
+            };
+            out.push(value.wrapping_add(predicted));
+        }
+    }
+    Some(out)
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let p = left as i16 + up as i16 - up_left as i16;
+    let (pa, pb, pc) = (
+        (p - left as i16).abs(),
+        (p - up as i16).abs(),
+        (p - up_left as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        left
+    } else if pb <= pc {
+        up
+    } else {
+        up_left
+    }
+}
+
+fn deflate(bytes: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(bytes, 6)
+}
+
+/// MIME type of common image files, from their leading bytes
+fn sniff_mime(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"\x89PNG") {
+        Some("image/png")
+    } else if bytes.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if bytes.len() > 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"<svg") || bytes.starts_with(b"<?xml") {
+        Some("image/svg+xml")
+    } else {
+        None
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Hex encoding for `ASCIIHexDecode`, terminated by `>`
+fn hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2 + bytes.len() / 32 + 1);
+    for (i, b) in bytes.iter().enumerate() {
+        if i > 0 && i % 64 == 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!("{b:02X}"));
+    }
+    out.push('>');
+    out
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escape text for a PDF string literal. Latin-1 characters map onto
+/// WinAnsiEncoding; anything else becomes `?`.
+fn pdf_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            '\u{a0}'..='\u{ff}' => out.push_str(&format!("\\{:03o}", c as u32)),
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+/// Parse a CSS color: `#rgb`, `#rrggbb`, `rgb()`/`rgba()` or a few names.
+/// Transparent and unknown colors give `None`.
+fn parse_color(css: &str) -> Option<[u8; 3]> {
+    let css = css.trim().to_ascii_lowercase();
+    if let Some(hex) = css.strip_prefix('#') {
+        let digit = |i: usize, len: usize| u8::from_str_radix(hex.get(i..i + len)?, 16).ok();
+        return match hex.len() {
+            3 | 4 => Some([digit(0, 1)? * 17, digit(1, 1)? * 17, digit(2, 1)? * 17]),
+            6 | 8 => Some([digit(0, 2)?, digit(2, 2)?, digit(4, 2)?]),
+            _ => None,
+        };
+    }
+    if let Some(args) = css
+        .strip_prefix("rgba(")
+        .or_else(|| css.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let mut channels = args
+            .split([',', ' ', '/'])
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.trim()
+                    .parse::<f32>()
+                    .ok()
+                    .map(|v| v.clamp(0.0, 255.0) as u8)
+            });
+        return Some([channels.next()??, channels.next()??, channels.next()??]);
+    }
+    match css.as_str() {
+        "white" => Some([255, 255, 255]),
+        "black" => Some([0, 0, 0]),
+        "red" => Some([255, 0, 0]),
+        "green" => Some([0, 128, 0]),
+        "blue" => Some([0, 0, 255]),
+        "yellow" => Some([255, 255, 0]),
+        "orange" => Some([255, 165, 0]),
+        "purple" => Some([128, 0, 128]),
+        "gray" | "grey" => Some([128, 128, 128]),
+        _ => None,
+    }
+}
+
+fn rgb_of([r, g, b]: [u8; 3]) -> String {
+    format!(
+        "{:.3} {:.3} {:.3}",
+        r as f32 / 255.0,
+        g as f32 / 255.0,
+        b as f32 / 255.0
+    )
+}
+
+fn rgb(css: &str) -> String {
+    rgb_of(parse_color(css).unwrap_or([0, 0, 0]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ButtonComponent, ContainerComponent, ImageComponent, TextComponent};
+
+    fn design() -> Vec<CanvasComponent> {
+        let mut title = TextComponent::new("Hello & welcome".to_string());
+        title.style = TextStyle::Heading1;
+        let mut container = ContainerComponent::new();
+        container.style.background_color = Some("#fef3c7".to_string());
+        container.children = vec![CanvasComponent::Button(ButtonComponent::new(
+            "Go".to_string(),
+        ))];
+        vec![
+            CanvasComponent::Text(title),
+            CanvasComponent::Container(container),
+            CanvasComponent::Image(ImageComponent::new(
+                "asset://logo".to_string(),
+                "Logo".to_string(),
+            )),
+        ]
+    }
+
+    #[test]
+    fn test_svg_draws_text_backgrounds_and_images() {
+        let svg = SvgGenerator::new(768).generate(&design(), &[]).unwrap();
+
+        assert!(svg.starts_with("<svg") && svg.contains("width=\"768\""));
+        assert!(svg.contains("font-size=\"30\" font-weight=\"700\""));
+        assert!(svg.contains(">Hello &amp; welcome</text>"));
+        assert!(svg.contains("fill=\"#fef3c7\""));
+        assert!(svg.contains("href=\"asset://logo\""));
+
+        let png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let embedded = SvgGenerator::new(768)
+            .with_assets(AssetData::from([("logo".to_string(), png)]))
+            .generate(&design(), &[])
+            .unwrap();
+        assert!(embedded.contains("href=\"data:image/png;base64,iVBORw0KGgo=\""));
+    }
+
+    #[test]
+    fn test_pdf_has_a_page_per_breakpoint() {
+        let pages = vec![
+            ExportPage {
+                title: "Small".to_string(),
+                width: 480,
+            },
+            ExportPage {
+                title: "Large".to_string(),
+                width: 1024,
+            },
+        ];
+        let pdf = PdfGenerator::new(pages).generate(&design(), &[]).unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4") && pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/Count 2"));
+        assert!(pdf.contains("/MediaBox [0 0 480 ") && pdf.contains("/MediaBox [0 0 1024 "));
+        assert!(pdf.contains("(Hello & welcome) Tj"));
+
+        // The cross-reference table points at each object
+        let xref: usize = pdf
+            .rsplit("startxref\n")
+            .next()
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pdf[xref..].starts_with("xref"));
+        let first = pdf[xref..].lines().nth(3).unwrap();
+        let offset: usize = first[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with("1 0 obj"));
+    }
+
+    #[test]
+    fn test_pdf_embeds_jpeg_assets() {
+        // SOI, then a SOF0 segment for a 4x2 RGB image
+        let jpeg = vec![
+            0xFF, 0xD8, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00, 0x02, 0x00, 0x04, 0x03, 0x01, 0x22,
+        ];
+        assert_eq!(
+            Jpeg::parse(&jpeg),
+            Some(Jpeg {
+                width: 4,
+                height: 2,
+                color_space: "DeviceRGB"
+            })
+        );
+        let pdf = PdfGenerator::new(vec![ExportPage {
+            title: "Medium".to_string(),
+            width: 768,
+        }])
+        .with_assets(AssetData::from([("logo".to_string(), jpeg)]))
+        .generate(&design(), &[])
+        .unwrap();
+        assert!(pdf.contains("/Width 4 /Height 2 /ColorSpace /DeviceRGB"));
+        assert!(pdf.contains("/Im0 Do"));
+    }
+
+    /// A PNG of `rows`, each starting with its filter byte
+    fn png(width: u32, height: u32, color_type: u8, rows: &[u8]) -> Vec<u8> {
+        fn chunk(out: &mut Vec<u8>, kind: &[u8], body: &[u8]) {
+            out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            out.extend_from_slice(kind);
+            out.extend_from_slice(body);
+            // The CRC is not checked
+            out.extend_from_slice(&[0; 4]);
+        }
+        let mut header = Vec::new();
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        header.extend_from_slice(&[8, color_type, 0, 0, 0]);
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        chunk(&mut out, b"IHDR", &header);
+        chunk(&mut out, b"IDAT", &deflate(rows));
+        chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    #[test]
+    fn test_png_decoding_undoes_filters() {
+        // Sub filter on RGBA; the second pixel is half transparent
+        let rgba = png(2, 1, 6, &[1, 10, 20, 30, 255, 5, 5, 5, 129]);
+        assert_eq!(
+            Png::decode(&rgba),
+            Some(Png {
+                width: 2,
+                height: 1,
+                color_space: "DeviceRGB",
+                pixels: vec![10, 20, 30, 15, 25, 35],
+                alpha: Some(vec![255, 128]),
+            })
+        );
+
+        // Up, average and Paeth filters on gray rows; opaque, so no mask
+        let gray = png(2, 4, 0, &[0, 100, 50, 2, 1, 2, 3, 1, 1, 4, 1, 1]);
+        let decoded = Png::decode(&gray).unwrap();
+        assert_eq!(decoded.color_space, "DeviceGray");
+        assert_eq!(decoded.pixels, vec![100, 50, 101, 52, 51, 52, 52, 53]);
+        assert_eq!(decoded.alpha, None);
+
+        // 16-bit and interlaced images are not embedded
+        let mut deep = rgba.clone();
+        deep[24] = 16;
+        assert_eq!(Png::decode(&deep), None);
+        let mut interlaced = rgba.clone();
+        interlaced[28] = 1;
+        assert_eq!(Png::decode(&interlaced), None);
+        assert_eq!(Png::decode(b"\x89PNG\r\n\x1a\n"), None);
+    }
+
+    #[test]
+    fn test_pdf_embeds_png_assets_and_keeps_placeholders_for_others() {
+        let page = || {
+            vec![ExportPage {
+                title: "Medium".to_string(),
+                width: 768,
+            }]
+        };
+        let rgba = png(2, 1, 6, &[0, 10, 20, 30, 255, 15, 25, 35, 128]);
+        let pdf = PdfGenerator::new(page())
+            .with_assets(AssetData::from([("logo".to_string(), rgba)]))
+            .generate(&design(), &[])
+            .unwrap();
+        assert!(pdf.contains("/Width 2 /Height 1 /ColorSpace /DeviceRGB"));
+        assert!(pdf.contains("/FlateDecode] /SMask"));
+        assert!(pdf.contains("/Im0 Do"));
+
+        // A WebP cannot be embedded; the image box still shows
+        let webp = b"RIFF\0\0\0\0WEBPVP8L".to_vec();
+        let pdf = PdfGenerator::new(page())
+            .with_assets(AssetData::from([("logo".to_string(), webp)]))
+            .generate(&design(), &[])
+            .unwrap();
+        assert!(!pdf.contains("/Im0 Do"));
+        assert!(pdf.contains(&format!("{} rg", rgb(PLACEHOLDER))));
+    }
+
+    #[test]
+    fn test_text_encodings() {
+        assert_eq!(parse_color("#fff"), Some([255, 255, 255]));
+        assert_eq!(parse_color("rgb(59, 130, 246)"), Some([59, 130, 246]));
+        assert_eq!(parse_color("transparent"), None);
+        assert_eq!(pdf_text("(a\\b) café ✓"), "\\(a\\\\b\\) caf\\351 ?");
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+    }
+}
@@ -0,0 +1,195 @@
+//! IndexedDB
+//!
+//! Thin async wrapper over the browser's IndexedDB. Records are JSON text
+//! stored under string keys in a handful of object stores; project-sized
+//! data gets a store of its own with one record per project, so saving one
+//! project never rewrites another.
+//!
+//! Every request opens its own transaction as soon as the shared database
+//! connection is available. IndexedDB runs overlapping read-write
+//! transactions in the order they were created, so writes issued in order
+//! land in order.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use futures::FutureExt;
+use futures::channel::oneshot;
+use futures::future::{LocalBoxFuture, Shared};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{EventTarget, IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+use crate::domain::{AppError, AppResult};
+
+const DATABASE_NAME: &str = "leptos_studio";
+const DATABASE_VERSION: u32 = 3;
+
+/// Object stores of the studio database
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectStore {
+    /// App-wide records: settings, the legacy canvas, the sync queue
+    App,
+    /// Cached copies of projects, keyed by project id
+    Projects,
+    /// Local git repositories, keyed by project
+    Repositories,
+    /// Crash recovery journals, keyed by project id
+    Journals,
+    /// Undo histories, keyed by project id
+    Histories,
+}
+
+impl ObjectStore {
+    pub const ALL: [ObjectStore; 5] = [
+        ObjectStore::App,
+        ObjectStore::Projects,
+        ObjectStore::Repositories,
+        ObjectStore::Journals,
+        ObjectStore::Histories,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ObjectStore::App => "app",
+            ObjectStore::Projects => "projects",
+            ObjectStore::Repositories => "repositories",
+            ObjectStore::Journals => "journals",
+            ObjectStore::Histories => "histories",
+        }
+    }
+}
+
+type Opening = Shared<LocalBoxFuture<'static, AppResult<IdbDatabase>>>;
+
+thread_local! {
+    static DATABASE: RefCell<Option<Opening>> = const { RefCell::new(None) };
+}
+
+/// The shared connection, opened on first use
+async fn database() -> AppResult<IdbDatabase> {
+    let opening = DATABASE.with(|db| {
+        db.borrow_mut()
+            .get_or_insert_with(|| open().boxed_local().shared())
+            .clone()
+    });
+    opening.await
+}
+
+async fn open() -> AppResult<IdbDatabase> {
+    let factory = web_sys::window()
+        .and_then(|w| w.indexed_db().ok().flatten())
+        .ok_or_else(|| AppError::Storage("IndexedDB not available".to_string()))?;
+    let request = factory
+        .open_with_u32(DATABASE_NAME, DATABASE_VERSION)
+        .map_err(|_| AppError::Storage("Failed to open IndexedDB".to_string()))?;
+
+    // Stores can only be created while the upgrade event is being handled
+    let on_upgrade = Closure::<dyn FnMut()>::new({
+        let request = request.clone();
+        move || {
+            let Ok(db) = request.result().map(JsCast::unchecked_into::<IdbDatabase>) else {
+                return;
+            };
+            for store in ObjectStore::ALL {
+                if !db.object_store_names().contains(store.name()) {
+                    let _ = db.create_object_store(store.name());
+                }
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+    let fired = next_event(&request, &["success", "error"]).await;
+    request.set_onupgradeneeded(None);
+
+    match (fired, request.result()) {
+        ("success", Ok(db)) => Ok(db.unchecked_into()),
+        _ => Err(AppError::Storage("Failed to open IndexedDB".to_string())),
+    }
+}
+
+/// Wait for the first of `events` to fire on `target` and return its name
+async fn next_event(target: &EventTarget, events: &[&'static str]) -> &'static str {
+    let (sender, receiver) = oneshot::channel();
+    let sender = Rc::new(Cell::new(Some(sender)));
+    let listeners: Vec<_> = events
+        .iter()
+        .map(|&event| {
+            let sender = sender.clone();
+            let listener = Closure::<dyn FnMut()>::new(move || {
+                if let Some(sender) = sender.take() {
+                    let _ = sender.send(event);
+                }
+            });
+            let _ =
+                target.add_event_listener_with_callback(event, listener.as_ref().unchecked_ref());
+            (event, listener)
+        })
+        .collect();
+
+    let fired = receiver.await.unwrap_or("error");
+    for (event, listener) in &listeners {
+        let _ =
+            target.remove_event_listener_with_callback(event, listener.as_ref().unchecked_ref());
+    }
+    fired
+}
+
+async fn object_store(store: ObjectStore, mode: IdbTransactionMode) -> AppResult<IdbObjectStore> {
+    database()
+        .await?
+        .transaction_with_str_and_mode(store.name(), mode)
+        .and_then(|tx| tx.object_store(store.name()))
+        .map_err(|_| AppError::Storage(format!("Failed to open object store {}", store.name())))
+}
+
+/// Result of a read request
+async fn read(request: Result<IdbRequest, JsValue>) -> AppResult<JsValue> {
+    let failed = || AppError::Storage("Failed to read from IndexedDB".to_string());
+    let request = request.map_err(|_| failed())?;
+    match next_event(&request, &["success", "error"]).await {
+        "success" => request.result().map_err(|_| failed()),
+        _ => Err(failed()),
+    }
+}
+
+/// Wait until the transaction of a write request has committed
+async fn write(request: Result<IdbRequest, JsValue>) -> AppResult<()> {
+    let failed = || AppError::Storage("Failed to write to IndexedDB".to_string());
+    let tx = request
+        .ok()
+        .and_then(|request| request.transaction())
+        .ok_or_else(failed)?;
+    match next_event(&tx, &["complete", "error", "abort"]).await {
+        "complete" => Ok(()),
+        _ => Err(failed()),
+    }
+}
+
+/// Record under `key`, if any
+pub async fn get(store: ObjectStore, key: &str) -> AppResult<Option<String>> {
+    let store = object_store(store, IdbTransactionMode::Readonly).await?;
+    Ok(read(store.get(&key.into())).await?.as_string())
+}
+
+/// Every record in `store`
+pub async fn get_all(store: ObjectStore) -> AppResult<Vec<String>> {
+    let store = object_store(store, IdbTransactionMode::Readonly).await?;
+    let records = read(store.get_all()).await?;
+    Ok(js_sys::Array::from(&records)
+        .iter()
+        .filter_map(|record| record.as_string())
+        .collect())
+}
+
+/// Create or replace the record under `key`
+pub async fn put(store: ObjectStore, key: &str, value: &str) -> AppResult<()> {
+    let store = object_store(store, IdbTransactionMode::Readwrite).await?;
+    write(store.put_with_key(&value.into(), &key.into())).await
+}
+
+/// Remove the record under `key`
+pub async fn delete(store: ObjectStore, key: &str) -> AppResult<()> {
+    let store = object_store(store, IdbTransactionMode::Readwrite).await?;
+    write(store.delete(&key.into())).await
+}
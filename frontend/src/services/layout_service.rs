@@ -0,0 +1,52 @@
+//! Layout Service
+//!
+//! Typed entry points to the headless `studio_layout` engine, which the
+//! backend shares. Components are handed over in their serialized form.
+
+use crate::domain::{AppError, AppResult, CanvasComponent};
+
+pub use studio_layout::{ComponentBox, ComponentKind, PageLayout};
+
+fn to_values(components: &[CanvasComponent]) -> AppResult<Vec<serde_json::Value>> {
+    components
+        .iter()
+        .map(|c| serde_json::to_value(c).map_err(|e| AppError::Serialization(e.to_string())))
+        .collect()
+}
+
+/// Lay out top-level components on a page `viewport_width` pixels wide
+pub fn compute_layout(
+    components: &[CanvasComponent],
+    viewport_width: f32,
+) -> AppResult<PageLayout> {
+    studio_layout::compute_layout(&to_values(components)?, viewport_width)
+        .map_err(|e| AppError::Export(e.to_string()))
+}
+
+/// Render top-level components as an SVG wireframe `data:` URL, usable as
+/// an `img` source. The backend draws the same wireframe for saved projects.
+pub fn render_thumbnail(components: &[CanvasComponent]) -> String {
+    to_values(components)
+        .map(|values| studio_layout::wireframe::render(&values))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ButtonComponent, TextComponent};
+
+    #[test]
+    fn test_compute_layout_of_typed_components() {
+        let components = vec![
+            CanvasComponent::Text(TextComponent::new("Hello".to_string())),
+            CanvasComponent::Button(ButtonComponent::new("Go".to_string())),
+        ];
+        let layout = compute_layout(&components, 400.0).unwrap();
+
+        assert_eq!(layout.boxes.len(), 2);
+        assert_eq!(layout.boxes[0].kind, ComponentKind::Text);
+        assert!(layout.boxes[1].y >= layout.boxes[0].bottom());
+        assert!(render_thumbnail(&components).starts_with("data:image/svg+xml,"));
+    }
+}
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::domain::{AppError, AppResult};
+use crate::services::indexed_db::ObjectStore;
 use crate::state::persistence::Persistable;
 use crate::state::project::Project;
 
@@ -23,13 +24,20 @@ pub struct RepositoryState {
     pub head: Option<String>, // Commit ID
 }
 
+/// Repository of projects that have not been saved to the backend yet
+pub const LOCAL_REPOSITORY: &str = "local";
+
 impl Persistable for RepositoryState {
-    fn storage_key() -> &'static str {
-        "leptos_studio_git_repo"
+    fn object_store() -> ObjectStore {
+        ObjectStore::Repositories
+    }
+
+    fn legacy_key(key: &str) -> Option<&'static str> {
+        (key == LOCAL_REPOSITORY).then_some("leptos_studio_git_repo")
     }
 }
 
-/// A Git backend that stores commits in browser LocalStorage
+/// A Git backend that stores commits in the browser's IndexedDB
 pub struct LocalStorageGitBackend;
 
 impl Default for LocalStorageGitBackend {
@@ -43,18 +51,14 @@ impl LocalStorageGitBackend {
         Self
     }
 
-    fn get_repo() -> AppResult<RepositoryState> {
-        match RepositoryState::load() {
-            Ok(repo) => Ok(repo),
-            Err(AppError::Storage(msg)) if msg.contains("No data found") => {
-                Ok(RepositoryState::default())
-            }
-            Err(e) => Err(e),
-        }
+    async fn get_repo() -> AppResult<RepositoryState> {
+        Ok(RepositoryState::load(LOCAL_REPOSITORY)
+            .await?
+            .unwrap_or_default())
     }
 
-    fn save_repo(repo: &RepositoryState) -> AppResult<()> {
-        repo.save()
+    async fn save_repo(repo: &RepositoryState) -> AppResult<()> {
+        repo.save(LOCAL_REPOSITORY).await
     }
 
     // Simulate network delay to mimic real backend behavior
@@ -85,11 +89,8 @@ impl GitBackend for LocalStorageGitBackend {
     async fn status(&self, current_project: Option<&Project>) -> AppResult<RepoStatus> {
         self.simulate_delay().await;
 
-        // Propagate get_repo error instead of swallowing it if it fails catastrophically,
-        // but for now, get_repo returns a default on error. We should ideally distinguish
-        // between "no repo" and "error loading repo", but the current implementation of get_repo
-        // masks the error.
-        let repo = Self::get_repo()?;
+        // A repository that was never saved is empty; storage errors propagate
+        let repo = Self::get_repo().await?;
         let commit_count = repo.commits.len();
 
         let has_changes = if let Some(current_project) = current_project {
@@ -110,7 +111,7 @@ impl GitBackend for LocalStorageGitBackend {
     async fn log(&self) -> AppResult<Vec<CommitInfo>> {
         self.simulate_delay().await;
 
-        let repo = Self::get_repo()?;
+        let repo = Self::get_repo().await?;
         if repo.commits.is_empty() {
             return Ok(Vec::new());
         }
@@ -145,7 +146,7 @@ impl GitBackend for LocalStorageGitBackend {
         // Check for changes before committing (Standards/Best Practice)
         // We can reuse our own status logic or duplicate the check here.
         // Reusing status implies another delay simulation, so we duplicate the check logic for efficiency.
-        let mut repo = Self::get_repo()?;
+        let mut repo = Self::get_repo().await?;
 
         if !Self::is_dirty(&repo, project) {
             return Err(AppError::Validation(
@@ -166,7 +167,7 @@ impl GitBackend for LocalStorageGitBackend {
         repo.commits.push(commit);
         repo.head = Some(commit_id);
 
-        Self::save_repo(&repo)?;
+        Self::save_repo(&repo).await?;
 
         Ok(())
     }
@@ -175,7 +176,7 @@ impl GitBackend for LocalStorageGitBackend {
         self.simulate_delay().await;
 
         // Return the whole repo state as JSON for download
-        let repo = Self::get_repo()?;
+        let repo = Self::get_repo().await?;
         let json = serde_json::to_string_pretty(&repo)
             .map_err(|e| AppError::Export(format!("Failed to serialize repo: {}", e)))?;
         Ok(Some(json))
@@ -187,14 +188,14 @@ impl GitBackend for LocalStorageGitBackend {
         let repo: RepositoryState = serde_json::from_str(json)
             .map_err(|e| AppError::Export(format!("Failed to deserialize repo: {}", e)))?;
 
-        Self::save_repo(&repo)?;
+        Self::save_repo(&repo).await?;
         Ok(())
     }
 
     async fn restore_head(&self) -> AppResult<Option<Project>> {
         self.simulate_delay().await;
 
-        let repo = Self::get_repo()?;
+        let repo = Self::get_repo().await?;
         let project = repo
             .head
             .as_ref()
@@ -209,7 +210,7 @@ impl GitBackend for LocalStorageGitBackend {
 
         // Reset to default state (empty repo)
         let repo = RepositoryState::default();
-        Self::save_repo(&repo)?;
+        Self::save_repo(&repo).await?;
 
         Ok(())
     }
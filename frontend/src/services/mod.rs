@@ -1,10 +1,16 @@
 pub mod analytics_service;
+pub mod api_client;
+pub mod asset_bundle;
 pub mod event_bus;
 pub mod export_advanced;
 pub mod export_service;
+pub mod export_visual;
 pub mod git_factory;
 pub mod git_service;
+pub mod indexed_db;
+pub mod layout_service;
 pub mod local_storage_git;
+pub mod offline_store;
 pub mod project_manager;
 pub mod project_service;
 pub mod property_service;
@@ -12,11 +18,16 @@ pub mod remote_git;
 pub mod template_service;
 
 pub use analytics_service::*;
+pub use api_client::*;
+pub use asset_bundle::*;
 pub use event_bus::*;
 pub use export_advanced::*;
 pub use export_service::*;
+pub use export_visual::*;
 pub use git_service::*;
+pub use layout_service::*;
 pub use local_storage_git::*;
+pub use offline_store::*;
 pub use project_manager::*;
 pub use project_service::*;
 pub use property_service::*;
@@ -0,0 +1,373 @@
+//! Offline Store
+//!
+//! Durable browser-side state for working without the backend: a queue of
+//! saves and commits waiting to be uploaded, and a cache of recently opened
+//! projects so they can still be listed and opened while offline.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::AppResult;
+use crate::services::api_client::ProjectMetadata;
+use crate::services::indexed_db::ObjectStore;
+use crate::services::layout_service::render_thumbnail;
+use crate::state::persistence::{Persistable, read_legacy, remove_legacy};
+use crate::state::project::Project;
+
+/// Projects kept in the cache, not counting ones with queued writes
+pub const CACHE_LIMIT: usize = 20;
+
+/// A write waiting for the backend
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PendingWrite {
+    /// Upload the cached copy of a project
+    Save { project_id: String },
+    /// Record a commit made while offline
+    Commit {
+        project_id: String,
+        message: String,
+        timestamp: f64,
+        snapshot: Box<Project>,
+    },
+}
+
+impl PendingWrite {
+    pub fn project_id(&self) -> &str {
+        match self {
+            PendingWrite::Save { project_id } | PendingWrite::Commit { project_id, .. } => {
+                project_id
+            }
+        }
+    }
+}
+
+/// Writes in the order they were made
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncQueue {
+    pub writes: Vec<PendingWrite>,
+}
+
+/// Key of the queue in the app object store
+pub const SYNC_QUEUE_KEY: &str = "sync_queue";
+
+impl Persistable for SyncQueue {
+    fn object_store() -> ObjectStore {
+        ObjectStore::App
+    }
+
+    fn legacy_key(key: &str) -> Option<&'static str> {
+        (key == SYNC_QUEUE_KEY).then_some("leptos_studio_sync_queue")
+    }
+}
+
+impl SyncQueue {
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Queue an upload of a project. Saves upload the latest cached copy, so
+    /// one queued save per project is enough.
+    pub fn push_save(&mut self, project_id: &str) {
+        if !self.has_save(project_id) {
+            self.writes.push(PendingWrite::Save {
+                project_id: project_id.to_string(),
+            });
+        }
+    }
+
+    pub fn push_commit(&mut self, project_id: &str, message: &str, snapshot: Project, now: f64) {
+        self.writes.push(PendingWrite::Commit {
+            project_id: project_id.to_string(),
+            message: message.to_string(),
+            timestamp: now,
+            snapshot: Box::new(snapshot),
+        });
+    }
+
+    /// Put writes restored from storage ahead of the ones made since
+    pub fn restore(&mut self, stored: SyncQueue) {
+        let newer = std::mem::replace(&mut self.writes, stored.writes);
+        for write in newer {
+            match write {
+                PendingWrite::Save { project_id } => self.push_save(&project_id),
+                commit => self.writes.push(commit),
+            }
+        }
+    }
+
+    pub fn has_save(&self, project_id: &str) -> bool {
+        self.writes
+            .iter()
+            .any(|w| matches!(w, PendingWrite::Save { project_id: id } if id == project_id))
+    }
+
+    /// Remove `write` from the front of the queue once it went through
+    pub fn complete(&mut self, write: &PendingWrite) {
+        if self.writes.first() == Some(write) {
+            self.writes.remove(0);
+        }
+    }
+
+    /// Forget the queued save of a project, keeping its commits
+    pub fn drop_save(&mut self, project_id: &str) {
+        self.writes
+            .retain(|w| !matches!(w, PendingWrite::Save { project_id: id } if id == project_id));
+    }
+
+    /// Point the queued save of a project at another project id
+    pub fn move_save(&mut self, from: &str, to: &str) {
+        for write in &mut self.writes {
+            if let PendingWrite::Save { project_id } = write
+                && project_id == from
+            {
+                *project_id = to.to_string();
+            }
+        }
+    }
+}
+
+/// The latest copy of a project on this device
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CachedProject {
+    pub project: Project,
+    pub metadata: ProjectMetadata,
+    /// `last_modified` of the server copy this one is based on; `None` for
+    /// projects the server has not seen
+    pub server_modified: Option<f64>,
+    /// Bumped on every local change, so an upload can tell whether the copy
+    /// changed while it was in flight
+    pub revision: u64,
+}
+
+/// Cached copies are kept one record per project, keyed by project id
+impl Persistable for CachedProject {
+    fn object_store() -> ObjectStore {
+        ObjectStore::Projects
+    }
+}
+
+/// LocalStorage key the whole cache was kept at before IndexedDB
+const LEGACY_CACHE_KEY: &str = "leptos_studio_project_cache";
+
+/// Recently opened projects, by id
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectCache {
+    pub projects: HashMap<String, CachedProject>,
+}
+
+impl ProjectCache {
+    /// Read every cached project, migrating a cache left in LocalStorage
+    pub async fn load() -> AppResult<Self> {
+        let mut cache = Self::default();
+        for cached in CachedProject::load_all().await? {
+            cache.projects.insert(cached.metadata.id.clone(), cached);
+        }
+        if let Some(legacy) = read_legacy::<ProjectCache>(LEGACY_CACHE_KEY) {
+            for (id, cached) in legacy.projects {
+                if let Entry::Vacant(entry) = cache.projects.entry(id) {
+                    cached.save(entry.key()).await?;
+                    entry.insert(cached);
+                }
+            }
+            remove_legacy(LEGACY_CACHE_KEY);
+        }
+        Ok(cache)
+    }
+
+    /// Add projects restored from storage that were not touched since
+    pub fn restore(&mut self, stored: ProjectCache) {
+        for (id, cached) in stored.projects {
+            self.projects.entry(id).or_insert(cached);
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CachedProject> {
+        self.projects.get(id)
+    }
+
+    /// Record the server's copy of a project
+    pub fn store_synced(&mut self, id: &str, project: Project, last_modified: f64) {
+        let metadata = self.local_metadata(id, &project, last_modified);
+        let revision = self.projects.get(id).map_or(0, |c| c.revision + 1);
+        self.projects.insert(
+            id.to_string(),
+            CachedProject {
+                project,
+                metadata,
+                server_modified: Some(last_modified),
+                revision,
+            },
+        );
+    }
+
+    /// Record a local change that is not on the server yet
+    pub fn store_local(&mut self, id: &str, project: Project, now: f64) {
+        let metadata = self.local_metadata(id, &project, now);
+        let entry = self.projects.entry(id.to_string());
+        let cached = entry.or_insert_with(|| CachedProject {
+            project: project.clone(),
+            metadata: metadata.clone(),
+            server_modified: None,
+            revision: 0,
+        });
+        cached.project = project;
+        cached.metadata = metadata;
+        cached.revision += 1;
+    }
+
+    /// Record the server's metadata after an upload
+    pub fn mark_synced(&mut self, id: &str, metadata: ProjectMetadata) {
+        if let Some(cached) = self.projects.get_mut(id) {
+            cached.server_modified = Some(metadata.last_modified);
+            cached.metadata = metadata;
+        }
+    }
+
+    /// Metadata for a cached copy, keeping what the dashboard set on the
+    /// server (tags, folder, favorite)
+    fn local_metadata(&self, id: &str, project: &Project, last_modified: f64) -> ProjectMetadata {
+        let previous = self.projects.get(id).map(|c| &c.metadata);
+        ProjectMetadata {
+            id: id.to_string(),
+            name: project.name.clone(),
+            last_modified,
+            component_count: project.layout.len(),
+            deleted_at: None,
+            forked_from: previous.and_then(|m| m.forked_from.clone()),
+            tags: previous.map(|m| m.tags.clone()).unwrap_or_default(),
+            favorite: previous.is_some_and(|m| m.favorite),
+            folder: previous.and_then(|m| m.folder.clone()),
+            thumbnail: Some(render_thumbnail(&project.layout)),
+        }
+    }
+
+    /// Cached projects, most recently modified first
+    pub fn list(&self) -> Vec<ProjectMetadata> {
+        let mut list: Vec<ProjectMetadata> =
+            self.projects.values().map(|c| c.metadata.clone()).collect();
+        list.sort_by(|a, b| b.last_modified.total_cmp(&a.last_modified));
+        list
+    }
+
+    /// Drop the least recently modified projects beyond [`CACHE_LIMIT`] and
+    /// return their ids. Projects for which `keep` holds are never dropped.
+    pub fn evict(&mut self, keep: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut evictable: Vec<(String, f64)> = self
+            .projects
+            .iter()
+            .filter(|(id, _)| !keep(id))
+            .map(|(id, c)| (id.clone(), c.metadata.last_modified))
+            .collect();
+        if evictable.len() <= CACHE_LIMIT {
+            return Vec::new();
+        }
+        evictable.sort_by(|a, b| b.1.total_cmp(&a.1));
+        evictable
+            .drain(CACHE_LIMIT..)
+            .map(|(id, _)| {
+                self.projects.remove(&id);
+                id
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::design_tokens::DesignTokens;
+    use crate::state::SettingsState;
+
+    fn project(name: &str) -> Project {
+        Project::new(
+            name.to_string(),
+            Vec::new(),
+            SettingsState::default(),
+            DesignTokens::default(),
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn test_queue_keeps_one_save_per_project() {
+        let mut queue = SyncQueue::default();
+        queue.push_save("a");
+        queue.push_commit("a", "First", project("A"), 1.0);
+        queue.push_save("a");
+        queue.push_save("b");
+        assert_eq!(queue.len(), 3);
+
+        // Only the write at the front can complete
+        let commit = queue.writes[1].clone();
+        queue.complete(&commit);
+        assert_eq!(queue.len(), 3);
+        queue.complete(&PendingWrite::Save {
+            project_id: "a".to_string(),
+        });
+        assert_eq!(queue.writes[0], commit);
+
+        queue.move_save("b", "c");
+        assert!(queue.has_save("c") && !queue.has_save("b"));
+        queue.drop_save("c");
+        assert_eq!(queue.writes, vec![commit.clone()]);
+
+        // Writes made before the stored queue was read go after it
+        let mut fresh = SyncQueue::default();
+        fresh.push_save("a");
+        fresh.push_save("d");
+        fresh.restore(SyncQueue {
+            writes: vec![
+                PendingWrite::Save {
+                    project_id: "a".to_string(),
+                },
+                commit,
+            ],
+        });
+        assert_eq!(fresh.len(), 3);
+        assert_eq!(
+            fresh.writes[2],
+            PendingWrite::Save {
+                project_id: "d".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_cache_tracks_server_version() {
+        let mut cache = ProjectCache::default();
+        cache.store_synced("a", project("A"), 10.0);
+        assert_eq!(cache.get("a").unwrap().server_modified, Some(10.0));
+
+        cache.store_local("a", project("A2"), 20.0);
+        let cached = cache.get("a").unwrap();
+        assert_eq!(cached.metadata.name, "A2");
+        // Still based on the server copy it was loaded from
+        assert_eq!(cached.server_modified, Some(10.0));
+        assert_eq!(cached.revision, 1);
+
+        cache.store_local("new", project("New"), 30.0);
+        assert_eq!(cache.get("new").unwrap().server_modified, None);
+        assert_eq!(cache.list()[0].id, "new");
+    }
+
+    #[test]
+    fn test_eviction_keeps_pending_projects() {
+        let mut cache = ProjectCache::default();
+        for i in 0..CACHE_LIMIT + 5 {
+            cache.store_synced(&i.to_string(), project("P"), i as f64);
+        }
+        let evicted = cache.evict(|id| id == "0");
+
+        assert_eq!(evicted.len(), 4);
+        assert_eq!(cache.projects.len(), CACHE_LIMIT + 1);
+        assert!(cache.get("0").is_some());
+        assert!(cache.get("1").is_none());
+        assert!(cache.get(&(CACHE_LIMIT + 4).to_string()).is_some());
+    }
+}
@@ -1,119 +1,85 @@
-use crate::domain::{AppError, AppResult};
+use crate::domain::AppResult;
+use crate::services::api_client::ApiClient;
 use crate::state::Project;
-use gloo_net::http::Request;
-use leptos::prelude::*;
-use serde::{Deserialize, Serialize};
 
-fn get_api_base() -> String {
-    // Try to get from runtime window.LEPTOS_API_URL first
-    let runtime_base = window()
-        .get("LEPTOS_API_URL")
-        .and_then(|val| val.as_string());
-
-    let base = runtime_base
-        .or_else(|| option_env!("API_URL").map(|s| s.to_string()))
-        .unwrap_or_else(|| "http://localhost:3000".to_string());
-
-    format!("{}/api/projects", base.trim_end_matches('/'))
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
-pub struct ProjectMetadata {
-    pub id: String,
-    pub name: String,
-    pub last_modified: f64,
-    pub component_count: usize,
-}
+pub use crate::services::api_client::{
+    FolderCount, ForkHistory, ForkOptions, OrganizationPatch, ProjectFacets, ProjectMetadata,
+    ProjectPage, ProjectQuery, ProjectSort, StoredProject, TagCount,
+};
 
 pub struct ProjectManager;
 
 impl ProjectManager {
-    /// List all projects
-    pub async fn list_projects() -> AppResult<Vec<ProjectMetadata>> {
-        let resp = Request::get(&get_api_base())
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !resp.ok() {
-            return Err(AppError::Network(format!(
-                "Server returned {}",
-                resp.status()
-            )));
-        }
-
-        resp.json()
-            .await
-            .map_err(|e| AppError::Serialization(e.to_string()))
+    /// List one page of projects matching `query`
+    pub async fn list_projects(query: &ProjectQuery) -> AppResult<ProjectPage> {
+        ApiClient::new().list_projects(query).await
     }
 
     /// Save a project
     pub async fn save_project(id: &str, project: &Project) -> AppResult<()> {
-        let mut json =
-            serde_json::to_value(project).map_err(|e| AppError::Serialization(e.to_string()))?;
-
-        // Ensure ID is in the payload
-        if let Some(obj) = json.as_object_mut() {
-            obj.insert("id".to_string(), serde_json::Value::String(id.to_string()));
-            obj.insert(
-                "last_modified".to_string(),
-                serde_json::Value::from(js_sys::Date::now()),
-            );
-        }
-
-        let resp = Request::post(&get_api_base())
-            .json(&json)
-            .map_err(|e| AppError::Serialization(e.to_string()))?
-            .send()
+        ApiClient::new()
+            .save_project(id, project, None)
             .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !resp.ok() {
-            return Err(AppError::Network(format!(
-                "Server returned {}",
-                resp.status()
-            )));
-        }
+            .map(drop)
+    }
 
-        Ok(())
+    /// Save a project unless the server copy changed since `base_modified`
+    pub async fn save_project_from(
+        id: &str,
+        project: &Project,
+        base_modified: Option<f64>,
+    ) -> AppResult<ProjectMetadata> {
+        ApiClient::new()
+            .save_project(id, project, base_modified)
+            .await
     }
 
     /// Load a project
     pub async fn load_project(id: &str) -> AppResult<Project> {
-        let url = format!("{}/{}", get_api_base(), id);
-        let resp = Request::get(&url)
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !resp.ok() {
-            return Err(AppError::Network(format!(
-                "Server returned {}",
-                resp.status()
-            )));
-        }
+        ApiClient::new().get_project(id).await
+    }
 
-        resp.json()
-            .await
-            .map_err(|e| AppError::Serialization(e.to_string()))
+    /// Load a project with its server modification time
+    pub async fn load_stored_project(id: &str) -> AppResult<StoredProject> {
+        ApiClient::new().get_stored_project(id).await
     }
 
-    /// Delete a project
+    /// Move a project to the trash
     pub async fn delete_project(id: &str) -> AppResult<()> {
-        let url = format!("{}/{}", get_api_base(), id);
-        let resp = Request::delete(&url)
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
+        ApiClient::new().delete_project(id).await
+    }
 
-        if !resp.ok() {
-            return Err(AppError::Network(format!(
-                "Server returned {}",
-                resp.status()
-            )));
-        }
+    /// Folders, tags and favorite count for the dashboard sidebar
+    pub async fn project_facets() -> AppResult<ProjectFacets> {
+        ApiClient::new().project_facets().await
+    }
+
+    /// Change a project's tags, favorite flag or folder
+    pub async fn organize_project(
+        id: &str,
+        patch: &OrganizationPatch,
+    ) -> AppResult<ProjectMetadata> {
+        ApiClient::new().organize_project(id, patch).await
+    }
+
+    /// Fork a project, returning the new project's metadata
+    pub async fn fork_project(id: &str, options: &ForkOptions) -> AppResult<ProjectMetadata> {
+        ApiClient::new().fork_project(id, options).await
+    }
+
+    /// List trashed projects
+    pub async fn list_trash() -> AppResult<Vec<ProjectMetadata>> {
+        ApiClient::new().list_trash().await
+    }
+
+    /// Restore a project from the trash
+    pub async fn restore_project(id: &str) -> AppResult<()> {
+        ApiClient::new().restore_project(id).await.map(drop)
+    }
 
-        Ok(())
+    /// Permanently delete a trashed project
+    pub async fn delete_project_permanently(id: &str) -> AppResult<()> {
+        ApiClient::new().delete_project_permanently(id).await
     }
 
     /// Rename a project
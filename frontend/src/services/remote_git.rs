@@ -1,17 +1,8 @@
 use crate::domain::{AppError, AppResult};
+use crate::services::api_client::{ApiClient, CommitRecord, NewCommit};
 use crate::services::git_service::{CommitInfo, GitBackend, RepoStatus};
 use crate::state::project::Project;
 use chrono::DateTime;
-use gloo_net::http::Request;
-use serde::{Deserialize, Serialize};
-
-#[derive(Clone, Debug, Serialize, Deserialize)]
-struct RemoteGitCommit {
-    pub id: String,
-    pub message: String,
-    pub timestamp: f64,
-    pub snapshot: serde_json::Value,
-}
 
 pub struct RemoteGitBackend {
     project_id: String,
@@ -24,40 +15,8 @@ impl RemoteGitBackend {
         }
     }
 
-    fn get_api_base() -> String {
-        let runtime_base = leptos::prelude::window()
-            .get("LEPTOS_API_URL")
-            .and_then(|val| val.as_string());
-
-        let base = runtime_base
-            .or_else(|| option_env!("API_URL").map(|s| s.to_string()))
-            .unwrap_or_else(|| "http://localhost:3000".to_string());
-
-        format!("{}/api/projects", base.trim_end_matches('/'))
-    }
-
-    async fn get_commits(&self) -> AppResult<Vec<RemoteGitCommit>> {
-        let url = format!("{}/{}/commits", Self::get_api_base(), self.project_id);
-        let resp = Request::get(&url)
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !resp.ok() {
-            // If 404, might mean no commits yet, return empty
-            if resp.status() == 404 {
-                return Ok(Vec::new());
-            }
-            return Err(AppError::Network(format!(
-                "Server returned {}: {}",
-                resp.status(),
-                resp.status_text()
-            )));
-        }
-
-        resp.json()
-            .await
-            .map_err(|e| AppError::Serialization(e.to_string()))
+    async fn get_commits(&self) -> AppResult<Vec<CommitRecord>> {
+        ApiClient::new().list_commits(&self.project_id).await
     }
 }
 
@@ -117,29 +76,15 @@ impl GitBackend for RemoteGitBackend {
     }
 
     async fn commit(&self, project: &Project, message: &str) -> AppResult<()> {
-        let url = format!("{}/{}/commits", Self::get_api_base(), self.project_id);
-
-        let payload = serde_json::json!({
-            "message": message,
-            "timestamp": js_sys::Date::now(),
-            "snapshot": project
-        });
-
-        let resp = Request::post(&url)
-            .json(&payload)
-            .map_err(|e| AppError::Serialization(e.to_string()))?
-            .send()
+        let commit = NewCommit {
+            message,
+            timestamp: js_sys::Date::now(),
+            snapshot: project,
+        };
+        ApiClient::new()
+            .create_commit(&self.project_id, &commit)
             .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !resp.ok() {
-            return Err(AppError::Network(format!(
-                "Server returned {}",
-                resp.status()
-            )));
-        }
-
-        Ok(())
+            .map(drop)
     }
 
     async fn push(&self) -> AppResult<Option<String>> {
@@ -169,18 +114,6 @@ impl GitBackend for RemoteGitBackend {
     }
 
     async fn reset(&self) -> AppResult<()> {
-        let url = format!("{}/{}/commits", Self::get_api_base(), self.project_id);
-        let resp = Request::delete(&url)
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !resp.ok() {
-            return Err(AppError::Network(format!(
-                "Server returned {}",
-                resp.status()
-            )));
-        }
-        Ok(())
+        ApiClient::new().delete_commits(&self.project_id).await
     }
 }
@@ -5,12 +5,11 @@
 //! rapid UI prototyping.
 
 use crate::domain::{
-    AppError, AppResult, ButtonComponent, ButtonSize, ButtonVariant, CanvasComponent,
-    ContainerComponent, FlexAlign, FlexDirection, FlexJustify, InputComponent, InputType,
-    LayoutType, Spacing, TextComponent, TextStyle, TextTag,
+    AppResult, ButtonComponent, ButtonSize, ButtonVariant, CanvasComponent, ContainerComponent,
+    FlexAlign, FlexDirection, FlexJustify, InputComponent, InputType, LayoutType, Spacing,
+    TextComponent, TextStyle, TextTag,
 };
-use gloo_net::http::Request;
-use leptos::prelude::*;
+use crate::services::api_client::ApiClient;
 use serde::{Deserialize, Serialize};
 
 /// Template category for organization
@@ -90,67 +89,19 @@ impl TemplateService {
         Self
     }
 
-    fn get_api_base() -> String {
-        let runtime_base = window()
-            .get("LEPTOS_API_URL")
-            .and_then(|val| val.as_string());
-
-        let base = runtime_base
-            .or_else(|| option_env!("API_URL").map(|s| s.to_string()))
-            .unwrap_or_else(|| "http://localhost:3000".to_string());
-
-        format!("{}/api/templates", base.trim_end_matches('/'))
-    }
-
+    /// Fetch user-defined templates from the backend
     pub async fn fetch_custom_templates() -> AppResult<Vec<Template>> {
-        let resp = Request::get(&Self::get_api_base())
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !resp.ok() {
-            return Err(AppError::Network(format!(
-                "Server returned {}",
-                resp.status()
-            )));
-        }
-
-        resp.json()
-            .await
-            .map_err(|e| AppError::Serialization(e.to_string()))
+        ApiClient::new().list_templates().await
     }
 
+    /// Save a user-defined template to the backend
     pub async fn save_custom_template(template: &Template) -> AppResult<()> {
-        let resp = Request::post(&Self::get_api_base())
-            .json(template)
-            .map_err(|e| AppError::Serialization(e.to_string()))?
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !resp.ok() {
-            return Err(AppError::Network(format!(
-                "Server returned {}",
-                resp.status()
-            )));
-        }
-        Ok(())
+        ApiClient::new().save_template(template).await.map(drop)
     }
 
+    /// Delete a user-defined template from the backend
     pub async fn delete_custom_template(id: &str) -> AppResult<()> {
-        let url = format!("{}/{}", Self::get_api_base(), id);
-        let resp = Request::delete(&url)
-            .send()
-            .await
-            .map_err(|e| AppError::Network(e.to_string()))?;
-
-        if !resp.ok() {
-            return Err(AppError::Network(format!(
-                "Server returned {}",
-                resp.status()
-            )));
-        }
-        Ok(())
+        ApiClient::new().delete_template(id).await
     }
 
     /// Get all built-in templates
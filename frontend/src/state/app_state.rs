@@ -1,227 +1,282 @@
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use wasm_bindgen::JsCast;
 
-use super::history::{History, Snapshot};
+use super::component_index::{ComponentIndex, IndexedTree};
+use super::find_replace::{Matcher, SearchMatch, SearchScope, search, text_field_mut, text_fields};
+use super::grouping::{GroupKind, carry_spacing};
+use super::history::{
+    History, HistoryEntry, NodeId, Operation, Slot, Transaction, children_of, children_of_mut,
+};
+use super::history_store::StoredHistory;
 use super::persistence::Persistable;
 use super::project::Project;
+use super::recovery::{RecoveryJournal, RecoveryOffer, RecoverySummary};
+use super::selection::{SelectMode, Selection};
+use super::sync::{FlushOutcome, SyncState, SyncStatus};
 use crate::builder::component_library::{LibraryComponent, builtin_library_components};
 use crate::builder::design_tokens::DesignTokens;
 use crate::builder::drag_drop::DragState;
-use crate::domain::{CanvasComponent, ComponentId, Variable};
+use crate::constants::{
+    HISTORY_COALESCE_MS, HISTORY_PERSIST_DEBOUNCE_MS, RECOVERY_JOURNAL_DEBOUNCE_MS,
+};
+use crate::domain::{
+    AppError, CanvasComponent, ComponentId, ComponentNameValidator, ValidationError, Validator,
+    Variable, find_named, name_key, without_hidden,
+};
+use crate::services::indexed_db::ObjectStore;
 
 /// Canvas-specific state
 #[derive(Clone, Copy)]
 pub struct CanvasState {
+    /// Top-level components. Change them through `CanvasState` so the
+    /// component index stays in step.
     pub components: RwSignal<Vec<CanvasComponent>>,
-    pub selected: RwSignal<Option<ComponentId>>,
+    pub selection: RwSignal<Selection>,
     pub history: RwSignal<History>,
     pub drag_state: RwSignal<DragState>,
+    transaction: StoredValue<Option<Transaction>>,
+    index: StoredValue<ComponentIndex>,
 }
 
 impl CanvasState {
     pub fn new() -> Self {
         Self {
             components: RwSignal::new(Vec::new()),
-            selected: RwSignal::new(None),
+            selection: RwSignal::new(Selection::new()),
             history: RwSignal::new(History::new()),
             drag_state: RwSignal::new(DragState::NotDragging),
+            transaction: StoredValue::new(None),
+            index: StoredValue::new(ComponentIndex::default()),
         }
     }
 
-    /// Add a component to the canvas (internal helper without snapshot)
-    pub fn add_component_without_snapshot(&self, component: CanvasComponent) {
-        self.components.update(|components| {
-            components.push(component);
-        });
+    /// Replace every component, as when a project is opened. History is
+    /// left alone.
+    pub fn set_components(&self, components: Vec<CanvasComponent>) {
+        self.index.set_value(ComponentIndex::new(&components));
+        self.components.set(components);
     }
 
-    /// Add a component to the canvas
-    pub fn add_component(&self, component: CanvasComponent) {
-        self.record_snapshot("Add Component");
-        self.add_component_without_snapshot(component);
+    /// Look something up in the components through their index. Tracks the
+    /// components.
+    pub fn with_index<R>(&self, f: impl FnOnce(&ComponentIndex, &[CanvasComponent]) -> R) -> R {
+        self.components
+            .with(|components| self.index.with_value(|index| f(index, components)))
     }
 
-    /// Add a child component to a specific parent. Returns true if successful.
-    pub fn add_child_component(&self, parent_id: &ComponentId, component: CanvasComponent) -> bool {
-        // Check if we can add child first (simple check: does parent exist and support children?)
-        let can_add = self.components.with(|comps| {
-            Self::get_recursive(comps, parent_id).is_some_and(|p| {
-                matches!(p, CanvasComponent::Container(_) | CanvasComponent::Card(_))
-            })
+    fn with_index_untracked<R>(
+        &self,
+        f: impl FnOnce(&ComponentIndex, &[CanvasComponent]) -> R,
+    ) -> R {
+        self.components
+            .with_untracked(|components| self.index.with_value(|index| f(index, components)))
+    }
+
+    /// Change the components and their index together; `f` returns whether
+    /// anything changed
+    fn update_tree(&self, f: impl FnOnce(&mut IndexedTree) -> bool) -> bool {
+        let mut changed = false;
+        self.components.maybe_update(|components| {
+            self.index.update_value(|index| {
+                changed = f(&mut IndexedTree { components, index });
+            });
+            changed
         });
+        changed
+    }
 
-        if can_add {
-            self.record_snapshot("Add Child Component");
-            self.add_child_component_without_snapshot(parent_id, component);
-            return true;
+    /// Apply `operations` as one undoable action. Nothing changes if any of
+    /// them does not fit the current tree.
+    ///
+    /// Inside a transaction the action joins the transaction's entry.
+    /// Otherwise it may be merged into the previous entry when both edit the
+    /// same fields in quick succession.
+    pub fn perform(&self, description: &str, operations: Vec<Operation>) -> bool {
+        if operations.is_empty() {
+            return false;
         }
-        false
+        let entry = HistoryEntry::new(description.to_string(), operations);
+        if !self.update_tree(|tree| entry.apply(tree)) {
+            return false;
+        }
+        if self.in_transaction() {
+            self.transaction.update_value(|open| {
+                if let Some(open) = open {
+                    open.entry.operations.extend(entry.operations);
+                }
+            });
+        } else {
+            self.history
+                .update(|h| h.push_coalescing(entry, HISTORY_COALESCE_MS));
+        }
+        true
     }
 
-    pub fn add_child_component_without_snapshot(
-        &self,
-        parent_id: &ComponentId,
-        component: CanvasComponent,
-    ) -> bool {
-        let mut result = false;
-        self.components.update(|components| {
-            result = Self::add_child_recursive(&mut components[..], parent_id, component);
-        });
-        result
-    }
-
-    #[allow(clippy::collapsible_match)]
-    fn add_child_recursive(
-        components: &mut [CanvasComponent],
-        parent_id: &ComponentId,
-        child: CanvasComponent,
-    ) -> bool {
-        for comp in components.iter_mut() {
-            if comp.id() == parent_id {
-                return match comp {
-                    CanvasComponent::Container(container) => {
-                        container.children.push(child);
-                        true
-                    }
-                    CanvasComponent::Card(card) => {
-                        card.children.push(child);
-                        true
-                    }
-                    _ => false,
-                };
+    /// Group the actions that follow into one undo step, recorded when
+    /// [`Self::commit_transaction`] is called. Nested transactions join the
+    /// outermost one.
+    pub fn begin_transaction(&self, description: &str) {
+        self.transaction.update_value(|open| match open {
+            Some(open) => open.depth += 1,
+            None => {
+                *open = Some(Transaction {
+                    entry: HistoryEntry::new(description.to_string(), Vec::new()),
+                    depth: 1,
+                })
             }
+        });
+    }
 
-            // Recurse into children
-            match comp {
-                CanvasComponent::Container(container) => {
-                    if Self::add_child_recursive(
-                        &mut container.children[..],
-                        parent_id,
-                        child.clone(),
-                    ) {
-                        return true;
-                    }
+    /// Close the innermost open transaction. Closing the outermost records
+    /// everything done since it began as one history entry.
+    pub fn commit_transaction(&self) {
+        let mut finished = None;
+        self.transaction.update_value(|open| {
+            if let Some(transaction) = open {
+                transaction.depth -= 1;
+                if transaction.depth == 0 {
+                    finished = open.take();
                 }
-                CanvasComponent::Card(card) => {
-                    if Self::add_child_recursive(&mut card.children[..], parent_id, child.clone()) {
-                        return true;
-                    }
-                }
-                _ => {}
             }
+        });
+        if let Some(transaction) = finished {
+            self.record_transaction(transaction);
         }
-        false
     }
 
-    /// Remove a component by ID
-    pub fn remove_component(&self, id: &ComponentId) {
-        self.record_snapshot("Remove Component");
-        self.components.update(|components| {
-            Self::remove_recursive(components, id);
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.with_value(Option::is_some)
+    }
+
+    /// Undo everything done since the outermost open transaction began,
+    /// without recording it
+    pub fn abort_transaction(&self) {
+        let Some(transaction) = self.transaction.try_update_value(Option::take).flatten() else {
+            return;
+        };
+        self.update_tree(|tree| {
+            !transaction.entry.operations.is_empty() && transaction.entry.revert(tree)
         });
+        self.deselect_missing();
     }
 
-    fn remove_recursive(components: &mut Vec<CanvasComponent>, id: &ComponentId) {
-        components.retain(|c| c.id() != id);
-        for comp in components.iter_mut() {
-            match comp {
-                CanvasComponent::Container(container) => {
-                    Self::remove_recursive(&mut container.children, id);
-                }
-                CanvasComponent::Card(card) => {
-                    Self::remove_recursive(&mut card.children, id);
-                }
-                _ => {}
-            }
+    fn record_transaction(&self, transaction: Transaction) {
+        if !transaction.entry.operations.is_empty() {
+            self.history.update(|h| h.push(transaction.entry));
         }
     }
 
-    /// Get a component by ID
-    pub fn get_component(&self, id: &ComponentId) -> Option<CanvasComponent> {
-        self.components
-            .with(|components| Self::get_recursive(components, id))
+    /// Add a component to the canvas
+    pub fn add_component(&self, component: CanvasComponent) {
+        let component = self.without_taken_names(component);
+        let index = self.components.with_untracked(Vec::len);
+        self.perform(
+            "Add Component",
+            vec![Operation::Insert {
+                slot: Slot::root(index),
+                component,
+            }],
+        );
     }
 
-    fn get_recursive(components: &[CanvasComponent], id: &ComponentId) -> Option<CanvasComponent> {
-        for comp in components {
-            if comp.id() == id {
-                return Some(comp.clone());
-            }
-            match comp {
-                CanvasComponent::Container(container) => {
-                    if let Some(found) = Self::get_recursive(&container.children, id) {
-                        return Some(found);
-                    }
-                }
-                CanvasComponent::Card(card) => {
-                    if let Some(found) = Self::get_recursive(&card.children, id) {
-                        return Some(found);
-                    }
-                }
-                _ => {}
-            }
+    /// Add components to the end of the canvas as one action
+    pub fn add_components(&self, description: &str, components: Vec<CanvasComponent>) {
+        self.begin_transaction(description);
+        for component in components {
+            self.add_component(component);
         }
-        None
+        self.commit_transaction();
+    }
+
+    /// Add a child component to a specific parent. Returns true if successful.
+    pub fn add_child_component(&self, parent_id: &ComponentId, component: CanvasComponent) -> bool {
+        // The parent must exist, support children and not be locked
+        let Some(index) = self.with_index_untracked(|index, c| {
+            if locked_in(index, c, parent_id) {
+                return None;
+            }
+            index.get(c, parent_id).and_then(children_of).map(Vec::len)
+        }) else {
+            return false;
+        };
+        let component = self.without_taken_names(component);
+        self.perform(
+            "Add Child Component",
+            vec![Operation::Insert {
+                slot: Slot::child(*parent_id, index),
+                component,
+            }],
+        )
     }
 
-    /// Update a component
-    pub fn update_component(&self, id: &ComponentId, f: impl FnOnce(&mut CanvasComponent)) {
-        self.components.update(|components| {
-            Self::update_recursive(components, id, f);
+    /// Remove a component by ID
+    pub fn remove_component(&self, id: &ComponentId) {
+        let removal = self.with_index_untracked(|index, c| {
+            Some(Operation::Remove {
+                slot: index.slot(id)?,
+                component: index.get(c, id)?.clone(),
+            })
         });
+        if let Some(removal) = removal {
+            self.perform("Remove Component", vec![removal]);
+        }
     }
 
-    fn update_recursive(
-        components: &mut [CanvasComponent],
+    /// Get a component by ID
+    pub fn get_component(&self, id: &ComponentId) -> Option<CanvasComponent> {
+        self.with_index(|index, components| index.get(components, id).cloned())
+    }
+
+    /// The container holding a component, `None` at the root
+    pub fn parent_of(&self, id: &ComponentId) -> Option<ComponentId> {
+        self.with_index(|index, _| index.parent(id))
+    }
+
+    /// Change a component as one undoable action. Only the fields that
+    /// changed are recorded.
+    pub fn edit_component(
+        &self,
         id: &ComponentId,
+        description: &str,
         f: impl FnOnce(&mut CanvasComponent),
-    ) -> bool {
-        #[allow(clippy::collapsible_match)]
-        fn recurse(
-            components: &mut [CanvasComponent],
-            id: &ComponentId,
-            f: &mut Option<impl FnOnce(&mut CanvasComponent)>,
-        ) -> bool {
-            for comp in components.iter_mut() {
-                if comp.id() == id {
-                    if let Some(func) = f.take() {
-                        func(comp);
-                    }
-                    return true;
-                }
-
-                // Recurse into children
-                match comp {
-                    CanvasComponent::Container(c) => {
-                        if recurse(&mut c.children, id, f) {
-                            return true;
-                        }
-                    }
-                    CanvasComponent::Card(c) => {
-                        if recurse(&mut c.children, id, f) {
-                            return true;
-                        }
-                    }
-                    _ => {}
-                }
+    ) {
+        let Some(before) = self.with_index_untracked(|index, c| index.get(c, id).cloned()) else {
+            return;
+        };
+        let mut after = before.clone();
+        f(&mut after);
+        let operations = match Operation::set_fields(&before, &after) {
+            Some(operations) => operations,
+            // A different kind of component takes its place
+            None => {
+                let Some(slot) = self.index.with_value(|index| index.slot(id)) else {
+                    return;
+                };
+                vec![
+                    Operation::Remove {
+                        slot,
+                        component: before,
+                    },
+                    Operation::Insert {
+                        slot,
+                        component: after,
+                    },
+                ]
             }
-            false
-        }
-
-        let mut f_opt = Some(f);
-        recurse(components, id, &mut f_opt)
+        };
+        self.perform(description, operations);
     }
 
-    /// Update a component and record a snapshot
-    pub fn update_component_with_snapshot(
+    /// Replace a component as one undoable action
+    pub fn replace_component(
         &self,
         id: &ComponentId,
         new_component: CanvasComponent,
         description: &str,
     ) {
-        self.record_snapshot(description);
-        self.update_component(id, |c| *c = new_component);
+        self.edit_component(id, description, |c| *c = new_component);
     }
 
     /// Move a component up within its parent container
@@ -234,219 +289,614 @@ impl CanvasState {
         self.move_component(id, 1);
     }
 
-    fn move_component(&self, id: &ComponentId, offset: i32) {
-        let mut components = self.components.get();
-        if Self::move_recursive(&mut components, id, offset) {
-            self.record_snapshot(if offset < 0 {
+    fn move_component(&self, id: &ComponentId, offset: isize) {
+        let operation = self.index.with_value(|index| {
+            let from = index.slot(id)?;
+            let len = index.children(from.parent.as_ref())?.len();
+            let index = from.index.checked_add_signed(offset).filter(|i| *i < len)?;
+            Some(Operation::Move {
+                id: *id,
+                from,
+                to: Slot {
+                    parent: from.parent,
+                    index,
+                },
+            })
+        });
+        if let Some(operation) = operation {
+            let description = if offset < 0 {
                 "Move Component Up"
             } else {
                 "Move Component Down"
-            });
-            self.components.set(components);
-        }
-    }
-
-    fn move_recursive(components: &mut [CanvasComponent], id: &ComponentId, offset: i32) -> bool {
-        if let Some(index) = components.iter().position(|c| c.id() == id) {
-            let new_index = index as i32 + offset;
-            if new_index >= 0 && new_index < components.len() as i32 {
-                components.swap(index, new_index as usize);
-                return true;
-            }
-            return false;
-        }
-
-        for comp in components.iter_mut() {
-            if let CanvasComponent::Container(container) = comp {
-                if Self::move_recursive(&mut container.children, id, offset) {
-                    return true;
-                }
-            } else if let CanvasComponent::Card(card) = comp
-                && Self::move_recursive(&mut card.children, id, offset)
-            {
-                return true;
-            }
+            };
+            self.perform(description, vec![operation]);
         }
-        false
     }
 
     // --- New Move Logic for Tree View ---
 
+    /// Move a component right after `target_id`
     pub fn move_component_relative(&self, id: ComponentId, target_id: ComponentId) {
-        if id == target_id {
+        if id == target_id || self.would_nest_in_itself(&id, &target_id) {
             return;
         }
-
-        let mut components = self.components.get();
-
-        // Check for descendant move to prevent infinite recursion/data loss
-        // We use the cloned components for check as well
-        if Self::get_recursive(&components, &id)
-            .is_some_and(|parent| Self::is_descendant(&parent, &target_id))
-        {
-            web_sys::console::warn_1(&"Cannot move a component into its own descendant".into());
+        let target_parent = self.index.with_value(|index| index.parent(&target_id));
+        if self.move_is_locked(&id, target_parent.as_ref()) {
             return;
         }
 
-        if let Some(comp) = Self::extract_recursive(&mut components, &id) {
-            let mut comp_opt = Some(comp);
-            if Self::insert_after_recursive(&mut components, &target_id, &mut comp_opt) {
-                // Success: record snapshot of OLD state (current signal) then set new state
-                self.record_snapshot("Reorder Component");
-                self.components.set(components);
-            } else if let Some(_c) = comp_opt {
-                // Failed to insert: logic might suggest putting it back or logging warning.
-                // Since we operated on a clone, the signal is untouched. We don't need to restore 'c'.
-                // But we should probably warn.
-                web_sys::console::warn_1(
-                    &"Failed to move component to target (insert failed)".into(),
-                );
-                // No mutation happened, no snapshot needed.
+        let operation = self.index.with_value(|index| {
+            let from = index.slot(&id)?;
+            let target = index.slot(&target_id)?;
+            // Positions after the component is taken out shift down by one
+            let mut index = target.index + 1;
+            if target.parent == from.parent && from.index < target.index {
+                index -= 1;
             }
+            Some(Operation::Move {
+                id,
+                from,
+                to: Slot {
+                    parent: target.parent,
+                    index,
+                },
+            })
+        });
+        match operation {
+            Some(operation) => {
+                self.perform("Reorder Component", vec![operation]);
+            }
+            None => web_sys::console::warn_1(
+                &"Failed to move component to target (insert failed)".into(),
+            ),
         }
     }
 
     // New: Move component into a parent (for drag and drop)
     pub fn move_component_to_parent(&self, id: ComponentId, parent_id: ComponentId) {
-        if id == parent_id {
-            return;
-        }
-
-        let mut components = self.components.get();
-
-        if Self::get_recursive(&components, &id)
-            .is_some_and(|parent| Self::is_descendant(&parent, &parent_id))
+        if id == parent_id
+            || self.would_nest_in_itself(&id, &parent_id)
+            || self.move_is_locked(&id, Some(&parent_id))
         {
-            web_sys::console::warn_1(&"Cannot move a component into its own descendant".into());
             return;
         }
 
-        if let Some(comp) = Self::extract_recursive(&mut components, &id) {
-            if Self::add_child_recursive(&mut components, &parent_id, comp) {
-                self.record_snapshot("Move Component Into Parent");
-                self.components.set(components);
-            } else {
-                // Failed to add child (e.g. parent not found or not container)
-                // Since it's a clone, no harm done to original state.
-                web_sys::console::warn_1(&"Failed to move component into parent".into());
+        let operation = self.with_index_untracked(|index, c| {
+            let from = index.slot(&id)?;
+            // The parent must exist and support children
+            let mut index = children_of(index.get(c, &parent_id)?)?.len();
+            if from.parent == Some(parent_id) {
+                index -= 1;
+            }
+            Some(Operation::Move {
+                id,
+                from,
+                to: Slot::child(parent_id, index),
+            })
+        });
+        match operation {
+            Some(operation) => {
+                self.perform("Move Component Into Parent", vec![operation]);
             }
+            None => web_sys::console::warn_1(&"Failed to move component into parent".into()),
         }
     }
 
     // New: Move component to root
     pub fn move_component_to_root(&self, id: ComponentId) {
-        let mut components = self.components.get();
-
-        // Optimization: Check if already at root
-        if components.iter().any(|comp| *comp.id() == id) {
+        if self.move_is_locked(&id, None) {
             return;
         }
+        let operation = self.with_index_untracked(|index, c| {
+            let from = index.slot(&id)?;
+            // Already at root
+            from.parent?;
+            Some(Operation::Move {
+                id,
+                from,
+                to: Slot::root(c.len()),
+            })
+        });
+        if let Some(operation) = operation {
+            self.perform("Move Component to Root", vec![operation]);
+        }
+    }
 
-        #[allow(clippy::collapsible_if)]
-        if let Some(comp) = Self::extract_recursive(&mut components, &id) {
-            components.push(comp);
-            self.record_snapshot("Move Component to Root");
-            self.components.set(components);
+    /// Whether moving `id` under `target_id` would put it inside itself
+    fn would_nest_in_itself(&self, id: &ComponentId, target_id: &ComponentId) -> bool {
+        let nested = self
+            .index
+            .with_value(|index| index.is_ancestor(id, target_id));
+        if nested {
+            web_sys::console::warn_1(&"Cannot move a component into its own descendant".into());
         }
+        nested
     }
 
-    fn is_descendant(parent: &CanvasComponent, target_id: &ComponentId) -> bool {
-        match parent {
-            CanvasComponent::Container(c) => {
-                for child in &c.children {
-                    if child.id() == target_id {
-                        return true;
-                    }
-                    if Self::is_descendant(child, target_id) {
-                        return true;
-                    }
+    /// Undo the last action. Returns false if there was nothing to undo.
+    pub fn undo(&self) -> bool {
+        self.step_history(|history, components| history.undo(components).is_some())
+    }
+
+    /// Redo the last undone action. Returns false if there was nothing to redo.
+    pub fn redo(&self) -> bool {
+        self.step_history(|history, components| history.redo(components).is_some())
+    }
+
+    /// Go to the state right after history entry `node`, on any branch,
+    /// or the initial state for `None`
+    pub fn jump_to(&self, node: Option<NodeId>) -> bool {
+        self.step_history(|history, components| history.jump_to(node, components))
+    }
+
+    fn step_history(&self, f: impl FnOnce(&mut History, &mut IndexedTree) -> bool) -> bool {
+        // Whatever an open transaction did so far becomes the latest entry
+        if let Some(transaction) = self.transaction.try_update_value(Option::take).flatten() {
+            self.record_transaction(transaction);
+        }
+        let mut changed = false;
+        self.update_tree(|tree| {
+            self.history.update(|history| changed = f(history, tree));
+            changed
+        });
+        self.deselect_missing();
+        changed
+    }
+
+    /// Deselect components that are no longer on the canvas
+    fn deselect_missing(&self) {
+        let missing = self.selection.with_untracked(|selection| {
+            self.index
+                .with_value(|index| selection.ids().iter().any(|id| !index.contains(id)))
+        });
+        if missing {
+            self.selection.update(|selection| {
+                self.index
+                    .with_value(|index| selection.retain(|id| index.contains(id)))
+            });
+        }
+    }
+
+    // --- Selection ---
+
+    /// The primary selection: the most recently selected component
+    pub fn selected(&self) -> Option<ComponentId> {
+        self.selection.with(Selection::primary)
+    }
+
+    /// Every selected component, in the order they were selected
+    pub fn selected_ids(&self) -> Vec<ComponentId> {
+        self.selection.with(|selection| selection.ids().to_vec())
+    }
+
+    pub fn is_selected(&self, id: &ComponentId) -> bool {
+        self.selection.with(|selection| selection.contains(id))
+    }
+
+    /// Select only `id`
+    pub fn select(&self, id: ComponentId) {
+        self.selection.set(Selection::only(id));
+    }
+
+    /// Change the selection for a click on `id`
+    pub fn click_select(&self, id: ComponentId, mode: SelectMode) {
+        let order = match mode {
+            SelectMode::Range => self.index.with_value(ComponentIndex::order),
+            _ => Vec::new(),
+        };
+        self.selection
+            .update(|selection| selection.click(id, mode, &order));
+    }
+
+    /// Select `ids`, or add them to the selection when `extend` is set
+    pub fn select_many(&self, ids: Vec<ComponentId>, extend: bool) {
+        self.selection.update(|selection| {
+            let mut all = if extend {
+                selection.ids().to_vec()
+            } else {
+                Vec::new()
+            };
+            all.extend(ids);
+            *selection = Selection::from_ids(all);
+        });
+    }
+
+    /// Select every top-level component
+    pub fn select_all(&self) {
+        let roots = self
+            .index
+            .with_value(|index| index.children(None).map(<[_]>::to_vec))
+            .unwrap_or_default();
+        self.select_many(roots, false);
+    }
+
+    pub fn clear_selection(&self) {
+        self.selection.set(Selection::new());
+    }
+
+    /// The selected components not inside another selected one, in
+    /// document order
+    pub fn selected_roots(&self) -> Vec<ComponentId> {
+        self.selection
+            .with_untracked(|selection| self.index.with_value(|index| selection.roots(index)))
+    }
+
+    /// Copies of the selected components, outermost only, in document order
+    pub fn selected_components(&self) -> Vec<CanvasComponent> {
+        let roots = self.selected_roots();
+        self.with_index_untracked(|index, c| {
+            roots
+                .iter()
+                .filter_map(|id| index.get(c, id).cloned())
+                .collect()
+        })
+    }
+
+    /// Remove every selected component as one action, leaving locked ones
+    /// in place. Returns how many were removed.
+    pub fn remove_selected(&self) -> usize {
+        let mut roots = self.selected_roots();
+        roots.retain(|id| !self.with_index_untracked(|index, c| locked_in(index, c, id)));
+        if roots.is_empty() {
+            return 0;
+        }
+        self.begin_transaction(&describe("Delete", roots.len()));
+        for id in &roots {
+            self.remove_component(id);
+        }
+        self.commit_transaction();
+        self.clear_selection();
+        roots.len()
+    }
+
+    /// Put a copy of each selected component right after it and select the
+    /// copies. Returns how many were duplicated.
+    pub fn duplicate_selected(&self) -> usize {
+        let roots = self.selected_roots();
+        let mut copies = Vec::new();
+        self.begin_transaction(&describe("Duplicate", roots.len()));
+        for id in &roots {
+            let insertion = self.with_index_untracked(|index, c| {
+                let slot = index.slot(id)?;
+                let copy = index.get(c, id)?.duplicate_with_new_id();
+                Some(Operation::Insert {
+                    slot: Slot {
+                        parent: slot.parent,
+                        index: slot.index + 1,
+                    },
+                    component: copy,
+                })
+            });
+            if let Some(insertion) = insertion {
+                if let Operation::Insert { component, .. } = &insertion {
+                    copies.push(*component.id());
                 }
+                self.perform("Duplicate Component", vec![insertion]);
             }
-            CanvasComponent::Card(c) => {
-                for child in &c.children {
-                    if child.id() == target_id {
-                        return true;
-                    }
-                    if Self::is_descendant(child, target_id) {
-                        return true;
-                    }
+        }
+        self.commit_transaction();
+        let count = copies.len();
+        self.select_many(copies, false);
+        count
+    }
+
+    /// Move every selected component one place up (`offset` -1) or down
+    /// (1) among its siblings, as one action. Components already at the
+    /// edge stay, and so do selected neighbours queued up behind them.
+    pub fn move_selected(&self, offset: isize) {
+        let mut roots = self.selected_roots();
+        if offset > 0 {
+            roots.reverse();
+        }
+        let direction = if offset < 0 { "Up" } else { "Down" };
+        self.begin_transaction(&format!("{} {}", describe("Move", roots.len()), direction));
+        let mut stuck: Vec<ComponentId> = Vec::new();
+        for id in roots {
+            let operation = self.index.with_value(|index| {
+                let from = index.slot(&id)?;
+                let siblings = index.children(from.parent.as_ref())?;
+                let to = from
+                    .index
+                    .checked_add_signed(offset)
+                    .filter(|i| *i < siblings.len())?;
+                if stuck.contains(&siblings[to]) {
+                    return None;
                 }
+                Some(Operation::Move {
+                    id,
+                    from,
+                    to: Slot {
+                        parent: from.parent,
+                        index: to,
+                    },
+                })
+            });
+            match operation {
+                Some(operation) => {
+                    self.perform("Move Component", vec![operation]);
+                }
+                None => stuck.push(id),
             }
-            _ => {}
         }
-        false
+        self.commit_transaction();
     }
 
-    fn extract_recursive(
-        components: &mut Vec<CanvasComponent>,
-        id: &ComponentId,
-    ) -> Option<CanvasComponent> {
-        if let Some(pos) = components.iter().position(|c| c.id() == id) {
-            return Some(components.remove(pos));
-        }
-        for comp in components.iter_mut() {
-            match comp {
-                CanvasComponent::Container(c) => {
-                    if let Some(found) = Self::extract_recursive(&mut c.children, id) {
-                        return Some(found);
-                    }
-                }
-                CanvasComponent::Card(c) => {
-                    if let Some(found) = Self::extract_recursive(&mut c.children, id) {
-                        return Some(found);
-                    }
+    /// Whether the component or one of its ancestors is locked. Tracks the
+    /// components.
+    pub fn is_locked(&self, id: &ComponentId) -> bool {
+        self.with_index(|index, c| locked_in(index, c, id))
+    }
+
+    /// Whether `id` may not be moved under `parent`, because it or the
+    /// place it would go is locked
+    fn move_is_locked(&self, id: &ComponentId, parent: Option<&ComponentId>) -> bool {
+        let locked = self.with_index_untracked(|index, c| {
+            locked_in(index, c, id) || parent.is_some_and(|parent| locked_in(index, c, parent))
+        });
+        if locked {
+            web_sys::console::warn_1(&"Cannot move a locked component or into one".into());
+        }
+        locked
+    }
+
+    /// Lock or unlock a component as one undoable action
+    pub fn set_locked(&self, id: &ComponentId, locked: bool) {
+        let description = if locked {
+            "Lock Component"
+        } else {
+            "Unlock Component"
+        };
+        self.edit_component(id, description, |c| c.editor_mut().locked = locked);
+    }
+
+    /// Hide or show a component on the canvas as one undoable action
+    pub fn set_hidden(&self, id: &ComponentId, hidden: bool) {
+        let description = if hidden {
+            "Hide Component"
+        } else {
+            "Show Component"
+        };
+        self.edit_component(id, description, |c| c.editor_mut().hidden = hidden);
+    }
+
+    /// Name a component, or clear its name with an empty one. A name must
+    /// be an identifier that no other component on the canvas has, even
+    /// spelled in another case or style: `submitButton` and `submit_button`
+    /// are the same name in generated code.
+    pub fn set_name(&self, id: &ComponentId, name: &str) -> Result<(), ValidationError> {
+        let name = name.trim();
+        if name.is_empty() {
+            self.edit_component(id, "Clear Component Name", |c| c.set_name(None));
+            return Ok(());
+        }
+        ComponentNameValidator.validate(&name.to_string())?;
+        let taken = self
+            .with_index_untracked(|_, c| find_named(c, name).is_some_and(|other| other.id() != id));
+        if taken {
+            return Err(ValidationError::DuplicateName(name.to_string()));
+        }
+        self.edit_component(id, "Rename Component", |c| {
+            c.set_name(Some(name.to_string()))
+        });
+        Ok(())
+    }
+
+    /// `component` with the names the canvas already uses cleared, as when
+    /// a template is applied twice, and with only the first of names that
+    /// clash inside it kept
+    fn without_taken_names(&self, mut component: CanvasComponent) -> CanvasComponent {
+        fn clear(
+            component: &mut CanvasComponent,
+            existing: &[CanvasComponent],
+            kept: &mut HashSet<String>,
+        ) {
+            if let Some(name) = component.name() {
+                let key = name_key(name);
+                if find_named(existing, name).is_some() || !kept.insert(key) {
+                    component.set_name(None);
                 }
-                _ => {}
+            }
+            for child in children_of_mut(component).into_iter().flatten() {
+                clear(child, existing, kept);
             }
         }
-        None
+        self.components
+            .with_untracked(|existing| clear(&mut component, existing, &mut HashSet::new()));
+        component
     }
 
-    #[allow(clippy::collapsible_if, clippy::collapsible_match)]
-    fn insert_after_recursive(
-        components: &mut Vec<CanvasComponent>,
-        target_id: &ComponentId,
-        item: &mut Option<CanvasComponent>,
-    ) -> bool {
-        if let Some(pos) = components.iter().position(|c| c.id() == target_id) {
-            if let Some(i) = item.take() {
-                components.insert(pos + 1, i);
-                return true;
+    /// Wrap the selected components in a new container or card, which takes
+    /// the place of the first of them and becomes the selection. They must
+    /// share a parent and none of them may be locked. Returns the new group.
+    pub fn group_selected(&self, kind: GroupKind) -> Option<ComponentId> {
+        let roots = self.selected_roots();
+        let (operations, group) = self.with_index_untracked(|index, c| {
+            if roots.iter().any(|id| locked_in(index, c, id)) {
+                return None;
+            }
+            let slots: Vec<Slot> = roots
+                .iter()
+                .map(|id| index.slot(id))
+                .collect::<Option<_>>()?;
+            let first = *slots.first()?;
+            if slots.iter().any(|slot| slot.parent != first.parent) {
+                return None;
             }
+            let members: Vec<CanvasComponent> = roots
+                .iter()
+                .map(|id| index.get(c, id).cloned())
+                .collect::<Option<_>>()?;
+            let group = kind.wrap(members.clone());
+            let group_id = *group.id();
+            // Take the members out from the back so earlier positions hold
+            let mut operations: Vec<Operation> = slots
+                .into_iter()
+                .zip(members)
+                .rev()
+                .map(|(slot, component)| Operation::Remove { slot, component })
+                .collect();
+            operations.push(Operation::Insert {
+                slot: first,
+                component: group,
+            });
+            Some((operations, group_id))
+        })?;
+        if !self.perform(&format!("Group into {}", kind.label()), operations) {
+            return None;
         }
+        self.select(group);
+        Some(group)
+    }
 
-        for comp in components.iter_mut() {
-            match comp {
-                CanvasComponent::Container(c) => {
-                    if Self::insert_after_recursive(&mut c.children, target_id, item) {
-                        return true;
-                    }
+    /// Dissolve every selected container or card, putting its children where
+    /// it was, in order, and selecting them. With `keep_spacing` the group's
+    /// padding and gap become margins on the children. Returns how many
+    /// groups were dissolved.
+    pub fn ungroup_selected(&self, keep_spacing: bool) -> usize {
+        let roots = self.selected_roots();
+        let mut freed = Vec::new();
+        let mut count = 0;
+        self.begin_transaction("Ungroup");
+        for id in &roots {
+            let operations = self.with_index_untracked(|index, c| {
+                let slot = index.slot(id)?;
+                let group = index.get(c, id)?.clone();
+                let mut children = children_of(&group)?.clone();
+                if keep_spacing {
+                    carry_spacing(&group, &mut children);
                 }
-                CanvasComponent::Card(c) => {
-                    if Self::insert_after_recursive(&mut c.children, target_id, item) {
-                        return true;
+                let mut operations = vec![Operation::Remove {
+                    slot,
+                    component: group,
+                }];
+                operations.extend(children.into_iter().enumerate().map(|(i, child)| {
+                    Operation::Insert {
+                        slot: Slot {
+                            parent: slot.parent,
+                            index: slot.index + i,
+                        },
+                        component: child,
                     }
-                }
-                _ => {}
+                }));
+                Some(operations)
+            });
+            let Some(operations) = operations else {
+                continue;
+            };
+            let children: Vec<ComponentId> = operations
+                .iter()
+                .filter_map(|operation| match operation {
+                    Operation::Insert { component, .. } => Some(*component.id()),
+                    _ => None,
+                })
+                .collect();
+            if self.perform("Ungroup", operations) {
+                freed.extend(children);
+                count += 1;
             }
         }
-        false
+        self.commit_transaction();
+        if count > 0 {
+            self.select_many(freed, false);
+        }
+        count
     }
 
-    /// Record a snapshot for undo/redo
-    pub fn record_snapshot(&self, description: &str) {
-        let snapshot = Snapshot::new(
-            self.components.get(),
-            self.selected.get(),
-            description.to_string(),
-        );
-        self.history.update(|h| h.push(snapshot));
+    /// Change several components as one undoable action, recording only the
+    /// fields that changed
+    pub fn edit_components(
+        &self,
+        ids: &[ComponentId],
+        description: &str,
+        f: impl Fn(&mut CanvasComponent),
+    ) {
+        let operations = self.with_index_untracked(|index, c| {
+            ids.iter()
+                .filter_map(|id| {
+                    let before = index.get(c, id)?;
+                    let mut after = before.clone();
+                    f(&mut after);
+                    Operation::set_fields(before, &after)
+                })
+                .flatten()
+                .collect()
+        });
+        self.perform(description, operations);
+    }
+
+    /// Every match of `matcher` in `scope`, in document order
+    pub fn find(&self, matcher: &Matcher, scope: SearchScope) -> Vec<SearchMatch> {
+        let roots = match scope {
+            SearchScope::Project => None,
+            SearchScope::Selection => Some(
+                self.selection
+                    .with(|selection| self.index.with_value(|index| selection.roots(index))),
+            ),
+        };
+        self.with_index(|index, c| match roots {
+            None => search(c, matcher),
+            Some(roots) => search(roots.iter().filter_map(|id| index.get(c, id)), matcher),
+        })
+    }
+
+    /// Replace one match, unless its field has changed since the search
+    pub fn replace_match(&self, found: &SearchMatch, matcher: &Matcher, replacement: &str) -> bool {
+        let operations = self.with_index_untracked(|index, c| {
+            let before = index.get(c, &found.id)?;
+            let mut after = before.clone();
+            let text =
+                text_field_mut(&mut after, &found.field).filter(|text| **text == found.text)?;
+            *text = found.replaced(matcher, replacement);
+            Operation::set_fields(before, &after)
+        });
+        operations.is_some_and(|operations| self.perform("Replace Text", operations))
     }
 
-    /// Apply a snapshot to the canvas
-    pub fn apply_snapshot(&self, snapshot: &Snapshot) {
-        self.components.set(snapshot.components.clone());
-        self.selected.set(snapshot.selected);
+    /// Replace every match in `scope` as one undo step. Returns how many
+    /// matches were replaced.
+    pub fn replace_all(&self, matcher: &Matcher, replacement: &str, scope: SearchScope) -> usize {
+        let found = untrack(|| self.find(matcher, scope));
+        // The matches of a component are next to each other
+        let mut ids: Vec<ComponentId> = found.iter().map(|m| m.id).collect();
+        ids.dedup();
+        let operations = self.with_index_untracked(|index, c| {
+            ids.iter()
+                .filter_map(|id| {
+                    let before = index.get(c, id)?;
+                    let mut after = before.clone();
+                    for (field, _) in text_fields(before) {
+                        if let Some(text) = text_field_mut(&mut after, &field) {
+                            *text = matcher.replace_all(text, replacement);
+                        }
+                    }
+                    Operation::set_fields(before, &after)
+                })
+                .flatten()
+                .collect()
+        });
+        if self.perform("Replace All", operations) {
+            found.len()
+        } else {
+            0
+        }
+    }
+}
+
+/// Whether the component or one of its ancestors is locked
+fn locked_in(index: &ComponentIndex, components: &[CanvasComponent], id: &ComponentId) -> bool {
+    std::iter::once(*id).chain(index.ancestors(id)).any(|id| {
+        index
+            .get(components, &id)
+            .is_some_and(|c| c.editor().locked)
+    })
+}
+
+/// "Delete Component", or "Delete 3 Components" for several
+fn describe(action: &str, count: usize) -> String {
+    if count == 1 {
+        format!("{} Component", action)
+    } else {
+        format!("{} {} Components", action, count)
     }
 }
 
@@ -534,6 +984,7 @@ pub struct UiState {
     pub show_export_modal: RwSignal<bool>,
     pub show_settings_modal: RwSignal<bool>,
     pub show_shortcuts_modal: RwSignal<bool>,
+    pub show_find_replace: RwSignal<bool>,
     pub show_git_panel: RwSignal<bool>,
     pub show_debug_panel: RwSignal<bool>,
     pub preview_mode: RwSignal<bool>,
@@ -554,6 +1005,7 @@ impl UiState {
             show_export_modal: RwSignal::new(false),
             show_settings_modal: RwSignal::new(false),
             show_shortcuts_modal: RwSignal::new(false),
+            show_find_replace: RwSignal::new(false),
             show_git_panel: RwSignal::new(false),
             show_debug_panel: RwSignal::new(false),
             preview_mode: RwSignal::new(false),
@@ -641,6 +1093,9 @@ pub struct SettingsState {
     pub theme: Theme,
     pub auto_save: bool,
     pub export_preset: ExportPreset,
+    /// Leave components hidden in the editor out of exports
+    #[serde(default)]
+    pub exclude_hidden_from_export: bool,
 }
 
 impl SettingsState {
@@ -649,6 +1104,7 @@ impl SettingsState {
             theme: Theme::default(),
             auto_save: true,
             export_preset: ExportPreset::default(),
+            exclude_hidden_from_export: false,
         }
     }
 }
@@ -659,13 +1115,23 @@ impl Default for SettingsState {
     }
 }
 
+/// Key of the settings in the app object store
+const SETTINGS_KEY: &str = "settings";
+
 impl Persistable for SettingsState {
-    fn storage_key() -> &'static str {
-        "leptos_studio_settings"
+    fn object_store() -> ObjectStore {
+        ObjectStore::App
+    }
+
+    fn legacy_key(key: &str) -> Option<&'static str> {
+        (key == SETTINGS_KEY).then_some("leptos_studio_settings")
     }
 }
 
-/// Persistable canvas data (for LocalStorage)
+/// Key of the legacy canvas in the app object store
+const CANVAS_KEY: &str = "canvas";
+
+/// Persistable canvas data (from before projects lived on the backend)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct CanvasData {
     components: Vec<CanvasComponent>,
@@ -675,8 +1141,12 @@ struct CanvasData {
 }
 
 impl Persistable for CanvasData {
-    fn storage_key() -> &'static str {
-        "leptos_studio_canvas"
+    fn object_store() -> ObjectStore {
+        ObjectStore::App
+    }
+
+    fn legacy_key(key: &str) -> Option<&'static str> {
+        (key == CANVAS_KEY).then_some("leptos_studio_canvas")
     }
 }
 
@@ -687,26 +1157,29 @@ use crate::services::project_manager::ProjectManager;
 pub struct AppState {
     pub canvas: CanvasState,
     pub ui: UiState,
+    pub sync: SyncState,
     pub settings: RwSignal<SettingsState>,
     pub project_name: RwSignal<String>,
     pub current_project_id: RwSignal<Option<String>>,
     pub variables: RwSignal<Vec<Variable>>,
     pub last_modified: RwSignal<f64>,
+    /// Unsaved edits found for the open project, waiting for the user
+    pub recovery: RwSignal<Option<RecoveryOffer>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        // Try to load settings from LocalStorage
-        let settings = SettingsState::load_or_default();
-
+        let ui = UiState::new();
         let state = Self {
             canvas: CanvasState::new(),
-            ui: UiState::new(),
-            settings: RwSignal::new(settings),
+            ui,
+            sync: SyncState::new(ui),
+            settings: RwSignal::new(SettingsState::default()),
             project_name: RwSignal::new("Untitled Project".to_string()),
             current_project_id: RwSignal::new(None),
             variables: RwSignal::new(Vec::new()),
             last_modified: RwSignal::new(js_sys::Date::now()),
+            recovery: RwSignal::new(None),
         };
 
         // Setup reactivity for last_modified
@@ -717,12 +1190,27 @@ impl AppState {
             last_modified.set(js_sys::Date::now());
         });
 
-        // Attempt to load the most recent project or legacy data
-        state.initialize_project_state();
-
         // Setup auto-save listener
         state.setup_auto_save();
 
+        // Keep a crash recovery journal of the open project
+        state.setup_recovery_journal();
+        state.setup_history_persistence();
+
+        leptos::task::spawn_local(async move {
+            if let Ok(Some(settings)) = SettingsState::load(SETTINGS_KEY).await {
+                state.settings.set(settings);
+            }
+            // Queued writes must be known before a project is opened
+            state.sync.restore().await;
+
+            // Migrate a canvas saved before projects existed
+            state.initialize_project_state().await;
+
+            // Replay saves and commits queued while offline
+            state.sync.start();
+        });
+
         state
     }
 
@@ -760,56 +1248,121 @@ impl AppState {
         });
     }
 
-    fn initialize_project_state(&self) {
+    fn setup_recovery_journal(&self) {
         let state = *self;
-        leptos::task::spawn_local(async move {
-            // 1. Check legacy LocalStorage first to ensure migration happens even if backend has projects
-            if let Ok(legacy) = CanvasData::load() {
-                // If we have legacy data, load it as "Recovered Legacy Project"
-                state.canvas.components.set(legacy.components);
-                state.canvas.selected.set(legacy.selected);
-                state.variables.set(legacy.variables);
-                state
-                    .project_name
-                    .set("Recovered Legacy Project".to_string());
-
-                // Manually save to handle success/failure explicitly
-                let project = state.to_project();
-                let id = ProjectManager::generate_id();
-                state.current_project_id.set(Some(id.clone()));
-
-                let ui = state.ui;
-                match ProjectManager::save_project(&id, &project).await {
-                    Ok(_) => {
-                        // Only clear legacy storage if save succeeds to prevent data loss
-                        if let Ok(Some(storage)) = window().local_storage() {
-                            let _ = storage.remove_item(CanvasData::storage_key());
-                        }
-                        ui.notify(Notification::success(
-                            "Legacy project migrated to backend".to_string(),
-                        ));
-                    }
-                    Err(e) => {
-                        ui.notify(Notification::error(format!(
-                            "Migration failed: {}. Legacy data preserved locally.",
-                            e.user_message()
-                        )));
-                    }
+        let debounce_token = StoredValue::new(0usize);
+
+        Effect::new(move |_| {
+            state.last_modified.track();
+            state.project_name.track();
+            state.variables.track();
+            state.ui.design_tokens.track();
+            state.canvas.history.track();
+
+            debounce_token.update_value(|t| *t = t.wrapping_add(1));
+            let current_token = debounce_token.get_value();
+            leptos::task::spawn_local(async move {
+                gloo_timers::future::TimeoutFuture::new(RECOVERY_JOURNAL_DEBOUNCE_MS).await;
+                if debounce_token.get_value() == current_token {
+                    state.write_journal().await;
                 }
-                return;
-            }
+            });
+        });
+    }
+
+    async fn write_journal(self) {
+        let Some(id) = self.current_project_id.get_untracked() else {
+            return;
+        };
+        // The journal on offer must survive until the user decides
+        let offered = self
+            .recovery
+            .with_untracked(|offer| offer.as_ref().is_some_and(|o| o.project_id == id));
+        if offered {
+            return;
+        }
+        let journal = RecoveryJournal {
+            project: self.to_project(),
+            history: self.canvas.history.get_untracked(),
+            written_at: js_sys::Date::now(),
+        };
+        if let Err(e) = journal.save(&id).await {
+            web_sys::console::error_1(&format!("Failed to write recovery journal: {}", e).into());
+        }
+    }
 
-            // 2. If no legacy data, check backend projects
-            if let Ok(projects) = ProjectManager::list_projects().await
-                && let Some(latest) = projects.first()
-            {
-                // Load the latest project
-                if let Ok(project) = ProjectManager::load_project(&latest.id).await {
-                    state.apply_project(project);
-                    state.current_project_id.set(Some(latest.id.clone()));
+    fn setup_history_persistence(&self) {
+        let state = *self;
+        let debounce_token = StoredValue::new(0usize);
+
+        Effect::new(move |_| {
+            state.canvas.history.track();
+
+            debounce_token.update_value(|t| *t = t.wrapping_add(1));
+            let current_token = debounce_token.get_value();
+            leptos::task::spawn_local(async move {
+                gloo_timers::future::TimeoutFuture::new(HISTORY_PERSIST_DEBOUNCE_MS).await;
+                if debounce_token.get_value() == current_token {
+                    state.write_history().await;
                 }
-            }
+            });
+        });
+    }
+
+    /// Store the undo history of the open project for the next visit
+    async fn write_history(self) {
+        let Some(id) = self.current_project_id.get_untracked() else {
+            return;
+        };
+        let stored = self.canvas.history.with_untracked(|history| {
+            self.canvas
+                .components
+                .with_untracked(|components| StoredHistory::new(history, components))
         });
+        if let Err(e) = match stored {
+            Ok(stored) => stored.save(&id).await,
+            Err(e) => Err(e),
+        } {
+            web_sys::console::error_1(&format!("Failed to store undo history: {}", e).into());
+        }
+    }
+
+    async fn initialize_project_state(self) {
+        // Check the legacy canvas to ensure migration happens even if backend has projects
+        if let Ok(Some(legacy)) = CanvasData::load(CANVAS_KEY).await {
+            // If we have legacy data, load it as "Recovered Legacy Project"
+            self.canvas.set_components(legacy.components);
+            self.canvas
+                .selection
+                .set(legacy.selected.map(Selection::only).unwrap_or_default());
+            self.variables.set(legacy.variables);
+            self.project_name
+                .set("Recovered Legacy Project".to_string());
+
+            // Manually save to handle success/failure explicitly
+            let project = self.to_project();
+            let id = ProjectManager::generate_id();
+            self.current_project_id.set(Some(id.clone()));
+
+            let ui = self.ui;
+            match ProjectManager::save_project(&id, &project).await {
+                Ok(_) => {
+                    // Only clear legacy storage if save succeeds to prevent data loss
+                    let _ = CanvasData::delete(CANVAS_KEY).await;
+                    ui.notify(Notification::success(
+                        "Legacy project migrated to backend".to_string(),
+                    ));
+                }
+                Err(e) => {
+                    ui.notify(Notification::error(format!(
+                        "Migration failed: {}. Legacy data preserved locally.",
+                        e.user_message()
+                    )));
+                }
+            }
+        }
+        // Otherwise nothing is opened here: the editor route loads its
+        // project through `load_project`, with its history and journal
     }
 
     /// Provide AppState as context
@@ -828,14 +1381,20 @@ impl AppState {
         use_context::<Self>()
     }
 
-    /// Save settings to LocalStorage
+    /// Save settings to IndexedDB
     pub fn save_settings(&self) {
-        if let Err(e) = self.settings.get().save() {
-            web_sys::console::error_1(&format!("Failed to save settings: {}", e).into());
-        }
+        let settings = self.settings.get();
+        leptos::task::spawn_local(async move {
+            if let Err(e) = settings.save(SETTINGS_KEY).await {
+                web_sys::console::error_1(&format!("Failed to save settings: {}", e).into());
+            }
+        });
     }
 
-    /// Save project to Backend (creates new if no ID)
+    /// Save project to Backend (creates new if no ID).
+    ///
+    /// The save is queued on this device first, so it survives the backend
+    /// being unreachable and is uploaded once it is back.
     pub fn save(&self) {
         let project = self.to_project();
         // Optimistically set ID if None to prevent duplicate saves (race condition)
@@ -846,18 +1405,31 @@ impl AppState {
         });
 
         let ui = self.ui;
-        // We capture id by value for the async block
+        let sync = self.sync;
+        let was_offline = sync.status.get_untracked() == SyncStatus::Offline;
+        sync.queue_save(&id, project);
 
         leptos::task::spawn_local(async move {
-            match ProjectManager::save_project(&id, &project).await {
-                Ok(_) => {
+            match sync.flush().await {
+                FlushOutcome::Synced => {
                     ui.notify(Notification::success(
                         "Project saved successfully".to_string(),
                     ));
                 }
-                Err(e) => {
-                    ui.notify(Notification::error(e.user_message()));
+                // Auto-save runs every few seconds; only say so once
+                FlushOutcome::Offline if !was_offline => {
+                    ui.notify(Notification::warning(
+                        "Backend unreachable. Changes are kept on this device and will sync when it is back."
+                            .to_string(),
+                    ));
                 }
+                FlushOutcome::Conflict => {
+                    ui.notify(Notification::warning(
+                        "This project was changed elsewhere. Resolve the conflict from the status bar."
+                            .to_string(),
+                    ));
+                }
+                FlushOutcome::Offline | FlushOutcome::Failed | FlushOutcome::Busy => {}
             }
         });
     }
@@ -868,34 +1440,122 @@ impl AppState {
         let state = *self;
 
         leptos::task::spawn_local(async move {
-            match ProjectManager::load_project(&id).await {
-                Ok(project) => {
-                    state.apply_project(project);
-                    state.current_project_id.set(Some(id));
+            // Read before the project is shown, which starts overwriting them
+            let journal = RecoveryJournal::load(&id).await.ok().flatten();
+            let history = StoredHistory::load(&id).await.ok().flatten();
+            let result = ProjectManager::load_stored_project(&id).await;
+            // Local changes not uploaded yet are newer than any server copy
+            let local = state
+                .sync
+                .has_pending_save(&id)
+                .then(|| state.sync.cached(&id))
+                .flatten();
+            match (result, local) {
+                (_, Some(cached)) => {
+                    let saved_at = cached.metadata.last_modified;
+                    state.open_project(id, cached.project, saved_at, journal, history);
+                    state.ui.notify(Notification::info(
+                        "Project loaded with changes not synced yet".to_string(),
+                    ));
+                    state.sync.flush().await;
+                }
+                (Ok(stored), None) => {
+                    state.sync.remember(&id, &stored);
+                    state.open_project(id, stored.project, stored.last_modified, journal, history);
                     state
                         .ui
                         .notify(Notification::success("Project loaded".to_string()));
                 }
-                Err(e) => {
+                (Err(AppError::Offline(_)), None) => match state.sync.cached(&id) {
+                    Some(cached) => {
+                        let saved_at = cached.metadata.last_modified;
+                        state.open_project(id, cached.project, saved_at, journal, history);
+                        state.ui.notify(Notification::warning(
+                            "Backend unreachable. Opened the copy saved on this device."
+                                .to_string(),
+                        ));
+                    }
+                    None => state.ui.notify(Notification::error(
+                        "Backend unreachable and this project is not saved on this device"
+                            .to_string(),
+                    )),
+                },
+                (Err(e), None) => {
                     state.ui.notify(Notification::error(e.user_message()));
                 }
             }
         });
     }
 
+    /// Show a loaded project with its stored undo history, offering its
+    /// recovery journal if it holds edits the project saved at `saved_at`
+    /// does not have
+    fn open_project(
+        &self,
+        id: String,
+        project: Project,
+        saved_at: f64,
+        journal: Option<RecoveryJournal>,
+        history: Option<StoredHistory>,
+    ) {
+        let history = history.and_then(|stored| stored.restore(&project.layout));
+        let offer = journal
+            .filter(|journal| journal.is_ahead_of(&project, saved_at))
+            .map(|journal| RecoveryOffer {
+                project_id: id.clone(),
+                summary: RecoverySummary::between(&project, &journal.project),
+                journal,
+            });
+        self.recovery.set(offer);
+        self.apply_project(project);
+        if let Some(history) = history {
+            self.canvas.history.set(history);
+        }
+        self.current_project_id.set(Some(id));
+    }
+
+    /// Bring back the unsaved edits on offer, undo history included
+    pub fn restore_journal(&self) {
+        let Some(offer) = self.recovery.get_untracked() else {
+            return;
+        };
+        self.recovery.set(None);
+        if self.current_project_id.get_untracked().as_deref() != Some(&offer.project_id) {
+            return;
+        }
+        self.apply_project(offer.journal.project);
+        self.canvas.history.set(offer.journal.history);
+        self.ui.notify(Notification::success(
+            "Unsaved changes restored".to_string(),
+        ));
+    }
+
+    /// Drop the unsaved edits on offer and keep the saved project
+    pub fn discard_journal(&self) {
+        let Some(offer) = self.recovery.get_untracked() else {
+            return;
+        };
+        self.recovery.set(None);
+        leptos::task::spawn_local(async move {
+            let _ = RecoveryJournal::delete(&offer.project_id).await;
+        });
+        self.ui
+            .notify(Notification::info("Unsaved changes discarded".to_string()));
+    }
+
     /// Create a new empty project
     pub fn create_new_project(&self) {
         self.project_name.set("Untitled Project".to_string());
-        self.canvas.components.set(Vec::new());
-        self.canvas.selected.set(None);
+        self.canvas.set_components(Vec::new());
+        self.canvas.clear_selection();
         self.canvas.history.update(|h| h.clear());
         self.variables.set(Vec::new());
         self.current_project_id.set(None);
         self.update_last_modified();
     }
 
-    /// Load canvas data from LocalStorage (Legacy / Import)
-    pub fn load(&self) -> Result<(), crate::domain::AppError> {
+    /// Load canvas data from IndexedDB (Legacy / Import)
+    pub async fn load(&self) -> Result<(), crate::domain::AppError> {
         // This is now an "Import from Legacy" or generic load.
         // For now, let's keep it wrapper for load_project if we had an active ID,
         // but if not, it tries legacy.
@@ -904,13 +1564,28 @@ impl AppState {
             return Ok(());
         }
 
-        let data = CanvasData::load()?;
-        self.canvas.components.set(data.components);
-        self.canvas.selected.set(data.selected);
+        let data = CanvasData::load(CANVAS_KEY)
+            .await?
+            .ok_or_else(|| AppError::Storage("No saved canvas found".to_string()))?;
+        self.canvas.set_components(data.components);
+        self.canvas
+            .selection
+            .set(data.selected.map(Selection::only).unwrap_or_default());
         self.variables.set(data.variables);
         Ok(())
     }
 
+    /// The components to export: the canvas, less hidden components if the
+    /// settings say so. Tracks both.
+    pub fn export_components(&self) -> Vec<CanvasComponent> {
+        let components = self.canvas.components.get();
+        if self.settings.with(|s| s.exclude_hidden_from_export) {
+            without_hidden(&components)
+        } else {
+            components
+        }
+    }
+
     /// Build a Project from current state
     pub fn to_project(&self) -> Project {
         Project::new(
@@ -925,8 +1600,8 @@ impl AppState {
     /// Apply a Project to the current state
     pub fn apply_project(&self, project: Project) {
         self.project_name.set(project.name);
-        self.canvas.components.set(project.layout);
-        self.canvas.selected.set(None);
+        self.canvas.set_components(project.layout);
+        self.canvas.clear_selection();
         self.canvas.history.update(|h| h.clear());
         self.settings.set(project.settings);
         self.ui.design_tokens.set(project.design_tokens);
@@ -944,3 +1619,237 @@ impl Default for AppState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ButtonComponent;
+    use crate::state::find_replace::SearchOptions;
+
+    fn canvas_with(labels: &[&str]) -> (CanvasState, Vec<ComponentId>) {
+        let canvas = CanvasState::new();
+        let components: Vec<CanvasComponent> = labels
+            .iter()
+            .map(|label| CanvasComponent::Button(ButtonComponent::new(label.to_string())))
+            .collect();
+        let ids = components.iter().map(|c| *c.id()).collect();
+        canvas.set_components(components);
+        (canvas, ids)
+    }
+
+    fn order(canvas: &CanvasState) -> Vec<ComponentId> {
+        canvas
+            .components
+            .with_untracked(|c| c.iter().map(|c| *c.id()).collect())
+    }
+
+    #[test]
+    fn test_move_selection_as_one_step() {
+        let (canvas, ids) = canvas_with(&["A", "B", "C", "D"]);
+        canvas.select_many(vec![ids[0], ids[1], ids[3]], false);
+
+        // A and B are at the top already; only D moves
+        canvas.move_selected(-1);
+        assert_eq!(order(&canvas), vec![ids[0], ids[1], ids[3], ids[2]]);
+        canvas.move_selected(1);
+        canvas.move_selected(1);
+        assert_eq!(order(&canvas), vec![ids[2], ids[0], ids[1], ids[3]]);
+
+        // The second move down was blocked and left no history entry
+        assert!(canvas.undo());
+        assert_eq!(order(&canvas), vec![ids[0], ids[1], ids[3], ids[2]]);
+    }
+
+    #[test]
+    fn test_duplicate_and_remove_selection() {
+        let (canvas, ids) = canvas_with(&["A", "B", "C"]);
+        canvas.select_many(vec![ids[2], ids[0]], false);
+        assert_eq!(canvas.duplicate_selected(), 2);
+        let after = order(&canvas);
+        assert_eq!(after.len(), 5);
+        assert_eq!((after[0], after[2], after[3]), (ids[0], ids[1], ids[2]));
+        // The copies are selected now
+        assert_eq!(canvas.selected_ids(), vec![after[1], after[4]]);
+
+        assert_eq!(canvas.remove_selected(), 2);
+        assert_eq!(order(&canvas), ids);
+        assert!(canvas.selected_ids().is_empty());
+        assert!(canvas.undo());
+        assert_eq!(order(&canvas), after);
+    }
+
+    #[test]
+    fn test_group_and_ungroup_in_place() {
+        let (canvas, ids) = canvas_with(&["A", "B", "C", "D"]);
+        canvas.select_many(vec![ids[2], ids[1]], false);
+        let group = canvas.group_selected(GroupKind::Card).unwrap();
+        assert_eq!(order(&canvas), vec![ids[0], group, ids[3]]);
+        assert_eq!(
+            canvas.with_index(|index, _| index.children(Some(&group)).map(<[_]>::to_vec)),
+            Some(vec![ids[1], ids[2]])
+        );
+        assert_eq!(canvas.selected_ids(), vec![group]);
+
+        assert_eq!(canvas.ungroup_selected(true), 1);
+        assert_eq!(order(&canvas), ids);
+        assert_eq!(canvas.selected_ids(), vec![ids[1], ids[2]]);
+        let margin = canvas
+            .get_component(&ids[1])
+            .and_then(|mut c| c.style_mut().margin.clone());
+        assert_eq!(margin, Some("16px 16px 0px 16px".to_string()));
+
+        // Each was one step
+        assert!(canvas.undo());
+        assert_eq!(order(&canvas), vec![ids[0], group, ids[3]]);
+        assert!(canvas.undo());
+        assert_eq!(order(&canvas), ids);
+
+        // Members of different parents cannot be grouped
+        canvas.select_many(vec![ids[0], ids[1]], false);
+        let outer = canvas.group_selected(GroupKind::Container).unwrap();
+        canvas.select_many(vec![outer, ids[2]], false);
+        assert!(canvas.group_selected(GroupKind::Container).is_some());
+        canvas.select_many(vec![ids[0], ids[3]], false);
+        assert!(canvas.group_selected(GroupKind::Container).is_none());
+    }
+
+    #[test]
+    fn test_lock_and_hide_flags() {
+        let (canvas, ids) = canvas_with(&["A", "B"]);
+        canvas.select_many(vec![ids[0]], false);
+        let group = canvas.group_selected(GroupKind::Container).unwrap();
+
+        // A lock covers everything inside
+        canvas.set_locked(&group, true);
+        assert!(canvas.is_locked(&ids[0]));
+        assert!(!canvas.is_locked(&ids[1]));
+        let button = CanvasComponent::Button(ButtonComponent::new("C".to_string()));
+        assert!(!canvas.add_child_component(&group, button));
+        assert!(canvas.undo());
+        assert!(!canvas.is_locked(&ids[0]));
+
+        // Bulk actions leave locked components alone
+        canvas.set_locked(&ids[1], true);
+        canvas.select_many(vec![ids[1]], false);
+        assert!(canvas.group_selected(GroupKind::Container).is_none());
+        canvas.select_many(vec![group, ids[1]], false);
+        assert_eq!(canvas.remove_selected(), 1);
+        assert_eq!(order(&canvas), vec![ids[1]]);
+        canvas.select_many(vec![ids[1]], false);
+        assert_eq!(canvas.remove_selected(), 0);
+        assert_eq!(order(&canvas), vec![ids[1]]);
+        assert!(canvas.undo());
+        assert!(canvas.undo());
+        assert_eq!(order(&canvas), vec![group, ids[1]]);
+
+        // Hidden components stay in the tree but can be left out of export
+        canvas.set_hidden(&ids[1], true);
+        assert_eq!(order(&canvas), vec![group, ids[1]]);
+        let visible = canvas.components.with_untracked(|c| without_hidden(c));
+        assert_eq!(
+            visible.iter().map(|c| *c.id()).collect::<Vec<_>>(),
+            vec![group]
+        );
+        let json = canvas
+            .components
+            .with_untracked(|c| serde_json::to_value(c).unwrap());
+        assert!(json[0]["Container"].get("editor").is_none());
+        assert_eq!(json[1]["Button"]["editor"]["hidden"], true);
+    }
+
+    #[test]
+    fn test_component_names_are_unique() {
+        let (canvas, ids) = canvas_with(&["A", "B"]);
+        assert!(canvas.set_name(&ids[0], "submit_button").is_ok());
+        assert!(matches!(
+            canvas.set_name(&ids[1], "submit_button"),
+            Err(ValidationError::DuplicateName(_))
+        ));
+        assert!(matches!(
+            canvas.set_name(&ids[1], "submit button"),
+            Err(ValidationError::InvalidName(_))
+        ));
+        // Renaming a component to its own name is not a clash
+        assert!(canvas.set_name(&ids[0], "submit_button").is_ok());
+
+        // Names differing only in case or style are the same name
+        for clash in [
+            "submitButton",
+            "SubmitButton",
+            "Submit_Button",
+            "SUBMIT_BUTTON",
+        ] {
+            assert!(matches!(
+                canvas.set_name(&ids[1], clash),
+                Err(ValidationError::DuplicateName(_))
+            ));
+        }
+        assert!(canvas.set_name(&ids[0], "submitButton").is_ok());
+        assert!(canvas.set_name(&ids[0], "submit_button").is_ok());
+
+        // A pasted copy of a named component must be renamed
+        let copy = canvas
+            .components
+            .with_untracked(|c| c[0].duplicate_with_new_id());
+        assert_eq!(copy.name(), None);
+        let mut template = ButtonComponent::new("C".to_string());
+        template.name = Some("submit_button".to_string());
+        canvas.add_component(CanvasComponent::Button(template));
+        canvas
+            .components
+            .with_untracked(|c| assert_eq!(c[2].name(), None));
+        let mut group = crate::domain::ContainerComponent::new();
+        for name in ["submitButton", "cancel_button", "CancelButton"] {
+            let mut member = ButtonComponent::new(name.to_string());
+            member.name = Some(name.to_string());
+            group.children.push(CanvasComponent::Button(member));
+        }
+        canvas.add_components("Paste", vec![CanvasComponent::Container(group)]);
+        let pasted = canvas.components.with_untracked(|c| c[3].clone());
+        let names: Vec<Option<&str>> = children_of(&pasted)
+            .unwrap()
+            .iter()
+            .map(CanvasComponent::name)
+            .collect();
+        assert_eq!(names, vec![None, Some("cancel_button"), None]);
+        assert!(canvas.undo());
+
+        assert!(canvas.set_name(&ids[0], " ").is_ok());
+        assert!(canvas.set_name(&ids[1], "submit_button").is_ok());
+        assert!(canvas.undo());
+        assert!(canvas.undo());
+        canvas
+            .components
+            .with_untracked(|c| assert_eq!(c[0].name(), Some("submit_button")));
+    }
+
+    #[test]
+    fn test_replace_all_is_one_step() {
+        let (canvas, ids) = canvas_with(&["Buy Acme", "Acme Acme", "Other"]);
+        let matcher = Matcher::new("acme", SearchOptions::default()).unwrap();
+        let found = canvas.find(&matcher, SearchScope::Project);
+        assert_eq!(found.len(), 3);
+
+        canvas.select(ids[0]);
+        assert_eq!(canvas.find(&matcher, SearchScope::Selection).len(), 1);
+
+        assert_eq!(
+            canvas.replace_all(&matcher, "Zenith", SearchScope::Project),
+            3
+        );
+        assert!(canvas.find(&matcher, SearchScope::Project).is_empty());
+        let label = |id| match canvas.get_component(id) {
+            Some(CanvasComponent::Button(button)) => button.label,
+            _ => String::new(),
+        };
+        assert_eq!(label(&ids[1]), "Zenith Zenith");
+
+        // A match found before the text changed is not replaced
+        assert!(!canvas.replace_match(&found[0], &matcher, "Other"));
+        assert!(canvas.undo());
+        assert_eq!(label(&ids[0]), "Buy Acme");
+        assert_eq!(label(&ids[1]), "Acme Acme");
+        assert!(canvas.replace_match(&found[2], &matcher, "Zenith"));
+        assert_eq!(label(&ids[1]), "Acme Zenith");
+    }
+}
@@ -0,0 +1,456 @@
+//! Component index
+//!
+//! Records where every component on the canvas sits: its parent, its
+//! position among its siblings and its children. Finding a component, its
+//! parent or its ancestors then costs as much as the tree is deep rather
+//! than as big as it is. Each operation that changes the tree updates the
+//! index in place instead of rebuilding it.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::history::{ComponentTree, Operation, Slot, children_of, children_of_mut, set_field};
+use crate::domain::{CanvasComponent, ComponentId};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+    slot: Slot,
+    children: Vec<ComponentId>,
+}
+
+/// Position of every component in a tree, by id
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ComponentIndex {
+    entries: HashMap<ComponentId, Entry>,
+    roots: Vec<ComponentId>,
+}
+
+impl ComponentIndex {
+    pub fn new(components: &[CanvasComponent]) -> Self {
+        let mut index = Self::default();
+        for (i, component) in components.iter().enumerate() {
+            index.register(component, Slot::root(i));
+            index.roots.push(*component.id());
+        }
+        index
+    }
+
+    /// Number of components in the tree
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, id: &ComponentId) -> bool {
+        self.entries.contains_key(id)
+    }
+
+    /// Where the component sits
+    pub fn slot(&self, id: &ComponentId) -> Option<Slot> {
+        self.entries.get(id).map(|entry| entry.slot)
+    }
+
+    /// The component's parent, `None` for top-level or unknown components
+    pub fn parent(&self, id: &ComponentId) -> Option<ComponentId> {
+        self.entries.get(id)?.slot.parent
+    }
+
+    /// Ids directly under `parent`, or the top level for `None`
+    pub fn children(&self, parent: Option<&ComponentId>) -> Option<&[ComponentId]> {
+        match parent {
+            None => Some(&self.roots),
+            Some(id) => self.entries.get(id).map(|entry| entry.children.as_slice()),
+        }
+    }
+
+    /// The component's ancestors, nearest first
+    pub fn ancestors(&self, id: &ComponentId) -> Vec<ComponentId> {
+        let mut ancestors = Vec::new();
+        let mut next = self.parent(id);
+        while let Some(parent) = next {
+            ancestors.push(parent);
+            next = self.parent(&parent);
+        }
+        ancestors
+    }
+
+    /// Whether `ancestor` contains `id`, at any depth
+    pub fn is_ancestor(&self, ancestor: &ComponentId, id: &ComponentId) -> bool {
+        let mut next = self.parent(id);
+        while let Some(parent) = next {
+            if parent == *ancestor {
+                return true;
+            }
+            next = self.parent(&parent);
+        }
+        false
+    }
+
+    /// Every id in document order: each component before its children,
+    /// and its children before its next sibling
+    pub fn order(&self) -> Vec<ComponentId> {
+        let mut order = Vec::with_capacity(self.entries.len());
+        let mut stack: Vec<ComponentId> = self.roots.iter().rev().copied().collect();
+        while let Some(id) = stack.pop() {
+            order.push(id);
+            if let Some(entry) = self.entries.get(&id) {
+                stack.extend(entry.children.iter().rev());
+            }
+        }
+        order
+    }
+
+    /// Positions leading from the top level down to the component
+    pub fn path(&self, id: &ComponentId) -> Option<Vec<Slot>> {
+        let mut path = vec![self.slot(id)?];
+        while let Some(parent) = path.last().and_then(|slot| slot.parent) {
+            path.push(self.slot(&parent)?);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The component in `components`, the tree this index describes
+    pub fn get<'a>(
+        &self,
+        components: &'a [CanvasComponent],
+        id: &ComponentId,
+    ) -> Option<&'a CanvasComponent> {
+        let mut list = components;
+        let mut found = None;
+        for slot in self.path(id)? {
+            let component = list.get(slot.index)?;
+            list = children_of(component).map_or(&[], Vec::as_slice);
+            found = Some(component);
+        }
+        found.filter(|component| component.id() == id)
+    }
+
+    pub fn get_mut<'a>(
+        &self,
+        components: &'a mut [CanvasComponent],
+        id: &ComponentId,
+    ) -> Option<&'a mut CanvasComponent> {
+        let path = self.path(id)?;
+        let (first, rest) = path.split_first()?;
+        let mut component = components.get_mut(first.index)?;
+        for slot in rest {
+            component = children_of_mut(component)?.get_mut(slot.index)?;
+        }
+        (component.id() == id).then_some(component)
+    }
+
+    /// The list `parent`'s children are kept in
+    fn list_mut<'a>(
+        &self,
+        components: &'a mut Vec<CanvasComponent>,
+        parent: Option<&ComponentId>,
+    ) -> Option<&'a mut Vec<CanvasComponent>> {
+        match parent {
+            None => Some(components),
+            Some(id) => self.get_mut(components, id).and_then(children_of_mut),
+        }
+    }
+
+    fn register(&mut self, component: &CanvasComponent, slot: Slot) {
+        let id = *component.id();
+        let children = children_of(component).map_or(&[][..], Vec::as_slice);
+        for (i, child) in children.iter().enumerate() {
+            self.register(child, Slot::child(id, i));
+        }
+        let children = children.iter().map(|child| *child.id()).collect();
+        self.entries.insert(id, Entry { slot, children });
+    }
+
+    fn unregister(&mut self, id: &ComponentId) {
+        if let Some(entry) = self.entries.remove(id) {
+            for child in &entry.children {
+                self.unregister(child);
+            }
+        }
+    }
+
+    fn siblings_mut(&mut self, parent: Option<&ComponentId>) -> Option<&mut Vec<ComponentId>> {
+        match parent {
+            None => Some(&mut self.roots),
+            Some(id) => self.entries.get_mut(id).map(|entry| &mut entry.children),
+        }
+    }
+
+    /// Bring the positions of `parent`'s children from `from` on up to date
+    fn renumber(&mut self, parent: Option<ComponentId>, from: usize) {
+        let Some(siblings) = self.children(parent.as_ref()) else {
+            return;
+        };
+        let moved: Vec<ComponentId> = siblings.iter().skip(from).copied().collect();
+        for (offset, id) in moved.iter().enumerate() {
+            if let Some(entry) = self.entries.get_mut(id) {
+                entry.slot = Slot {
+                    parent,
+                    index: from + offset,
+                };
+            }
+        }
+    }
+
+    /// Add `component` and its descendants, now at `slot`
+    fn insert(&mut self, slot: Slot, component: &CanvasComponent) {
+        self.register(component, slot);
+        if let Some(siblings) = self.siblings_mut(slot.parent.as_ref()) {
+            siblings.insert(slot.index.min(siblings.len()), *component.id());
+        }
+        self.renumber(slot.parent, slot.index);
+    }
+
+    /// Take out the component at `slot` and its descendants
+    fn remove(&mut self, slot: Slot) {
+        if let Some(siblings) = self.siblings_mut(slot.parent.as_ref())
+            && slot.index < siblings.len()
+        {
+            let id = siblings.remove(slot.index);
+            self.unregister(&id);
+        }
+        self.renumber(slot.parent, slot.index);
+    }
+
+    /// Take the component at `slot` out of its parent's children, keeping
+    /// its own entry and its descendants for [`Self::attach`]
+    fn detach(&mut self, slot: Slot) {
+        if let Some(siblings) = self.siblings_mut(slot.parent.as_ref())
+            && slot.index < siblings.len()
+        {
+            siblings.remove(slot.index);
+        }
+        self.renumber(slot.parent, slot.index);
+    }
+
+    /// Put a detached component back into the tree at `slot`
+    fn attach(&mut self, id: &ComponentId, slot: Slot) {
+        if let Some(siblings) = self.siblings_mut(slot.parent.as_ref()) {
+            siblings.insert(slot.index.min(siblings.len()), *id);
+        }
+        self.renumber(slot.parent, slot.index);
+    }
+}
+
+/// The top-level components together with their index, which every
+/// operation run on them keeps up to date
+pub struct IndexedTree<'a> {
+    pub components: &'a mut Vec<CanvasComponent>,
+    pub index: &'a mut ComponentIndex,
+}
+
+impl IndexedTree<'_> {
+    fn insert(&mut self, slot: Slot, component: &CanvasComponent) -> bool {
+        match self.index.list_mut(self.components, slot.parent.as_ref()) {
+            Some(list) if slot.index <= list.len() => {
+                list.insert(slot.index, component.clone());
+                self.index.insert(slot, component);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn remove(&mut self, slot: Slot, id: &ComponentId) -> bool {
+        match self.index.list_mut(self.components, slot.parent.as_ref()) {
+            Some(list) if list.get(slot.index).is_some_and(|c| c.id() == id) => {
+                list.remove(slot.index);
+                self.index.remove(slot);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn relocate(&mut self, id: &ComponentId, from: Slot, to: Slot) -> bool {
+        let Some(component) = self
+            .index
+            .list_mut(self.components, from.parent.as_ref())
+            .filter(|list| list.get(from.index).is_some_and(|c| c.id() == id))
+            .map(|list| list.remove(from.index))
+        else {
+            return false;
+        };
+        // Positions after the component are now one lower
+        self.index.detach(from);
+
+        let index = &*self.index;
+        let into_itself = to
+            .parent
+            .is_some_and(|parent| parent == *id || index.is_ancestor(id, &parent));
+        let list = if into_itself {
+            None
+        } else {
+            index.list_mut(self.components, to.parent.as_ref())
+        };
+        let (list, slot) = match list {
+            Some(list) if to.index <= list.len() => (list, to),
+            // Put it back where it was
+            _ => match index.list_mut(self.components, from.parent.as_ref()) {
+                Some(list) => (list, from),
+                None => return false,
+            },
+        };
+        list.insert(slot.index, component);
+        self.index.attach(id, slot);
+        slot == to
+    }
+
+    fn set_field(&mut self, id: &ComponentId, field: &str, value: Option<&Value>) -> bool {
+        let Some(component) = self.index.get_mut(self.components, id) else {
+            return false;
+        };
+        set_field(component, field, value)
+    }
+}
+
+impl ComponentTree for IndexedTree<'_> {
+    fn run(&mut self, operation: &Operation, reverse: bool) -> bool {
+        match (operation, reverse) {
+            (Operation::Insert { slot, component }, false)
+            | (Operation::Remove { slot, component }, true) => self.insert(*slot, component),
+            (Operation::Insert { slot, component }, true)
+            | (Operation::Remove { slot, component }, false) => self.remove(*slot, component.id()),
+            (Operation::Move { id, from, to }, false) => self.relocate(id, *from, *to),
+            (Operation::Move { id, from, to }, true) => self.relocate(id, *to, *from),
+            (
+                Operation::SetField {
+                    id,
+                    field,
+                    before,
+                    after,
+                },
+                reverse,
+            ) => {
+                let value = if reverse { before } else { after };
+                self.set_field(id, field, value.as_ref())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ButtonComponent, CardComponent, ContainerComponent, TextComponent};
+    use crate::state::history::{HistoryEntry, find, locate};
+
+    fn sample() -> (Vec<CanvasComponent>, ComponentId, ComponentId, ComponentId) {
+        let text = TextComponent::new("Nested".to_string());
+        let text_id = text.id;
+        let mut card = CardComponent::new();
+        let card_id = card.id;
+        card.children.push(CanvasComponent::Text(text));
+        let mut container = ContainerComponent::new();
+        let container_id = container.id;
+        container.children = vec![
+            CanvasComponent::Button(ButtonComponent::new("First".to_string())),
+            CanvasComponent::Card(card),
+        ];
+        let components = vec![
+            CanvasComponent::Button(ButtonComponent::new("Top".to_string())),
+            CanvasComponent::Container(container),
+        ];
+        (components, container_id, card_id, text_id)
+    }
+
+    #[test]
+    fn test_lookups_match_tree_walk() {
+        let (components, container_id, card_id, text_id) = sample();
+        let index = ComponentIndex::new(&components);
+
+        assert_eq!(index.len(), 5);
+        assert_eq!(index.slot(&text_id), locate(&components, &text_id));
+        assert_eq!(
+            index.get(&components, &text_id),
+            find(&components, &text_id)
+        );
+        assert_eq!(index.parent(&card_id), Some(container_id));
+        assert_eq!(index.ancestors(&text_id), vec![card_id, container_id]);
+        assert!(index.is_ancestor(&container_id, &text_id));
+        assert!(!index.is_ancestor(&text_id, &container_id));
+        assert_eq!(
+            index.path(&text_id),
+            Some(vec![
+                Slot::root(1),
+                Slot::child(container_id, 1),
+                Slot::child(card_id, 0)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_operations_keep_index_in_step() {
+        let (mut components, container_id, card_id, text_id) = sample();
+        let mut index = ComponentIndex::new(&components);
+        let top_id = *components[0].id();
+
+        let mut edited = find(&components, &card_id).unwrap().clone();
+        if let CanvasComponent::Card(card) = &mut edited {
+            card.children
+                .push(CanvasComponent::Button(ButtonComponent::new(
+                    "New".to_string(),
+                )));
+        }
+        let mut operations =
+            Operation::set_fields(find(&components, &card_id).unwrap(), &edited).unwrap();
+        operations.extend([
+            Operation::Move {
+                id: top_id,
+                from: Slot::root(0),
+                to: Slot::child(card_id, 1),
+            },
+            Operation::Insert {
+                slot: Slot::child(container_id, 0),
+                component: CanvasComponent::Text(TextComponent::new("Added".to_string())),
+            },
+            Operation::Move {
+                id: text_id,
+                from: Slot::child(card_id, 0),
+                to: Slot::root(0),
+            },
+        ]);
+        let entry = HistoryEntry::new("Edit".to_string(), operations);
+
+        let original = components.clone();
+        let mut tree = IndexedTree {
+            components: &mut components,
+            index: &mut index,
+        };
+        assert!(entry.apply(&mut tree));
+        assert_eq!(index, ComponentIndex::new(&components));
+        assert_eq!(index.parent(&top_id), Some(card_id));
+
+        let mut tree = IndexedTree {
+            components: &mut components,
+            index: &mut index,
+        };
+        assert!(entry.revert(&mut tree));
+        assert_eq!(components, original);
+        assert_eq!(index, ComponentIndex::new(&components));
+    }
+
+    #[test]
+    fn test_move_into_own_descendant_fails() {
+        let (mut components, container_id, card_id, _) = sample();
+        let mut index = ComponentIndex::new(&components);
+        let original = components.clone();
+        let mut tree = IndexedTree {
+            components: &mut components,
+            index: &mut index,
+        };
+        let operation = Operation::Move {
+            id: container_id,
+            from: Slot::root(1),
+            to: Slot::child(card_id, 0),
+        };
+        assert!(!tree.run(&operation, false));
+        assert_eq!(components, original);
+        assert_eq!(index, ComponentIndex::new(&components));
+    }
+}
@@ -6,7 +6,7 @@
 
 use leptos::prelude::*;
 
-use crate::domain::{CanvasComponent, ComponentId, ComponentType};
+use crate::domain::{CanvasComponent, ComponentType};
 use crate::state::AppState;
 
 /// Derived state computations for the canvas
@@ -64,8 +64,7 @@ impl DerivedState {
         let canvas = app_state.canvas;
 
         // Component count
-        let component_count =
-            Memo::new(move |_| count_components_recursive(&canvas.components.get()));
+        let component_count = Memo::new(move |_| canvas.with_index(|index, _| index.len()));
 
         // Type counts
         let type_counts = Memo::new(move |_| count_types_recursive(&canvas.components.get()));
@@ -74,15 +73,11 @@ impl DerivedState {
         let is_empty = Memo::new(move |_| canvas.components.get().is_empty());
 
         // Has selection
-        let has_selection = Memo::new(move |_| canvas.selected.get().is_some());
+        let has_selection = Memo::new(move |_| canvas.selection.with(|s| !s.is_empty()));
 
         // Selected component
-        let selected_component = Memo::new(move |_| {
-            canvas
-                .selected
-                .get()
-                .and_then(|id| find_component_by_id(&canvas.components.get(), &id))
-        });
+        let selected_component =
+            Memo::new(move |_| canvas.selected().and_then(|id| canvas.get_component(&id)));
 
         // Max nesting depth
         let max_nesting_depth =
@@ -121,23 +116,6 @@ impl DerivedState {
     }
 }
 
-/// Count all components recursively including nested containers
-fn count_components_recursive(components: &[CanvasComponent]) -> usize {
-    let mut count = components.len();
-    for comp in components {
-        match comp {
-            CanvasComponent::Container(container) => {
-                count += count_components_recursive(&container.children);
-            }
-            CanvasComponent::Card(card) => {
-                count += count_components_recursive(&card.children);
-            }
-            _ => {}
-        }
-    }
-    count
-}
-
 /// Count components by type recursively
 fn count_types_recursive(components: &[CanvasComponent]) -> TypeCounts {
     let mut counts = TypeCounts::default();
@@ -180,32 +158,6 @@ fn count_types_recursive(components: &[CanvasComponent]) -> TypeCounts {
     counts
 }
 
-/// Find a component by ID in the tree
-fn find_component_by_id(
-    components: &[CanvasComponent],
-    id: &ComponentId,
-) -> Option<CanvasComponent> {
-    for comp in components {
-        if comp.id() == id {
-            return Some(comp.clone());
-        }
-        match comp {
-            CanvasComponent::Container(container) => {
-                if let Some(found) = find_component_by_id(&container.children, id) {
-                    return Some(found);
-                }
-            }
-            CanvasComponent::Card(card) => {
-                if let Some(found) = find_component_by_id(&card.children, id) {
-                    return Some(found);
-                }
-            }
-            _ => {}
-        }
-    }
-    None
-}
-
 /// Calculate maximum nesting depth
 fn calculate_max_depth(components: &[CanvasComponent], current_depth: usize) -> usize {
     let mut max_depth = current_depth;
@@ -274,6 +226,7 @@ impl CanvasStats {
 mod tests {
     use super::*;
     use crate::domain::{ButtonComponent, ContainerComponent, TextComponent};
+    use crate::state::ComponentIndex;
 
     #[test]
     fn test_count_components() {
@@ -281,7 +234,7 @@ mod tests {
         let text = CanvasComponent::Text(TextComponent::new("Hello".to_string()));
         let components = vec![button, text];
 
-        assert_eq!(count_components_recursive(&components), 2);
+        assert_eq!(ComponentIndex::new(&components).len(), 2);
     }
 
     #[test]
@@ -294,7 +247,7 @@ mod tests {
         ];
 
         let components = vec![button, CanvasComponent::Container(container)];
-        assert_eq!(count_components_recursive(&components), 4);
+        assert_eq!(ComponentIndex::new(&components).len(), 4);
     }
 
     #[test]
@@ -332,7 +285,7 @@ mod tests {
         let component = CanvasComponent::Button(button);
         let components = vec![component.clone()];
 
-        let found = find_component_by_id(&components, &button_id);
-        assert!(found.is_some());
+        let found = ComponentIndex::new(&components).get(&components, &button_id);
+        assert_eq!(found, Some(&component));
     }
 }
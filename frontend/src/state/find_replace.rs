@@ -0,0 +1,345 @@
+//! Find and replace
+//!
+//! Searches the text a user typed into components: button labels, text
+//! content, placeholders, select options, image alt text, string props of
+//! custom components and event handler names. A query is compiled into a
+//! `Matcher` once; plain queries are escaped so that only regex mode treats
+//! them as patterns, and only regex mode expands `$1` in the replacement.
+
+use std::ops::Range;
+
+use regex::{NoExpand, Regex, RegexBuilder};
+
+use super::history::children_of;
+use crate::domain::{CanvasComponent, ComponentId, ComponentType, PropValue, ValidationError};
+
+/// How the query is matched
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool,
+}
+
+/// Which components are searched
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchScope {
+    /// Every component on the canvas
+    #[default]
+    Project,
+    /// The selected components and everything inside them
+    Selection,
+}
+
+/// A searchable text property of a component
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TextField {
+    Label,
+    Content,
+    Placeholder,
+    Options,
+    Alt,
+    /// A string prop of a custom component
+    Prop(String),
+    /// An event handler name, such as `on_click`
+    Handler(&'static str),
+}
+
+impl TextField {
+    pub fn label(&self) -> String {
+        match self {
+            TextField::Label => "Label".to_string(),
+            TextField::Content => "Content".to_string(),
+            TextField::Placeholder => "Placeholder".to_string(),
+            TextField::Options => "Options".to_string(),
+            TextField::Alt => "Alt text".to_string(),
+            TextField::Prop(name) => format!("Prop {}", name),
+            TextField::Handler(name) => (*name).to_string(),
+        }
+    }
+}
+
+/// The searchable fields of `component` and their text, in a stable order
+pub fn text_fields(component: &CanvasComponent) -> Vec<(TextField, &str)> {
+    fn handler<'a>(
+        fields: &mut Vec<(TextField, &'a str)>,
+        name: &'static str,
+        value: &'a Option<String>,
+    ) {
+        if let Some(value) = value {
+            fields.push((TextField::Handler(name), value));
+        }
+    }
+
+    let mut fields = Vec::new();
+    match component {
+        CanvasComponent::Button(c) => {
+            fields.push((TextField::Label, c.label.as_str()));
+            handler(&mut fields, "on_click", &c.on_click);
+        }
+        CanvasComponent::Text(c) => fields.push((TextField::Content, c.content.as_str())),
+        CanvasComponent::Input(c) => {
+            fields.push((TextField::Placeholder, c.placeholder.as_str()));
+            handler(&mut fields, "on_change", &c.on_change);
+            handler(&mut fields, "on_input", &c.on_input);
+        }
+        CanvasComponent::Select(c) => {
+            fields.push((TextField::Options, c.options.as_str()));
+            fields.push((TextField::Placeholder, c.placeholder.as_str()));
+            handler(&mut fields, "on_change", &c.on_change);
+        }
+        CanvasComponent::Image(c) => {
+            fields.push((TextField::Alt, c.alt.as_str()));
+            handler(&mut fields, "on_click", &c.on_click);
+        }
+        CanvasComponent::Container(c) => handler(&mut fields, "on_click", &c.on_click),
+        CanvasComponent::Card(c) => handler(&mut fields, "on_click", &c.on_click),
+        CanvasComponent::Custom(c) => {
+            let mut props: Vec<_> = c
+                .props
+                .iter()
+                .filter_map(|(name, value)| match value {
+                    PropValue::String(text) => Some((TextField::Prop(name.clone()), text.as_str())),
+                    _ => None,
+                })
+                .collect();
+            props.sort_by_key(|(field, _)| field.label());
+            fields.extend(props);
+        }
+    }
+    fields
+}
+
+/// The text of `field` on `component`, for writing
+pub fn text_field_mut<'a>(
+    component: &'a mut CanvasComponent,
+    field: &TextField,
+) -> Option<&'a mut String> {
+    match (component, field) {
+        (CanvasComponent::Button(c), TextField::Label) => Some(&mut c.label),
+        (CanvasComponent::Text(c), TextField::Content) => Some(&mut c.content),
+        (CanvasComponent::Input(c), TextField::Placeholder) => Some(&mut c.placeholder),
+        (CanvasComponent::Select(c), TextField::Placeholder) => Some(&mut c.placeholder),
+        (CanvasComponent::Select(c), TextField::Options) => Some(&mut c.options),
+        (CanvasComponent::Image(c), TextField::Alt) => Some(&mut c.alt),
+        (CanvasComponent::Custom(c), TextField::Prop(name)) => match c.props.get_mut(name) {
+            Some(PropValue::String(text)) => Some(text),
+            _ => None,
+        },
+        (component, TextField::Handler(name)) => match (component, *name) {
+            (CanvasComponent::Button(c), "on_click") => c.on_click.as_mut(),
+            (CanvasComponent::Container(c), "on_click") => c.on_click.as_mut(),
+            (CanvasComponent::Image(c), "on_click") => c.on_click.as_mut(),
+            (CanvasComponent::Card(c), "on_click") => c.on_click.as_mut(),
+            (CanvasComponent::Input(c), "on_change") => c.on_change.as_mut(),
+            (CanvasComponent::Input(c), "on_input") => c.on_input.as_mut(),
+            (CanvasComponent::Select(c), "on_change") => c.on_change.as_mut(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A compiled search query
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    regex: Regex,
+    /// The query was plain text, so the replacement is too
+    literal: bool,
+}
+
+impl Matcher {
+    pub fn new(query: &str, options: SearchOptions) -> Result<Self, ValidationError> {
+        if query.is_empty() {
+            return Err(ValidationError::Generic("Search text is empty".to_string()));
+        }
+        let pattern = if options.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let pattern = if options.whole_word {
+            format!(r"\b(?:{})\b", pattern)
+        } else {
+            pattern
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|e| ValidationError::Generic(format!("Invalid pattern: {}", e)))?;
+        // An empty match would be "replaced" between every character
+        if regex.is_match("") {
+            return Err(ValidationError::Generic(
+                "Pattern must not match empty text".to_string(),
+            ));
+        }
+        Ok(Self {
+            regex,
+            literal: !options.regex,
+        })
+    }
+
+    /// Where the query matches in `text`
+    pub fn find_in<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        self.regex.find_iter(text).map(|m| m.range())
+    }
+
+    /// What the match of `text` at `range` becomes
+    pub fn replacement_for(&self, text: &str, range: Range<usize>, replacement: &str) -> String {
+        if self.literal {
+            return replacement.to_string();
+        }
+        let mut expanded = String::new();
+        if let Some(captures) = self.regex.captures_at(text, range.start) {
+            captures.expand(replacement, &mut expanded);
+        }
+        expanded
+    }
+
+    /// `text` with every match replaced
+    pub fn replace_all(&self, text: &str, replacement: &str) -> String {
+        if self.literal {
+            self.regex
+                .replace_all(text, NoExpand(replacement))
+                .into_owned()
+        } else {
+            self.regex.replace_all(text, replacement).into_owned()
+        }
+    }
+}
+
+/// One occurrence of the query
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub id: ComponentId,
+    pub kind: ComponentType,
+    pub field: TextField,
+    /// The whole field as it was searched
+    pub text: String,
+    pub range: Range<usize>,
+}
+
+impl SearchMatch {
+    /// The field with this one occurrence replaced
+    pub fn replaced(&self, matcher: &Matcher, replacement: &str) -> String {
+        let mut text = self.text.clone();
+        text.replace_range(
+            self.range.clone(),
+            &matcher.replacement_for(&self.text, self.range.clone(), replacement),
+        );
+        text
+    }
+
+    /// The text around the match, split as (before, matched, after), with
+    /// long context cut down to `context` characters on each side
+    pub fn excerpt(&self, context: usize) -> (String, &str, String) {
+        let before = &self.text[..self.range.start];
+        let after = &self.text[self.range.end..];
+        let before = match before.char_indices().rev().nth(context) {
+            Some((i, _)) => format!("…{}", &before[i + 1..]),
+            None => before.to_string(),
+        };
+        let after = match after.char_indices().nth(context) {
+            Some((i, _)) => format!("{}…", &after[..i]),
+            None => after.to_string(),
+        };
+        (before, &self.text[self.range.clone()], after)
+    }
+}
+
+/// Every match in `roots` and their descendants, in document order
+pub fn search<'a>(
+    roots: impl IntoIterator<Item = &'a CanvasComponent>,
+    matcher: &Matcher,
+) -> Vec<SearchMatch> {
+    fn visit(component: &CanvasComponent, matcher: &Matcher, matches: &mut Vec<SearchMatch>) {
+        for (field, text) in text_fields(component) {
+            matches.extend(matcher.find_in(text).map(|range| SearchMatch {
+                id: *component.id(),
+                kind: component.component_type(),
+                field: field.clone(),
+                text: text.to_string(),
+                range,
+            }));
+        }
+        for child in children_of(component).into_iter().flatten() {
+            visit(child, matcher, matches);
+        }
+    }
+
+    let mut matches = Vec::new();
+    for root in roots {
+        visit(root, matcher, &mut matches);
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ButtonComponent, ContainerComponent, CustomComponent, TextComponent};
+
+    fn options(case_sensitive: bool, whole_word: bool, regex: bool) -> SearchOptions {
+        SearchOptions {
+            case_sensitive,
+            whole_word,
+            regex,
+        }
+    }
+
+    #[test]
+    fn test_search_fields_in_document_order() {
+        let mut button = ButtonComponent::new("Buy Acme".to_string());
+        button.on_click = Some("acme_checkout".to_string());
+        let mut custom = CustomComponent::new("Banner".to_string(), String::new());
+        custom.props.insert(
+            "title".to_string(),
+            PropValue::String("ACME sale".to_string()),
+        );
+        custom
+            .props
+            .insert("count".to_string(), PropValue::Number(3.0));
+        let mut container = ContainerComponent::new();
+        container.children.push(CanvasComponent::Button(button));
+        let components = vec![
+            CanvasComponent::Container(container),
+            CanvasComponent::Custom(custom),
+        ];
+
+        let matcher = Matcher::new("acme", SearchOptions::default()).unwrap();
+        let found: Vec<_> = search(&components, &matcher)
+            .into_iter()
+            .map(|m| m.field.label())
+            .collect();
+        assert_eq!(found, vec!["Label", "on_click", "Prop title"]);
+
+        // Whole words only: not inside `acme_checkout`
+        let matcher = Matcher::new("acme", options(false, true, false)).unwrap();
+        assert_eq!(search(&components, &matcher).len(), 2);
+        let matcher = Matcher::new("Acme", options(true, false, false)).unwrap();
+        assert_eq!(search(&components, &matcher).len(), 1);
+    }
+
+    #[test]
+    fn test_replacements_expand_only_in_regex_mode() {
+        let text = TextComponent::new("Price: $5 or $7".to_string());
+        let components = vec![CanvasComponent::Text(text)];
+
+        let plain = Matcher::new("$5", SearchOptions::default()).unwrap();
+        let found = search(&components, &plain);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].replaced(&plain, "$1"), "Price: $1 or $7");
+
+        let regex = Matcher::new(r"\$(\d)", options(false, false, true)).unwrap();
+        let found = search(&components, &regex);
+        assert_eq!(found[1].replaced(&regex, "${1}0"), "Price: $5 or 70");
+        assert_eq!(regex.replace_all(&found[0].text, "€$1"), "Price: €5 or €7");
+        assert_eq!(
+            found[1].excerpt(3),
+            ("…or ".to_string(), "$7", String::new())
+        );
+
+        assert!(Matcher::new("(", options(false, false, true)).is_err());
+        assert!(Matcher::new("a*", options(false, false, true)).is_err());
+    }
+}
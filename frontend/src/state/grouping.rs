@@ -0,0 +1,151 @@
+//! Grouping
+//!
+//! Wrapping sibling components in a new container or card, and dissolving
+//! one while its children stay where it was.
+
+use crate::domain::{
+    CanvasComponent, CardComponent, ContainerComponent, FlexDirection, LayoutType,
+};
+
+/// What a group is wrapped in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupKind {
+    Container,
+    Card,
+}
+
+impl GroupKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupKind::Container => "Container",
+            GroupKind::Card => "Card",
+        }
+    }
+
+    /// A new group holding `children`
+    pub fn wrap(self, children: Vec<CanvasComponent>) -> CanvasComponent {
+        match self {
+            GroupKind::Container => {
+                let mut container = ContainerComponent::new();
+                container.children = children;
+                CanvasComponent::Container(container)
+            }
+            GroupKind::Card => {
+                let mut card = CardComponent::new();
+                card.children = children;
+                CanvasComponent::Card(card)
+            }
+        }
+    }
+}
+
+/// Turn the padding and gap `group` puts around its children into margins
+/// on the children, for when the group is dissolved. Padding goes on the
+/// outer side of the children at the edges, the gap after every child but
+/// the last, along the layout's direction. Children with a margin of their
+/// own keep it.
+pub fn carry_spacing(group: &CanvasComponent, children: &mut [CanvasComponent]) {
+    let (padding, gap, across) = match group {
+        CanvasComponent::Container(container) => {
+            let p = &container.padding;
+            let across = matches!(
+                container.layout,
+                LayoutType::Flex {
+                    direction: FlexDirection::Row,
+                    ..
+                }
+            );
+            ([p.top, p.right, p.bottom, p.left], container.gap, across)
+        }
+        CanvasComponent::Card(card) => ([card.padding; 4], 0, false),
+        _ => return,
+    };
+    let [top, right, bottom, left] = padding;
+    let last = children.len().saturating_sub(1);
+    for (i, child) in children.iter_mut().enumerate() {
+        let (start, end) = (i == 0, i == last);
+        let after = if end { 0 } else { gap };
+        let margin = if across {
+            [
+                top,
+                if end { right } else { after },
+                bottom,
+                if start { left } else { 0 },
+            ]
+        } else {
+            [
+                if start { top } else { 0 },
+                right,
+                if end { bottom } else { after },
+                left,
+            ]
+        };
+        let style = child.style_mut();
+        if style.margin.is_none() && margin.iter().any(|side| *side > 0) {
+            style.margin = Some(format!(
+                "{}px {}px {}px {}px",
+                margin[0], margin[1], margin[2], margin[3]
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ButtonComponent, Spacing};
+
+    fn buttons(count: usize) -> Vec<CanvasComponent> {
+        (0..count)
+            .map(|i| CanvasComponent::Button(ButtonComponent::new(i.to_string())))
+            .collect()
+    }
+
+    fn margins(children: &mut [CanvasComponent]) -> Vec<Option<String>> {
+        children
+            .iter_mut()
+            .map(|child| child.style_mut().margin.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_carry_spacing_follows_layout_direction() {
+        let mut container = ContainerComponent::new();
+        container.gap = 8;
+        container.padding = Spacing {
+            top: 1,
+            right: 2,
+            bottom: 3,
+            left: 4,
+        };
+        let column = CanvasComponent::Container(container.clone());
+        let mut children = buttons(3);
+        carry_spacing(&column, &mut children);
+        assert_eq!(
+            margins(&mut children),
+            vec![
+                Some("1px 2px 8px 4px".to_string()),
+                Some("0px 2px 8px 4px".to_string()),
+                Some("0px 2px 3px 4px".to_string()),
+            ]
+        );
+
+        container.layout = LayoutType::Flex {
+            direction: FlexDirection::Row,
+            wrap: false,
+            align_items: Default::default(),
+            justify_content: Default::default(),
+        };
+        let row = CanvasComponent::Container(container);
+        let mut children = buttons(2);
+        children[1].style_mut().margin = Some("auto".to_string());
+        carry_spacing(&row, &mut children);
+        assert_eq!(
+            margins(&mut children),
+            vec![
+                Some("1px 8px 3px 4px".to_string()),
+                Some("auto".to_string())
+            ]
+        );
+    }
+}
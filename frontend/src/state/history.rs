@@ -1,32 +1,497 @@
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
-use crate::constants::MAX_HISTORY_SIZE;
-use crate::domain::{CanvasComponent, ComponentId};
+use crate::constants::{MAX_HISTORY_NODES, MAX_HISTORY_SIZE};
+use crate::domain::{CanvasComponent, CardComponent, ComponentId, ContainerComponent};
 
-/// Snapshot of canvas state at a point in time
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
-pub struct Snapshot {
-    pub components: Vec<CanvasComponent>,
-    pub selected: Option<ComponentId>,
-    pub timestamp: f64,
+/// Position of a component in the tree
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Slot {
+    /// Containing component, `None` for the top level
+    pub parent: Option<ComponentId>,
+    pub index: usize,
+}
+
+impl Slot {
+    pub fn root(index: usize) -> Self {
+        Self {
+            parent: None,
+            index,
+        }
+    }
+
+    pub fn child(parent: ComponentId, index: usize) -> Self {
+        Self {
+            parent: Some(parent),
+            index,
+        }
+    }
+}
+
+/// An invertible change to the component tree
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Operation {
+    /// Insert `component` at `slot`
+    Insert {
+        slot: Slot,
+        component: CanvasComponent,
+    },
+    /// Remove `component` from `slot`
+    Remove {
+        slot: Slot,
+        component: CanvasComponent,
+    },
+    /// Take a component out of `from` and put it at `to`, where `to` is a
+    /// position in the tree after the removal
+    Move {
+        id: ComponentId,
+        from: Slot,
+        to: Slot,
+    },
+    /// Change one serialized field of a component. `None` means the field
+    /// is absent.
+    SetField {
+        id: ComponentId,
+        field: String,
+        before: Option<Value>,
+        after: Option<Value>,
+    },
+}
+
+impl Operation {
+    /// The operation undoing this one
+    pub fn inverse(&self) -> Operation {
+        match self.clone() {
+            Operation::Insert { slot, component } => Operation::Remove { slot, component },
+            Operation::Remove { slot, component } => Operation::Insert { slot, component },
+            Operation::Move { id, from, to } => Operation::Move {
+                id,
+                from: to,
+                to: from,
+            },
+            Operation::SetField {
+                id,
+                field,
+                before,
+                after,
+            } => Operation::SetField {
+                id,
+                field,
+                before: after,
+                after: before,
+            },
+        }
+    }
+
+    /// Apply to `components`. Returns false, leaving them untouched, if the
+    /// tree does not match what the operation expects.
+    pub fn apply(&self, components: &mut Vec<CanvasComponent>) -> bool {
+        self.run(components, false)
+    }
+
+    /// Apply the inverse to `components`
+    pub fn revert(&self, components: &mut Vec<CanvasComponent>) -> bool {
+        self.run(components, true)
+    }
+
+    fn run(&self, components: &mut Vec<CanvasComponent>, reverse: bool) -> bool {
+        match (self, reverse) {
+            (Operation::Insert { slot, component }, false)
+            | (Operation::Remove { slot, component }, true) => {
+                insert_at(components, slot, component.clone())
+            }
+            (Operation::Insert { slot, component }, true)
+            | (Operation::Remove { slot, component }, false) => {
+                remove_at(components, slot, component.id()).is_some()
+            }
+            (Operation::Move { id, from, to }, false) => move_between(components, id, from, to),
+            (Operation::Move { id, from, to }, true) => move_between(components, id, to, from),
+            (
+                Operation::SetField {
+                    id,
+                    field,
+                    before,
+                    after,
+                },
+                reverse,
+            ) => {
+                let value = if reverse { before } else { after };
+                find_mut(components, id).is_some_and(|c| set_field(c, field, value.as_ref()))
+            }
+        }
+    }
+
+    /// Field changes turning `before` into `after`. `None` if they are
+    /// different kinds of component, which no field change can express.
+    /// Children are not fields; they change through inserts, removals and
+    /// moves.
+    pub fn set_fields(before: &CanvasComponent, after: &CanvasComponent) -> Option<Vec<Operation>> {
+        let (before_kind, before_fields) = fields(before)?;
+        let (after_kind, after_fields) = fields(after)?;
+        if before_kind != after_kind {
+            return None;
+        }
+        let names: BTreeSet<&String> = before_fields.keys().chain(after_fields.keys()).collect();
+        Some(
+            names
+                .into_iter()
+                .filter(|name| before_fields.get(*name) != after_fields.get(*name))
+                .map(|name| Operation::SetField {
+                    id: *before.id(),
+                    field: name.clone(),
+                    before: before_fields.get(name).cloned(),
+                    after: after_fields.get(name).cloned(),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A component tree operations can run on: the top-level components alone,
+/// or together with something kept in step with them
+pub trait ComponentTree {
+    /// Run `operation`, or its inverse if `reverse`. Returns false, leaving
+    /// the tree untouched, if it does not fit.
+    fn run(&mut self, operation: &Operation, reverse: bool) -> bool;
+}
+
+impl ComponentTree for Vec<CanvasComponent> {
+    fn run(&mut self, operation: &Operation, reverse: bool) -> bool {
+        operation.run(self, reverse)
+    }
+}
+
+/// The serialized field holding a component's children
+const CHILDREN: &str = "children";
+
+/// Variant name and fields of a serialized component, leaving out its
+/// children
+fn fields(component: &CanvasComponent) -> Option<(String, serde_json::Map<String, Value>)> {
+    let value = match component {
+        CanvasComponent::Container(c) => {
+            serde_json::to_value(CanvasComponent::Container(ContainerComponent {
+                id: c.id,
+                children: Vec::new(),
+                layout: c.layout.clone(),
+                gap: c.gap,
+                padding: c.padding.clone(),
+                on_click: c.on_click.clone(),
+                animation: c.animation.clone(),
+                bindings: c.bindings.clone(),
+                style: c.style.clone(),
+                name: c.name.clone(),
+                editor: c.editor.clone(),
+            }))
+        }
+        CanvasComponent::Card(c) => serde_json::to_value(CanvasComponent::Card(CardComponent {
+            id: c.id,
+            children: Vec::new(),
+            padding: c.padding,
+            shadow: c.shadow,
+            border: c.border,
+            border_radius: c.border_radius,
+            on_click: c.on_click.clone(),
+            animation: c.animation.clone(),
+            bindings: c.bindings.clone(),
+            style: c.style.clone(),
+            name: c.name.clone(),
+            editor: c.editor.clone(),
+        })),
+        _ => serde_json::to_value(component),
+    };
+    match value.ok()? {
+        Value::Object(map) => match map.into_iter().next()? {
+            (kind, Value::Object(mut fields)) => {
+                fields.remove(CHILDREN);
+                Some((kind, fields))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Children of a component that can have them
+pub fn children_of(component: &CanvasComponent) -> Option<&Vec<CanvasComponent>> {
+    match component {
+        CanvasComponent::Container(c) => Some(&c.children),
+        CanvasComponent::Card(c) => Some(&c.children),
+        _ => None,
+    }
+}
+
+pub fn children_of_mut(component: &mut CanvasComponent) -> Option<&mut Vec<CanvasComponent>> {
+    match component {
+        CanvasComponent::Container(c) => Some(&mut c.children),
+        CanvasComponent::Card(c) => Some(&mut c.children),
+        _ => None,
+    }
+}
+
+/// The component with `id`, anywhere in the tree
+pub fn find_mut<'a>(
+    components: &'a mut [CanvasComponent],
+    id: &ComponentId,
+) -> Option<&'a mut CanvasComponent> {
+    for component in components {
+        if component.id() == id {
+            return Some(component);
+        }
+        if let Some(found) = children_of_mut(component).and_then(|c| find_mut(c, id)) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// The component with `id`, anywhere in the tree
+pub fn find<'a>(
+    components: &'a [CanvasComponent],
+    id: &ComponentId,
+) -> Option<&'a CanvasComponent> {
+    for component in components {
+        if component.id() == id {
+            return Some(component);
+        }
+        if let Some(found) = children_of(component).and_then(|c| find(c, id)) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// The components directly under `parent`, or the top level for `None`.
+/// `None` if `parent` is missing or cannot have children.
+pub fn child_list<'a>(
+    components: &'a [CanvasComponent],
+    parent: Option<&ComponentId>,
+) -> Option<&'a [CanvasComponent]> {
+    match parent {
+        None => Some(components),
+        Some(id) => find(components, id)
+            .and_then(children_of)
+            .map(Vec::as_slice),
+    }
+}
+
+fn child_list_mut<'a>(
+    components: &'a mut Vec<CanvasComponent>,
+    parent: Option<&ComponentId>,
+) -> Option<&'a mut Vec<CanvasComponent>> {
+    match parent {
+        None => Some(components),
+        Some(id) => find_mut(components, id).and_then(children_of_mut),
+    }
+}
+
+/// Where the component with `id` sits
+pub fn locate(components: &[CanvasComponent], id: &ComponentId) -> Option<Slot> {
+    fn search(
+        list: &[CanvasComponent],
+        parent: Option<ComponentId>,
+        id: &ComponentId,
+    ) -> Option<Slot> {
+        for (index, component) in list.iter().enumerate() {
+            if component.id() == id {
+                return Some(Slot { parent, index });
+            }
+            if let Some(slot) =
+                children_of(component).and_then(|kids| search(kids, Some(*component.id()), id))
+            {
+                return Some(slot);
+            }
+        }
+        None
+    }
+    search(components, None, id)
+}
+
+fn insert_at(
+    components: &mut Vec<CanvasComponent>,
+    slot: &Slot,
+    component: CanvasComponent,
+) -> bool {
+    match child_list_mut(components, slot.parent.as_ref()) {
+        Some(list) if slot.index <= list.len() => {
+            list.insert(slot.index, component);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn remove_at(
+    components: &mut Vec<CanvasComponent>,
+    slot: &Slot,
+    id: &ComponentId,
+) -> Option<CanvasComponent> {
+    let list = child_list_mut(components, slot.parent.as_ref())?;
+    if list.get(slot.index)?.id() != id {
+        return None;
+    }
+    Some(list.remove(slot.index))
+}
+
+fn move_between(
+    components: &mut Vec<CanvasComponent>,
+    id: &ComponentId,
+    from: &Slot,
+    to: &Slot,
+) -> bool {
+    let Some(component) = remove_at(components, from, id) else {
+        return false;
+    };
+    match child_list_mut(components, to.parent.as_ref()) {
+        Some(list) if to.index <= list.len() => {
+            list.insert(to.index, component);
+            true
+        }
+        // Put it back where it was
+        _ => insert_at(components, from, component),
+    }
+}
+
+/// One serialized field of `component`
+pub fn field(component: &CanvasComponent, name: &str) -> Option<Value> {
+    fields(component)?.1.remove(name)
+}
+
+/// Set one serialized field of `component`, or remove it for `None`.
+/// Returns false, leaving it untouched, if the result is not a valid
+/// component or `field` is its children. Children are carried over
+/// without being serialized.
+pub fn set_field(component: &mut CanvasComponent, field: &str, value: Option<&Value>) -> bool {
+    if field == CHILDREN {
+        return false;
+    }
+    let Some((kind, mut fields)) = fields(component) else {
+        return false;
+    };
+    match value {
+        Some(value) => fields.insert(field.to_string(), value.clone()),
+        None => fields.remove(field),
+    };
+    if children_of(component).is_some() {
+        fields.insert(CHILDREN.to_string(), Value::Array(Vec::new()));
+    }
+    let mut serialized = serde_json::Map::new();
+    serialized.insert(kind, Value::Object(fields));
+    let Ok(mut updated) = serde_json::from_value::<CanvasComponent>(Value::Object(serialized))
+    else {
+        return false;
+    };
+    if let (Some(children), Some(slot)) =
+        (children_of_mut(component), children_of_mut(&mut updated))
+    {
+        *slot = std::mem::take(children);
+    }
+    *component = updated;
+    true
+}
+
+/// One undoable user action
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
     pub description: String,
+    pub timestamp: f64,
+    /// Applied in order; undone in reverse
+    pub operations: Vec<Operation>,
 }
 
-impl Snapshot {
-    pub fn new(
-        components: Vec<CanvasComponent>,
-        selected: Option<ComponentId>,
-        description: String,
-    ) -> Self {
-        let timestamp = get_timestamp();
+impl HistoryEntry {
+    pub fn new(description: String, operations: Vec<Operation>) -> Self {
         Self {
-            components,
-            selected,
-            timestamp,
             description,
+            timestamp: get_timestamp(),
+            operations,
         }
     }
+
+    /// Apply the entry's operations; on failure nothing changes
+    pub fn apply(&self, tree: &mut impl ComponentTree) -> bool {
+        run_all(tree, self.operations.iter(), false)
+    }
+
+    /// Undo the entry's operations; on failure nothing changes
+    pub fn revert(&self, tree: &mut impl ComponentTree) -> bool {
+        run_all(tree, self.operations.iter().rev(), true)
+    }
+}
+
+/// Actions collected into one history entry until the transaction grouping
+/// them is committed
+#[derive(Clone, Debug)]
+pub struct Transaction {
+    pub entry: HistoryEntry,
+    /// Open `begin` calls; nested transactions join the outermost one
+    pub depth: usize,
+}
+
+impl HistoryEntry {
+    /// The component fields the entry changes, or `None` if it does more
+    /// than change fields
+    fn fields_changed(&self) -> Option<HashSet<(ComponentId, &str)>> {
+        self.operations
+            .iter()
+            .map(|operation| match operation {
+                Operation::SetField { id, field, .. } => Some((*id, field.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Fold `next` into this entry if both change exactly the same fields,
+    /// keeping this entry's starting values and `next`'s results
+    pub fn absorb(&mut self, next: &HistoryEntry) -> bool {
+        match (self.fields_changed(), next.fields_changed()) {
+            (Some(mine), Some(theirs)) if mine == theirs => {}
+            _ => return false,
+        }
+        for operation in &next.operations {
+            let Operation::SetField {
+                id, field, after, ..
+            } = operation
+            else {
+                continue;
+            };
+            for own in &mut self.operations {
+                if let Operation::SetField {
+                    id: own_id,
+                    field: own_field,
+                    after: own_after,
+                    ..
+                } = own
+                    && own_id == id
+                    && own_field == field
+                {
+                    *own_after = after.clone();
+                }
+            }
+        }
+        self.timestamp = next.timestamp;
+        true
+    }
+}
+
+/// Run each operation, or its inverse if `reverse`. If one fails, the ones
+/// already run are rolled back so the tree is left as it was.
+fn run_all<'a>(
+    tree: &mut impl ComponentTree,
+    operations: impl Iterator<Item = &'a Operation>,
+    reverse: bool,
+) -> bool {
+    let mut done = Vec::new();
+    for operation in operations {
+        if !tree.run(operation, reverse) {
+            for operation in done.into_iter().rev() {
+                tree.run(operation, !reverse);
+            }
+            return false;
+        }
+        done.push(operation);
+    }
+    true
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -39,216 +504,751 @@ fn get_timestamp() -> f64 {
     0.0 // Mock timestamp for tests
 }
 
-/// History system for undo/redo functionality
-#[derive(Clone, Debug)]
+/// Identifies an entry in the undo tree
+pub type NodeId = u64;
+
+/// An entry in the undo tree with the entries recorded after it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryNode {
+    pub entry: HistoryEntry,
+    /// Entry this one was recorded after, `None` for the initial state
+    pub parent: Option<NodeId>,
+    /// Branches recorded after this entry, oldest first
+    pub children: Vec<NodeId>,
+    /// Branch redo follows: the one most recently created or visited
+    active_child: Option<NodeId>,
+}
+
+/// How an entry relates to the current state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HistoryNodeState {
+    /// Part of the current state
+    Applied,
+    /// The latest applied entry
+    Current,
+    /// On the branch redo would follow
+    Redo,
+    /// On another branch
+    Branch,
+}
+
+/// An entry as the history panel lists it
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryRow {
+    pub id: NodeId,
+    /// How many branches away from the first line of history
+    pub depth: usize,
+    pub description: String,
+    pub timestamp: f64,
+    pub state: HistoryNodeState,
+}
+
+/// History system for undo/redo functionality.
+///
+/// Entries record the operations an action performed rather than copies of
+/// the tree, so each one costs about as much as the change it describes.
+/// They form a tree: recording an action after undoing keeps the undone
+/// entries as a branch of their own, which can be returned to with
+/// [`History::jump_to`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct History {
-    undo_stack: VecDeque<Snapshot>,
-    redo_stack: VecDeque<Snapshot>,
+    nodes: BTreeMap<NodeId, HistoryNode>,
+    /// Entries recorded right after the initial state, oldest first
+    roots: Vec<NodeId>,
+    active_root: Option<NodeId>,
+    /// Latest applied entry, `None` at the initial state
+    current: Option<NodeId>,
+    next_id: NodeId,
 }
 
 impl History {
-    pub fn get_undo_stack(&self) -> Vec<Snapshot> {
-        self.undo_stack.iter().rev().cloned().collect()
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    pub fn new() -> Self {
-        Self {
-            undo_stack: VecDeque::new(),
-            redo_stack: VecDeque::new(),
-        }
+    /// Latest applied entry, `None` at the initial state
+    pub fn current(&self) -> Option<NodeId> {
+        self.current
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&HistoryNode> {
+        self.nodes.get(&id)
     }
 
-    /// Push a new snapshot to the history
-    pub fn push(&mut self, snapshot: Snapshot) {
-        // Clear redo stack when new action is performed
-        self.redo_stack.clear();
+    /// Number of entries across all branches
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
 
-        // Add to undo stack
-        self.undo_stack.push_back(snapshot);
+    /// Record an action that has already been applied
+    pub fn push(&mut self, entry: HistoryEntry) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let parent = self.current;
+        self.nodes.insert(
+            id,
+            HistoryNode {
+                entry,
+                parent,
+                children: Vec::new(),
+                active_child: None,
+            },
+        );
+        match parent.and_then(|p| self.nodes.get_mut(&p)) {
+            Some(node) => node.children.push(id),
+            None => self.roots.push(id),
+        }
+        self.set_active(parent, id);
+        self.current = Some(id);
+        self.prune();
+    }
 
-        // Limit stack size
-        if self.undo_stack.len() > MAX_HISTORY_SIZE {
-            self.undo_stack.pop_front();
+    /// Record an action that has already been applied, merging it into the
+    /// latest entry if that changed the same fields of the same components
+    /// less than `window` milliseconds earlier. Typing into a field then
+    /// takes a single undo step rather than one per keystroke.
+    pub fn push_coalescing(&mut self, entry: HistoryEntry, window: f64) {
+        if let Some(latest) = self.current.and_then(|id| self.nodes.get_mut(&id))
+            // Undone entries after the latest one were recorded against it
+            && latest.children.is_empty()
+            && entry.timestamp - latest.entry.timestamp <= window
+            && latest.entry.absorb(&entry)
+        {
+            return;
         }
+        self.push(entry);
     }
 
-    /// Undo the last action
-    pub fn undo(&mut self) -> Option<Snapshot> {
-        if let Some(snapshot) = self.undo_stack.pop_back() {
-            self.redo_stack.push_back(snapshot.clone());
-            // Return the previous state
-            self.undo_stack.back().cloned()
-        } else {
-            None
+    /// Undo the last action on `components`
+    pub fn undo(&mut self, components: &mut impl ComponentTree) -> Option<&HistoryEntry> {
+        let id = self.current?;
+        let node = self.nodes.get(&id)?;
+        if !node.entry.revert(components) {
+            // The tree no longer matches; the entry cannot be undone
+            return None;
         }
+        self.current = node.parent;
+        self.nodes.get(&id).map(|n| &n.entry)
     }
 
-    /// Redo the last undone action
-    pub fn redo(&mut self) -> Option<Snapshot> {
-        if let Some(snapshot) = self.redo_stack.pop_back() {
-            self.undo_stack.push_back(snapshot.clone());
-            Some(snapshot)
-        } else {
-            None
+    /// Redo the last undone action on `components`, following the most
+    /// recent branch
+    pub fn redo(&mut self, components: &mut impl ComponentTree) -> Option<&HistoryEntry> {
+        let id = self.active_child(self.current)?;
+        if !self.step_down(id, components) {
+            return None;
         }
+        self.nodes.get(&id).map(|n| &n.entry)
     }
 
     /// Check if undo is available
     pub fn can_undo(&self) -> bool {
-        self.undo_stack.len() > 1 // Need at least 2 items to undo
+        self.current.is_some()
     }
 
     /// Check if redo is available
     pub fn can_redo(&self) -> bool {
-        !self.redo_stack.is_empty()
+        self.active_child(self.current).is_some()
     }
 
     /// Clear all history
     pub fn clear(&mut self) {
-        self.undo_stack.clear();
-        self.redo_stack.clear();
+        *self = Self::default();
     }
 
-    /// Restore to a specific index in the undo stack
-    /// Index 0 is the oldest action.
-    /// This function moves newer actions to the redo stack.
-    pub fn restore_to_index(&mut self, index: usize) -> Option<Snapshot> {
-        if index >= self.undo_stack.len() {
-            return None;
+    /// Move to the state right after entry `target`, or the initial state
+    /// for `None`, undoing back to the nearest shared entry and redoing
+    /// down the target's branch. Returns false, leaving everything as it
+    /// was, if `target` is unknown or a step fails.
+    pub fn jump_to(&mut self, target: Option<NodeId>, components: &mut impl ComponentTree) -> bool {
+        if target.is_some_and(|id| !self.nodes.contains_key(&id)) {
+            return false;
         }
+        let up = self.ancestors(self.current);
+        let down = self.ancestors(target);
+        let shared = up
+            .iter()
+            .rev()
+            .zip(down.iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let start = self.current;
 
-        // We want to keep elements up to `index` (inclusive) in the undo stack.
-        // Elements after `index` should be moved to redo stack in reverse order.
-        let keep_count = index + 1;
-        let remove_count = self.undo_stack.len() - keep_count;
+        let mut ok = true;
+        for _ in shared..up.len() {
+            if self.undo(components).is_none() {
+                ok = false;
+                break;
+            }
+        }
+        if ok {
+            for &id in down[..down.len() - shared].iter().rev() {
+                if !self.step_down(id, components) {
+                    ok = false;
+                    break;
+                }
+            }
+        }
+        if !ok && self.current != start {
+            // Retrace the steps taken; they worked once, so they work back
+            self.jump_to(start, components);
+        }
+        ok
+    }
 
-        for _ in 0..remove_count {
-            if let Some(snapshot) = self.undo_stack.pop_back() {
-                self.redo_stack.push_back(snapshot);
+    /// Move to the entry whose state `components` is, when they are not the
+    /// state the history ended at. `is_latest` tells whether a tree is that
+    /// state; the entries leading to it are replayed on `components` from
+    /// each earlier entry in turn until one gets there. The entries after
+    /// the match stay available to redo. Returns false if none matches.
+    pub fn rebase(
+        &mut self,
+        components: &[CanvasComponent],
+        is_latest: impl Fn(&[CanvasComponent]) -> bool,
+    ) -> bool {
+        let path = self.ancestors(self.current);
+        // Zero entries to replay first, then one, and so on
+        for skip in 0..=path.len() {
+            let mut replayed = components.to_vec();
+            let replays = path[..skip].iter().rev().all(|id| {
+                self.nodes
+                    .get(id)
+                    .is_some_and(|node| node.entry.apply(&mut replayed))
+            });
+            if replays && is_latest(&replayed) {
+                self.current = path.get(skip).copied();
+                return true;
             }
         }
+        false
+    }
+
+    /// Every entry for display, each followed by the entries recorded after
+    /// it. The first branch of an entry continues at its depth, later ones
+    /// are one level deeper.
+    pub fn rows(&self) -> Vec<HistoryRow> {
+        let applied = self.ancestors(self.current);
+        let mut redo = Vec::new();
+        let mut next = self.active_child(self.current);
+        while let Some(id) = next {
+            redo.push(id);
+            next = self.active_child(Some(id));
+        }
 
-        // Return the snapshot at the new tip of undo stack
-        self.undo_stack.back().cloned()
+        let mut rows = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<(NodeId, usize)> = self.branches(&self.roots, 0);
+        while let Some((id, depth)) = stack.pop() {
+            let Some(node) = self.nodes.get(&id) else {
+                continue;
+            };
+            let state = if self.current == Some(id) {
+                HistoryNodeState::Current
+            } else if applied.contains(&id) {
+                HistoryNodeState::Applied
+            } else if redo.contains(&id) {
+                HistoryNodeState::Redo
+            } else {
+                HistoryNodeState::Branch
+            };
+            rows.push(HistoryRow {
+                id,
+                depth,
+                description: node.entry.description.clone(),
+                timestamp: node.entry.timestamp,
+                state,
+            });
+            stack.extend(self.branches(&node.children, depth));
+        }
+        rows
     }
-}
 
-impl Default for History {
-    fn default() -> Self {
-        Self::new()
+    /// `children` with their depths, in reverse so popping yields the
+    /// oldest first
+    fn branches(&self, children: &[NodeId], depth: usize) -> Vec<(NodeId, usize)> {
+        children
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, &id)| (id, if i == 0 { depth } else { depth + 1 }))
+            .collect()
+    }
+
+    /// `id` and the entries before it, newest first
+    fn ancestors(&self, id: Option<NodeId>) -> Vec<NodeId> {
+        let mut path = Vec::new();
+        let mut next = id;
+        while let Some(id) = next {
+            path.push(id);
+            next = self.nodes.get(&id).and_then(|n| n.parent);
+        }
+        path
+    }
+
+    fn active_child(&self, parent: Option<NodeId>) -> Option<NodeId> {
+        match parent {
+            None => self.active_root,
+            Some(id) => self.nodes.get(&id)?.active_child,
+        }
+    }
+
+    fn set_active(&mut self, parent: Option<NodeId>, child: NodeId) {
+        match parent {
+            None => self.active_root = Some(child),
+            Some(id) => {
+                if let Some(node) = self.nodes.get_mut(&id) {
+                    node.active_child = Some(child);
+                }
+            }
+        }
+    }
+
+    /// Apply `id`, an entry recorded right after the current one
+    fn step_down(&mut self, id: NodeId, components: &mut impl ComponentTree) -> bool {
+        let Some(node) = self.nodes.get(&id) else {
+            return false;
+        };
+        if node.parent != self.current || !node.entry.apply(components) {
+            return false;
+        }
+        self.set_active(node.parent, id);
+        self.current = Some(id);
+        true
+    }
+
+    fn prune(&mut self) {
+        self.trim(MAX_HISTORY_SIZE, MAX_HISTORY_NODES);
+    }
+
+    /// Keep at most `max_depth` entries in a row before the current state
+    /// and, branches permitting, `max_nodes` overall. The oldest go first.
+    pub fn trim(&mut self, max_depth: usize, max_nodes: usize) {
+        let mut path = self.ancestors(self.current);
+        while path.len() > max_depth {
+            // The oldest applied entry becomes part of the initial state;
+            // branches before it can no longer be reached
+            let Some(oldest) = path.pop() else {
+                break;
+            };
+            for root in std::mem::take(&mut self.roots) {
+                if root != oldest {
+                    self.remove_subtree(root);
+                }
+            }
+            let Some(node) = self.nodes.remove(&oldest) else {
+                break;
+            };
+            for child in &node.children {
+                if let Some(child) = self.nodes.get_mut(child) {
+                    child.parent = None;
+                }
+            }
+            self.roots = node.children;
+            self.active_root = node.active_child;
+        }
+
+        while self.nodes.len() > max_nodes {
+            // Drop the oldest dead end; the current entry is never one
+            let leaf = self
+                .nodes
+                .iter()
+                .find(|(id, node)| node.children.is_empty() && self.current != Some(**id))
+                .map(|(id, _)| *id);
+            let Some(leaf) = leaf else {
+                break;
+            };
+            self.remove_subtree(leaf);
+        }
+    }
+
+    /// Remove `id` and everything recorded after it
+    fn remove_subtree(&mut self, id: NodeId) {
+        let Some(node) = self.nodes.remove(&id) else {
+            return;
+        };
+        for child in node.children {
+            self.remove_subtree(child);
+        }
+        let (siblings, active) = match node.parent.and_then(|p| self.nodes.get_mut(&p)) {
+            Some(parent) => (&mut parent.children, &mut parent.active_child),
+            None => (&mut self.roots, &mut self.active_root),
+        };
+        siblings.retain(|&sibling| sibling != id);
+        if *active == Some(id) {
+            *active = siblings.last().copied();
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{ButtonComponent, CanvasComponent};
-
-    fn create_test_snapshot(label: &str) -> Snapshot {
-        let button = ButtonComponent::new(label.to_string());
-        let button_id = button.id;
-        let component = CanvasComponent::Button(button.clone());
-        Snapshot::new(
-            vec![component],
-            Some(button_id),
-            format!("Update {}", label),
-        )
+    use crate::domain::{ButtonComponent, ContainerComponent, TextComponent};
+
+    fn button(label: &str) -> CanvasComponent {
+        CanvasComponent::Button(ButtonComponent::new(label.to_string()))
+    }
+
+    fn insert(components: &mut Vec<CanvasComponent>, history: &mut History, label: &str) {
+        let operation = Operation::Insert {
+            slot: Slot::root(components.len()),
+            component: button(label),
+        };
+        assert!(operation.apply(components));
+        history.push(HistoryEntry::new(format!("Add {}", label), vec![operation]));
+    }
+
+    fn labels(components: &[CanvasComponent]) -> Vec<String> {
+        components
+            .iter()
+            .map(|c| match c {
+                CanvasComponent::Button(b) => b.label.clone(),
+                CanvasComponent::Text(t) => t.content.clone(),
+                _ => String::new(),
+            })
+            .collect()
     }
 
     #[test]
     fn test_history_push() {
         let mut history = History::new();
-        let snapshot1 = create_test_snapshot("Button 1");
-        let snapshot2 = create_test_snapshot("Button 2");
-
-        history.push(snapshot1);
-        history.push(snapshot2);
+        let mut components = Vec::new();
+        insert(&mut components, &mut history, "Button 1");
+        insert(&mut components, &mut history, "Button 2");
 
-        assert_eq!(history.undo_stack.len(), 2);
-        assert_eq!(history.redo_stack.len(), 0);
+        assert_eq!(history.len(), 2);
+        assert!(!history.can_redo());
     }
 
     #[test]
     fn test_history_max_size() {
         let mut history = History::new();
+        let mut components = Vec::new();
 
-        // Push more than MAX_HISTORY_SIZE snapshots
+        // Push more than MAX_HISTORY_SIZE entries
         for i in 0..MAX_HISTORY_SIZE + 10 {
-            let snapshot = create_test_snapshot(&format!("Button {}", i));
-            history.push(snapshot);
+            insert(&mut components, &mut history, &format!("Button {}", i));
         }
 
-        assert_eq!(history.undo_stack.len(), MAX_HISTORY_SIZE);
+        assert_eq!(history.len(), MAX_HISTORY_SIZE);
     }
 
     #[test]
     fn test_history_undo() {
         let mut history = History::new();
-        let snapshot1 = create_test_snapshot("Button 1");
-        let snapshot2 = create_test_snapshot("Button 2");
-
-        history.push(snapshot1.clone());
-        history.push(snapshot2);
+        let mut components = Vec::new();
+        insert(&mut components, &mut history, "Button 1");
+        insert(&mut components, &mut history, "Button 2");
 
-        let undone = history.undo();
-        assert!(undone.is_some());
-        assert_eq!(history.undo_stack.len(), 1);
-        assert_eq!(history.redo_stack.len(), 1);
+        let undone = history.undo(&mut components).map(|e| e.description.clone());
+        assert_eq!(undone.as_deref(), Some("Add Button 2"));
+        assert_eq!(labels(&components), vec!["Button 1"]);
+        assert!(history.can_undo());
+        assert!(history.can_redo());
     }
 
     #[test]
     fn test_history_redo() {
         let mut history = History::new();
-        let snapshot1 = create_test_snapshot("Button 1");
-        let snapshot2 = create_test_snapshot("Button 2");
-
-        history.push(snapshot1);
-        history.push(snapshot2.clone());
-        history.undo();
+        let mut components = Vec::new();
+        insert(&mut components, &mut history, "Button 1");
+        insert(&mut components, &mut history, "Button 2");
+        history.undo(&mut components);
 
-        let redone = history.redo();
+        let redone = history.redo(&mut components);
         assert!(redone.is_some());
-        assert_eq!(history.undo_stack.len(), 2);
-        assert_eq!(history.redo_stack.len(), 0);
+        assert_eq!(labels(&components), vec!["Button 1", "Button 2"]);
+        assert_eq!(history.len(), 2);
+        assert!(!history.can_redo());
     }
 
     #[test]
     fn test_history_can_undo() {
         let mut history = History::new();
+        let mut components = Vec::new();
         assert!(!history.can_undo());
 
-        history.push(create_test_snapshot("Button 1"));
-        assert!(!history.can_undo()); // Need at least 2 items
-
-        history.push(create_test_snapshot("Button 2"));
+        insert(&mut components, &mut history, "Button 1");
         assert!(history.can_undo());
+
+        history.undo(&mut components);
+        assert!(!history.can_undo());
     }
 
     #[test]
     fn test_history_can_redo() {
         let mut history = History::new();
+        let mut components = Vec::new();
         assert!(!history.can_redo());
 
-        history.push(create_test_snapshot("Button 1"));
-        history.push(create_test_snapshot("Button 2"));
+        insert(&mut components, &mut history, "Button 1");
+        insert(&mut components, &mut history, "Button 2");
         assert!(!history.can_redo());
 
-        history.undo();
+        history.undo(&mut components);
         assert!(history.can_redo());
     }
 
     #[test]
-    fn test_history_clear_on_new_action() {
+    fn test_new_action_after_undo_starts_a_branch() {
         let mut history = History::new();
+        let mut components = Vec::new();
 
-        history.push(create_test_snapshot("Button 1"));
-        history.push(create_test_snapshot("Button 2"));
-        history.undo();
+        insert(&mut components, &mut history, "Button 1");
+        insert(&mut components, &mut history, "Button 2");
+        history.undo(&mut components);
+        insert(&mut components, &mut history, "Button 3");
 
-        assert!(history.can_redo());
-
-        // New action should clear redo stack
-        history.push(create_test_snapshot("Button 3"));
+        // The undone entry is kept, but redo follows the newest branch
+        assert_eq!(history.len(), 3);
         assert!(!history.can_redo());
+        history.undo(&mut components);
+        assert_eq!(
+            history.redo(&mut components).map(|e| e.description.clone()),
+            Some("Add Button 3".to_string())
+        );
+        assert_eq!(labels(&components), vec!["Button 1", "Button 3"]);
+    }
+
+    #[test]
+    fn test_jump_between_branches() {
+        let mut history = History::new();
+        let mut components = Vec::new();
+        for label in ["A", "B", "C"] {
+            insert(&mut components, &mut history, label);
+        }
+        let c = history.current();
+        history.undo(&mut components);
+        history.undo(&mut components);
+        insert(&mut components, &mut history, "D");
+        let d = history.current();
+
+        assert!(history.jump_to(c, &mut components));
+        assert_eq!(labels(&components), vec!["A", "B", "C"]);
+        // Visiting a branch makes it the one redo follows again
+        history.undo(&mut components);
+        history.undo(&mut components);
+        history.redo(&mut components);
+        assert_eq!(labels(&components), vec!["A", "B"]);
+
+        assert!(history.jump_to(d, &mut components));
+        assert_eq!(labels(&components), vec!["A", "D"]);
+        assert!(history.jump_to(None, &mut components));
+        assert!(components.is_empty());
+        assert!(!history.jump_to(Some(99), &mut components));
+    }
+
+    #[test]
+    fn test_rows_show_branches() {
+        let mut history = History::new();
+        let mut components = Vec::new();
+        for label in ["A", "B", "C"] {
+            insert(&mut components, &mut history, label);
+        }
+        history.undo(&mut components);
+        history.undo(&mut components);
+        insert(&mut components, &mut history, "D");
+        history.undo(&mut components);
+
+        let rows: Vec<_> = history
+            .rows()
+            .into_iter()
+            .map(|r| (r.description, r.depth, r.state))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Add A".to_string(), 0, HistoryNodeState::Current),
+                ("Add B".to_string(), 0, HistoryNodeState::Branch),
+                ("Add C".to_string(), 0, HistoryNodeState::Branch),
+                ("Add D".to_string(), 1, HistoryNodeState::Redo),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune_keeps_current_branch() {
+        let mut history = History::new();
+        let mut components = Vec::new();
+        for i in 0..MAX_HISTORY_NODES + 10 {
+            insert(&mut components, &mut history, &format!("Dead end {}", i));
+            history.undo(&mut components);
+        }
+        assert_eq!(history.len(), MAX_HISTORY_NODES);
+
+        // Once the first entry falls off, the branches beside it go too
+        insert(&mut components, &mut history, "Start");
+        for i in 0..MAX_HISTORY_SIZE {
+            insert(&mut components, &mut history, &format!("Button {}", i));
+        }
+        assert_eq!(history.len(), MAX_HISTORY_SIZE);
+        assert!(history.jump_to(None, &mut components));
+        assert_eq!(labels(&components), vec!["Start"]);
+    }
+
+    fn rename(components: &mut [CanvasComponent], label: &str, at: f64) -> HistoryEntry {
+        let before = components[0].clone();
+        if let CanvasComponent::Button(b) = &mut components[0] {
+            b.label = label.to_string();
+        }
+        let mut entry = HistoryEntry::new(
+            "Update Label".to_string(),
+            Operation::set_fields(&before, &components[0]).unwrap(),
+        );
+        entry.timestamp = at;
+        entry
+    }
+
+    #[test]
+    fn test_coalesce_typing_into_one_entry() {
+        let mut history = History::new();
+        let mut components = Vec::new();
+        insert(&mut components, &mut history, "B");
+        for (i, label) in ["Bu", "But", "Butt"].into_iter().enumerate() {
+            let entry = rename(&mut components, label, i as f64 * 100.0);
+            history.push_coalescing(entry, 500.0);
+        }
+        assert_eq!(history.len(), 2);
+
+        // A pause starts a new entry
+        let entry = rename(&mut components, "Button", 2000.0);
+        history.push_coalescing(entry, 500.0);
+        assert_eq!(history.len(), 3);
+
+        history.undo(&mut components);
+        assert_eq!(labels(&components), vec!["Butt"]);
+        history.undo(&mut components);
+        assert_eq!(labels(&components), vec!["B"]);
+    }
+
+    #[test]
+    fn test_coalesce_only_same_fields_at_branch_tip() {
+        let mut history = History::new();
+        let mut components = Vec::new();
+        insert(&mut components, &mut history, "A");
+
+        let first = rename(&mut components, "AB", 0.0);
+        history.push_coalescing(first, 500.0);
+        let mut other_field = rename(&mut components, "AB", 0.0);
+        other_field.operations = vec![Operation::SetField {
+            id: *components[0].id(),
+            field: "disabled".to_string(),
+            before: Some(Value::Bool(false)),
+            after: Some(Value::Bool(true)),
+        }];
+        history.push_coalescing(other_field, 500.0);
+        assert_eq!(history.len(), 3);
+
+        // Nothing merges into an entry that has undone entries after it
+        history.undo(&mut components);
+        history.undo(&mut components);
+        let retyped = rename(&mut components, "AC", 0.0);
+        history.push_coalescing(retyped, 500.0);
+        assert_eq!(history.len(), 4);
+    }
+
+    #[test]
+    fn test_operations_round_trip() {
+        let container = ContainerComponent::new();
+        let container_id = container.id;
+        let text = TextComponent::new("Hello".to_string());
+        let original = vec![
+            CanvasComponent::Container(container),
+            CanvasComponent::Text(text.clone()),
+        ];
+
+        let mut edited = text.clone();
+        edited.content = "Bye".to_string();
+        let mut operations = Operation::set_fields(
+            &CanvasComponent::Text(text.clone()),
+            &CanvasComponent::Text(edited),
+        )
+        .unwrap();
+        assert_eq!(operations.len(), 1);
+        operations.push(Operation::Move {
+            id: text.id,
+            from: Slot::root(1),
+            to: Slot::child(container_id, 0),
+        });
+        operations.push(Operation::Insert {
+            slot: Slot::root(0),
+            component: button("New"),
+        });
+
+        let entry = HistoryEntry::new("Edit".to_string(), operations.clone());
+        let mut components = original.clone();
+        assert!(entry.apply(&mut components));
+        assert_eq!(labels(&components), vec!["New", ""]);
+        assert_eq!(
+            locate(&components, &text.id),
+            Some(Slot::child(container_id, 0))
+        );
+        match find(&components, &text.id) {
+            Some(CanvasComponent::Text(t)) => assert_eq!(t.content, "Bye"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(entry.revert(&mut components));
+        assert_eq!(components, original);
+        for operation in &operations {
+            assert_eq!(operation.inverse().inverse(), *operation);
+        }
+    }
+
+    #[test]
+    fn test_container_fields_leave_children_alone() {
+        let mut container = ContainerComponent::new();
+        container.children.push(button("A"));
+        let before = CanvasComponent::Container(container.clone());
+        container.gap = 24;
+        container.children.push(button("B"));
+        let after = CanvasComponent::Container(container);
+
+        // Only the gap is a field change; children are not
+        let operations = Operation::set_fields(&before, &after).unwrap();
+        assert_eq!(operations.len(), 1);
+        assert!(matches!(&operations[0], Operation::SetField { field, .. } if field == "gap"));
+        assert_eq!(field(&before, "children"), None);
+
+        let mut edited = before.clone();
+        assert!(set_field(&mut edited, "gap", Some(&Value::from(24))));
+        match &edited {
+            CanvasComponent::Container(c) => {
+                assert_eq!(c.gap, 24);
+                assert_eq!(labels(&c.children), vec!["A"]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(!set_field(
+            &mut edited,
+            "children",
+            Some(&Value::Array(Vec::new()))
+        ));
+        assert_eq!(children_of(&edited).map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn test_failed_entry_leaves_tree_untouched() {
+        let original = vec![button("A")];
+        let entry = HistoryEntry::new(
+            "Broken".to_string(),
+            vec![
+                Operation::Insert {
+                    slot: Slot::root(1),
+                    component: button("B"),
+                },
+                Operation::Remove {
+                    slot: Slot::root(5),
+                    component: button("C"),
+                },
+            ],
+        );
+        let mut components = original.clone();
+        assert!(!entry.apply(&mut components));
+        assert_eq!(components, original);
     }
 }
@@ -0,0 +1,143 @@
+//! Persistent undo history
+//!
+//! Each project's undo tree is kept in IndexedDB between visits, deflated
+//! and trimmed to a size bound. It is stored with a fingerprint of the canvas
+//! it ends at. When the project loads with a different canvas, because the
+//! last edits were never saved, another device saved over them, or an older
+//! git commit was checked out, the history is rebased onto the entry whose
+//! state the loaded canvas is. If there is none, it is dropped.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+
+use super::history::History;
+use super::persistence::Persistable;
+use crate::constants::{MAX_HISTORY_NODES, MAX_HISTORY_SIZE, MAX_PERSISTED_HISTORY_BYTES};
+use crate::domain::{AppError, AppResult, CanvasComponent};
+use crate::services::indexed_db::ObjectStore;
+
+/// Undo history of a project, keyed by project id
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredHistory {
+    /// Fingerprint of the canvas the history ends at
+    pub fingerprint: u64,
+    /// The `History` as deflated JSON, base64 encoded
+    pub data: String,
+}
+
+impl Persistable for StoredHistory {
+    fn object_store() -> ObjectStore {
+        ObjectStore::Histories
+    }
+}
+
+impl StoredHistory {
+    /// Pack `history`, which ends at `components`. The oldest entries are
+    /// dropped until it fits in `MAX_PERSISTED_HISTORY_BYTES`.
+    pub fn new(history: &History, components: &[CanvasComponent]) -> AppResult<Self> {
+        let mut history = history.clone();
+        let (mut depth, mut nodes) = (MAX_HISTORY_SIZE, MAX_HISTORY_NODES);
+        let data = loop {
+            let json =
+                serde_json::to_vec(&history).map_err(|e| AppError::Serialization(e.to_string()))?;
+            let data = STANDARD.encode(miniz_oxide::deflate::compress_to_vec(&json, 6));
+            if data.len() <= MAX_PERSISTED_HISTORY_BYTES {
+                break data;
+            }
+            if history.is_empty() {
+                return Err(AppError::Storage(
+                    "Undo history too large to keep".to_string(),
+                ));
+            }
+            (depth, nodes) = (depth / 2, nodes / 2);
+            history.trim(depth, nodes);
+        };
+        Ok(Self {
+            fingerprint: fingerprint(components),
+            data,
+        })
+    }
+
+    /// Unpack the history and line it up with `components`, the canvas the
+    /// project loaded with. `None` if it does not fit them.
+    pub fn restore(&self, components: &[CanvasComponent]) -> Option<History> {
+        let deflated = STANDARD.decode(&self.data).ok()?;
+        let json = miniz_oxide::inflate::decompress_to_vec(&deflated).ok()?;
+        let mut history: History = serde_json::from_slice(&json).ok()?;
+        let fits = history.rebase(components, |replayed| {
+            fingerprint(replayed) == self.fingerprint
+        });
+        fits.then_some(history)
+    }
+}
+
+/// Stable hash of a component tree (FNV-1a over its JSON with sorted keys)
+pub fn fingerprint(components: &[CanvasComponent]) -> u64 {
+    // Going through `Value` sorts map keys, which `HashMap` fields don't
+    let json = serde_json::to_value(components)
+        .map(|value| value.to_string())
+        .unwrap_or_default();
+    json.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ButtonComponent;
+    use crate::state::history::{HistoryEntry, Operation, Slot};
+
+    fn add(components: &mut Vec<CanvasComponent>, history: &mut History, label: &str) {
+        let operation = Operation::Insert {
+            slot: Slot::root(components.len()),
+            component: CanvasComponent::Button(ButtonComponent::new(label.to_string())),
+        };
+        assert!(operation.apply(components));
+        history.push(HistoryEntry::new(format!("Add {}", label), vec![operation]));
+    }
+
+    #[test]
+    fn test_restore_rebases_onto_older_canvas() {
+        let mut history = History::new();
+        let mut components = Vec::new();
+        add(&mut components, &mut history, "A");
+        let saved = components.clone();
+        add(&mut components, &mut history, "B");
+        add(&mut components, &mut history, "C");
+
+        let stored = StoredHistory::new(&history, &components).unwrap();
+        assert_eq!(
+            stored.restore(&components).map(|h| h.current()),
+            Some(history.current())
+        );
+
+        // The last two edits never reached the server
+        let mut restored = stored.restore(&saved).unwrap();
+        let mut canvas = saved.clone();
+        assert!(restored.redo(&mut canvas).is_some());
+        assert!(restored.redo(&mut canvas).is_some());
+        assert_eq!(canvas, components);
+
+        // A canvas the history never passed through
+        let unrelated = vec![CanvasComponent::Button(ButtonComponent::new(
+            "Z".to_string(),
+        ))];
+        assert!(stored.restore(&unrelated).is_none());
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_map_order() {
+        let mut button = ButtonComponent::new("A".to_string());
+        for key in ["label", "disabled", "variant", "size"] {
+            button
+                .bindings
+                .insert(key.to_string(), format!("{}_var", key));
+        }
+        let components = vec![CanvasComponent::Button(button)];
+        let copy: Vec<CanvasComponent> =
+            serde_json::from_str(&serde_json::to_string(&components).unwrap()).unwrap();
+        assert_eq!(fingerprint(&components), fingerprint(&copy));
+    }
+}
@@ -1,11 +1,25 @@
 pub mod app_state;
+pub mod component_index;
 pub mod derived;
+pub mod find_replace;
+pub mod grouping;
 pub mod history;
+pub mod history_store;
 pub mod persistence;
 pub mod project;
+pub mod recovery;
+pub mod selection;
+pub mod sync;
 
 pub use app_state::*;
+pub use component_index::*;
 pub use derived::*;
+pub use find_replace::*;
+pub use grouping::*;
 pub use history::*;
+pub use history_store::*;
 pub use persistence::*;
 pub use project::*;
+pub use recovery::*;
+pub use selection::*;
+pub use sync::*;
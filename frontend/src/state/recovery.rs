@@ -0,0 +1,211 @@
+//! Crash recovery
+//!
+//! While a project is open the editor keeps a journal of its latest state,
+//! undo history included, written shortly after every change. If the tab
+//! dies between saves, the next visit finds a journal newer than the saved
+//! project and offers to bring it back.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::history::History;
+use super::persistence::Persistable;
+use super::project::Project;
+use crate::domain::{CanvasComponent, ComponentId};
+use crate::services::indexed_db::ObjectStore;
+
+/// Unsaved editor state of a project, keyed by project id
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecoveryJournal {
+    pub project: Project,
+    pub history: History,
+    /// When the journal was written, in milliseconds since the epoch
+    pub written_at: f64,
+}
+
+impl Persistable for RecoveryJournal {
+    fn object_store() -> ObjectStore {
+        ObjectStore::Journals
+    }
+}
+
+impl RecoveryJournal {
+    /// Whether the journal holds edits the saved project, last modified at
+    /// `saved_at`, does not have
+    pub fn is_ahead_of(&self, saved: &Project, saved_at: f64) -> bool {
+        self.written_at > saved_at && self.project != *saved
+    }
+}
+
+/// A journal the user can restore or discard
+#[derive(Clone, Debug)]
+pub struct RecoveryOffer {
+    pub project_id: String,
+    pub journal: RecoveryJournal,
+    pub summary: RecoverySummary,
+}
+
+/// What a journal changes compared to the saved project
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecoverySummary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    /// New project name, if it was renamed
+    pub renamed: Option<String>,
+    pub variables_changed: bool,
+    pub tokens_changed: bool,
+}
+
+impl RecoverySummary {
+    pub fn between(saved: &Project, recovered: &Project) -> Self {
+        let before = by_id(&saved.layout);
+        let after = by_id(&recovered.layout);
+        Self {
+            added: after.keys().filter(|id| !before.contains_key(id)).count(),
+            removed: before.keys().filter(|id| !after.contains_key(id)).count(),
+            changed: after
+                .iter()
+                .filter(|(id, c)| before.get(id).is_some_and(|b| shallow(b) != shallow(c)))
+                .count(),
+            renamed: (saved.name != recovered.name).then(|| recovered.name.clone()),
+            variables_changed: saved.variables != recovered.variables,
+            tokens_changed: saved.design_tokens != recovered.design_tokens,
+        }
+    }
+
+    /// One line per kind of change
+    pub fn describe(&self) -> Vec<String> {
+        let count = |n: usize, what: &str| {
+            let plural = if n == 1 { "component" } else { "components" };
+            format!("{} {} {}", n, plural, what)
+        };
+        let mut lines = Vec::new();
+        if self.added > 0 {
+            lines.push(count(self.added, "added"));
+        }
+        if self.removed > 0 {
+            lines.push(count(self.removed, "removed"));
+        }
+        if self.changed > 0 {
+            lines.push(count(self.changed, "changed"));
+        }
+        if let Some(name) = &self.renamed {
+            lines.push(format!("Renamed to \"{}\"", name));
+        }
+        if self.variables_changed {
+            lines.push("Variables changed".to_string());
+        }
+        if self.tokens_changed {
+            lines.push("Design tokens changed".to_string());
+        }
+        if lines.is_empty() {
+            lines.push("Settings changed".to_string());
+        }
+        lines
+    }
+}
+
+/// Every component in the tree, by id
+fn by_id(components: &[CanvasComponent]) -> HashMap<ComponentId, &CanvasComponent> {
+    let mut map = HashMap::new();
+    let mut stack: Vec<&CanvasComponent> = components.iter().collect();
+    while let Some(component) = stack.pop() {
+        map.insert(*component.id(), component);
+        match component {
+            CanvasComponent::Container(c) => stack.extend(&c.children),
+            CanvasComponent::Card(c) => stack.extend(&c.children),
+            _ => {}
+        }
+    }
+    map
+}
+
+/// A component without its children, so a changed child does not count as
+/// a change to every ancestor
+fn shallow(component: &CanvasComponent) -> CanvasComponent {
+    let mut component = component.clone();
+    match &mut component {
+        CanvasComponent::Container(c) => c.children.clear(),
+        CanvasComponent::Card(c) => c.children.clear(),
+        _ => {}
+    }
+    component
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::design_tokens::DesignTokens;
+    use crate::domain::{ButtonComponent, ContainerComponent, TextComponent};
+    use crate::state::SettingsState;
+
+    fn project(layout: Vec<CanvasComponent>) -> Project {
+        Project::new(
+            "Landing".to_string(),
+            layout,
+            SettingsState::default(),
+            DesignTokens::default(),
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn test_summary_counts_nested_changes_once() {
+        let button = ButtonComponent::new("Go".to_string());
+        let text = TextComponent::new("Old".to_string());
+        let mut container = ContainerComponent::new();
+        container.children = vec![
+            CanvasComponent::Button(button.clone()),
+            CanvasComponent::Text(text.clone()),
+        ];
+        let saved = project(vec![CanvasComponent::Container(container.clone())]);
+
+        let mut edited_text = text.clone();
+        edited_text.content = "New".to_string();
+        container.children = vec![
+            CanvasComponent::Text(edited_text),
+            CanvasComponent::Text(TextComponent::new("Added".to_string())),
+        ];
+        let mut recovered = project(vec![CanvasComponent::Container(container)]);
+        recovered.name = "Landing v2".to_string();
+
+        let summary = RecoverySummary::between(&saved, &recovered);
+        assert_eq!(
+            summary,
+            RecoverySummary {
+                added: 1,
+                removed: 1,
+                changed: 1,
+                renamed: Some("Landing v2".to_string()),
+                variables_changed: false,
+                tokens_changed: false,
+            }
+        );
+        assert_eq!(
+            summary.describe(),
+            vec![
+                "1 component added",
+                "1 component removed",
+                "1 component changed",
+                "Renamed to \"Landing v2\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_journal_must_be_newer_and_different() {
+        let saved = project(Vec::new());
+        let mut journal = RecoveryJournal {
+            project: saved.clone(),
+            history: History::new(),
+            written_at: 20.0,
+        };
+        assert!(!journal.is_ahead_of(&saved, 10.0));
+
+        journal.project.name = "Renamed".to_string();
+        assert!(journal.is_ahead_of(&saved, 10.0));
+        assert!(!journal.is_ahead_of(&saved, 30.0));
+    }
+}
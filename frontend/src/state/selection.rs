@@ -0,0 +1,216 @@
+//! Canvas selection
+//!
+//! Any number of components can be selected at once. The most recently
+//! selected one is the primary selection, which single-component views like
+//! the breadcrumbs follow. The anchor is where a shift-click range starts.
+
+use std::collections::HashSet;
+
+use super::component_index::ComponentIndex;
+use crate::domain::ComponentId;
+
+/// How a click changes the selection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectMode {
+    /// Select only the clicked component
+    Replace,
+    /// Add or remove the clicked component (ctrl/cmd-click)
+    Toggle,
+    /// Select everything from the anchor to the clicked component
+    /// (shift-click)
+    Range,
+}
+
+impl SelectMode {
+    pub fn from_modifiers(shift: bool, ctrl_or_meta: bool) -> Self {
+        if shift {
+            Self::Range
+        } else if ctrl_or_meta {
+            Self::Toggle
+        } else {
+            Self::Replace
+        }
+    }
+
+    pub fn from_mouse(ev: &web_sys::MouseEvent) -> Self {
+        Self::from_modifiers(ev.shift_key(), ev.ctrl_key() || ev.meta_key())
+    }
+}
+
+/// The selected components, in the order they were selected
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Selection {
+    ids: Vec<ComponentId>,
+    anchor: Option<ComponentId>,
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Just `id`
+    pub fn only(id: ComponentId) -> Self {
+        Self {
+            ids: vec![id],
+            anchor: Some(id),
+        }
+    }
+
+    /// `ids` without repeats, the first one anchoring later ranges
+    pub fn from_ids(ids: impl IntoIterator<Item = ComponentId>) -> Self {
+        let mut selection = Self::new();
+        for id in ids {
+            selection.add(id);
+        }
+        selection.anchor = selection.ids.first().copied();
+        selection
+    }
+
+    pub fn ids(&self) -> &[ComponentId] {
+        &self.ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn contains(&self, id: &ComponentId) -> bool {
+        self.ids.contains(id)
+    }
+
+    /// The most recently selected component
+    pub fn primary(&self) -> Option<ComponentId> {
+        self.ids.last().copied()
+    }
+
+    fn add(&mut self, id: ComponentId) {
+        self.ids.retain(|selected| *selected != id);
+        self.ids.push(id);
+    }
+
+    /// Apply a click on `id`. `order` lists the components in document
+    /// order, which ranges follow.
+    pub fn click(&mut self, id: ComponentId, mode: SelectMode, order: &[ComponentId]) {
+        match mode {
+            SelectMode::Replace => *self = Self::only(id),
+            SelectMode::Toggle => {
+                if self.contains(&id) {
+                    self.ids.retain(|selected| *selected != id);
+                } else {
+                    self.add(id);
+                }
+                self.anchor = Some(id);
+            }
+            SelectMode::Range => {
+                let anchor = self.anchor.unwrap_or(id);
+                let position = |target| order.iter().position(|other| *other == target);
+                let (Some(from), Some(to)) = (position(anchor), position(id)) else {
+                    *self = Self::only(id);
+                    return;
+                };
+                // Walk from the anchor so the clicked component ends up primary
+                let range: Vec<ComponentId> = if from <= to {
+                    order[from..=to].to_vec()
+                } else {
+                    order[to..=from].iter().rev().copied().collect()
+                };
+                self.ids = range;
+                self.anchor = Some(anchor);
+            }
+        }
+    }
+
+    /// Keep only the components `keep` accepts
+    pub fn retain(&mut self, keep: impl Fn(&ComponentId) -> bool) {
+        self.ids.retain(|id| keep(id));
+        if self.anchor.is_some_and(|anchor| !keep(&anchor)) {
+            self.anchor = self.ids.first().copied();
+        }
+    }
+
+    /// The selected components that are not inside another selected one, in
+    /// document order. Actions on the selection apply to these, since
+    /// moving or copying a container takes its children along.
+    pub fn roots(&self, index: &ComponentIndex) -> Vec<ComponentId> {
+        let selected: HashSet<&ComponentId> = self.ids.iter().collect();
+        let mut roots: Vec<(Vec<usize>, ComponentId)> = self
+            .ids
+            .iter()
+            .filter(|id| {
+                !index
+                    .ancestors(id)
+                    .iter()
+                    .any(|ancestor| selected.contains(ancestor))
+            })
+            .filter_map(|id| {
+                let path = index.path(id)?;
+                Some((path.iter().map(|slot| slot.index).collect(), *id))
+            })
+            .collect();
+        roots.sort_by(|a, b| a.0.cmp(&b.0));
+        roots.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ButtonComponent, CanvasComponent, ContainerComponent};
+
+    /// A container holding two buttons, followed by a third button
+    fn tree() -> (Vec<CanvasComponent>, [ComponentId; 4]) {
+        let mut container = ContainerComponent::new();
+        let a = ButtonComponent::new("A".to_string());
+        let b = ButtonComponent::new("B".to_string());
+        let c = ButtonComponent::new("C".to_string());
+        let ids = [container.id, a.id, b.id, c.id];
+        container.children.push(CanvasComponent::Button(a));
+        container.children.push(CanvasComponent::Button(b));
+        (
+            vec![
+                CanvasComponent::Container(container),
+                CanvasComponent::Button(c),
+            ],
+            ids,
+        )
+    }
+
+    #[test]
+    fn test_toggle_and_range() {
+        let (components, [container, a, b, c]) = tree();
+        let order = ComponentIndex::new(&components).order();
+        assert_eq!(order, vec![container, a, b, c]);
+
+        let mut selection = Selection::only(a);
+        selection.click(c, SelectMode::Toggle, &order);
+        assert_eq!(selection.ids(), &[a, c]);
+        selection.click(a, SelectMode::Toggle, &order);
+        assert_eq!(selection.ids(), &[c]);
+
+        // The last toggle moved the anchor to `a`
+        selection.click(c, SelectMode::Range, &order);
+        assert_eq!(selection.ids(), &[a, b, c]);
+        // Ranges are redrawn from the same anchor
+        selection.click(container, SelectMode::Range, &order);
+        assert_eq!(selection.ids(), &[a, container]);
+        assert_eq!(selection.primary(), Some(container));
+
+        selection.click(b, SelectMode::Replace, &order);
+        assert_eq!(selection, Selection::only(b));
+    }
+
+    #[test]
+    fn test_roots_skip_nested_and_follow_document_order() {
+        let (components, [container, a, b, c]) = tree();
+        let index = ComponentIndex::new(&components);
+        let selection = Selection::from_ids([c, b, container]);
+        assert_eq!(selection.roots(&index), vec![container, c]);
+        let selection = Selection::from_ids([c, b, a]);
+        assert_eq!(selection.roots(&index), vec![a, b, c]);
+    }
+}
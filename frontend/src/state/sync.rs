@@ -0,0 +1,463 @@
+//! Offline-first sync
+//!
+//! Saves and commits land in a durable queue first and are replayed against
+//! the backend in order, right away when it is reachable and otherwise when
+//! the browser comes back online. A save only goes through if the server
+//! copy is still the one the edit started from; if it changed in the
+//! meantime the queue stops at a conflict until the user picks a version.
+//! Any other rejection also stops the queue, keeping the write, until the
+//! user retries or discards it.
+
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+
+use super::app_state::{Notification, UiState};
+use super::persistence::Persistable;
+use super::project::Project;
+use crate::domain::AppError;
+use crate::services::api_client::{ApiClient, NewCommit, StoredProject};
+use crate::services::offline_store::{
+    CachedProject, PendingWrite, ProjectCache, SYNC_QUEUE_KEY, SyncQueue,
+};
+use crate::services::project_manager::{ProjectManager, ProjectMetadata};
+
+/// How often to retry while the backend is unreachable, in milliseconds
+const RETRY_INTERVAL_MS: u32 = 30_000;
+
+/// Suffix for the project a conflicting local copy is saved as
+const CONFLICT_COPY_SUFFIX: &str = " (conflicted copy)";
+
+/// Where the queue stands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncStatus {
+    /// Nothing is waiting
+    Synced,
+    /// Writes are being uploaded
+    Syncing,
+    /// The backend is unreachable; writes wait in the queue
+    Offline,
+    /// A save was refused because the server copy changed
+    Conflict,
+    /// A write was rejected for another reason and waits for the user
+    Failed,
+}
+
+/// A queued save refused by the backend
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncConflict {
+    pub project_id: String,
+    pub project_name: String,
+    /// `last_modified` of the server copy, if it could be fetched
+    pub server_modified: Option<f64>,
+}
+
+/// A queued write the backend rejected for a reason other than a conflict
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncFailure {
+    pub project_id: String,
+    pub project_name: String,
+    /// What the backend said, for the user
+    pub message: String,
+}
+
+/// How to settle a [`SyncConflict`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Overwrite the server copy with the local one
+    KeepLocal,
+    /// Discard the local changes
+    UseServer,
+    /// Save the local copy as a new project and keep the server copy
+    SaveAsCopy,
+}
+
+/// Result of replaying the queue
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushOutcome {
+    /// Every write went through
+    Synced,
+    /// A write was rejected and is kept until the user retries or
+    /// discards it
+    Failed,
+    /// Another flush is already running and will pick up new writes
+    Busy,
+    Offline,
+    Conflict,
+}
+
+/// Offline queue, project cache and sync status
+#[derive(Clone, Copy)]
+pub struct SyncState {
+    pub status: RwSignal<SyncStatus>,
+    pub queue: RwSignal<SyncQueue>,
+    pub cache: RwSignal<ProjectCache>,
+    pub conflict: RwSignal<Option<SyncConflict>>,
+    pub failure: RwSignal<Option<SyncFailure>>,
+    flushing: StoredValue<bool>,
+    ui: UiState,
+}
+
+impl SyncState {
+    /// Empty state; [`SyncState::restore`] reads what was saved on this device
+    pub fn new(ui: UiState) -> Self {
+        Self {
+            status: RwSignal::new(SyncStatus::Synced),
+            queue: RwSignal::new(SyncQueue::default()),
+            cache: RwSignal::new(ProjectCache::default()),
+            conflict: RwSignal::new(None),
+            failure: RwSignal::new(None),
+            flushing: StoredValue::new(false),
+            ui,
+        }
+    }
+
+    /// Read the queue and cache saved on this device. Writes made before
+    /// this finishes are kept, after the restored ones.
+    pub async fn restore(self) {
+        match SyncQueue::load(SYNC_QUEUE_KEY).await {
+            Ok(Some(stored)) => self.update_queue(|queue| queue.restore(stored)),
+            Ok(None) => {}
+            Err(e) => self.notify_storage_error(&e),
+        }
+        match ProjectCache::load().await {
+            Ok(stored) => self.cache.update(|cache| cache.restore(stored)),
+            Err(e) => self.notify_storage_error(&e),
+        }
+        if !self.queue.with_untracked(SyncQueue::is_empty)
+            && self.status.get_untracked() == SyncStatus::Synced
+        {
+            self.status.set(SyncStatus::Offline);
+        }
+    }
+
+    fn notify_storage_error(&self, e: &AppError) {
+        self.ui.notify(Notification::error(format!(
+            "Could not access offline data: {}",
+            e.user_message()
+        )));
+    }
+
+    /// Replay the queue now, whenever the browser comes back online, and
+    /// periodically while the backend is unreachable
+    pub fn start(&self) {
+        let sync = *self;
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let on_online = Closure::<dyn FnMut()>::new(move || {
+            leptos::task::spawn_local(async move {
+                sync.flush().await;
+            });
+        });
+        let _ =
+            window.add_event_listener_with_callback("online", on_online.as_ref().unchecked_ref());
+        // Lives as long as the app
+        on_online.forget();
+
+        leptos::task::spawn_local(async move {
+            sync.flush().await;
+            loop {
+                gloo_timers::future::TimeoutFuture::new(RETRY_INTERVAL_MS).await;
+                if sync.status.get_untracked() == SyncStatus::Offline {
+                    sync.flush().await;
+                }
+            }
+        });
+    }
+
+    /// Number of writes waiting for the backend
+    pub fn pending(&self) -> usize {
+        self.queue.with(SyncQueue::len)
+    }
+
+    /// Whether a project has local changes the server has not seen
+    pub fn has_pending_save(&self, id: &str) -> bool {
+        self.queue.with_untracked(|q| q.has_save(id))
+    }
+
+    pub fn cached(&self, id: &str) -> Option<CachedProject> {
+        self.cache.with_untracked(|c| c.get(id).cloned())
+    }
+
+    /// Cached projects for the dashboard, most recently modified first
+    pub fn cached_projects(&self) -> Vec<ProjectMetadata> {
+        self.cache.with_untracked(ProjectCache::list)
+    }
+
+    /// Remember the server copy of a project, unless local changes to it
+    /// are still waiting to be uploaded
+    pub fn remember(&self, id: &str, stored: &StoredProject) {
+        if self.has_pending_save(id) {
+            return;
+        }
+        self.update_cache(&[id], |cache| {
+            cache.store_synced(id, stored.project.clone(), stored.last_modified)
+        });
+    }
+
+    /// Queue a save of `project`
+    pub fn queue_save(&self, id: &str, project: Project) {
+        self.update_cache(&[id], |cache| {
+            cache.store_local(id, project, js_sys::Date::now())
+        });
+        self.update_queue(|queue| queue.push_save(id));
+    }
+
+    /// Queue a commit of `snapshot`
+    pub fn queue_commit(&self, id: &str, message: &str, snapshot: Project) {
+        self.update_queue(|queue| queue.push_commit(id, message, snapshot, js_sys::Date::now()));
+        if self.status.get_untracked() == SyncStatus::Synced {
+            self.status.set(SyncStatus::Offline);
+        }
+    }
+
+    fn update_queue(&self, f: impl FnOnce(&mut SyncQueue)) {
+        self.queue.update(f);
+        let queue = self.queue.get_untracked();
+        let sync = *self;
+        leptos::task::spawn_local(async move {
+            if let Err(e) = queue.save(SYNC_QUEUE_KEY).await {
+                sync.notify_storage_error(&e);
+            }
+        });
+    }
+
+    /// Change the cache and write back the records of the projects in `ids`
+    fn update_cache(&self, ids: &[&str], f: impl FnOnce(&mut ProjectCache)) {
+        let queue = self.queue;
+        let mut evicted = Vec::new();
+        self.cache.update(|cache| {
+            f(cache);
+            evicted = cache.evict(|id| {
+                queue.with_untracked(|q| q.writes.iter().any(|w| w.project_id() == id))
+            });
+        });
+
+        // Started together so the transactions are created in call order
+        let writes = ids
+            .iter()
+            .map(|id| (id.to_string(), self.cached(id)))
+            .chain(evicted.into_iter().map(|id| (id, None)))
+            .map(|(id, cached)| async move {
+                match cached {
+                    Some(cached) => cached.save(&id).await,
+                    None => CachedProject::delete(&id).await,
+                }
+            });
+        let writes = futures::future::join_all(writes);
+        let sync = *self;
+        leptos::task::spawn_local(async move {
+            if let Some(Err(e)) = writes.await.into_iter().find(Result::is_err) {
+                sync.notify_storage_error(&e);
+            }
+        });
+    }
+
+    /// Upload queued writes in order until the queue is empty, the
+    /// backend is unreachable, or it reports a conflict or rejects a write
+    pub async fn flush(self) -> FlushOutcome {
+        if self.flushing.get_value() {
+            return FlushOutcome::Busy;
+        }
+        if self.conflict.get_untracked().is_some() {
+            return FlushOutcome::Conflict;
+        }
+        if self.failure.get_untracked().is_some() {
+            return FlushOutcome::Failed;
+        }
+        self.flushing.set_value(true);
+        self.status.set(SyncStatus::Syncing);
+
+        let mut outcome = FlushOutcome::Synced;
+        while let Some(write) = self.queue.with_untracked(|q| q.writes.first().cloned()) {
+            match self.send(&write).await {
+                Ok(()) => {}
+                Err(AppError::Offline(_)) => {
+                    outcome = FlushOutcome::Offline;
+                    break;
+                }
+                Err(AppError::Conflict(_)) => {
+                    self.report_conflict(write.project_id()).await;
+                    outcome = FlushOutcome::Conflict;
+                    break;
+                }
+                Err(e) => {
+                    self.report_failure(write.project_id(), &e);
+                    outcome = FlushOutcome::Failed;
+                    break;
+                }
+            }
+        }
+
+        self.status.set(match outcome {
+            FlushOutcome::Offline => SyncStatus::Offline,
+            FlushOutcome::Conflict => SyncStatus::Conflict,
+            FlushOutcome::Failed => SyncStatus::Failed,
+            _ => SyncStatus::Synced,
+        });
+        self.flushing.set_value(false);
+        outcome
+    }
+
+    async fn send(&self, write: &PendingWrite) -> Result<(), AppError> {
+        match write {
+            PendingWrite::Save { project_id } => {
+                let Some(cached) = self.cached(project_id) else {
+                    // Nothing left to upload
+                    self.update_queue(|q| q.complete(write));
+                    return Ok(());
+                };
+                let metadata = ProjectManager::save_project_from(
+                    project_id,
+                    &cached.project,
+                    cached.server_modified,
+                )
+                .await?;
+                // Edits made during the upload keep the save queued
+                let changed = self
+                    .cached(project_id)
+                    .is_some_and(|c| c.revision != cached.revision);
+                self.update_cache(&[project_id], |cache| {
+                    cache.mark_synced(project_id, metadata)
+                });
+                if !changed {
+                    self.update_queue(|q| q.complete(write));
+                }
+            }
+            PendingWrite::Commit {
+                project_id,
+                message,
+                timestamp,
+                snapshot,
+            } => {
+                let commit = NewCommit {
+                    message,
+                    timestamp: *timestamp,
+                    snapshot,
+                };
+                ApiClient::new().create_commit(project_id, &commit).await?;
+                self.update_queue(|q| q.complete(write));
+            }
+        }
+        Ok(())
+    }
+
+    async fn report_conflict(&self, id: &str) {
+        let server_modified = ProjectManager::load_stored_project(id)
+            .await
+            .ok()
+            .map(|stored| stored.last_modified);
+        let project_name = self
+            .cached(id)
+            .map(|c| c.project.name)
+            .unwrap_or_else(|| id.to_string());
+        self.conflict.set(Some(SyncConflict {
+            project_id: id.to_string(),
+            project_name,
+            server_modified,
+        }));
+    }
+
+    fn report_failure(&self, id: &str, e: &AppError) {
+        let project_name = self
+            .cached(id)
+            .map(|c| c.project.name)
+            .unwrap_or_else(|| id.to_string());
+        self.ui.notify(Notification::error(format!(
+            "Could not sync \"{}\": {}. Retry or discard the change from the status bar.",
+            project_name,
+            e.user_message()
+        )));
+        self.failure.set(Some(SyncFailure {
+            project_id: id.to_string(),
+            project_name,
+            message: e.user_message(),
+        }));
+    }
+
+    /// Send the rejected write again, and the ones queued behind it
+    pub async fn retry(self) -> FlushOutcome {
+        self.failure.set(None);
+        self.flush().await
+    }
+
+    /// Drop the rejected write, losing what it would have uploaded, and
+    /// resume syncing
+    pub fn discard_failed(self) {
+        if self.failure.get_untracked().is_none() {
+            return;
+        }
+        if let Some(write) = self.queue.with_untracked(|q| q.writes.first().cloned()) {
+            self.update_queue(|q| q.complete(&write));
+        }
+        self.failure.set(None);
+        leptos::task::spawn_local(async move {
+            self.flush().await;
+        });
+    }
+
+    /// Settle the current conflict and resume syncing.
+    ///
+    /// Returns the project to show for the conflicting id: the server copy
+    /// for [`ConflictResolution::UseServer`], and the id of the new project
+    /// for [`ConflictResolution::SaveAsCopy`].
+    pub async fn resolve(
+        self,
+        resolution: ConflictResolution,
+    ) -> Result<ConflictResolved, AppError> {
+        let Some(conflict) = self.conflict.get_untracked() else {
+            return Ok(ConflictResolved::Unchanged);
+        };
+        let id = conflict.project_id.as_str();
+        let resolved = match resolution {
+            ConflictResolution::KeepLocal => {
+                // Base the save on the current server copy so it overwrites it
+                let stored = ProjectManager::load_stored_project(id).await?;
+                self.update_cache(&[id], |cache| {
+                    if let Some(cached) = cache.projects.get_mut(id) {
+                        cached.server_modified = Some(stored.last_modified);
+                    }
+                });
+                ConflictResolved::Unchanged
+            }
+            ConflictResolution::UseServer => {
+                let stored = ProjectManager::load_stored_project(id).await?;
+                self.update_queue(|q| q.drop_save(id));
+                self.remember(id, &stored);
+                ConflictResolved::Reload(stored.project)
+            }
+            ConflictResolution::SaveAsCopy => {
+                let copy_id = ProjectManager::generate_id();
+                self.update_cache(&[id, &copy_id], |cache| {
+                    if let Some(mut cached) = cache.projects.remove(id) {
+                        cached.project.name.push_str(CONFLICT_COPY_SUFFIX);
+                        cached.metadata.id = copy_id.clone();
+                        cached.metadata.name = cached.project.name.clone();
+                        cached.server_modified = None;
+                        cache.projects.insert(copy_id.clone(), cached);
+                    }
+                });
+                self.update_queue(|q| q.move_save(id, &copy_id));
+                ConflictResolved::Copied(copy_id)
+            }
+        };
+
+        self.conflict.set(None);
+        leptos::task::spawn_local(async move {
+            self.flush().await;
+        });
+        Ok(resolved)
+    }
+}
+
+/// What the editor should do after a conflict was settled
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConflictResolved {
+    /// Keep showing the local copy
+    Unchanged,
+    /// Show this copy of the conflicting project
+    Reload(Project),
+    /// The local copy now lives under this project id
+    Copied(String),
+}
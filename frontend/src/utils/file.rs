@@ -17,7 +17,12 @@ pub fn download_file(content: &str, filename: &str, mime_type: &str) -> AppResul
             )
         })?;
 
-    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(|e| {
+    download_blob(&blob, filename)
+}
+
+/// Trigger a browser download of a Blob
+fn download_blob(blob: &web_sys::Blob, filename: &str) -> AppResult<()> {
+    let url = web_sys::Url::create_object_url_with_blob(blob).map_err(|e| {
         AppError::Export(
             e.as_string()
                 .unwrap_or_else(|| "Unknown URL error".to_string()),
@@ -58,6 +63,26 @@ pub fn download_file(content: &str, filename: &str, mime_type: &str) -> AppResul
     ))
 }
 
+/// Download binary content as a file
+pub fn download_bytes(content: &[u8], filename: &str, mime_type: &str) -> AppResult<()> {
+    let bytes = js_sys::Uint8Array::from(content);
+    let array = js_sys::Array::new();
+    array.push(&bytes.buffer());
+
+    let blob_options = web_sys::BlobPropertyBag::new();
+    blob_options.set_type(mime_type);
+
+    let blob = web_sys::Blob::new_with_buffer_source_sequence_and_options(&array, &blob_options)
+        .map_err(|e| {
+            AppError::Export(
+                e.as_string()
+                    .unwrap_or_else(|| "Unknown Blob error".to_string()),
+            )
+        })?;
+
+    download_blob(&blob, filename)
+}
+
 /// Reads the content of a File object as text
 pub async fn read_file_as_text(file: &web_sys::File) -> AppResult<String> {
     let promise = file.text();
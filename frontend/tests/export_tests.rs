@@ -1,7 +1,11 @@
 use leptos_studio::domain::{
-    ButtonComponent, CanvasComponent, ContainerComponent, FlexDirection, LayoutType,
+    ButtonComponent, CanvasComponent, ContainerComponent, FlexDirection, ImageComponent,
+    ImageSource, LayoutType,
+};
+use leptos_studio::services::export_advanced::TailwindHtmlGenerator;
+use leptos_studio::services::export_service::{
+    CodeGenerator, HtmlCodeGenerator, LeptosCodeGenerator,
 };
-use leptos_studio::services::export_service::{CodeGenerator, LeptosCodeGenerator};
 use leptos_studio::state::ExportPreset;
 
 #[test]
@@ -40,3 +44,33 @@ fn test_leptos_generator_structure() {
     assert!(code.contains("Nested Button"));
     assert!(code.contains("<button"));
 }
+
+#[test]
+fn test_image_srcset_in_generators() {
+    let mut image = ImageComponent::new("asset://hero.jpg".to_string(), "Hero".to_string());
+    image.width = Some("600px".to_string());
+    image.srcset = vec![
+        ImageSource {
+            src: "asset://hero-320w.webp".to_string(),
+            width: 320,
+        },
+        ImageSource {
+            src: "asset://hero.jpg".to_string(),
+            width: 1200,
+        },
+    ];
+    let components = vec![CanvasComponent::Image(image)];
+    let expected = "srcset=\"asset://hero-320w.webp 320w, asset://hero.jpg 1200w\" sizes=\"600px\"";
+
+    let generators: Vec<Box<dyn CodeGenerator>> = vec![
+        Box::new(LeptosCodeGenerator::new(ExportPreset::Plain)),
+        Box::new(HtmlCodeGenerator),
+        Box::new(TailwindHtmlGenerator),
+    ];
+    for generator in generators {
+        let code = generator
+            .generate(&components, &[])
+            .expect("Failed to generate code");
+        assert!(code.contains(expected), "missing srcset in:\n{}", code);
+    }
+}
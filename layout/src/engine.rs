@@ -0,0 +1,574 @@
+use serde::Serialize;
+use serde_json::Value;
+use taffy::TaffyError;
+use taffy::prelude::*;
+
+use crate::style;
+use crate::typography::Typography;
+
+/// Padding around the page and space between top-level components
+pub const PAGE_SPACING: f32 = 16.0;
+/// Height of empty containers and of custom components
+pub const MIN_BOX_HEIGHT: f32 = 48.0;
+/// Width-to-height ratio of images without an explicit height
+const IMAGE_ASPECT_RATIO: f32 = 16.0 / 9.0;
+/// Inputs and selects: 20px line, 10px padding and 1.5px border on each side
+const FIELD_HEIGHT: f32 = 43.0;
+
+/// The `CanvasComponent` variant a box was laid out for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum ComponentKind {
+    Button,
+    Text,
+    Input,
+    Container,
+    Image,
+    Card,
+    Select,
+    Custom,
+}
+
+impl ComponentKind {
+    fn from_variant(variant: &str) -> Option<Self> {
+        Some(match variant {
+            "Button" => Self::Button,
+            "Text" => Self::Text,
+            "Input" => Self::Input,
+            "Container" => Self::Container,
+            "Image" => Self::Image,
+            "Card" => Self::Card,
+            "Select" => Self::Select,
+            "Custom" => Self::Custom,
+            _ => return None,
+        })
+    }
+
+    /// Containers and cards, the kinds that can have children
+    pub fn has_children(self) -> bool {
+        matches!(self, Self::Container | Self::Card)
+    }
+}
+
+/// Where a component ends up, in page coordinates
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ComponentBox {
+    pub id: String,
+    pub kind: ComponentKind,
+    /// Id of the enclosing container or card
+    pub parent: Option<String>,
+    /// Nesting depth; top-level components are at 0
+    pub depth: usize,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ComponentBox {
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    /// Whether the two boxes share some area; touching edges don't count
+    pub fn overlaps(&self, other: &ComponentBox) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+}
+
+/// Computed geometry of a whole design
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PageLayout {
+    pub width: f32,
+    pub height: f32,
+    /// Every component in document order, parents before their children
+    pub boxes: Vec<ComponentBox>,
+}
+
+impl PageLayout {
+    pub fn get(&self, id: &str) -> Option<&ComponentBox> {
+        self.boxes.iter().find(|b| b.id == id)
+    }
+
+    /// Boxes directly inside `parent`, or the top-level boxes for `None`
+    pub fn children<'a>(
+        &'a self,
+        parent: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a ComponentBox> {
+        self.boxes
+            .iter()
+            .filter(move |b| b.parent.as_deref() == parent)
+    }
+}
+
+#[derive(Debug)]
+pub struct LayoutError(TaffyError);
+
+impl std::fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "layout failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+impl From<TaffyError> for LayoutError {
+    fn from(e: TaffyError) -> Self {
+        Self(e)
+    }
+}
+
+/// Content of a leaf node, measured during layout
+enum Content {
+    Text {
+        text: String,
+        typography: Typography,
+    },
+    Fixed {
+        width: f32,
+        height: f32,
+    },
+    Image,
+    Block {
+        height: f32,
+    },
+}
+
+/// A laid out component, before positions are made absolute
+struct Entry {
+    node: NodeId,
+    id: String,
+    kind: ComponentKind,
+    /// Index of the parent entry
+    parent: Option<usize>,
+    depth: usize,
+}
+
+struct Builder {
+    tree: TaffyTree<Content>,
+    entries: Vec<Entry>,
+}
+
+/// Lay out `components`, the serialized top-level `CanvasComponent`s of a
+/// design, on a page `viewport_width` pixels wide. Components that don't
+/// deserialize as a known variant are skipped along with their children.
+pub fn compute_layout(
+    components: &[Value],
+    viewport_width: f32,
+) -> Result<PageLayout, LayoutError> {
+    let mut builder = Builder {
+        tree: TaffyTree::new(),
+        entries: Vec::new(),
+    };
+    let mut children = Vec::new();
+    for component in components {
+        if let Some(node) = builder.build(component, None, 0, true)? {
+            children.push(node);
+        }
+    }
+    let root = builder.tree.new_with_children(
+        Style {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            size: Size {
+                width: length(viewport_width),
+                height: auto(),
+            },
+            padding: Rect::length(PAGE_SPACING),
+            gap: Size::length(PAGE_SPACING),
+            ..Default::default()
+        },
+        &children,
+    )?;
+
+    let Builder { mut tree, entries } = builder;
+    tree.compute_layout_with_measure(
+        root,
+        Size {
+            width: AvailableSpace::Definite(viewport_width),
+            height: AvailableSpace::MaxContent,
+        },
+        |known, available, _, content, _| measure(known, available, content),
+    )?;
+
+    let mut boxes: Vec<ComponentBox> = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        let layout = tree.layout(entry.node)?;
+        let (origin_x, origin_y) = entry
+            .parent
+            .map(|p| (boxes[p].x, boxes[p].y))
+            .unwrap_or((0.0, 0.0));
+        boxes.push(ComponentBox {
+            id: entry.id.clone(),
+            kind: entry.kind,
+            parent: entry.parent.map(|p| entries[p].id.clone()),
+            depth: entry.depth,
+            x: origin_x + layout.location.x,
+            y: origin_y + layout.location.y,
+            width: layout.size.width,
+            height: layout.size.height,
+        });
+    }
+
+    let page = tree.layout(root)?;
+    Ok(PageLayout {
+        width: page.size.width,
+        height: page.size.height,
+        boxes,
+    })
+}
+
+impl Builder {
+    /// Add a component and its children. In block flow (the page, stacks
+    /// and cards) buttons and images keep their own width, the way inline
+    /// elements do, while everything else spans the full width.
+    fn build(
+        &mut self,
+        component: &Value,
+        parent: Option<usize>,
+        depth: usize,
+        block_flow: bool,
+    ) -> Result<Option<NodeId>, LayoutError> {
+        let Some((kind, props)) = variant(component) else {
+            return Ok(None);
+        };
+
+        let mut node_style = Style::default();
+        if block_flow && matches!(kind, ComponentKind::Button | ComponentKind::Image) {
+            node_style.align_self = Some(AlignSelf::FlexStart);
+        }
+        let content = match kind {
+            ComponentKind::Button => {
+                let (typography, vertical, horizontal) = match str_prop(props, "size") {
+                    "Small" => (Typography::XS, 4.0, 8.0),
+                    "Large" => (Typography::BASE, 12.0, 24.0),
+                    _ => (Typography::SM, 8.0, 16.0),
+                };
+                Some(Content::Fixed {
+                    width: typography.text_width(str_prop(props, "label")) + 2.0 * horizontal,
+                    height: typography.line_height + 2.0 * vertical,
+                })
+            }
+            ComponentKind::Text => {
+                let mut typography = Typography::for_text_style(str_prop(props, "style"));
+                if let Some(size) = props
+                    .get("custom_style")
+                    .and_then(|s| s.get("font_size"))
+                    .and_then(|v| v.as_f64())
+                {
+                    typography = typography.with_font_size(size as f32);
+                }
+                Some(Content::Text {
+                    text: str_prop(props, "content").to_string(),
+                    typography,
+                })
+            }
+            ComponentKind::Input | ComponentKind::Select => {
+                node_style.size.width = percent(1.0);
+                Some(Content::Block {
+                    height: FIELD_HEIGHT,
+                })
+            }
+            ComponentKind::Image => {
+                node_style.size = Size {
+                    width: props
+                        .get("width")
+                        .and_then(|v| v.as_str())
+                        .and_then(style::dimension)
+                        .unwrap_or(percent(1.0)),
+                    height: props
+                        .get("height")
+                        .and_then(|v| v.as_str())
+                        .and_then(style::dimension)
+                        .unwrap_or(auto()),
+                };
+                node_style.aspect_ratio = Some(IMAGE_ASPECT_RATIO);
+                Some(Content::Image)
+            }
+            ComponentKind::Custom => Some(Content::Block {
+                height: MIN_BOX_HEIGHT,
+            }),
+            ComponentKind::Container => {
+                container_style(&mut node_style, props);
+                None
+            }
+            ComponentKind::Card => {
+                node_style.display = Display::Flex;
+                node_style.flex_direction = FlexDirection::Column;
+                node_style.padding = Rect::length(num(props, "padding"));
+                if props
+                    .get("border")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+                {
+                    node_style.border = Rect::length(1.0);
+                }
+                None
+            }
+        };
+        if kind.has_children() {
+            node_style.min_size.height = length(MIN_BOX_HEIGHT);
+        }
+        // Text keeps its `ComponentStyle` in `custom_style`
+        if let Some(component_style) = props
+            .get("custom_style")
+            .or_else(|| props.get("style"))
+            .filter(|s| s.is_object())
+        {
+            style::apply(&mut node_style, component_style);
+        }
+
+        let node = match content {
+            Some(content) => self.tree.new_leaf_with_context(node_style, content)?,
+            None => self.tree.new_leaf(node_style)?,
+        };
+        let index = self.entries.len();
+        self.entries.push(Entry {
+            node,
+            id: str_prop(props, "id").to_string(),
+            kind,
+            parent,
+            depth,
+        });
+
+        if kind.has_children() {
+            let child_flow = kind == ComponentKind::Card
+                || matches!(props.get("layout"), Some(Value::String(s)) if s == "Stack");
+            let mut children = Vec::new();
+            for child in props
+                .get("children")
+                .and_then(|c| c.as_array())
+                .into_iter()
+                .flatten()
+            {
+                if let Some(child) = self.build(child, Some(index), depth + 1, child_flow)? {
+                    children.push(child);
+                }
+            }
+            self.tree.set_children(node, &children)?;
+        }
+        Ok(Some(node))
+    }
+}
+
+/// Kind and fields of a serialized `CanvasComponent`, if it is a known variant
+pub(crate) fn variant(component: &Value) -> Option<(ComponentKind, &Value)> {
+    let (variant, props) = component.as_object()?.iter().next()?;
+    Some((ComponentKind::from_variant(variant)?, props))
+}
+
+/// Every known component in document order, the order of `PageLayout::boxes`
+pub(crate) fn flatten<'a>(components: &'a [Value], out: &mut Vec<&'a Value>) {
+    for (kind, props) in components.iter().filter_map(variant) {
+        out.push(props);
+        if kind.has_children()
+            && let Some(children) = props.get("children").and_then(|c| c.as_array())
+        {
+            flatten(children, out);
+        }
+    }
+}
+
+/// Flexbox or grid settings of a container, from its `LayoutType`, `gap`
+/// and `Spacing` padding
+fn container_style(node_style: &mut Style, props: &Value) {
+    node_style.display = Display::Flex;
+    node_style.flex_direction = FlexDirection::Column;
+    // `Stack` keeps the column flow, like a block
+    if let Some(Value::Object(layout)) = props.get("layout") {
+        if let Some(flex) = layout.get("Flex") {
+            if str_prop(flex, "direction") == "Row" {
+                node_style.flex_direction = FlexDirection::Row;
+            }
+            if flex.get("wrap").and_then(|v| v.as_bool()).unwrap_or(false) {
+                node_style.flex_wrap = FlexWrap::Wrap;
+            }
+            node_style.align_items = Some(match str_prop(flex, "align_items") {
+                "Center" => AlignItems::Center,
+                "End" => AlignItems::FlexEnd,
+                "Stretch" => AlignItems::Stretch,
+                "Baseline" => AlignItems::Baseline,
+                _ => AlignItems::FlexStart,
+            });
+            node_style.justify_content = Some(match str_prop(flex, "justify_content") {
+                "Center" => JustifyContent::Center,
+                "End" => JustifyContent::FlexEnd,
+                "Between" => JustifyContent::SpaceBetween,
+                "Around" => JustifyContent::SpaceAround,
+                "Evenly" => JustifyContent::SpaceEvenly,
+                _ => JustifyContent::FlexStart,
+            });
+        } else if let Some(grid) = layout.get("Grid") {
+            node_style.display = Display::Grid;
+            let columns = num(grid, "columns").max(1.0) as u16;
+            node_style.grid_template_columns = evenly_sized_tracks(columns);
+        }
+    }
+    node_style.gap = Size::length(num(props, "gap"));
+    if let Some(padding) = props.get("padding") {
+        node_style.padding = Rect {
+            left: length(num(padding, "left")),
+            right: length(num(padding, "right")),
+            top: length(num(padding, "top")),
+            bottom: length(num(padding, "bottom")),
+        };
+    }
+}
+
+fn measure(
+    known: Size<Option<f32>>,
+    available: Size<AvailableSpace>,
+    content: Option<&mut Content>,
+) -> Size<f32> {
+    if let Size {
+        width: Some(width),
+        height: Some(height),
+    } = known
+    {
+        return Size { width, height };
+    }
+    let available_width = match available.width {
+        AvailableSpace::Definite(width) => Some(width),
+        AvailableSpace::MinContent => Some(0.0),
+        AvailableSpace::MaxContent => None,
+    };
+    let (width, height) = match content {
+        None => (0.0, 0.0),
+        Some(Content::Fixed { width, height }) => (*width, *height),
+        Some(Content::Block { height }) => {
+            (known.width.or(available_width).unwrap_or(0.0), *height)
+        }
+        Some(Content::Image) => {
+            let width = known.width.or(available_width).unwrap_or(0.0);
+            (width, width / IMAGE_ASPECT_RATIO)
+        }
+        Some(Content::Text { text, typography }) => {
+            let max_width = known.width.or(available_width).unwrap_or(f32::INFINITY);
+            let lines = typography.wrap(text, max_width.max(typography.min_content_width(text)));
+            let widest = lines
+                .iter()
+                .map(|l| typography.text_width(l))
+                .fold(0.0, f32::max);
+            (widest, lines.len() as f32 * typography.line_height)
+        }
+    };
+    Size {
+        width: known.width.unwrap_or(width),
+        height: known.height.unwrap_or(height),
+    }
+}
+
+fn str_prop<'a>(props: &'a Value, name: &str) -> &'a str {
+    props.get(name).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn num(props: &Value, name: &str) -> f32 {
+    props.get(name).and_then(|v| v.as_f64()).unwrap_or(0.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn button(id: &str, label: &str) -> Value {
+        json!({ "Button": { "id": id, "label": label, "variant": "Primary", "size": "Medium" } })
+    }
+
+    fn container(id: &str, layout: Value, children: Vec<Value>) -> Value {
+        json!({ "Container": {
+            "id": id,
+            "children": children,
+            "layout": layout,
+            "gap": 8,
+            "padding": { "top": 10, "right": 10, "bottom": 10, "left": 10 }
+        }})
+    }
+
+    fn geometry(b: &ComponentBox) -> (f32, f32, f32, f32) {
+        (b.x, b.y, b.width, b.height)
+    }
+
+    #[test]
+    fn test_flex_row_with_gap_and_padding() {
+        let row = json!({ "Flex": { "direction": "Row", "wrap": false, "align_items": "Start", "justify_content": "Start" } });
+        let layout = compute_layout(
+            &[container(
+                "row",
+                row,
+                vec![button("a", "Go"), button("b", "Stop")],
+            )],
+            400.0,
+        )
+        .unwrap();
+
+        // 14px labels are 7.7px per character, plus 16px padding each side;
+        // positions and sizes are rounded to whole pixels
+        let row = layout.get("row").unwrap();
+        assert_eq!(geometry(row), (16.0, 16.0, 368.0, 56.0));
+        let a = layout.get("a").unwrap();
+        assert_eq!(geometry(a), (26.0, 26.0, 47.0, 36.0));
+        let b = layout.get("b").unwrap();
+        assert_eq!((b.x, b.y), (a.right() + 8.0, 26.0));
+        assert_eq!((b.parent.as_deref(), b.depth), (Some("row"), 1));
+        assert!(!a.overlaps(b));
+        assert_eq!(layout.height, 16.0 + 56.0 + 16.0);
+    }
+
+    #[test]
+    fn test_grid_columns_share_the_width() {
+        let grid = json!({ "Grid": { "columns": 3, "rows": 1 } });
+        let children = (0..4).map(|i| button(&i.to_string(), "x")).collect();
+        let layout = compute_layout(&[container("grid", grid, children)], 400.0).unwrap();
+
+        // 368 wide, minus padding and two gaps, leaves 110.7px per column
+        let xs: Vec<f32> = layout.children(Some("grid")).map(|b| b.x).collect();
+        assert_eq!(xs, vec![26.0, 145.0, 263.0, 26.0]);
+        assert_eq!(layout.get("3").unwrap().y, 26.0 + 36.0 + 8.0);
+    }
+
+    #[test]
+    fn test_text_wraps_at_the_viewport() {
+        let text = json!({ "Text": { "id": "t", "content": "one two three four", "style": "Body", "tag": "P" } });
+        let wide = compute_layout(std::slice::from_ref(&text), 1024.0).unwrap();
+        let narrow = compute_layout(&[text], 120.0).unwrap();
+
+        assert_eq!(wide.get("t").unwrap().height, 24.0);
+        // 88px content box: "one two" and "three four"
+        assert_eq!(narrow.get("t").unwrap().height, 2.0 * 24.0);
+    }
+
+    #[test]
+    fn test_component_style_sizes_and_images() {
+        let sized = json!({ "Custom": { "id": "c", "name": "X", "template": "", "props": {},
+            "style": { "width": "50%", "height": "100px", "margin": "0 0 20px 0" } } });
+        let image = json!({ "Image": { "id": "i", "src": "", "alt": "", "width": "320px", "height": null } });
+        let layout = compute_layout(&[sized, image], 400.0).unwrap();
+
+        assert_eq!(
+            geometry(layout.get("c").unwrap()),
+            (16.0, 16.0, 184.0, 100.0)
+        );
+        assert_eq!(
+            geometry(layout.get("i").unwrap()),
+            (16.0, 152.0, 320.0, 180.0)
+        );
+    }
+
+    #[test]
+    fn test_unknown_variants_are_skipped() {
+        let layout = compute_layout(
+            &[json!({ "Video": { "id": "v" } }), button("a", "Go")],
+            400.0,
+        )
+        .unwrap();
+        assert_eq!(layout.boxes.len(), 1);
+        assert_eq!(layout.get("a").unwrap().y, 16.0);
+    }
+}
@@ -0,0 +1,20 @@
+//! Headless layout of Leptos Studio designs.
+//!
+//! Maps a `CanvasComponent` tree onto flexbox and grid nodes and computes
+//! where every component ends up at a given viewport width, without a
+//! browser. Text is measured with approximate font metrics, so positions
+//! are close to, not exactly, what a browser renders.
+//!
+//! The input is the serialized component tree, the JSON that projects are
+//! stored as, so the backend can lay out stored projects without the
+//! frontend's component types.
+
+mod engine;
+mod style;
+pub mod typography;
+pub mod wireframe;
+
+pub use engine::{
+    ComponentBox, ComponentKind, LayoutError, MIN_BOX_HEIGHT, PAGE_SPACING, PageLayout,
+    compute_layout,
+};
@@ -0,0 +1,158 @@
+//! CSS values from `ComponentStyle` mapped onto taffy styles.
+//!
+//! Only lengths in `px` (or unitless) and percentages are understood;
+//! anything else, such as `em` or `calc()`, is ignored.
+
+use serde_json::Value;
+use taffy::prelude::*;
+
+/// A length or percentage, such as `12px`, `12` or `50%`
+fn parse(value: &str) -> Option<Length> {
+    let value = value.trim();
+    if let Some(percent) = value.strip_suffix('%') {
+        return percent
+            .trim()
+            .parse()
+            .ok()
+            .map(|p: f32| Length::Percent(p / 100.0));
+    }
+    value
+        .strip_suffix("px")
+        .unwrap_or(value)
+        .trim()
+        .parse()
+        .ok()
+        .map(Length::Px)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Length {
+    Px(f32),
+    Percent(f32),
+}
+
+impl Length {
+    fn dimension(self) -> Dimension {
+        match self {
+            Length::Px(v) => Dimension::length(v),
+            Length::Percent(v) => Dimension::percent(v),
+        }
+    }
+
+    fn length_percentage(self) -> LengthPercentage {
+        match self {
+            Length::Px(v) => LengthPercentage::length(v),
+            Length::Percent(v) => LengthPercentage::percent(v),
+        }
+    }
+
+    fn length_percentage_auto(self) -> LengthPercentageAuto {
+        match self {
+            Length::Px(v) => LengthPercentageAuto::length(v),
+            Length::Percent(v) => LengthPercentageAuto::percent(v),
+        }
+    }
+}
+
+pub(crate) fn dimension(value: &str) -> Option<Dimension> {
+    if value.trim() == "auto" {
+        return Some(Dimension::auto());
+    }
+    parse(value).map(Length::dimension)
+}
+
+/// CSS box shorthand: one to four values, clockwise from the top
+fn shorthand<T: Copy>(value: &str, parse_one: impl Fn(&str) -> Option<T>) -> Option<Rect<T>> {
+    let values = value
+        .split_whitespace()
+        .map(parse_one)
+        .collect::<Option<Vec<T>>>()?;
+    let (top, right, bottom, left) = match values[..] {
+        [all] => (all, all, all, all),
+        [vertical, horizontal] => (vertical, horizontal, vertical, horizontal),
+        [top, horizontal, bottom] => (top, horizontal, bottom, horizontal),
+        [top, right, bottom, left] => (top, right, bottom, left),
+        _ => return None,
+    };
+    Some(Rect {
+        left,
+        right,
+        top,
+        bottom,
+    })
+}
+
+pub(crate) fn padding(value: &str) -> Option<Rect<LengthPercentage>> {
+    shorthand(value, |v| parse(v).map(Length::length_percentage))
+}
+
+pub(crate) fn margin(value: &str) -> Option<Rect<LengthPercentageAuto>> {
+    shorthand(value, |v| {
+        if v == "auto" {
+            Some(LengthPercentageAuto::auto())
+        } else {
+            parse(v).map(Length::length_percentage_auto)
+        }
+    })
+}
+
+/// Apply the sizing parts of a serialized `ComponentStyle` to `style`
+pub(crate) fn apply(style: &mut Style, component_style: &Value) {
+    let field = |name: &str| component_style.get(name).and_then(|v| v.as_str());
+
+    if let Some(width) = field("width").and_then(dimension) {
+        style.size.width = width;
+    }
+    if let Some(height) = field("height").and_then(dimension) {
+        style.size.height = height;
+    }
+    if let Some(padding) = field("padding").and_then(padding) {
+        style.padding = padding;
+    }
+    if let Some(margin) = field("margin").and_then(margin) {
+        style.margin = margin;
+    }
+    if let Some(gap) = field("gap").and_then(parse) {
+        style.gap = Size {
+            width: gap.length_percentage(),
+            height: gap.length_percentage(),
+        };
+    }
+    if let Some(border) = component_style.get("border_width").and_then(|v| v.as_f64()) {
+        style.border = Rect::length(border as f32);
+    }
+    match field("display") {
+        Some("flex") => style.display = Display::Flex,
+        Some("grid") => style.display = Display::Grid,
+        Some("block") => style.display = Display::Block,
+        _ => {}
+    }
+    match field("flex_direction") {
+        Some("row") => style.flex_direction = FlexDirection::Row,
+        Some("column") => style.flex_direction = FlexDirection::Column,
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lengths() {
+        assert_eq!(dimension("120px"), Some(Dimension::length(120.0)));
+        assert_eq!(dimension(" 50% "), Some(Dimension::percent(0.5)));
+        assert_eq!(dimension("auto"), Some(Dimension::auto()));
+        assert_eq!(dimension("2em"), None);
+    }
+
+    #[test]
+    fn test_box_shorthand() {
+        let p = padding("8px 16px").unwrap();
+        assert_eq!(p.top, LengthPercentage::length(8.0));
+        assert_eq!(p.left, LengthPercentage::length(16.0));
+        let m = margin("0 auto").unwrap();
+        assert_eq!(m.right, LengthPercentageAuto::auto());
+        assert!(padding("1px 2px 3px 4px 5px").is_none());
+    }
+}
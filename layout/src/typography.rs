@@ -0,0 +1,119 @@
+//! Approximate font metrics.
+//!
+//! There is no font rasterizer here, so text is measured with an average
+//! glyph width. Sizes follow the default typography design tokens.
+
+/// Average glyph width, as a fraction of the font size
+pub const CHAR_WIDTH: f32 = 0.55;
+
+/// Metrics of a typography design token
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Typography {
+    /// Design token name, such as `text-base`
+    pub token: &'static str,
+    pub font_size: f32,
+    pub line_height: f32,
+    pub font_weight: u16,
+}
+
+impl Typography {
+    pub const XS: Self = Self::token("text-xs", 12.0, 16.0, 400);
+    pub const SM: Self = Self::token("text-sm", 14.0, 20.0, 400);
+    pub const BASE: Self = Self::token("text-base", 16.0, 24.0, 400);
+    pub const LG: Self = Self::token("text-lg", 18.0, 28.0, 400);
+    pub const XL: Self = Self::token("text-xl", 20.0, 28.0, 400);
+    pub const XXL: Self = Self::token("text-2xl", 24.0, 32.0, 700);
+    pub const XXXL: Self = Self::token("text-3xl", 30.0, 36.0, 700);
+
+    const fn token(
+        token: &'static str,
+        font_size: f32,
+        line_height: f32,
+        font_weight: u16,
+    ) -> Self {
+        Self {
+            token,
+            font_size,
+            line_height,
+            font_weight,
+        }
+    }
+
+    /// Token used for a `TextStyle` variant name
+    pub fn for_text_style(style: &str) -> Self {
+        match style {
+            "Heading1" => Self::XXXL,
+            "Heading2" => Self::XXL,
+            "Heading3" => Self::XL,
+            "Caption" => Self::XS,
+            _ => Self::BASE,
+        }
+    }
+
+    /// The same token at another font size, keeping the line height ratio
+    pub fn with_font_size(self, font_size: f32) -> Self {
+        Self {
+            line_height: self.line_height * font_size / self.font_size,
+            font_size,
+            ..self
+        }
+    }
+
+    pub fn text_width(&self, text: &str) -> f32 {
+        text.chars().count() as f32 * self.font_size * CHAR_WIDTH
+    }
+
+    /// Break `text` into lines no wider than `max_width`, at spaces. A word
+    /// wider than `max_width` gets a line of its own. Empty text is one
+    /// empty line.
+    pub fn wrap(&self, text: &str, max_width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                if !line.is_empty()
+                    && self.text_width(&line) + self.text_width(word) + self.text_width(" ")
+                        > max_width
+                {
+                    lines.push(std::mem::take(&mut line));
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Width of the widest word, the narrowest the text can get
+    pub fn min_content_width(&self, text: &str) -> f32 {
+        text.split_whitespace()
+            .map(|w| self.text_width(w))
+            .fold(0.0, f32::max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_at_spaces() {
+        let body = Typography::BASE;
+        // 8.8px per character: "hello world" is 96.8px wide
+        assert_eq!(body.wrap("hello world", 100.0), vec!["hello world"]);
+        assert_eq!(body.wrap("hello world", 60.0), vec!["hello", "world"]);
+        assert_eq!(body.wrap("a\nb", 100.0), vec!["a", "b"]);
+        assert_eq!(body.wrap("", 100.0), vec![""]);
+        assert_eq!(body.min_content_width("hi there"), 5.0 * 8.8);
+    }
+
+    #[test]
+    fn test_font_size_override_keeps_ratio() {
+        let caption = Typography::for_text_style("Caption").with_font_size(24.0);
+        assert_eq!(caption.token, "text-xs");
+        assert_eq!(caption.line_height, 32.0);
+    }
+}
@@ -0,0 +1,211 @@
+//! SVG wireframe thumbnails.
+//!
+//! A design is laid out on a 640px page and drawn as boxes, text bars and
+//! image placeholders, cropped to the top 400px.
+
+use serde_json::Value;
+
+use crate::engine::{self, ComponentBox, ComponentKind};
+use crate::typography::Typography;
+
+/// Width of the page the design is laid out on
+pub const WIDTH: f32 = 640.0;
+/// Height of the visible top part of the page
+pub const HEIGHT: f32 = 400.0;
+
+const BACKGROUND: &str = "#f9fafb";
+const OUTLINE: &str = "#d1d5db";
+const SURFACE: &str = "#ffffff";
+const TEXT: &str = "#9ca3af";
+const HEADING: &str = "#4b5563";
+const PRIMARY: &str = "#3b82f6";
+const PLACEHOLDER: &str = "#e5e7eb";
+
+/// Render serialized top-level components as an SVG `data:` URL, usable as
+/// an `img` source
+pub fn render(components: &[Value]) -> String {
+    let layout = engine::compute_layout(components, WIDTH).unwrap_or_default();
+    let mut props = Vec::new();
+    engine::flatten(components, &mut props);
+
+    let mut svg = format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 {WIDTH} {HEIGHT}'>\
+         <rect width='{WIDTH}' height='{HEIGHT}' fill='{BACKGROUND}'/>"
+    );
+    // Parents come first, so they are drawn behind their children
+    for (b, props) in layout.boxes.iter().zip(props) {
+        if b.y < HEIGHT {
+            draw(&mut svg, b, props);
+        }
+    }
+    svg.push_str("</svg>");
+    data_url(&svg)
+}
+
+fn draw(svg: &mut String, b: &ComponentBox, props: &Value) {
+    let (x, y, w, h) = (b.x, b.y, b.width, b.height);
+    match b.kind {
+        ComponentKind::Button => {
+            let (fill, bar) = match str_prop(props, "variant") {
+                "Outline" | "Ghost" => (format!("fill='{SURFACE}' stroke='{PRIMARY}'"), PRIMARY),
+                _ => (format!("fill='{PRIMARY}'"), SURFACE),
+            };
+            rect(svg, x, y, w, h, 6.0, &fill);
+            let inset = (w * 0.25).min(16.0);
+            rect(
+                svg,
+                x + inset,
+                y + h / 2.0 - 2.0,
+                w - 2.0 * inset,
+                4.0,
+                2.0,
+                &format!("fill='{bar}'"),
+            );
+        }
+        ComponentKind::Text => {
+            let typography = Typography::for_text_style(str_prop(props, "style"));
+            let color = if typography.font_weight >= 700 {
+                HEADING
+            } else {
+                TEXT
+            };
+            let bar = typography.font_size * 0.5;
+            let lines = typography.wrap(str_prop(props, "content"), w);
+            for (i, line) in lines.iter().enumerate() {
+                let top =
+                    y + i as f32 * typography.line_height + (typography.line_height - bar) / 2.0;
+                let width = typography.text_width(line).min(w);
+                rect(
+                    svg,
+                    x,
+                    top,
+                    width,
+                    bar,
+                    bar / 2.0,
+                    &format!("fill='{color}'"),
+                );
+            }
+        }
+        ComponentKind::Input | ComponentKind::Select => {
+            rect(
+                svg,
+                x,
+                y,
+                w,
+                h,
+                4.0,
+                &format!("fill='{SURFACE}' stroke='{OUTLINE}'"),
+            );
+            let placeholder = Typography::SM.text_width(str_prop(props, "placeholder"));
+            rect(
+                svg,
+                x + 12.0,
+                y + h / 2.0 - 3.0,
+                placeholder.clamp(24.0, w * 0.6),
+                6.0,
+                3.0,
+                &format!("fill='{PLACEHOLDER}'"),
+            );
+            if b.kind == ComponentKind::Select {
+                let (cx, cy) = (x + w - 20.0, y + h / 2.0);
+                line(svg, cx - 5.0, cy - 2.0, cx, cy + 3.0);
+                line(svg, cx, cy + 3.0, cx + 5.0, cy - 2.0);
+            }
+        }
+        ComponentKind::Image => {
+            rect(svg, x, y, w, h, 4.0, &format!("fill='{PLACEHOLDER}'"));
+            line(svg, x, y, x + w, y + h);
+            line(svg, x + w, y, x, y + h);
+        }
+        ComponentKind::Container => {
+            rect(
+                svg,
+                x,
+                y,
+                w,
+                h,
+                6.0,
+                &format!("fill='none' stroke='{OUTLINE}' stroke-dasharray='4 3'"),
+            );
+        }
+        ComponentKind::Card => {
+            let radius = props
+                .get("border_radius")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0) as f32;
+            rect(
+                svg,
+                x,
+                y,
+                w,
+                h,
+                radius,
+                &format!("fill='{SURFACE}' stroke='{OUTLINE}'"),
+            );
+        }
+        ComponentKind::Custom => {
+            rect(
+                svg,
+                x,
+                y,
+                w,
+                h,
+                4.0,
+                &format!("fill='{PLACEHOLDER}' stroke='{TEXT}' stroke-dasharray='4 3'"),
+            );
+        }
+    }
+}
+
+fn rect(svg: &mut String, x: f32, y: f32, w: f32, h: f32, rx: f32, style: &str) {
+    if w > 0.0 && h > 0.0 {
+        svg.push_str(&format!(
+            "<rect x='{x:.0}' y='{y:.0}' width='{w:.0}' height='{h:.0}' rx='{rx:.0}' {style}/>"
+        ));
+    }
+}
+
+fn line(svg: &mut String, x1: f32, y1: f32, x2: f32, y2: f32) {
+    svg.push_str(&format!(
+        "<line x1='{x1:.0}' y1='{y1:.0}' x2='{x2:.0}' y2='{y2:.0}' stroke='{TEXT}'/>"
+    ));
+}
+
+fn str_prop<'a>(props: &'a Value, name: &str) -> &'a str {
+    props.get(name).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Percent-encode the characters a `data:image/svg+xml` URL cannot carry
+fn data_url(svg: &str) -> String {
+    let mut url = String::from("data:image/svg+xml,");
+    for c in svg.chars() {
+        match c {
+            '%' => url.push_str("%25"),
+            '#' => url.push_str("%23"),
+            '<' => url.push_str("%3C"),
+            '>' => url.push_str("%3E"),
+            '"' => url.push_str("%22"),
+            c => url.push(c),
+        }
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_is_cropped_to_the_top() {
+        let heading =
+            json!({ "Text": { "id": "t", "content": "Title", "style": "Heading1", "tag": "H1" } });
+        let url = render(&vec![heading; 30]);
+
+        assert!(url.starts_with("data:image/svg+xml,%3Csvg"));
+        assert!(!url.contains('#') && !url.contains('<'));
+        // 36px lines with 16px spacing: only the first eight fit
+        assert_eq!(url.matches("%3Crect").count() - 1, 8);
+        assert_eq!(render(&[]).matches("%3Crect").count(), 1);
+    }
+}